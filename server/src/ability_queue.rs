@@ -0,0 +1,99 @@
+use crate::client_compat::{client_supports, FEATURE_ABILITY_QUEUE};
+use crate::config::{get_config_u64, ABILITY_QUEUE_WINDOW_MS};
+use crate::cooldown::{attack_cooldown_ms, remaining_cooldown, start_cooldown, ATTACK_COOLDOWN_KEY, CATEGORY_ABILITY};
+use crate::{player, Player};
+use spacetimedb::{table, Identity, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+/// Janela padrão em que um ataque pode ser enfileirado antes do fim do cooldown
+pub const DEFAULT_QUEUE_WINDOW_MS: u64 = 250;
+
+/// Próximo ataque enfileirado de cada player (no máximo um por player).
+/// O tick executa a linha quando `execute_at` é alcançado.
+#[table(name = queued_ability, public)]
+#[derive(Clone)]
+pub struct QueuedAbility {
+    #[primary_key]
+    pub player_id: u32,
+    pub identity: Identity,
+    pub weapon_type: String,
+    pub direction_x: f32,
+    pub direction_y: f32,
+    pub queued_at: Timestamp,
+    pub execute_at: Timestamp,
+}
+
+/// Enfileira o ataque se o cooldown restante estiver dentro da janela configurada.
+/// Um novo pedido substitui o anterior (o último input do jogador vence).
+pub fn try_queue_ability(
+    ctx: &ReducerContext,
+    player: &Player,
+    weapon_type: &str,
+    direction_x: f32,
+    direction_y: f32,
+    remaining: Duration,
+) -> bool {
+    // Clientes antigos não esperam ataques atrasados: mantém o comportamento de rejeição
+    if !client_supports(ctx, player.identity, FEATURE_ABILITY_QUEUE) {
+        return false;
+    }
+
+    let window_ms = get_config_u64(ctx, ABILITY_QUEUE_WINDOW_MS, DEFAULT_QUEUE_WINDOW_MS);
+    if remaining.as_millis() as u64 > window_ms {
+        return false;
+    }
+
+    let queued = QueuedAbility {
+        player_id: player.id,
+        identity: player.identity,
+        weapon_type: weapon_type.to_string(),
+        direction_x,
+        direction_y,
+        queued_at: ctx.timestamp,
+        execute_at: ctx.timestamp + TimeDuration::from_duration(remaining),
+    };
+
+    if ctx.db.queued_ability().player_id().find(player.id).is_some() {
+        ctx.db.queued_ability().player_id().update(queued);
+    } else {
+        ctx.db.queued_ability().insert(queued);
+    }
+    true
+}
+
+/// Executa os ataques enfileirados cujo cooldown terminou.
+/// Toda a validação é refeita aqui: o estado pode ter mudado desde o enfileiramento.
+pub fn process_ability_queue(ctx: &ReducerContext) {
+    let due: Vec<QueuedAbility> = ctx.db.queued_ability().iter()
+        .filter(|q| q.execute_at <= ctx.timestamp)
+        .collect();
+
+    for queued in due {
+        ctx.db.queued_ability().player_id().delete(queued.player_id);
+
+        let player = match ctx.db.player().id().find(queued.player_id) {
+            Some(p) if p.identity == queued.identity => p,
+            _ => {
+                log::warn!("Queued attack dropped: player {} no longer valid", queued.player_id);
+                continue;
+            }
+        };
+
+        if player.is_downed {
+            log::info!("Queued attack dropped: player {} is downed", player.id);
+            continue;
+        }
+
+        if remaining_cooldown(ctx, queued.identity, CATEGORY_ABILITY, ATTACK_COOLDOWN_KEY).is_some() {
+            log::warn!("Queued attack dropped: player {} still on cooldown", player.id);
+            continue;
+        }
+
+        start_cooldown(ctx, queued.identity, CATEGORY_ABILITY, ATTACK_COOLDOWN_KEY, attack_cooldown_ms(&queued.weapon_type));
+
+        let player_id = player.id;
+        if let Err(e) = crate::combat::perform_attack(ctx, player, &queued.weapon_type, queued.direction_x, queued.direction_y) {
+            log::warn!("Queued attack for player {} failed: {}", player_id, e);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::achievement::{has_achievement, player_achievement, PlayerAchievement};
+use crate::exploration::{has_discovered, map_discovery, MapDiscovery};
+use crate::player;
+use spacetimedb::{reducer, table, Identity, ReducerContext, Table, Timestamp};
+
+pub const UNLOCK_COSMETIC: &str = "cosmetic";
+pub const UNLOCK_WAYPOINT: &str = "waypoint";
+pub const UNLOCK_ACHIEVEMENT: &str = "achievement";
+
+/// Conquistas que valem para a conta inteira; as demais são do personagem
+const ACCOUNT_WIDE_ACHIEVEMENTS: &[&str] = &["explore_all_maps", "explore_tavern_district"];
+
+/// Desbloqueio guardado no nível da conta (a `Identity`). Personagens novos da
+/// mesma conta herdam o que estiver aqui, sem repetir XP ou recompensas.
+#[table(name = account_unlock, public)]
+#[derive(Clone)]
+pub struct AccountUnlock {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub kind: String,
+    pub unlock_id: String,
+    pub unlocked_at: Timestamp,
+}
+
+/// Regra de escopo: cosméticos e waypoints são sempre da conta; conquistas só
+/// as listadas em `ACCOUNT_WIDE_ACHIEVEMENTS`
+pub fn is_account_wide(kind: &str, unlock_id: &str) -> bool {
+    match kind {
+        UNLOCK_COSMETIC | UNLOCK_WAYPOINT => true,
+        UNLOCK_ACHIEVEMENT => ACCOUNT_WIDE_ACHIEVEMENTS.contains(&unlock_id),
+        _ => false,
+    }
+}
+
+pub fn has_account_unlock(ctx: &ReducerContext, identity: Identity, kind: &str, unlock_id: &str) -> bool {
+    ctx.db.account_unlock().identity().filter(identity).any(|u| u.kind == kind && u.unlock_id == unlock_id)
+}
+
+/// Promove um desbloqueio do personagem para a conta, se a regra de escopo permitir (idempotente)
+pub fn record_account_unlock(ctx: &ReducerContext, player_id: u32, kind: &str, unlock_id: &str) {
+    if !is_account_wide(kind, unlock_id) {
+        return;
+    }
+    let Some(player) = ctx.db.player().id().find(player_id) else { return };
+    if has_account_unlock(ctx, player.identity, kind, unlock_id) {
+        return;
+    }
+    ctx.db.account_unlock().insert(AccountUnlock {
+        id: 0,
+        identity: player.identity,
+        kind: kind.to_string(),
+        unlock_id: unlock_id.to_string(),
+        unlocked_at: ctx.timestamp,
+    });
+    log::info!("🔓 Account of player {} unlocked {} '{}'", player_id, kind, unlock_id);
+}
+
+/// Autorização dada pela identidade atual da conta para que a nova identidade
+/// leve os dados da conta num reclaim. Sem ela o reclaim só move o personagem.
+#[table(name = account_transfer)]
+#[derive(Clone)]
+pub struct AccountTransfer {
+    #[primary_key]
+    pub from_identity: Identity,
+    pub to_identity: Identity,
+    pub authorized_at: Timestamp,
+}
+
+/// Chamado pelo dono da conta (ainda na identidade antiga) antes de trocar de login
+#[reducer]
+pub fn authorize_account_transfer(ctx: &ReducerContext, to_identity: Identity) -> Result<(), String> {
+    ctx.db.player().identity().find(ctx.sender).ok_or("Player not found")?;
+    if to_identity == ctx.sender {
+        return Err("That is already your identity".to_string());
+    }
+    let transfer = AccountTransfer { from_identity: ctx.sender, to_identity, authorized_at: ctx.timestamp };
+    if ctx.db.account_transfer().from_identity().find(ctx.sender).is_some() {
+        ctx.db.account_transfer().from_identity().update(transfer);
+    } else {
+        ctx.db.account_transfer().insert(transfer);
+    }
+    Ok(())
+}
+
+/// Consome a autorização: `true` só se a identidade antiga liberou exatamente `to`
+pub fn take_account_transfer(ctx: &ReducerContext, from: Identity, to: Identity) -> bool {
+    let Some(transfer) = ctx.db.account_transfer().from_identity().find(from) else { return false };
+    if transfer.to_identity != to {
+        return false;
+    }
+    ctx.db.account_transfer().from_identity().delete(from);
+    true
+}
+
+/// Reclaim autorizado: os desbloqueios seguem o player para a nova identidade
+pub fn transfer_account_unlocks(ctx: &ReducerContext, from: Identity, to: Identity) {
+    for mut unlock in ctx.db.account_unlock().identity().filter(from).collect::<Vec<_>>() {
+        if has_account_unlock(ctx, to, &unlock.kind, &unlock.unlock_id) {
+            ctx.db.account_unlock().id().delete(unlock.id);
+            continue;
+        }
+        unlock.identity = to;
+        ctx.db.account_unlock().id().update(unlock);
+    }
+}
+
+/// Aplica ao personagem o que a conta já desbloqueou. Grava direto nas tabelas
+/// do personagem para não pagar de novo XP de descoberta nem recompensas.
+/// Hoje cada `Identity` tem um único personagem; isto roda na criação dele.
+pub fn inherit_account_unlocks(ctx: &ReducerContext, player_id: u32) {
+    let Some(player) = ctx.db.player().id().find(player_id) else { return };
+    let mut inherited = 0;
+    for unlock in ctx.db.account_unlock().identity().filter(player.identity) {
+        match unlock.kind.as_str() {
+            UNLOCK_WAYPOINT if !has_discovered(ctx, player_id, &unlock.unlock_id) => {
+                ctx.db.map_discovery().insert(MapDiscovery {
+                    id: 0,
+                    player_id,
+                    map_id: unlock.unlock_id,
+                    discovered_at: ctx.timestamp,
+                });
+            }
+            UNLOCK_ACHIEVEMENT if !has_achievement(ctx, player_id, &unlock.unlock_id) => {
+                ctx.db.player_achievement().insert(PlayerAchievement {
+                    id: 0,
+                    player_id,
+                    achievement_id: unlock.unlock_id,
+                    unlocked_at: ctx.timestamp,
+                });
+            }
+            // Cosméticos não são copiados: `dye_item` consulta a conta
+            _ => continue,
+        }
+        inherited += 1;
+    }
+    if inherited > 0 {
+        log::info!("🔓 Player {} inherited {} account unlocks", player_id, inherited);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::account_unlock::{record_account_unlock, UNLOCK_ACHIEVEMENT};
+use crate::reward::{grant_reward_bundle, RewardBundle};
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+
+/// Ouro e XP pagos ao desbloquear cada conquista (as demais não dão recompensa)
+const ACHIEVEMENT_REWARDS: &[(&str, u64, u64)] = &[
+    ("explore_all_maps", 500, 1000),
+    ("first_blood", 50, 100),
+    ("team_player", 200, 300),
+];
+
+fn achievement_reward(achievement_id: &str) -> Option<RewardBundle> {
+    ACHIEVEMENT_REWARDS.iter()
+        .find(|(id, _, _)| *id == achievement_id)
+        .map(|(_, gold, xp)| RewardBundle::gold(*gold).with_xp(*xp))
+}
+
+/// Conquistas desbloqueadas por player
+#[table(name = player_achievement, public)]
+#[derive(Clone)]
+pub struct PlayerAchievement {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub achievement_id: String,
+    pub unlocked_at: Timestamp,
+}
+
+pub fn has_achievement(ctx: &ReducerContext, player_id: u32, achievement_id: &str) -> bool {
+    ctx.db.player_achievement().player_id().filter(player_id).any(|a| a.achievement_id == achievement_id)
+}
+
+/// Desbloqueia uma conquista (idempotente). Retorna `true` se foi desbloqueada agora.
+pub fn unlock_achievement(ctx: &ReducerContext, player_id: u32, achievement_id: &str) -> bool {
+    if has_achievement(ctx, player_id, achievement_id) {
+        return false;
+    }
+
+    ctx.db.player_achievement().insert(PlayerAchievement {
+        id: 0,
+        player_id,
+        achievement_id: achievement_id.to_string(),
+        unlocked_at: ctx.timestamp,
+    });
+    log::info!("🏆 Player {} unlocked achievement '{}'", player_id, achievement_id);
+    record_account_unlock(ctx, player_id, UNLOCK_ACHIEVEMENT, achievement_id);
+    if let Some(reward) = achievement_reward(achievement_id) {
+        if let Err(e) = grant_reward_bundle(ctx, player_id, &reward, achievement_id) {
+            log::warn!("Achievement reward for player {} failed: {}", player_id, e);
+        }
+    }
+    true
+}
@@ -0,0 +1,53 @@
+use crate::localization::Message;
+use spacetimedb::{reducer, table, Identity, ReducerContext, Table, Timestamp};
+
+/// Identidades com permissão para reducers administrativos
+#[table(name = admin)]
+#[derive(Clone)]
+pub struct Admin {
+    #[primary_key]
+    pub identity: Identity,
+    pub granted_at: Timestamp,
+}
+
+/// Registra quem publicou o módulo como primeiro admin (chamado no init)
+pub fn bootstrap_admin(ctx: &ReducerContext) {
+    if ctx.db.admin().identity().find(ctx.sender).is_none() {
+        ctx.db.admin().insert(Admin { identity: ctx.sender, granted_at: ctx.timestamp });
+        log::info!("🔑 Admin inicial registrado: {:?}", ctx.sender);
+    }
+}
+
+pub fn is_admin(ctx: &ReducerContext, identity: Identity) -> bool {
+    identity == ctx.identity() || ctx.db.admin().identity().find(identity).is_some()
+}
+
+pub fn require_admin(ctx: &ReducerContext) -> Result<(), String> {
+    if is_admin(ctx, ctx.sender) {
+        Ok(())
+    } else {
+        log::warn!("⛔ Admin reducer rejected for {:?}", ctx.sender);
+        Err(Message::new("error.admin_required").into())
+    }
+}
+
+#[reducer]
+pub fn grant_admin(ctx: &ReducerContext, identity: Identity) -> Result<(), String> {
+    require_admin(ctx)?;
+    if ctx.db.admin().identity().find(identity).is_none() {
+        ctx.db.admin().insert(Admin { identity, granted_at: ctx.timestamp });
+        log::info!("🔑 Admin concedido para {:?}", identity);
+    }
+    Ok(())
+}
+
+#[reducer]
+pub fn revoke_admin(ctx: &ReducerContext, identity: Identity) -> Result<(), String> {
+    require_admin(ctx)?;
+    if identity == ctx.sender {
+        return Err("Cannot revoke your own admin permission".to_string());
+    }
+    ctx.db.admin().identity().delete(identity);
+    log::info!("🔑 Admin revogado de {:?}", identity);
+    Ok(())
+}
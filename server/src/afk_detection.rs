@@ -0,0 +1,170 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use crate::map::TILE_SIZE;
+use crate::player;
+use spacetimedb::{reducer, table, ReducerContext, SpacetimeType, Table, Timestamp};
+
+/// Abates guardados por player para a análise
+const MAX_SAMPLES: usize = 30;
+/// Mínimo de abates na janela antes de julgar alguma coisa
+const MIN_SAMPLES: usize = 20;
+/// A janela precisa cobrir pelo menos este tempo (farm prolongado)
+const MIN_SPAN_SECS: u64 = 15 * 60;
+/// Dispersão máxima das posições de abate (distância média ao centro)
+const STATIONARY_SPREAD: f32 = TILE_SIZE * 2.0;
+/// Coeficiente de variação máximo dos intervalos entre abates (ritmo de máquina)
+const MACHINE_INTERVAL_CV: f32 = 0.15;
+/// A cada abate suspeito os retornos caem por este fator, até o piso
+const RETURNS_DECAY: f32 = 0.9;
+const MIN_RETURNS: f32 = 0.1;
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct KillSample {
+    pub x: f32,
+    pub y: f32,
+    pub map_id: String,
+    pub at: Timestamp,
+}
+
+/// Janela recente de abates do player e o multiplicador de XP/loot em vigor
+#[table(name = farming_session)]
+#[derive(Clone)]
+pub struct FarmingSession {
+    #[primary_key]
+    pub player_id: u32,
+    pub samples: Vec<KillSample>,
+    pub returns_multiplier: f32,
+    /// Já existe revisão aberta para esta sequência
+    pub flagged: bool,
+}
+
+/// Fila de revisão para os admins (consultada via SQL)
+#[table(name = afk_review)]
+#[derive(Clone)]
+pub struct AfkReview {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub map_id: String,
+    pub position_spread: f32,
+    pub interval_cv: f32,
+    pub kills_sampled: u32,
+    pub flagged_at: Timestamp,
+    pub resolved: bool,
+}
+
+struct FarmingStats {
+    spread: f32,
+    interval_cv: f32,
+    span_secs: u64,
+}
+
+fn analyze(samples: &[KillSample]) -> Option<FarmingStats> {
+    if samples.len() < MIN_SAMPLES || samples.iter().any(|s| s.map_id != samples[0].map_id) {
+        return None;
+    }
+    let n = samples.len() as f32;
+    let (cx, cy) = samples.iter().fold((0.0, 0.0), |(x, y), s| (x + s.x / n, y + s.y / n));
+    let spread = samples.iter().map(|s| ((s.x - cx).powi(2) + (s.y - cy).powi(2)).sqrt()).sum::<f32>() / n;
+
+    let intervals: Vec<f32> = samples.windows(2)
+        .map(|w| w[1].at.duration_since(w[0].at).map(|d| d.as_secs_f32()).unwrap_or(0.0))
+        .collect();
+    let mean = intervals.iter().sum::<f32>() / intervals.len() as f32;
+    if mean <= 0.0 {
+        return None;
+    }
+    let variance = intervals.iter().map(|i| (i - mean).powi(2)).sum::<f32>() / intervals.len() as f32;
+    let span_secs = samples[samples.len() - 1].at.duration_since(samples[0].at).map(|d| d.as_secs()).unwrap_or(0);
+
+    Some(FarmingStats { spread, interval_cv: variance.sqrt() / mean, span_secs })
+}
+
+fn is_machine_like(stats: &FarmingStats) -> bool {
+    stats.span_secs >= MIN_SPAN_SECS && stats.spread <= STATIONARY_SPREAD && stats.interval_cv <= MACHINE_INTERVAL_CV
+}
+
+/// Registra um abate do player e reavalia a janela. Sequências paradas e com
+/// ritmo regular reduzem os retornos e abrem uma revisão; sair do padrão restaura tudo.
+pub fn record_kill(ctx: &ReducerContext, player_id: u32) {
+    let Some(player) = ctx.db.player().id().find(player_id) else {
+        return;
+    };
+    let existing = ctx.db.farming_session().player_id().find(player_id);
+    let mut session = existing.clone().unwrap_or(FarmingSession {
+        player_id,
+        samples: Vec::new(),
+        returns_multiplier: 1.0,
+        flagged: false,
+    });
+
+    session.samples.push(KillSample {
+        x: player.position_x,
+        y: player.position_y,
+        map_id: player.current_map_id.clone(),
+        at: ctx.timestamp,
+    });
+    if session.samples.len() > MAX_SAMPLES {
+        let excess = session.samples.len() - MAX_SAMPLES;
+        session.samples.drain(..excess);
+    }
+
+    match analyze(&session.samples).filter(is_machine_like) {
+        Some(stats) => {
+            session.returns_multiplier = (session.returns_multiplier * RETURNS_DECAY).max(MIN_RETURNS);
+            if !session.flagged {
+                session.flagged = true;
+                ctx.db.afk_review().insert(AfkReview {
+                    id: 0,
+                    player_id,
+                    map_id: player.current_map_id.clone(),
+                    position_spread: stats.spread,
+                    interval_cv: stats.interval_cv,
+                    kills_sampled: session.samples.len() as u32,
+                    flagged_at: ctx.timestamp,
+                    resolved: false,
+                });
+                log::warn!("🤖 Player {} flagged for AFK farming in {} (spread {:.1}, interval cv {:.3})",
+                           player_id, player.current_map_id, stats.spread, stats.interval_cv);
+            }
+        }
+        None => {
+            session.returns_multiplier = 1.0;
+            session.flagged = false;
+        }
+    }
+
+    if existing.is_some() {
+        ctx.db.farming_session().player_id().update(session);
+    } else {
+        ctx.db.farming_session().insert(session);
+    }
+}
+
+/// Multiplicador de XP e loot de abates (1.0 = normal)
+pub fn kill_returns(ctx: &ReducerContext, player_id: u32) -> f32 {
+    ctx.db.farming_session().player_id().find(player_id)
+        .map(|s| s.returns_multiplier)
+        .unwrap_or(1.0)
+}
+
+/// Fecha uma revisão; `clear_penalty` devolve os retornos normais e zera a janela
+#[reducer]
+pub fn resolve_afk_review(ctx: &ReducerContext, review_id: u64, clear_penalty: bool) -> Result<(), String> {
+    require_admin(ctx)?;
+    let mut review = ctx.db.afk_review().id().find(review_id).ok_or("Review not found")?;
+    if review.resolved {
+        return Err("Review already resolved".to_string());
+    }
+    review.resolved = true;
+    let player_id = review.player_id;
+    ctx.db.afk_review().id().update(review);
+
+    if clear_penalty {
+        ctx.db.farming_session().player_id().delete(player_id);
+    }
+    record_audit(ctx, "afk", format!("AFK review {} for player {} resolved (penalty cleared: {})", review_id, player_id, clear_penalty));
+    Ok(())
+}
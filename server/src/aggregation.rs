@@ -0,0 +1,51 @@
+use crate::admin::require_admin;
+use spacetimedb::{reducer, table, ReducerContext, ScheduleAt, Table};
+use std::time::Duration;
+
+pub const AGGREGATION_INTERVAL_SECS: u64 = 300;
+
+/// Agenda das agregações periódicas (leaderboards e outros resumos derivados)
+#[table(name = aggregation_schedule, scheduled(run_aggregation))]
+pub struct AggregationSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+pub fn ensure_aggregation_schedule(ctx: &ReducerContext) {
+    if ctx.db.aggregation_schedule().count() == 0 {
+        ctx.db.aggregation_schedule().insert(AggregationSchedule {
+            scheduled_id: 0,
+            scheduled_at: Duration::from_secs(AGGREGATION_INTERVAL_SECS).into(),
+        });
+    }
+}
+
+#[reducer]
+pub fn run_aggregation(ctx: &ReducerContext, _schedule: AggregationSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("run_aggregation may only be invoked by the scheduler".to_string());
+    }
+    if crate::upgrade::is_frozen(ctx) {
+        return Ok(());
+    }
+    aggregate_all(ctx);
+    Ok(())
+}
+
+/// Recalcula todas as agregações imediatamente (admin)
+#[reducer]
+pub fn run_aggregation_now(ctx: &ReducerContext) -> Result<(), String> {
+    require_admin(ctx)?;
+    aggregate_all(ctx);
+    Ok(())
+}
+
+pub fn aggregate_all(ctx: &ReducerContext) {
+    crate::leaderboard::refresh_speedrun_leaderboard(ctx);
+    crate::leaderboard::refresh_alliance_leaderboard(ctx);
+    crate::scenic::refresh_popular_spots(ctx);
+    crate::heatmap::aggregate_heatmaps(ctx);
+    crate::economy::rollup_currency_ledger(ctx);
+}
@@ -0,0 +1,191 @@
+use crate::guild::{guild, guild_of, require_guild_leader};
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+pub const MAX_ALLIANCE_GUILDS: usize = 5;
+
+/// Aliança entre guildas. Quem manda é o líder da guilda fundadora (`leader_guild_id`).
+#[table(name = alliance, public)]
+#[derive(Clone)]
+pub struct Alliance {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[unique]
+    pub name: String,
+    pub leader_guild_id: u64,
+    pub created_at: Timestamp,
+}
+
+/// Uma guilda pertence a no máximo uma aliança
+#[table(name = alliance_member, public)]
+#[derive(Clone)]
+pub struct AllianceMember {
+    #[primary_key]
+    pub guild_id: u64,
+    #[index(btree)]
+    pub alliance_id: u64,
+    pub joined_at: Timestamp,
+}
+
+#[table(name = alliance_invite, public)]
+#[derive(Clone)]
+pub struct AllianceInvite {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub guild_id: u64,
+    pub alliance_id: u64,
+    pub invited_at: Timestamp,
+}
+
+pub fn alliance_of_guild(ctx: &ReducerContext, guild_id: u64) -> Option<u64> {
+    ctx.db.alliance_member().guild_id().find(guild_id).map(|m| m.alliance_id)
+}
+
+pub fn alliance_of_player(ctx: &ReducerContext, player_id: u32) -> Option<u64> {
+    guild_of(ctx, player_id).and_then(|g| alliance_of_guild(ctx, g))
+}
+
+pub fn same_alliance_guilds(ctx: &ReducerContext, a: u64, b: u64) -> bool {
+    matches!((alliance_of_guild(ctx, a), alliance_of_guild(ctx, b)), (Some(x), Some(y)) if x == y)
+}
+
+/// Pacto de não agressão: membros de guildas aliadas nunca se atacam por flag/guerra
+pub fn are_allied(ctx: &ReducerContext, player_a: u32, player_b: u32) -> bool {
+    match (guild_of(ctx, player_a), guild_of(ctx, player_b)) {
+        (Some(a), Some(b)) if a != b => same_alliance_guilds(ctx, a, b),
+        _ => false,
+    }
+}
+
+/// Aliança liderada pela guilda do remetente (erro se não for o líder dela)
+fn require_alliance_leader(ctx: &ReducerContext) -> Result<(u64, Alliance), String> {
+    let (_, guild) = require_guild_leader(ctx)?;
+    let alliance = alliance_of_guild(ctx, guild.id)
+        .and_then(|id| ctx.db.alliance().id().find(id))
+        .ok_or("Your guild is not in an alliance")?;
+    if alliance.leader_guild_id != guild.id {
+        return Err("Only the alliance leader can do that".to_string());
+    }
+    Ok((guild.id, alliance))
+}
+
+fn remove_guild(ctx: &ReducerContext, guild_id: u64) {
+    let Some(alliance_id) = alliance_of_guild(ctx, guild_id) else { return };
+    ctx.db.alliance_member().guild_id().delete(guild_id);
+
+    let remaining: Vec<AllianceMember> = ctx.db.alliance_member().alliance_id().filter(alliance_id).collect();
+    let Some(mut alliance) = ctx.db.alliance().id().find(alliance_id) else { return };
+    if remaining.len() > 1 {
+        // Liderança passa para a guilda mais antiga da aliança
+        if alliance.leader_guild_id == guild_id {
+            if let Some(oldest) = remaining.iter().min_by_key(|m| m.joined_at) {
+                alliance.leader_guild_id = oldest.guild_id;
+                ctx.db.alliance().id().update(alliance);
+            }
+        }
+        return;
+    }
+
+    // Aliança com uma guilda só não faz sentido: dissolve
+    for member in remaining {
+        ctx.db.alliance_member().guild_id().delete(member.guild_id);
+    }
+    let invites: Vec<u64> = ctx.db.alliance_invite().iter().filter(|i| i.alliance_id == alliance_id).map(|i| i.id).collect();
+    for id in invites {
+        ctx.db.alliance_invite().id().delete(id);
+    }
+    ctx.db.alliance().id().delete(alliance_id);
+    log::info!("🤝 Alliance {} dissolved", alliance_id);
+}
+
+/// Guilda dissolvida sai da aliança
+pub fn on_guild_disbanded(ctx: &ReducerContext, guild_id: u64) {
+    remove_guild(ctx, guild_id);
+    let invites: Vec<u64> = ctx.db.alliance_invite().guild_id().filter(guild_id).map(|i| i.id).collect();
+    for id in invites {
+        ctx.db.alliance_invite().id().delete(id);
+    }
+}
+
+#[reducer]
+pub fn create_alliance(ctx: &ReducerContext, name: String) -> Result<(), String> {
+    let (_, guild) = require_guild_leader(ctx)?;
+    let name = name.trim().to_string();
+    if name.len() < 3 || name.len() > 32 {
+        return Err("Alliance name must have 3 to 32 characters".to_string());
+    }
+    if alliance_of_guild(ctx, guild.id).is_some() {
+        return Err("Your guild is already in an alliance".to_string());
+    }
+    if ctx.db.alliance().name().find(name.clone()).is_some() {
+        return Err("Alliance name already taken".to_string());
+    }
+
+    let alliance = ctx.db.alliance().insert(Alliance { id: 0, name, leader_guild_id: guild.id, created_at: ctx.timestamp });
+    ctx.db.alliance_member().insert(AllianceMember { guild_id: guild.id, alliance_id: alliance.id, joined_at: ctx.timestamp });
+    log::info!("🤝 Alliance '{}' ({}) founded by guild {}", alliance.name, alliance.id, guild.id);
+    Ok(())
+}
+
+#[reducer]
+pub fn invite_guild_to_alliance(ctx: &ReducerContext, guild_id: u64) -> Result<(), String> {
+    let (_, alliance) = require_alliance_leader(ctx)?;
+    ctx.db.guild().id().find(guild_id).ok_or("Guild not found")?;
+    if alliance_of_guild(ctx, guild_id).is_some() {
+        return Err("That guild is already in an alliance".to_string());
+    }
+    if ctx.db.alliance_invite().guild_id().filter(guild_id).any(|i| i.alliance_id == alliance.id) {
+        return Ok(());
+    }
+    ctx.db.alliance_invite().insert(AllianceInvite { id: 0, guild_id, alliance_id: alliance.id, invited_at: ctx.timestamp });
+    Ok(())
+}
+
+#[reducer]
+pub fn accept_alliance_invite(ctx: &ReducerContext, alliance_id: u64) -> Result<(), String> {
+    let (_, guild) = require_guild_leader(ctx)?;
+    let invite = ctx.db.alliance_invite().guild_id().filter(guild.id)
+        .find(|i| i.alliance_id == alliance_id)
+        .ok_or("No invite from that alliance")?;
+    ctx.db.alliance_invite().id().delete(invite.id);
+
+    if alliance_of_guild(ctx, guild.id).is_some() {
+        return Err("Your guild is already in an alliance".to_string());
+    }
+    ctx.db.alliance().id().find(alliance_id).ok_or("Alliance no longer exists")?;
+    if ctx.db.alliance_member().alliance_id().filter(alliance_id).count() >= MAX_ALLIANCE_GUILDS {
+        return Err("Alliance is full".to_string());
+    }
+    if crate::guild_war::at_war_with_alliance(ctx, guild.id, alliance_id) {
+        return Err("Cannot join an alliance with a guild you are at war with".to_string());
+    }
+
+    ctx.db.alliance_member().insert(AllianceMember { guild_id: guild.id, alliance_id, joined_at: ctx.timestamp });
+    log::info!("🤝 Guild {} joined alliance {}", guild.id, alliance_id);
+    Ok(())
+}
+
+#[reducer]
+pub fn leave_alliance(ctx: &ReducerContext) -> Result<(), String> {
+    let (_, guild) = require_guild_leader(ctx)?;
+    if alliance_of_guild(ctx, guild.id).is_none() {
+        return Err("Your guild is not in an alliance".to_string());
+    }
+    remove_guild(ctx, guild.id);
+    Ok(())
+}
+
+#[reducer]
+pub fn kick_guild_from_alliance(ctx: &ReducerContext, guild_id: u64) -> Result<(), String> {
+    let (leader_guild_id, alliance) = require_alliance_leader(ctx)?;
+    if guild_id == leader_guild_id {
+        return Err("Use leave_alliance to leave your own alliance".to_string());
+    }
+    if alliance_of_guild(ctx, guild_id) != Some(alliance.id) {
+        return Err("That guild is not in your alliance".to_string());
+    }
+    remove_guild(ctx, guild_id);
+    Ok(())
+}
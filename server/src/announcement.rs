@@ -0,0 +1,24 @@
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+
+/// Mensagens para todos os clientes (banner/chat do sistema)
+#[table(name = server_announcement, public)]
+#[derive(Clone)]
+pub struct ServerAnnouncement {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub category: String, // Ex: "world_boss", "invasion"
+    pub message: String,
+    pub created_at: Timestamp,
+}
+
+/// Publica o anúncio e retorna o id gerado
+pub fn announce(ctx: &ReducerContext, category: &str, message: String) -> u64 {
+    log::info!("📢 [{}] {}", category, message);
+    ctx.db.server_announcement().insert(ServerAnnouncement {
+        id: 0,
+        category: category.to_string(),
+        message,
+        created_at: ctx.timestamp,
+    }).id
+}
@@ -0,0 +1,278 @@
+use crate::admin::require_admin;
+use crate::kill_credit::KillCredit;
+use crate::map::{create_map_instance, destroy_map_instance, get_spawn_point, relocate_player};
+use crate::party::sender_player;
+use crate::player;
+use spacetimedb::{reducer, table, ReducerContext, SpacetimeType, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+pub const ARENA_MATCH_SECS: u64 = 300;
+
+pub const TEAM_A: u8 = 0;
+pub const TEAM_B: u8 = 1;
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct ArenaParticipant {
+    pub player_id: u32,
+    pub team: u8,
+    // Para onde volta ao fim da partida
+    pub return_map_id: String,
+    pub return_x: f32,
+    pub return_y: f32,
+    pub kills: u32,
+    pub assists: u32,
+}
+
+/// Partida de arena em uma instância própria (`map_key` = "<template>@arena<id>")
+#[table(name = arena_match, public)]
+#[derive(Clone)]
+pub struct ArenaMatch {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[unique]
+    pub map_key: String,
+    pub template_name: String,
+    pub participants: Vec<ArenaParticipant>,
+    /// Partidas ranqueadas alteram o rating dos participantes
+    pub ranked: bool,
+    pub started_at: Timestamp,
+    pub ends_at: Timestamp,
+}
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct ParticipantHealth {
+    pub player_id: u32,
+    pub team: u8,
+    pub health: f32,
+    pub max_health: f32,
+    pub is_downed: bool,
+    pub kills: u32,
+    pub assists: u32,
+}
+
+/// Estado ao vivo da partida (placar, tempo restante, vida dos participantes),
+/// atualizado pelo tick para players e espectadores
+#[table(name = match_state, public)]
+#[derive(Clone)]
+pub struct MatchState {
+    #[primary_key]
+    pub match_id: u64,
+    pub map_key: String,
+    pub score_a: u32,
+    pub score_b: u32,
+    pub remaining_secs: u32,
+    pub participants: Vec<ParticipantHealth>,
+    pub updated_at: Timestamp,
+}
+
+/// Espectador de uma partida. Os clientes usam esta linha para assinar as tabelas
+/// da instância (players, projéteis) filtradas pelo `map_key` sem entrar no mapa.
+#[table(name = match_spectator, public)]
+#[derive(Clone)]
+pub struct MatchSpectator {
+    #[primary_key]
+    pub player_id: u32,
+    #[index(btree)]
+    pub match_id: u64,
+    pub map_key: String,
+    pub started_at: Timestamp,
+}
+
+fn match_for_map(ctx: &ReducerContext, map_id: &str) -> Option<ArenaMatch> {
+    ctx.db.arena_match().map_key().find(map_id.to_string())
+}
+
+fn team_of(arena: &ArenaMatch, player_id: u32) -> Option<u8> {
+    arena.participants.iter().find(|p| p.player_id == player_id).map(|p| p.team)
+}
+
+/// Dois players em times opostos da mesma partida podem se atacar
+pub fn are_opponents(ctx: &ReducerContext, attacker_id: u32, target_id: u32) -> bool {
+    let Some(target) = ctx.db.player().id().find(target_id) else {
+        return false;
+    };
+    match_for_map(ctx, &target.current_map_id).is_some_and(|arena| {
+        matches!((team_of(&arena, attacker_id), team_of(&arena, target_id)), (Some(a), Some(b)) if a != b)
+    })
+}
+
+/// Participante derrubado: ponto para o time adversário
+pub fn on_player_downed(ctx: &ReducerContext, player: &crate::Player, credit: &KillCredit) {
+    let Some(mut arena) = match_for_map(ctx, &player.current_map_id) else { return };
+    let Some(team) = team_of(&arena, player.id) else { return };
+
+    // Golpe final e assistências só contam para adversários da vítima
+    for participant in arena.participants.iter_mut().filter(|p| p.team != team) {
+        if credit.killer_id == Some(participant.player_id) {
+            participant.kills += 1;
+        }
+        if credit.assist_ids.contains(&participant.player_id) {
+            participant.assists += 1;
+        }
+    }
+    ctx.db.arena_match().id().update(arena.clone());
+
+    if let Some(mut state) = ctx.db.match_state().match_id().find(arena.id) {
+        if team == TEAM_A {
+            state.score_b += 1;
+        } else {
+            state.score_a += 1;
+        }
+        ctx.db.match_state().match_id().update(state);
+    }
+}
+
+#[reducer]
+pub fn start_arena_match(ctx: &ReducerContext, template_name: String, team_a: Vec<u32>, team_b: Vec<u32>, ranked: bool) -> Result<(), String> {
+    require_admin(ctx)?;
+    create_arena_match(ctx, &template_name, &team_a, &team_b, ranked)?;
+    Ok(())
+}
+
+/// Cria a instância da partida e leva os dois times para dentro
+pub fn create_arena_match(ctx: &ReducerContext, template_name: &str, team_a: &[u32], team_b: &[u32], ranked: bool) -> Result<ArenaMatch, String> {
+    if team_a.is_empty() || team_b.is_empty() {
+        return Err("Both teams need players".to_string());
+    }
+    let mut participants = Vec::new();
+    for (team, ids) in [(TEAM_A, team_a), (TEAM_B, team_b)] {
+        for player_id in ids {
+            let p = ctx.db.player().id().find(*player_id).ok_or_else(|| format!("Player {} not found", player_id))?;
+            if match_for_map(ctx, &p.current_map_id).is_some() || participants.iter().any(|a: &ArenaParticipant| a.player_id == p.id) {
+                return Err(format!("Player {} is already in a match", p.id));
+            }
+            participants.push(ArenaParticipant {
+                player_id: p.id,
+                team,
+                return_map_id: p.current_map_id.clone(),
+                return_x: p.position_x,
+                return_y: p.position_y,
+                kills: 0,
+                assists: 0,
+            });
+        }
+    }
+
+    let arena = ctx.db.arena_match().insert(ArenaMatch {
+        id: 0,
+        map_key: String::new(),
+        template_name: template_name.to_string(),
+        participants: participants.clone(),
+        ranked,
+        started_at: ctx.timestamp,
+        ends_at: ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(ARENA_MATCH_SECS)),
+    });
+    let map_key = format!("{}@arena{}", template_name, arena.id);
+    create_map_instance(ctx, &map_key, template_name)?;
+    let mut arena = arena;
+    arena.map_key = map_key.clone();
+    ctx.db.arena_match().id().update(arena.clone());
+
+    let (spawn_x, spawn_y) = get_spawn_point(ctx, &map_key);
+    for participant in &participants {
+        if let Some(p) = ctx.db.player().id().find(participant.player_id) {
+            relocate_player(ctx, &p, &map_key, spawn_x, spawn_y)?;
+        }
+    }
+    ctx.db.match_state().insert(MatchState {
+        match_id: arena.id,
+        map_key: map_key.clone(),
+        score_a: 0,
+        score_b: 0,
+        remaining_secs: ARENA_MATCH_SECS as u32,
+        participants: Vec::new(),
+        updated_at: ctx.timestamp,
+    });
+
+    crate::replay::start_replay(ctx, crate::replay::REPLAY_ARENA, arena.id, &map_key, None);
+    log::info!("🏟️ Arena match {} started in {} ({} vs {})", arena.id, map_key, team_a.len(), team_b.len());
+    Ok(arena)
+}
+
+#[reducer]
+pub fn spectate_match(ctx: &ReducerContext, match_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let arena = ctx.db.arena_match().id().find(match_id).ok_or("Match not found")?;
+    if team_of(&arena, player.id).is_some() {
+        return Err("Participants cannot spectate their own match".to_string());
+    }
+
+    let spectator = MatchSpectator {
+        player_id: player.id,
+        match_id,
+        map_key: arena.map_key,
+        started_at: ctx.timestamp,
+    };
+    if ctx.db.match_spectator().player_id().find(player.id).is_some() {
+        ctx.db.match_spectator().player_id().update(spectator);
+    } else {
+        ctx.db.match_spectator().insert(spectator);
+    }
+    log::info!("👀 Player {} is spectating match {}", player.id, match_id);
+    Ok(())
+}
+
+#[reducer]
+pub fn stop_spectating(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    ctx.db.match_spectator().player_id().delete(player.id);
+    Ok(())
+}
+
+/// Checagem por tick: atualiza o estado ao vivo e encerra partidas expiradas
+pub fn process_arena_matches(ctx: &ReducerContext) {
+    for arena in ctx.db.arena_match().iter().collect::<Vec<_>>() {
+        if arena.ends_at <= ctx.timestamp {
+            if let Err(e) = finish_match(ctx, &arena) {
+                log::error!("Failed to finish arena match {}: {}", arena.id, e);
+            }
+            continue;
+        }
+        let Some(mut state) = ctx.db.match_state().match_id().find(arena.id) else { continue };
+        state.remaining_secs = arena.ends_at.duration_since(ctx.timestamp).unwrap_or_default().as_secs() as u32;
+        state.participants = arena.participants.iter()
+            .filter_map(|a| ctx.db.player().id().find(a.player_id).map(|p| ParticipantHealth {
+                player_id: p.id,
+                team: a.team,
+                health: p.health,
+                max_health: p.max_health,
+                is_downed: p.is_downed,
+                kills: a.kills,
+                assists: a.assists,
+            }))
+            .collect();
+        state.updated_at = ctx.timestamp;
+        ctx.db.match_state().match_id().update(state);
+    }
+}
+
+fn finish_match(ctx: &ReducerContext, arena: &ArenaMatch) -> Result<(), String> {
+    crate::replay::stop_replay(ctx, crate::replay::REPLAY_ARENA, arena.id);
+    if let Some(state) = ctx.db.match_state().match_id().find(arena.id) {
+        log::info!("🏟️ Arena match {} finished {} x {}", arena.id, state.score_a, state.score_b);
+        let score_a = match state.score_a.cmp(&state.score_b) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0,
+        };
+        if arena.ranked {
+            let team = |t: u8| -> Vec<u32> { arena.participants.iter().filter(|p| p.team == t).map(|p| p.player_id).collect() };
+            crate::rating::record_match_result(ctx, &team(TEAM_A), &team(TEAM_B), score_a);
+        }
+        crate::tournament::on_arena_match_finished(ctx, arena.id, score_a);
+        ctx.db.match_state().match_id().delete(arena.id);
+    }
+    for participant in &arena.participants {
+        if let Some(p) = ctx.db.player().id().find(participant.player_id).filter(|p| p.current_map_id == arena.map_key) {
+            relocate_player(ctx, &p, &participant.return_map_id, participant.return_x, participant.return_y)?;
+        }
+    }
+    let spectators: Vec<u32> = ctx.db.match_spectator().match_id().filter(arena.id).map(|s| s.player_id).collect();
+    for player_id in spectators {
+        ctx.db.match_spectator().player_id().delete(player_id);
+    }
+    destroy_map_instance(ctx, &arena.map_key);
+    ctx.db.arena_match().id().delete(arena.id);
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use spacetimedb::{table, Identity, ReducerContext, Table, Timestamp};
+
+/// Registro de ações administrativas e de manutenção do servidor
+#[table(name = audit_log)]
+#[derive(Clone)]
+pub struct AuditLog {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub category: String, // "sanitation", "admin", ...
+    pub actor: Identity,
+    pub message: String,
+    pub created_at: Timestamp,
+}
+
+pub fn record_audit(ctx: &ReducerContext, category: &str, message: String) {
+    log::info!("📝 AUDIT [{}] {}", category, message);
+    ctx.db.audit_log().insert(AuditLog {
+        id: 0,
+        category: category.to_string(),
+        actor: ctx.sender,
+        message,
+        created_at: ctx.timestamp,
+    });
+}
@@ -0,0 +1,93 @@
+use crate::inventory::player_equipment;
+use crate::party::{party_member_ids, party_of};
+use crate::status_effect::{apply_grouped_status_effect, status_effect};
+use crate::{player, Player};
+use spacetimedb::{ReducerContext, Table};
+use std::collections::HashMap;
+
+/// Acessório que irradia um buff passivo para o grupo ao redor de quem o usa
+pub struct AuraDef {
+    pub item_id: &'static str,
+    pub effect_id: &'static str,
+    pub magnitude: f32,
+    pub radius: f32,
+}
+
+pub const AURAS: &[AuraDef] = &[
+    AuraDef { item_id: "war_banner", effect_id: crate::status_effect::EFFECT_DAMAGE_BOOST, magnitude: 0.1, radius: 160.0 },
+    AuraDef { item_id: "warding_totem", effect_id: crate::status_effect::EFFECT_DAMAGE_REDUCTION, magnitude: 0.1, radius: 160.0 },
+];
+
+// Fonte "aura:<portador>:<item>"; o grupo de acúmulo é por item, então duas
+// bandeiras iguais no mesmo grupo não somam
+const AURA_SOURCE_PREFIX: &str = "aura:";
+
+pub fn aura_for_item(item_id: &str) -> Option<&'static AuraDef> {
+    AURAS.iter().find(|a| a.item_id == item_id)
+}
+
+fn aura_source(carrier_id: u32, aura: &AuraDef) -> String {
+    format!("{}{}:{}", AURA_SOURCE_PREFIX, carrier_id, aura.item_id)
+}
+
+fn aura_group(aura: &AuraDef) -> String {
+    format!("{}{}", AURA_SOURCE_PREFIX, aura.item_id)
+}
+
+fn within(a: &Player, b: &Player, radius: f32) -> bool {
+    a.current_map_id == b.current_map_id
+        && ((a.position_x - b.position_x).powi(2) + (a.position_y - b.position_y).powi(2)).sqrt() <= radius
+}
+
+/// Checagem por tick: aplica a aura em quem entrou no raio e remove de quem
+/// saiu, caiu ou deixou o grupo. Retorna quantos portadores foram avaliados.
+pub fn process_party_auras(ctx: &ReducerContext) -> u64 {
+    // Efeito desejado por (player, fonte)
+    let mut desired: HashMap<(u32, String), &'static AuraDef> = HashMap::new();
+    let mut carriers = 0;
+    for equipment in ctx.db.player_equipment().iter() {
+        let Some(aura) = aura_for_item(&equipment.accessory) else {
+            continue;
+        };
+        let Some(carrier) = ctx.db.player().id().find(equipment.player_id).filter(|p| !p.is_downed) else {
+            continue;
+        };
+        carriers += 1;
+        let members = party_of(ctx, carrier.id)
+            .map(|party_id| party_member_ids(ctx, party_id))
+            .unwrap_or_else(|| vec![carrier.id]);
+        for member_id in members {
+            let in_range = ctx.db.player().id().find(member_id)
+                .is_some_and(|m| !m.is_downed && within(&carrier, &m, aura.radius));
+            if in_range {
+                desired.insert((member_id, aura_source(carrier.id, aura)), aura);
+            }
+        }
+    }
+
+    let current: Vec<(u64, u32, String)> = ctx.db.status_effect().iter()
+        .filter(|e| e.source.starts_with(AURA_SOURCE_PREFIX))
+        .map(|e| (e.id, e.player_id, e.source))
+        .collect();
+    for (id, player_id, source) in current {
+        if desired.remove(&(player_id, source)).is_none() {
+            ctx.db.status_effect().id().delete(id);
+        }
+    }
+    for ((player_id, source), aura) in desired {
+        apply_grouped_status_effect(ctx, player_id, aura.effect_id, aura.magnitude, &source, None, &aura_group(aura));
+    }
+    carriers
+}
+
+/// Quem cai perde as auras recebidas e deixa de irradiar a sua na hora
+pub fn on_player_downed(ctx: &ReducerContext, player_id: u32) {
+    let carried = format!("{}{}:", AURA_SOURCE_PREFIX, player_id);
+    let ids: Vec<u64> = ctx.db.status_effect().iter()
+        .filter(|e| (e.player_id == player_id && e.source.starts_with(AURA_SOURCE_PREFIX)) || e.source.starts_with(&carried))
+        .map(|e| e.id)
+        .collect();
+    for id in ids {
+        ctx.db.status_effect().id().delete(id);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::combat::{apply_damage_to_enemy, enemy, generate_projectile_id, Enemy, Projectile};
+use crate::player;
+use spacetimedb::ReducerContext;
+
+/// Projétil arremessado em arco (bombas, morteiros). Voa por cima dos alvos
+/// e só causa dano em área ao tocar o chão.
+pub struct LobDef {
+    pub kind: &'static str,
+    /// Velocidade no plano do mapa (px/s)
+    pub speed: f32,
+    /// Aceleração vertical (px/s²); a altura só existe para o arco
+    pub gravity: f32,
+    /// Alvos mais distantes são trazidos para este alcance
+    pub max_range: f32,
+    pub damage: f32,
+    pub aoe_radius: f32,
+}
+
+pub const LOB_DEFS: &[LobDef] = &[
+    LobDef { kind: "Bomb", speed: 180.0, gravity: 600.0, max_range: 200.0, damage: 45.0, aoe_radius: 48.0 },
+    LobDef { kind: "MortarShell", speed: 140.0, gravity: 500.0, max_range: 320.0, damage: 30.0, aoe_radius: 40.0 },
+];
+
+// Folga no tempo de vida para o pouso acontecer antes de o projétil expirar
+const LOB_TTL_MARGIN: f32 = 0.5;
+const MIN_FLIGHT_TIME: f32 = 0.1;
+/// Quem está na área do pouso é arremessado para longe do centro
+const BLAST_KNOCKBACK: f32 = 16.0;
+
+pub fn lob_def(kind: &str) -> Option<&'static LobDef> {
+    LOB_DEFS.iter().find(|d| d.kind == kind)
+}
+
+/// Projétil que cada tipo de inimigo de artilharia dispara
+pub fn enemy_lob_kind(enemy_type: &str) -> Option<&'static str> {
+    match enemy_type {
+        "Mortar" => Some("MortarShell"),
+        _ => None,
+    }
+}
+
+/// Monta um projétil em arco que pousa no alvo (limitado ao alcance máximo).
+/// A velocidade vertical é escolhida para a altura voltar a zero no ponto de pouso.
+pub fn launch_lob(def: &LobDef, owner_id: u32, map_id: &str, (origin_x, origin_y): (f32, f32), (target_x, target_y): (f32, f32)) -> Projectile {
+    let dx = target_x - origin_x;
+    let dy = target_y - origin_y;
+    let distance = (dx * dx + dy * dy).sqrt();
+    let range = distance.min(def.max_range);
+    let (dir_x, dir_y) = if distance > 0.0 { (dx / distance, dy / distance) } else { (0.0, 0.0) };
+    let flight_time = (range / def.speed).max(MIN_FLIGHT_TIME);
+
+    Projectile {
+        id: generate_projectile_id(),
+        owner_id,
+        position_x: origin_x,
+        position_y: origin_y,
+        velocity_x: dir_x * def.speed,
+        velocity_y: dir_y * def.speed,
+        damage: def.damage,
+        time_to_live: flight_time + LOB_TTL_MARGIN,
+        projectile_type: def.kind.to_string(),
+        map_id: map_id.to_string(),
+        is_active: true,
+        height: 0.0,
+        velocity_z: def.gravity * flight_time / 2.0,
+        gravity: def.gravity,
+        aoe_radius: def.aoe_radius,
+        bounces_left: 0,
+        bounce_falloff: 1.0,
+        hit_ids: Vec::new(),
+    }
+}
+
+pub fn is_lobbed(projectile: &Projectile) -> bool {
+    projectile.gravity > 0.0
+}
+
+/// Integra um passo do arco. Retorna `true` quando o projétil toca o chão.
+pub fn step_lob(projectile: &mut Projectile, step_time: f32) -> bool {
+    projectile.position_x += projectile.velocity_x * step_time;
+    projectile.position_y += projectile.velocity_y * step_time;
+    projectile.velocity_z -= projectile.gravity * step_time;
+    projectile.height += projectile.velocity_z * step_time;
+    if projectile.height > 0.0 {
+        return false;
+    }
+    projectile.height = 0.0;
+    true
+}
+
+/// Dano em área no ponto de pouso. Projéteis de inimigos só atingem players;
+/// os de players atingem inimigos e, com PvP liberado, outros players.
+pub fn resolve_landing(ctx: &ReducerContext, projectile: &Projectile) -> Result<(), Box<dyn std::error::Error>> {
+    let in_blast = |x: f32, y: f32| {
+        let dx = x - projectile.position_x;
+        let dy = y - projectile.position_y;
+        (dx * dx + dy * dy).sqrt() <= projectile.aoe_radius
+    };
+    let from_player = crate::character::attacking_player(ctx, projectile.owner_id).is_some();
+
+    let mut targets: Vec<(u32, (f32, f32))> = Vec::new();
+    if from_player {
+        targets.extend(ctx.db.enemy().map_id().filter(&projectile.map_id)
+            .filter(|e| e.is_active && in_blast(e.position_x, e.position_y))
+            .map(|e| (e.id, (e.position_x, e.position_y))));
+    }
+    targets.extend(ctx.db.player().current_map_id().filter(&projectile.map_id)
+        .filter(|p| p.id != projectile.owner_id && !p.is_downed && in_blast(p.position_x, p.position_y))
+        .map(|p| (p.id, (p.position_x, p.position_y))));
+
+    log::info!("💣 {} {} landed at ({}, {}) hitting {} targets",
+               projectile.projectile_type, projectile.id, projectile.position_x, projectile.position_y, targets.len());
+    for (target_id, position) in targets {
+        apply_damage_to_enemy(ctx, target_id, projectile.damage, projectile.owner_id, projectile.projectile_type.clone())?;
+        // Bosses ancorados e players com bulwark ficam no lugar
+        crate::forced_movement::knockback(ctx, target_id, projectile.position_x, projectile.position_y, position, BLAST_KNOCKBACK);
+    }
+    Ok(())
+}
+
+/// Projétil em cadeia (raio): depois de acertar, salta para o inimigo mais
+/// próximo ainda não atingido, perdendo uma fração do dano a cada salto.
+pub struct ChainDef {
+    pub kind: &'static str,
+    pub speed: f32,
+    pub damage: f32,
+    pub time_to_live: f32,
+    pub max_bounces: u32,
+    /// Multiplicador de dano aplicado a cada salto
+    pub falloff: f32,
+    /// Distância máxima entre o alvo atingido e o próximo
+    pub bounce_radius: f32,
+}
+
+pub const CHAIN_DEFS: &[ChainDef] = &[
+    ChainDef { kind: "ChainBolt", speed: 350.0, damage: 18.0, time_to_live: 3.0, max_bounces: 3, falloff: 0.7, bounce_radius: 150.0 },
+];
+
+pub fn chain_def(kind: &str) -> Option<&'static ChainDef> {
+    CHAIN_DEFS.iter().find(|d| d.kind == kind)
+}
+
+pub fn launch_chain(def: &ChainDef, owner_id: u32, map_id: &str, (origin_x, origin_y): (f32, f32), (dir_x, dir_y): (f32, f32)) -> Projectile {
+    Projectile {
+        id: generate_projectile_id(),
+        owner_id,
+        position_x: origin_x,
+        position_y: origin_y,
+        velocity_x: dir_x * def.speed,
+        velocity_y: dir_y * def.speed,
+        damage: def.damage,
+        time_to_live: def.time_to_live,
+        projectile_type: def.kind.to_string(),
+        map_id: map_id.to_string(),
+        is_active: true,
+        height: 0.0,
+        velocity_z: 0.0,
+        gravity: 0.0,
+        aoe_radius: 0.0,
+        bounces_left: def.max_bounces,
+        bounce_falloff: def.falloff,
+        hit_ids: Vec::new(),
+    }
+}
+
+/// Registra o alvo atingido e, se ainda houver saltos e alguém por perto,
+/// redireciona o projétil a partir do alvo. Retorna `false` quando a cadeia acaba.
+pub fn try_chain(ctx: &ReducerContext, projectile: &mut Projectile, hit: &Enemy) -> bool {
+    projectile.hit_ids.push(hit.id);
+    if projectile.bounces_left == 0 {
+        return false;
+    }
+    let Some(def) = chain_def(&projectile.projectile_type) else {
+        return false;
+    };
+
+    let distance_to = |e: &Enemy| ((e.position_x - hit.position_x).powi(2) + (e.position_y - hit.position_y).powi(2)).sqrt();
+    let next = ctx.db.enemy().map_id().filter(&projectile.map_id)
+        .filter(|e| e.is_active && !projectile.hit_ids.contains(&e.id) && distance_to(e) <= def.bounce_radius)
+        .min_by(|a, b| distance_to(a).total_cmp(&distance_to(b)));
+    let Some(next) = next else {
+        return false;
+    };
+
+    let dx = next.position_x - hit.position_x;
+    let dy = next.position_y - hit.position_y;
+    let distance = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+    let speed = (projectile.velocity_x.powi(2) + projectile.velocity_y.powi(2)).sqrt();
+    projectile.position_x = hit.position_x;
+    projectile.position_y = hit.position_y;
+    projectile.velocity_x = dx / distance * speed;
+    projectile.velocity_y = dy / distance * speed;
+    projectile.damage *= projectile.bounce_falloff;
+    projectile.bounces_left -= 1;
+    log::info!("⚡ {} {} chains from enemy {} to {} ({} bounces left)",
+               projectile.projectile_type, projectile.id, hit.id, next.id, projectile.bounces_left);
+    true
+}
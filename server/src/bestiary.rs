@@ -0,0 +1,69 @@
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+
+/// Abates de um arquétipo necessários para revelar fraquezas/resistências
+pub const BESTIARY_UNLOCK_KILLS: u32 = 10;
+
+/// Fraquezas e resistências por arquétipo (informativo: o dano não muda)
+const ENEMY_TRAITS: &[(&str, &[&str], &[&str])] = &[
+    ("Goblin", &["Axe"], &["Bow"]),
+    ("Orc", &["Bow"], &["Sword"]),
+    ("Troll", &["Axe"], &["Sword"]),
+    ("DungeonBoss", &["Bow"], &["Axe"]),
+    ("WorldBoss", &["Sword"], &["Bow"]),
+];
+
+/// Conhecimento do player sobre cada arquétipo. `weaknesses`/`resistances`
+/// só são preenchidas depois de `BESTIARY_UNLOCK_KILLS` abates.
+#[table(name = bestiary_entry, public)]
+#[derive(Clone)]
+pub struct BestiaryEntry {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub enemy_type: String,
+    pub kills: u32,
+    pub weaknesses: Vec<String>,
+    pub resistances: Vec<String>,
+    pub first_kill_at: Timestamp,
+    pub unlocked_at: Option<Timestamp>,
+}
+
+fn traits_of(enemy_type: &str) -> (Vec<String>, Vec<String>) {
+    ENEMY_TRAITS.iter()
+        .find(|(t, _, _)| *t == enemy_type)
+        .map(|(_, weak, resist)| (
+            weak.iter().map(|s| s.to_string()).collect(),
+            resist.iter().map(|s| s.to_string()).collect(),
+        ))
+        .unwrap_or_default()
+}
+
+/// Conta o abate e revela as características ao atingir o limite
+pub fn record_kill(ctx: &ReducerContext, player_id: u32, enemy_type: &str) {
+    let existing = ctx.db.bestiary_entry().player_id().filter(player_id).find(|e| e.enemy_type == enemy_type);
+    let mut entry = existing.unwrap_or(BestiaryEntry {
+        id: 0,
+        player_id,
+        enemy_type: enemy_type.to_string(),
+        kills: 0,
+        weaknesses: Vec::new(),
+        resistances: Vec::new(),
+        first_kill_at: ctx.timestamp,
+        unlocked_at: None,
+    });
+
+    entry.kills += 1;
+    if entry.unlocked_at.is_none() && entry.kills >= BESTIARY_UNLOCK_KILLS {
+        (entry.weaknesses, entry.resistances) = traits_of(enemy_type);
+        entry.unlocked_at = Some(ctx.timestamp);
+        log::info!("📖 Player {} unlocked bestiary entry for {}", player_id, enemy_type);
+    }
+
+    if entry.id == 0 {
+        ctx.db.bestiary_entry().insert(entry);
+    } else {
+        ctx.db.bestiary_entry().id().update(entry);
+    }
+}
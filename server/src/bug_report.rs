@@ -0,0 +1,147 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use crate::client_compat::{client_error_event, client_info};
+use crate::cooldown::try_start_cooldown;
+use crate::external_event::{emit, EVENT_REPORT_FILED};
+use crate::party::sender_player;
+use spacetimedb::{reducer, table, Identity, ReducerContext, Table, Timestamp};
+
+/// Ações recentes guardadas por player para anexar aos reports
+const ACTION_TRAIL_SIZE: usize = 20;
+const MAX_CLIENT_ERRORS_IN_REPORT: usize = 10;
+const MIN_DESCRIPTION_LEN: usize = 10;
+const MAX_DESCRIPTION_LEN: usize = 2000;
+const REPORT_COOLDOWN_MS: u64 = 60 * 1000;
+const CATEGORY_REPORT: &str = "report";
+
+pub const REPORT_OPEN: &str = "open";
+pub const REPORT_TRIAGED: &str = "triaged";
+pub const REPORT_RESOLVED: &str = "resolved";
+pub const REPORT_WONT_FIX: &str = "wont_fix";
+pub const REPORT_DUPLICATE: &str = "duplicate";
+const REPORT_STATUSES: &[&str] = &[REPORT_OPEN, REPORT_TRIAGED, REPORT_RESOLVED, REPORT_WONT_FIX, REPORT_DUPLICATE];
+
+/// Últimas chamadas relevantes e violações (posição corrigida, erro tipado)
+/// de cada player. Janela curta: só serve de contexto para bug reports.
+#[table(name = action_trail)]
+#[derive(Clone)]
+pub struct ActionTrail {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub action: String,
+    pub is_violation: bool,
+    pub at: Timestamp,
+}
+
+/// Report enviado de dentro do jogo, com o contexto do momento. Só admins leem.
+#[table(name = bug_report)]
+#[derive(Clone)]
+pub struct BugReport {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub reporter_id: u32,
+    pub map_id: String,
+    pub position_x: f32,
+    pub position_y: f32,
+    pub client_version: Option<String>,
+    pub description: String,
+    /// "[v] ação" para violações, "ação" para chamadas normais; mais antigas primeiro
+    pub recent_actions: Vec<String>,
+    pub recent_client_errors: Vec<String>,
+    pub status: String,
+    pub assignee: Option<Identity>,
+    pub admin_note: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+/// Anota uma ação na trilha do player, descartando as mais antigas
+pub fn record_action(ctx: &ReducerContext, player_id: u32, action: String, is_violation: bool) {
+    ctx.db.action_trail().insert(ActionTrail { id: 0, player_id, action, is_violation, at: ctx.timestamp });
+    let mut ids: Vec<u64> = ctx.db.action_trail().player_id().filter(player_id).map(|a| a.id).collect();
+    if ids.len() > ACTION_TRAIL_SIZE {
+        ids.sort_unstable();
+        for id in &ids[..ids.len() - ACTION_TRAIL_SIZE] {
+            ctx.db.action_trail().id().delete(id);
+        }
+    }
+}
+
+#[reducer]
+pub fn submit_bug_report(ctx: &ReducerContext, description: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let description = description.trim().to_string();
+    if description.len() < MIN_DESCRIPTION_LEN || description.len() > MAX_DESCRIPTION_LEN {
+        return Err(format!("Description must have {}-{} characters", MIN_DESCRIPTION_LEN, MAX_DESCRIPTION_LEN));
+    }
+    try_start_cooldown(ctx, ctx.sender, CATEGORY_REPORT, "bug_report", REPORT_COOLDOWN_MS)?;
+
+    let mut trail: Vec<ActionTrail> = ctx.db.action_trail().player_id().filter(player.id).collect();
+    trail.sort_by_key(|a| a.id);
+    let mut errors: Vec<_> = ctx.db.client_error_event().identity().filter(player.identity).collect();
+    errors.sort_by_key(|e| std::cmp::Reverse(e.id));
+    errors.truncate(MAX_CLIENT_ERRORS_IN_REPORT);
+    errors.reverse();
+
+    let report = ctx.db.bug_report().insert(BugReport {
+        id: 0,
+        reporter_id: player.id,
+        map_id: player.current_map_id.clone(),
+        position_x: player.position_x,
+        position_y: player.position_y,
+        client_version: ctx.db.client_info().identity().find(player.identity).map(|c| c.client_version),
+        description,
+        recent_actions: trail.into_iter()
+            .map(|a| if a.is_violation { format!("[v] {}", a.action) } else { a.action })
+            .collect(),
+        recent_client_errors: errors.into_iter().map(|e| format!("{}: {}", e.error_code, e.message)).collect(),
+        status: REPORT_OPEN.to_string(),
+        assignee: None,
+        admin_note: String::new(),
+        created_at: ctx.timestamp,
+        updated_at: ctx.timestamp,
+    });
+    log::info!("🐞 Player {} filed bug report {} on '{}'", player.id, report.id, report.map_id);
+    emit(ctx, EVENT_REPORT_FILED, &[
+        ("report_id", report.id.to_string()),
+        ("player_id", player.id.to_string()),
+        ("map_id", report.map_id.clone()),
+    ]);
+    Ok(())
+}
+
+/// Assume o report (ou solta, com `assign = false`); um report aberto passa a triado
+#[reducer]
+pub fn assign_bug_report(ctx: &ReducerContext, report_id: u64, assign: bool) -> Result<(), String> {
+    require_admin(ctx)?;
+    let mut report = ctx.db.bug_report().id().find(report_id).ok_or("Bug report not found")?;
+    report.assignee = assign.then_some(ctx.sender);
+    if assign && report.status == REPORT_OPEN {
+        report.status = REPORT_TRIAGED.to_string();
+    }
+    report.updated_at = ctx.timestamp;
+    ctx.db.bug_report().id().update(report);
+    Ok(())
+}
+
+#[reducer]
+pub fn set_bug_report_status(ctx: &ReducerContext, report_id: u64, status: String, note: Option<String>) -> Result<(), String> {
+    require_admin(ctx)?;
+    if !REPORT_STATUSES.contains(&status.as_str()) {
+        return Err(format!("Unknown status '{}'", status));
+    }
+    let mut report = ctx.db.bug_report().id().find(report_id).ok_or("Bug report not found")?;
+    let previous = std::mem::replace(&mut report.status, status.clone());
+    if let Some(note) = note {
+        report.admin_note = note;
+    }
+    report.updated_at = ctx.timestamp;
+    ctx.db.bug_report().id().update(report);
+    record_audit(ctx, "bug_report", format!("Report {}: {} -> {}", report_id, previous, status));
+    Ok(())
+}
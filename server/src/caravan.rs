@@ -0,0 +1,239 @@
+use crate::combat::{allocate_enemy_id, enemy, spawn_enemy};
+use crate::inventory::{count_item, remove_item_from_inventory};
+use crate::map::{random_spawn_point, TILE_SIZE};
+use crate::party::sender_player;
+use crate::reputation::{add_reputation, FACTION_TAVERN_GUILD};
+use crate::reward::{grant_reward_bundle, RewardBundle};
+use crate::status_effect::{apply_status_effect, remove_effects_from_source, EFFECT_MOVEMENT_SLOW};
+use crate::{player, Player};
+use spacetimedb::rand::Rng;
+use spacetimedb::{reducer, table, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+pub const CARGO_ITEM: &str = "caravan_cargo";
+const CARGO_SOURCE: &str = "caravan_cargo";
+/// Carga volumosa: reduz a velocidade de quem carrega
+const CARGO_SLOW: f32 = 0.4;
+const CARGO_PICKUP_RANGE: f32 = TILE_SIZE * 2.0;
+
+/// Emboscadas: a cada intervalo há uma chance de inimigos aparecerem perto do carregador
+const AMBUSH_CHECK_SECS: u64 = 20;
+const AMBUSH_CHANCE: f64 = 0.25;
+const MAX_AMBUSHES: u32 = 2;
+const AMBUSH_SIZE: u32 = 2;
+const AMBUSH_RADIUS: f32 = 80.0;
+const AMBUSH_ENEMY_TYPE: &str = "Goblin";
+
+const CARAVAN_REPUTATION: i32 = 150;
+
+const DELIVERY_CARRYING: &str = "Carrying";
+const DELIVERY_DROPPED: &str = "Dropped";
+
+struct CaravanRoute {
+    route_id: &'static str,
+    origin_map: &'static str,
+    dest_map: &'static str,
+    time_limit_secs: u64,
+    reward_gold: u64,
+    /// Bônus se a carga nunca caiu no chão
+    intact_bonus_gold: u64,
+    reward_xp: u64,
+}
+
+const CARAVAN_ROUTES: &[CaravanRoute] = &[
+    CaravanRoute { route_id: "tavern_supplies", origin_map: "tavern_outside", dest_map: "tavern_inside", time_limit_secs: 300, reward_gold: 40, intact_bonus_gold: 20, reward_xp: 60 },
+    CaravanRoute { route_id: "ale_barrels", origin_map: "tavern_inside", dest_map: "tavern_outside", time_limit_secs: 300, reward_gold: 40, intact_bonus_gold: 20, reward_xp: 60 },
+];
+
+/// Entrega em andamento (uma por player)
+#[table(name = caravan_delivery, public)]
+#[derive(Clone)]
+pub struct CaravanDelivery {
+    #[primary_key]
+    pub player_id: u32,
+    pub route_id: String,
+    pub dest_map_id: String,
+    pub state: String,
+    pub started_at: Timestamp,
+    pub deadline: Timestamp,
+    pub intact: bool,
+    pub dropped_map_id: String,
+    pub dropped_x: f32,
+    pub dropped_y: f32,
+    pub ambushes: u32,
+    pub next_ambush_check_at: Timestamp,
+}
+
+fn route(route_id: &str) -> Option<&'static CaravanRoute> {
+    CARAVAN_ROUTES.iter().find(|r| r.route_id == route_id)
+}
+
+fn after(ctx: &ReducerContext, secs: u64) -> Timestamp {
+    ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(secs))
+}
+
+fn pick_up_cargo(ctx: &ReducerContext, player_id: u32) -> Result<(), String> {
+    crate::inventory::add_item_to_inventory(ctx, player_id, CARGO_ITEM.to_string(), 1).map_err(|e| e.to_string())?;
+    apply_status_effect(ctx, player_id, EFFECT_MOVEMENT_SLOW, CARGO_SLOW, CARGO_SOURCE, None);
+    Ok(())
+}
+
+fn discard_cargo(ctx: &ReducerContext, player_id: u32) {
+    if count_item(ctx, player_id, CARGO_ITEM) > 0 {
+        let _ = remove_item_from_inventory(ctx, player_id, CARGO_ITEM, 1);
+    }
+    remove_effects_from_source(ctx, player_id, CARGO_SOURCE);
+}
+
+#[reducer]
+pub fn accept_caravan_quest(ctx: &ReducerContext, route_id: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let route = route(&route_id).ok_or("Unknown caravan route")?;
+    if ctx.db.caravan_delivery().player_id().find(player.id).is_some() {
+        return Err("You are already delivering cargo".to_string());
+    }
+    if player.current_map_id != route.origin_map {
+        return Err(format!("This contract starts in {}", route.origin_map));
+    }
+
+    pick_up_cargo(ctx, player.id)?;
+    ctx.db.caravan_delivery().insert(CaravanDelivery {
+        player_id: player.id,
+        route_id: route.route_id.to_string(),
+        dest_map_id: route.dest_map.to_string(),
+        state: DELIVERY_CARRYING.to_string(),
+        started_at: ctx.timestamp,
+        deadline: after(ctx, route.time_limit_secs),
+        intact: true,
+        dropped_map_id: String::new(),
+        dropped_x: 0.0,
+        dropped_y: 0.0,
+        ambushes: 0,
+        next_ambush_check_at: after(ctx, AMBUSH_CHECK_SECS),
+    });
+    log::info!("📦 Player {} accepted caravan '{}' to {}", player.id, route.route_id, route.dest_map);
+    Ok(())
+}
+
+#[reducer]
+pub fn deliver_caravan_cargo(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    let delivery = ctx.db.caravan_delivery().player_id().find(player.id).ok_or("No cargo to deliver")?;
+    if delivery.state != DELIVERY_CARRYING || count_item(ctx, player.id, CARGO_ITEM) < 1 {
+        return Err("You are not carrying the cargo".to_string());
+    }
+    if player.current_map_id != delivery.dest_map_id {
+        return Err(format!("Deliver the cargo in {}", delivery.dest_map_id));
+    }
+    let route = route(&delivery.route_id).ok_or("Unknown caravan route")?;
+
+    discard_cargo(ctx, player.id);
+    ctx.db.caravan_delivery().player_id().delete(player.id);
+
+    let gold = route.reward_gold + if delivery.intact { route.intact_bonus_gold } else { 0 };
+    grant_reward_bundle(ctx, player.id, &RewardBundle::gold(gold).with_xp(route.reward_xp), "caravan")?;
+    add_reputation(ctx, player.id, FACTION_TAVERN_GUILD, CARAVAN_REPUTATION);
+    crate::story_flag::set_story_flag(ctx, player.id, &format!("caravan_delivered:{}", route.route_id));
+    log::info!("📦 Player {} delivered '{}' (intact: {}) for {} gold", player.id, route.route_id, delivery.intact, gold);
+    Ok(())
+}
+
+/// Recolhe a carga que caiu quando o player foi derrubado
+#[reducer]
+pub fn recover_caravan_cargo(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let mut delivery = ctx.db.caravan_delivery().player_id().find(player.id).ok_or("No caravan contract")?;
+    if delivery.state != DELIVERY_DROPPED {
+        return Err("Your cargo was not dropped".to_string());
+    }
+    if player.is_downed {
+        return Err("Cannot pick up cargo while downed".to_string());
+    }
+    let distance = ((player.position_x - delivery.dropped_x).powi(2) + (player.position_y - delivery.dropped_y).powi(2)).sqrt();
+    if player.current_map_id != delivery.dropped_map_id || distance > CARGO_PICKUP_RANGE {
+        return Err("Too far from the cargo".to_string());
+    }
+
+    pick_up_cargo(ctx, player.id)?;
+    delivery.state = DELIVERY_CARRYING.to_string();
+    ctx.db.caravan_delivery().player_id().update(delivery);
+    Ok(())
+}
+
+#[reducer]
+pub fn abandon_caravan_quest(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    ctx.db.caravan_delivery().player_id().find(player.id).ok_or("No caravan contract")?;
+    discard_cargo(ctx, player.id);
+    ctx.db.caravan_delivery().player_id().delete(player.id);
+    Ok(())
+}
+
+/// Ao ser derrubado, o carregador deixa a carga no chão (a entrega deixa de ser intacta)
+pub fn drop_cargo_on_death(ctx: &ReducerContext, player: &Player) {
+    let Some(mut delivery) = ctx.db.caravan_delivery().player_id().find(player.id) else { return };
+    if delivery.state != DELIVERY_CARRYING {
+        return;
+    }
+
+    discard_cargo(ctx, player.id);
+    delivery.state = DELIVERY_DROPPED.to_string();
+    delivery.intact = false;
+    delivery.dropped_map_id = player.current_map_id.clone();
+    delivery.dropped_x = player.position_x;
+    delivery.dropped_y = player.position_y;
+    ctx.db.caravan_delivery().player_id().update(delivery);
+    log::info!("📦 Player {} dropped caravan cargo in {}", player.id, player.current_map_id);
+}
+
+/// Checagem por tick: prazos vencidos e emboscadas no caminho
+pub fn process_caravans(ctx: &ReducerContext) {
+    let deliveries: Vec<CaravanDelivery> = ctx.db.caravan_delivery().iter().collect();
+    for mut delivery in deliveries {
+        if delivery.deadline <= ctx.timestamp {
+            discard_cargo(ctx, delivery.player_id);
+            ctx.db.caravan_delivery().player_id().delete(delivery.player_id);
+            log::info!("📦 Caravan '{}' of player {} expired", delivery.route_id, delivery.player_id);
+            continue;
+        }
+
+        if delivery.state != DELIVERY_CARRYING || delivery.next_ambush_check_at > ctx.timestamp {
+            continue;
+        }
+        delivery.next_ambush_check_at = after(ctx, AMBUSH_CHECK_SECS);
+        if delivery.ambushes < MAX_AMBUSHES && ctx.rng().gen_bool(AMBUSH_CHANCE) {
+            if let Some(carrier) = ctx.db.player().id().find(delivery.player_id) {
+                if !carrier.is_downed && spawn_ambush(ctx, &carrier) {
+                    delivery.ambushes += 1;
+                }
+            }
+        }
+        ctx.db.caravan_delivery().player_id().update(delivery);
+    }
+}
+
+fn spawn_ambush(ctx: &ReducerContext, carrier: &Player) -> bool {
+    let mut spawned = 0;
+    for _ in 0..AMBUSH_SIZE {
+        let Some((x, y)) = random_spawn_point(ctx, &carrier.current_map_id, carrier.position_x, carrier.position_y, AMBUSH_RADIUS) else { continue };
+        let enemy_id = allocate_enemy_id(ctx);
+        if spawn_enemy(ctx, enemy_id, x, y, carrier.current_map_id.clone(), AMBUSH_ENEMY_TYPE.to_string()).is_err() {
+            continue;
+        }
+        // Já chegam perseguindo o carregador
+        if let Some(mut ambusher) = ctx.db.enemy().id().find(enemy_id) {
+            ambusher.state = "Chasing".to_string();
+            ambusher.target_player_id = Some(carrier.id);
+            ambusher.target_map_id = Some(carrier.current_map_id.clone());
+            ambusher.last_known_player_x = carrier.position_x;
+            ambusher.last_known_player_y = carrier.position_y;
+            ctx.db.enemy().id().update(ambusher);
+        }
+        spawned += 1;
+    }
+    if spawned > 0 {
+        log::info!("⚔️ Caravan carrier {} ambushed by {} enemies in {}", carrier.id, spawned, carrier.current_map_id);
+    }
+    spawned > 0
+}
@@ -0,0 +1,320 @@
+use crate::combat::{allocate_enemy_id, enemy, spawn_enemy};
+use crate::map::{create_map_instance, destroy_map_instance, get_spawn_point, random_spawn_point, relocate_player, template_for_map};
+use crate::party::{party_member_ids, party_of, require_party_leader, sender_player};
+use crate::player;
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+/// Template da arena usada em todos os andares (cada andar é uma instância nova)
+pub const TOWER_ARENA_TEMPLATE: &str = "tower_arena";
+const TOWER_SPAWN_RADIUS: f32 = 160.0;
+const MICROS_PER_WEEK: i64 = 7 * 24 * 3600 * 1_000_000;
+
+/// Composição de inimigos de um andar; a quantidade cresce com o andar
+pub struct TowerArchetype {
+    pub name: &'static str,
+    pub enemies: &'static [(&'static str, u32)],
+}
+
+pub const TOWER_ARCHETYPES: &[TowerArchetype] = &[
+    TowerArchetype { name: "skirmishers", enemies: &[("Goblin", 3)] },
+    TowerArchetype { name: "brutes", enemies: &[("Orc", 2)] },
+    TowerArchetype { name: "siege", enemies: &[("Mortar", 2), ("Goblin", 2)] },
+    TowerArchetype { name: "warband", enemies: &[("Orc", 2), ("Troll", 1)] },
+];
+/// A cada `BOSS_FLOOR_INTERVAL` andares o andar é de chefe
+const BOSS_FLOOR_INTERVAL: u32 = 10;
+const BOSS_ARCHETYPE: TowerArchetype = TowerArchetype { name: "boss", enemies: &[("DungeonBoss", 1)] };
+/// Inimigos extras por grupo a cada `FLOORS_PER_EXTRA_ENEMY` andares
+const FLOORS_PER_EXTRA_ENEMY: u32 = 5;
+/// Vida e dano extras dos inimigos por andar acima do primeiro
+const SCALING_PER_FLOOR: f32 = 0.12;
+
+/// Modificador da semana, igual para todos
+pub struct TowerModifier {
+    pub id: &'static str,
+    pub enemy_health: f32,
+    pub enemy_damage: f32,
+    pub enemy_speed: f32,
+    pub extra_enemies: u32,
+}
+
+pub const TOWER_MODIFIERS: &[TowerModifier] = &[
+    TowerModifier { id: "fortified", enemy_health: 1.3, enemy_damage: 1.0, enemy_speed: 1.0, extra_enemies: 0 },
+    TowerModifier { id: "frenzied", enemy_health: 1.0, enemy_damage: 1.15, enemy_speed: 1.25, extra_enemies: 0 },
+    TowerModifier { id: "swarming", enemy_health: 0.85, enemy_damage: 1.0, enemy_speed: 1.0, extra_enemies: 1 },
+];
+
+/// Run em andamento: um grupo (ou um player solo) dentro da instância do andar atual
+#[table(name = tower_run, public)]
+#[derive(Clone)]
+pub struct TowerRun {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[unique]
+    pub leader_id: u32,
+    #[unique]
+    pub map_key: String,
+    pub member_ids: Vec<u32>,
+    pub floor: u32,
+    pub archetype: String,
+    pub week: i64,
+    pub modifier_id: String,
+    pub enemy_ids: Vec<u32>,
+    pub floor_cleared: bool,
+    pub floor_started_at: Timestamp,
+    pub return_map_id: String,
+    pub return_x: f32,
+    pub return_y: f32,
+}
+
+/// Andar mais alto liberado para o player começar
+#[table(name = tower_progress, public)]
+#[derive(Clone)]
+pub struct TowerProgress {
+    #[primary_key]
+    pub player_id: u32,
+    pub highest_unlocked: u32,
+}
+
+/// Ladder semanal: melhor andar concluído por player
+#[table(name = tower_ladder_entry, public)]
+#[derive(Clone)]
+pub struct TowerLadderEntry {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub week: i64,
+    pub player_id: u32,
+    pub best_floor: u32,
+    pub reached_at: Timestamp,
+}
+
+pub fn current_week(ctx: &ReducerContext) -> i64 {
+    ctx.timestamp.to_micros_since_unix_epoch().div_euclid(MICROS_PER_WEEK)
+}
+
+pub fn weekly_modifier(week: i64) -> &'static TowerModifier {
+    &TOWER_MODIFIERS[week.rem_euclid(TOWER_MODIFIERS.len() as i64) as usize]
+}
+
+fn modifier_by_id(id: &str) -> &'static TowerModifier {
+    TOWER_MODIFIERS.iter().find(|m| m.id == id).unwrap_or(&TOWER_MODIFIERS[0])
+}
+
+/// Arquétipo do andar: fixo por (semana, andar), então todos enfrentam a mesma torre na semana
+fn floor_archetype(week: i64, floor: u32) -> &'static TowerArchetype {
+    if floor.is_multiple_of(BOSS_FLOOR_INTERVAL) {
+        return &BOSS_ARCHETYPE;
+    }
+    let index = (week + floor as i64).rem_euclid(TOWER_ARCHETYPES.len() as i64) as usize;
+    &TOWER_ARCHETYPES[index]
+}
+
+pub fn highest_unlocked(ctx: &ReducerContext, player_id: u32) -> u32 {
+    ctx.db.tower_progress().player_id().find(player_id).map(|p| p.highest_unlocked).unwrap_or(1)
+}
+
+pub fn tower_run_for_map(ctx: &ReducerContext, map_id: &str) -> Option<TowerRun> {
+    ctx.db.tower_run().map_key().find(map_id.to_string())
+}
+
+fn floor_map_key(run_id: u64, floor: u32) -> String {
+    format!("{}@tower{}-{}", TOWER_ARENA_TEMPLATE, run_id, floor)
+}
+
+/// Cria a instância do andar, espalha os inimigos escalados e leva o grupo para dentro
+fn enter_floor(ctx: &ReducerContext, run: &mut TowerRun, floor: u32) -> Result<(), String> {
+    let map_key = floor_map_key(run.id, floor);
+    create_map_instance(ctx, &map_key, TOWER_ARENA_TEMPLATE)?;
+    let archetype = floor_archetype(run.week, floor);
+    let modifier = modifier_by_id(&run.modifier_id);
+    let scale = 1.0 + SCALING_PER_FLOOR * (floor - 1) as f32;
+    let extra = floor / FLOORS_PER_EXTRA_ENEMY + modifier.extra_enemies;
+    let (spawn_x, spawn_y) = get_spawn_point(ctx, &map_key);
+
+    let mut enemy_ids = Vec::new();
+    for (enemy_type, count) in archetype.enemies {
+        let count = if archetype.name == BOSS_ARCHETYPE.name { *count } else { count + extra };
+        for _ in 0..count {
+            let Some((x, y)) = random_spawn_point(ctx, &map_key, spawn_x, spawn_y, TOWER_SPAWN_RADIUS) else { continue };
+            let enemy_id = allocate_enemy_id(ctx);
+            spawn_enemy(ctx, enemy_id, x, y, map_key.clone(), enemy_type.to_string()).map_err(|e| e.to_string())?;
+            if let Some(mut spawned) = ctx.db.enemy().id().find(enemy_id) {
+                spawned.max_health *= scale * modifier.enemy_health;
+                spawned.health = spawned.max_health;
+                spawned.attack_damage *= scale * modifier.enemy_damage;
+                spawned.movement_speed *= modifier.enemy_speed;
+                ctx.db.enemy().id().update(spawned);
+                enemy_ids.push(enemy_id);
+            }
+        }
+    }
+    if enemy_ids.is_empty() {
+        destroy_map_instance(ctx, &map_key);
+        return Err("No room to spawn the floor".to_string());
+    }
+
+    let previous_map = std::mem::replace(&mut run.map_key, map_key.clone());
+    run.floor = floor;
+    run.archetype = archetype.name.to_string();
+    run.enemy_ids = enemy_ids;
+    run.floor_cleared = false;
+    run.floor_started_at = ctx.timestamp;
+    ctx.db.tower_run().id().update(run.clone());
+
+    for member_id in &run.member_ids {
+        let Some(member) = ctx.db.player().id().find(*member_id) else { continue };
+        if member.current_map_id == previous_map || (previous_map.is_empty() && member.current_map_id == run.return_map_id) {
+            relocate_player(ctx, &member, &map_key, spawn_x, spawn_y)?;
+        }
+    }
+    if !previous_map.is_empty() {
+        destroy_map_instance(ctx, &previous_map);
+    }
+    log::info!("🗼 Tower run {} entered floor {} ({}, {})", run.id, floor, archetype.name, run.modifier_id);
+    Ok(())
+}
+
+/// Entra na torre a partir de um andar já liberado. Em party, só o líder inicia
+/// e os membros no mesmo mapa entram junto.
+#[reducer]
+pub fn start_tower_run(ctx: &ReducerContext, floor: u32) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let member_ids = match party_of(ctx, player.id) {
+        Some(party_id) => {
+            require_party_leader(ctx)?;
+            party_member_ids(ctx, party_id).into_iter()
+                .filter(|id| ctx.db.player().id().find(*id).is_some_and(|p| p.current_map_id == player.current_map_id))
+                .collect()
+        }
+        None => vec![player.id],
+    };
+    if floor == 0 || floor > highest_unlocked(ctx, player.id) {
+        return Err(format!("Floor {} is not unlocked", floor));
+    }
+    if member_ids.iter().any(|id| ctx.db.tower_run().iter().any(|r| r.member_ids.contains(id))) {
+        return Err("A member is already in the tower".to_string());
+    }
+    template_for_map(ctx, TOWER_ARENA_TEMPLATE).ok_or("The challenge tower is not available")?;
+
+    let week = current_week(ctx);
+    let mut run = ctx.db.tower_run().insert(TowerRun {
+        id: 0,
+        leader_id: player.id,
+        map_key: String::new(),
+        member_ids,
+        floor,
+        archetype: String::new(),
+        week,
+        modifier_id: weekly_modifier(week).id.to_string(),
+        enemy_ids: Vec::new(),
+        floor_cleared: false,
+        floor_started_at: ctx.timestamp,
+        return_map_id: player.current_map_id.clone(),
+        return_x: player.position_x,
+        return_y: player.position_y,
+    });
+    enter_floor(ctx, &mut run, floor)
+}
+
+/// Sobe para o próximo andar depois de limpar o atual
+#[reducer]
+pub fn advance_tower_floor(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let mut run = ctx.db.tower_run().leader_id().find(player.id).ok_or("You are not leading a tower run")?;
+    if !run.floor_cleared {
+        return Err("Clear this floor first".to_string());
+    }
+    let next = run.floor + 1;
+    enter_floor(ctx, &mut run, next)
+}
+
+#[reducer]
+pub fn leave_tower(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let run = ctx.db.tower_run().leader_id().find(player.id).ok_or("You are not leading a tower run")?;
+    close_run(ctx, &run)
+}
+
+fn close_run(ctx: &ReducerContext, run: &TowerRun) -> Result<(), String> {
+    let inside: Vec<_> = ctx.db.player().current_map_id().filter(&run.map_key).collect();
+    for p in inside {
+        relocate_player(ctx, &p, &run.return_map_id, run.return_x, run.return_y)?;
+    }
+    for enemy_id in &run.enemy_ids {
+        ctx.db.enemy().id().delete(enemy_id);
+    }
+    destroy_map_instance(ctx, &run.map_key);
+    ctx.db.tower_run().id().delete(run.id);
+    log::info!("🗼 Tower run {} ended on floor {}", run.id, run.floor);
+    Ok(())
+}
+
+/// Chamado na derrota de um inimigo: o último do andar libera o próximo andar
+/// para quem está dentro e registra o andar na ladder da semana
+pub fn on_enemy_defeated(ctx: &ReducerContext, map_id: &str, enemy_id: u32) {
+    let Some(mut run) = tower_run_for_map(ctx, map_id) else {
+        return;
+    };
+    // Reconfere os vivos: inimigo removido sem abate não pode travar o andar
+    run.enemy_ids.retain(|id| *id != enemy_id && ctx.db.enemy().id().find(id).is_some_and(|e| e.map_id == run.map_key));
+    if !run.enemy_ids.is_empty() || run.floor_cleared {
+        ctx.db.tower_run().id().update(run);
+        return;
+    }
+
+    run.floor_cleared = true;
+    let inside: Vec<u32> = ctx.db.player().current_map_id().filter(map_id).map(|p| p.id).collect();
+    for player_id in inside {
+        unlock_floor(ctx, player_id, run.floor + 1);
+        record_ladder(ctx, run.week, player_id, run.floor);
+    }
+    log::info!("🗼 Tower run {} cleared floor {}", run.id, run.floor);
+    ctx.db.tower_run().id().update(run);
+}
+
+/// Chamado quando um inimigo sai do andar sem ser abatido (remoção, troca de mapa)
+pub fn on_enemy_removed(ctx: &ReducerContext, map_id: &str, enemy_id: u32) {
+    on_enemy_defeated(ctx, map_id, enemy_id);
+}
+
+fn unlock_floor(ctx: &ReducerContext, player_id: u32, floor: u32) {
+    match ctx.db.tower_progress().player_id().find(player_id) {
+        Some(mut progress) if progress.highest_unlocked < floor => {
+            progress.highest_unlocked = floor;
+            ctx.db.tower_progress().player_id().update(progress);
+        }
+        Some(_) => {}
+        None => {
+            ctx.db.tower_progress().insert(TowerProgress { player_id, highest_unlocked: floor });
+        }
+    }
+}
+
+fn record_ladder(ctx: &ReducerContext, week: i64, player_id: u32, floor: u32) {
+    match ctx.db.tower_ladder_entry().week().filter(week).find(|e| e.player_id == player_id) {
+        Some(mut entry) if entry.best_floor < floor => {
+            entry.best_floor = floor;
+            entry.reached_at = ctx.timestamp;
+            ctx.db.tower_ladder_entry().id().update(entry);
+        }
+        Some(_) => {}
+        None => {
+            ctx.db.tower_ladder_entry().insert(TowerLadderEntry { id: 0, week, player_id, best_floor: floor, reached_at: ctx.timestamp });
+        }
+    }
+}
+
+/// Limpeza periódica: runs sem ninguém dentro são encerradas
+pub fn prune_abandoned_tower_runs(ctx: &ReducerContext) {
+    let abandoned: Vec<TowerRun> = ctx.db.tower_run().iter()
+        .filter(|r| ctx.db.player().current_map_id().filter(&r.map_key).next().is_none())
+        .collect();
+    for run in abandoned {
+        if let Err(e) = close_run(ctx, &run) {
+            log::warn!("Could not close abandoned tower run {}: {}", run.id, e);
+        }
+    }
+}
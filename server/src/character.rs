@@ -1,5 +1,45 @@
 use spacetimedb::{reducer, ReducerContext, Table};
 use crate::{player};
+use crate::inventory::{count_item, remove_item_from_inventory};
+use crate::map::TILE_SIZE;
+use crate::movement::refresh_player_motion;
+use crate::party::party_of;
+
+// Token de revive: usado à distância em membros caídos da party
+const REVIVE_TOKEN_ITEM: &str = "revive_token";
+const REVIVE_TOKEN_RANGE: f32 = TILE_SIZE * 8.0;
+const REVIVE_TOKEN_HEALTH: f32 = 0.3;
+
+// Bônus de crítico no crafting ao comer uma torta
+const FRUIT_PIE_FOCUS: f32 = 0.1;
+const FRUIT_PIE_FOCUS_SECS: u64 = 600;
+
+/// Side effects of a player going down (row already saved as downed)
+/// `killer_id` is set when another player landed the final hit
+pub fn on_player_downed(ctx: &ReducerContext, player: &crate::Player, killer_id: Option<u32>) {
+    let recap = crate::death_recap::snapshot(ctx, player, killer_id);
+    let credit = crate::kill_credit::credit_for(ctx, &recap, killer_id);
+    crate::kill_credit::record_credit(ctx, &credit);
+
+    crate::run_report::record_player_downed(ctx, player);
+    crate::heatmap::record_death(ctx, player);
+    crate::replay::record_replay_event(ctx, &player.current_map_id, crate::replay::REPLAY_EVENT_DOWNED, player.id, killer_id.unwrap_or(0), 0.0);
+    crate::aura::on_player_downed(ctx, player.id);
+    crate::threat::on_player_downed(ctx, player.id);
+    crate::caravan::drop_cargo_on_death(ctx, player);
+    crate::arena::on_player_downed(ctx, player, &credit);
+    crate::damage_meter::on_player_downed(ctx, &player.current_map_id);
+    if let Some(killer_id) = credit.killer_id {
+        crate::guild_war::record_kill(ctx, killer_id, player.id);
+        crate::kill_feed::record_kill(ctx, player, killer_id, &credit);
+    }
+}
+
+/// Id do atacante se ele for um player. Ids de player cobrem todo o u32 e
+/// cruzam a faixa dos inimigos, então só a tabela de players decide.
+pub fn attacking_player(ctx: &ReducerContext, attacker_id: u32) -> Option<u32> {
+    ctx.db.player().id().find(attacker_id).map(|p| p.id)
+}
 
 #[reducer]
 pub fn apply_damage_to_player(
@@ -11,7 +51,7 @@ pub fn apply_damage_to_player(
     let _identity = ctx.sender;
     
     // Find the player
-    if let Some(player) = ctx.db.player().id().find(&player_id) {
+    if let Some(player) = ctx.db.player().id().find(player_id) {
         // Check if player is already downed
         if player.is_downed {
             log::warn!("Player {} is already downed, cannot take more damage", player_id);
@@ -21,16 +61,23 @@ pub fn apply_damage_to_player(
         // Apply damage
         let mut updated_player = player.clone();
         updated_player.health = (updated_player.health - damage).max(0.0);
+        crate::combat::mark_in_combat(ctx, player_id);
+        crate::damage_meter::record_hit(ctx, &player.current_map_id, attacker_id, player_id, damage, false);
+        crate::death_recap::record_damage_taken(ctx, player_id, attacker_id, "Attack", damage);
         
         // Check if player is downed
         if updated_player.health <= 0.0 {
             updated_player.is_downed = true;
             log::info!("Player {} downed by attacker {}", player_id, attacker_id);
         }
+        refresh_player_motion(&mut updated_player, ctx.timestamp);
         
         // Delete old and insert updated
-        ctx.db.player().id().delete(&player_id);
+        ctx.db.player().id().delete(player_id);
         ctx.db.player().insert(updated_player.clone());
+        if updated_player.is_downed {
+            on_player_downed(ctx, &updated_player, attacking_player(ctx, attacker_id));
+        }
         
         log::info!("Player {} took {} damage from {}, health: {}/{}", 
                   player_id, damage, attacker_id, updated_player.health, updated_player.max_health);
@@ -52,7 +99,7 @@ pub fn heal_player(
     let identity = ctx.sender;
     
     // Find the player
-    if let Some(player) = ctx.db.player().id().find(&player_id) {
+    if let Some(player) = ctx.db.player().id().find(player_id) {
         // Verify the player belongs to the sender
         if player.identity != identity {
             return Err("Unauthorized player update".into());
@@ -70,9 +117,10 @@ pub fn heal_player(
         updated_player.health = (updated_player.health + heal_amount).min(updated_player.max_health);
         
         let actual_healing = updated_player.health - old_health;
+        crate::damage_meter::record_healing(ctx, &player.current_map_id, player_id, actual_healing);
         if actual_healing > 0.0 {
             // Delete old and insert updated
-            ctx.db.player().id().delete(&player_id);
+            ctx.db.player().id().delete(player_id);
             ctx.db.player().insert(updated_player.clone());
             
             log::info!("Player {} healed for {}, health: {}/{}", 
@@ -96,20 +144,27 @@ pub fn revive_player(
     let _identity = ctx.sender;
     
     // Find the player to revive
-    if let Some(player) = ctx.db.player().id().find(&player_id) {
+    if let Some(player) = ctx.db.player().id().find(player_id) {
+        // Hardcore: queda é definitiva, sem revive
+        if crate::feature_flag::is_feature_enabled(ctx, crate::feature_flag::HARDCORE_ENABLED) {
+            return Err("Revive is disabled in hardcore mode".into());
+        }
+
         // Check if player is actually downed
         if !player.is_downed {
             log::warn!("Player {} is not downed, cannot revive", player_id);
             return Ok(());
         }
+        crate::dungeon::consume_battle_res(ctx, &player.current_map_id)?;
         
         // Revive player with partial health
         let mut updated_player = player.clone();
         updated_player.is_downed = false;
         updated_player.health = updated_player.max_health * 0.5; // Revive with 50% health
+        refresh_player_motion(&mut updated_player, ctx.timestamp);
         
         // Delete old and insert updated
-        ctx.db.player().id().delete(&player_id);
+        ctx.db.player().id().delete(player_id);
         ctx.db.player().insert(updated_player.clone());
         
         log::info!("Player {} revived by player {} with {} health", 
@@ -121,6 +176,80 @@ pub fn revive_player(
     Ok(())
 }
 
+/// Revive a downed party member from range by consuming a revive token
+#[reducer]
+pub fn use_revive_token(ctx: &ReducerContext, target_id: u32) -> Result<(), String> {
+    let reviver = crate::party::sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    if reviver.is_downed {
+        return Err("Cannot revive while downed".to_string());
+    }
+    if crate::feature_flag::is_feature_enabled(ctx, crate::feature_flag::HARDCORE_ENABLED) {
+        return Err("Revive is disabled in hardcore mode".to_string());
+    }
+    let target = ctx.db.player().id().find(target_id).ok_or("Player not found")?;
+    if !target.is_downed {
+        return Err("Player is not downed".to_string());
+    }
+    let allies = matches!((party_of(ctx, reviver.id), party_of(ctx, target.id)), (Some(a), Some(b)) if a == b);
+    if !allies {
+        return Err("You can only revive party members".to_string());
+    }
+    let distance = ((reviver.position_x - target.position_x).powi(2) + (reviver.position_y - target.position_y).powi(2)).sqrt();
+    if reviver.current_map_id != target.current_map_id || distance > REVIVE_TOKEN_RANGE {
+        return Err("Target is out of range".to_string());
+    }
+    if count_item(ctx, reviver.id, REVIVE_TOKEN_ITEM) < 1 {
+        return Err("You have no revive tokens".to_string());
+    }
+
+    crate::dungeon::consume_battle_res(ctx, &target.current_map_id)?;
+    remove_item_from_inventory(ctx, reviver.id, REVIVE_TOKEN_ITEM, 1)?;
+
+    let mut revived = target;
+    revived.is_downed = false;
+    revived.health = revived.max_health * REVIVE_TOKEN_HEALTH;
+    refresh_player_motion(&mut revived, ctx.timestamp);
+    ctx.db.player().id().update(revived.clone());
+
+    log::info!("🕯️ Player {} revived player {} with a token ({} health)", reviver.id, revived.id, revived.health);
+    Ok(())
+}
+
+/// Release a downed player back to the map's respawn point
+/// Inside a dungeon this is the party's active checkpoint (or the instance entrance)
+#[reducer]
+pub fn respawn_player(ctx: &ReducerContext, player_id: u32) -> Result<(), Box<dyn std::error::Error>> {
+    let player = ctx.db.player().id().find(player_id).ok_or("Player not found")?;
+    if player.identity != ctx.sender {
+        return Err("Unauthorized player update".into());
+    }
+    if crate::feature_flag::is_feature_enabled(ctx, crate::feature_flag::HARDCORE_ENABLED) {
+        return Err("Respawn is disabled in hardcore mode".into());
+    }
+    if !player.is_downed {
+        return Err("Player is not downed".into());
+    }
+
+    let (spawn_x, spawn_y) = crate::dungeon::respawn_point(ctx, &player.current_map_id)
+        .unwrap_or_else(|| crate::map::get_spawn_point(ctx, &player.current_map_id));
+
+    let mut updated_player = player.clone();
+    updated_player.is_downed = false;
+    updated_player.health = updated_player.max_health;
+    updated_player.position_x = spawn_x;
+    updated_player.position_y = spawn_y;
+    updated_player.velocity_x = 0.0;
+    updated_player.velocity_y = 0.0;
+    refresh_player_motion(&mut updated_player, ctx.timestamp);
+
+    ctx.db.player().id().delete(player_id);
+    ctx.db.player().insert(updated_player);
+
+    log::info!("Player {} respawned at ({}, {}) in {}", player_id, spawn_x, spawn_y, player.current_map_id);
+    Ok(())
+}
+
 /// Set player max health (for upgrades, etc.)
 /// Requirements 9.1: Player health system with maximum health capacity
 #[reducer]
@@ -132,7 +261,7 @@ pub fn set_player_max_health(
     let identity = ctx.sender;
     
     // Find the player
-    if let Some(player) = ctx.db.player().id().find(&player_id) {
+    if let Some(player) = ctx.db.player().id().find(player_id) {
         // Verify the player belongs to the sender
         if player.identity != identity {
             return Err("Unauthorized player update".into());
@@ -146,7 +275,7 @@ pub fn set_player_max_health(
         updated_player.health = (health_ratio * updated_player.max_health).min(updated_player.max_health);
         
         // Delete old and insert updated
-        ctx.db.player().id().delete(&player_id);
+        ctx.db.player().id().delete(player_id);
         ctx.db.player().insert(updated_player.clone());
         
         log::info!("Player {} max health set to {}, current health: {}", 
@@ -166,10 +295,11 @@ pub fn use_health_consumable(
     player_id: u32,
     item_id: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    crate::upgrade::require_not_frozen(ctx)?;
     let identity = ctx.sender;
     
     // Find the player
-    if let Some(player) = ctx.db.player().id().find(&player_id) {
+    if let Some(player) = ctx.db.player().id().find(player_id) {
         // Verify the player belongs to the sender
         if player.identity != identity {
             return Err("Unauthorized player update".into());
@@ -186,11 +316,25 @@ pub fn use_health_consumable(
             "fruit" => 25.0,
             "health_potion" => 50.0,
             "mega_health_potion" => 100.0,
+            "fruit_pie" => 40.0,
+            "stale_pie" => 15.0,
             _ => {
                 log::warn!("Unknown consumable item: {}", item_id);
                 return Err("Unknown consumable item".into());
             }
         };
+
+        crate::cooldown::try_start_cooldown(
+            ctx, identity, crate::cooldown::CATEGORY_CONSUMABLE, &item_id,
+            crate::cooldown::CONSUMABLE_COOLDOWN_MS,
+        )?;
+
+        if item_id == "fruit_pie" {
+            crate::status_effect::apply_status_effect(
+                ctx, player_id, crate::status_effect::EFFECT_CRAFTING_FOCUS, FRUIT_PIE_FOCUS,
+                "consumable:fruit_pie", Some(std::time::Duration::from_secs(FRUIT_PIE_FOCUS_SECS)),
+            );
+        }
         
         // Apply healing
         let mut updated_player = player.clone();
@@ -198,9 +342,10 @@ pub fn use_health_consumable(
         updated_player.health = (updated_player.health + heal_amount).min(updated_player.max_health);
         
         let actual_healing = updated_player.health - old_health;
+        crate::damage_meter::record_healing(ctx, &player.current_map_id, player_id, actual_healing);
         if actual_healing > 0.0 {
             // Delete old and insert updated
-            ctx.db.player().id().delete(&player_id);
+            ctx.db.player().id().delete(player_id);
             ctx.db.player().insert(updated_player.clone());
             
             log::info!("Player {} consumed {} and healed for {}, health: {}/{}", 
@@ -0,0 +1,63 @@
+use crate::alliance::alliance_of_player;
+use crate::guild::guild_of;
+use crate::party::{party_of, sender_player};
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+pub const CHANNEL_PARTY: &str = "party";
+pub const CHANNEL_GUILD: &str = "guild";
+pub const CHANNEL_ALLIANCE: &str = "alliance";
+
+const MAX_MESSAGE_LENGTH: usize = 280;
+
+/// Mensagem em um canal de grupo. `channel_id` é o id da party/guilda/aliança;
+/// clientes assinam filtrando pelos canais a que pertencem.
+#[table(name = chat_message, public)]
+#[derive(Clone)]
+pub struct ChatMessage {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub channel: String,
+    #[index(btree)]
+    pub channel_id: u64,
+    pub sender_id: u32,
+    pub sender_name: String,
+    pub text: String,
+    pub sent_at: Timestamp,
+}
+
+/// Canal a que o player tem acesso (None = não participa)
+pub fn channel_id_for(ctx: &ReducerContext, player_id: u32, channel: &str) -> Option<u64> {
+    match channel {
+        CHANNEL_PARTY => party_of(ctx, player_id),
+        CHANNEL_GUILD => guild_of(ctx, player_id),
+        CHANNEL_ALLIANCE => alliance_of_player(ctx, player_id),
+        _ => None,
+    }
+}
+
+#[reducer]
+pub fn send_chat_message(ctx: &ReducerContext, channel: String, text: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let text = text.trim().to_string();
+    if text.is_empty() || text.chars().count() > MAX_MESSAGE_LENGTH {
+        return Err(format!("Messages must have 1 to {} characters", MAX_MESSAGE_LENGTH));
+    }
+    let channel_id = channel_id_for(ctx, player.id, &channel)
+        .ok_or_else(|| format!("You don't have access to the {} channel", channel))?;
+
+    if crate::shadow_ban::is_shadow_banned(ctx, player.identity) {
+        crate::shadow_ban::record_shadow_chat(ctx, channel, channel_id, &player, text);
+        return Ok(());
+    }
+    ctx.db.chat_message().insert(ChatMessage {
+        id: 0,
+        channel,
+        channel_id,
+        sender_id: player.id,
+        sender_name: player.username_display,
+        text,
+        sent_at: ctx.timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use crate::config::get_config_string;
+use crate::player;
+use spacetimedb::{reducer, table, Identity, ReducerContext, Table, Timestamp};
+
+/// Versão mínima aceita quando `min_client_version` não está configurado
+pub const DEFAULT_MIN_CLIENT_VERSION: &str = "0.1.0";
+
+// Versões mínimas de cliente para features mais novas
+pub const FEATURE_ABILITY_QUEUE: &str = "0.2.0";
+pub const FEATURE_EMOTES: &str = "0.2.0";
+
+// Códigos de erro tipados enviados ao cliente
+pub const ERROR_CLIENT_VERSION_TOO_OLD: &str = "CLIENT_VERSION_TOO_OLD";
+pub const ERROR_CLIENT_VERSION_INVALID: &str = "CLIENT_VERSION_INVALID";
+
+/// Versão informada por cada cliente no handshake pós-conexão
+#[table(name = client_info, public)]
+#[derive(Clone)]
+pub struct ClientInfo {
+    #[primary_key]
+    pub identity: Identity,
+    pub client_version: String,
+    pub version_major: u32,
+    pub version_minor: u32,
+    pub version_patch: u32,
+    pub is_compatible: bool,
+    pub reported_at: Timestamp,
+}
+
+/// Erros tipados para o cliente (ele filtra pela própria identidade)
+#[table(name = client_error_event, public)]
+#[derive(Clone)]
+pub struct ClientErrorEvent {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub error_code: String,
+    pub message: String,
+    pub created_at: Timestamp,
+}
+
+/// "1.2.3" -> (1, 2, 3). Componentes ausentes valem 0 ("1.2" == "1.2.0").
+pub fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map(|p| p.parse()).unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().map(|p| p.parse()).unwrap_or(Ok(0)).ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+pub fn emit_client_error(ctx: &ReducerContext, identity: Identity, error_code: &str, message: String) {
+    log::warn!("📵 Client error {} for {:?}: {}", error_code, identity, message);
+    if let Some(player) = ctx.db.player().identity().find(identity) {
+        crate::bug_report::record_action(ctx, player.id, error_code.to_string(), true);
+    }
+    ctx.db.client_error_event().insert(ClientErrorEvent {
+        id: 0,
+        identity,
+        error_code: error_code.to_string(),
+        message,
+        created_at: ctx.timestamp,
+    });
+}
+
+/// O cliente desta identidade suporta a feature que exige `min_version`?
+/// Clientes que nunca fizeram o handshake (builds antigos) não recebem features novas.
+pub fn client_supports(ctx: &ReducerContext, identity: Identity, min_version: &str) -> bool {
+    let Some(required) = parse_version(min_version) else {
+        return false;
+    };
+    match ctx.db.client_info().identity().find(identity) {
+        Some(info) => (info.version_major, info.version_minor, info.version_patch) >= required,
+        None => false,
+    }
+}
+
+/// Handshake chamado pelo cliente logo após conectar. A recusa vai pelo
+/// `client_error_event` (e `is_compatible = false`), não pelo retorno: um `Err`
+/// desfaria o registro.
+#[reducer]
+pub fn client_version(ctx: &ReducerContext, version: String) -> Result<(), String> {
+    let Some((major, minor, patch)) = parse_version(&version) else {
+        emit_client_error(ctx, ctx.sender, ERROR_CLIENT_VERSION_INVALID, format!("Invalid client version '{}'", version));
+        return Ok(());
+    };
+
+    let min_version = get_config_string(ctx, crate::config::MIN_CLIENT_VERSION, DEFAULT_MIN_CLIENT_VERSION);
+    let minimum = parse_version(&min_version).unwrap_or((0, 0, 0));
+    let is_compatible = (major, minor, patch) >= minimum;
+
+    let info = ClientInfo {
+        identity: ctx.sender,
+        client_version: version.clone(),
+        version_major: major,
+        version_minor: minor,
+        version_patch: patch,
+        is_compatible,
+        reported_at: ctx.timestamp,
+    };
+    if ctx.db.client_info().identity().find(ctx.sender).is_some() {
+        ctx.db.client_info().identity().update(info);
+    } else {
+        ctx.db.client_info().insert(info);
+    }
+
+    if !is_compatible {
+        emit_client_error(ctx, ctx.sender, ERROR_CLIENT_VERSION_TOO_OLD, format!(
+            "Client version {} is below the minimum {}. Please update.", version, min_version,
+        ));
+        return Ok(());
+    }
+
+    log::info!("🤝 Client {:?} handshake OK (v{})", ctx.sender, version);
+    Ok(())
+}
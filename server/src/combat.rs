@@ -1,6 +1,10 @@
-use spacetimedb::{reducer, table, ReducerContext, Table};
+use spacetimedb::rand::Rng;
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
 use crate::{Player, player};
 use crate::inventory::{inventory_item};
+use crate::movement::{motion_hints, refresh_player_motion, ANIM_ATTACK, ANIM_IDLE};
+use crate::cooldown::{attack_cooldown_ms, remaining_cooldown, start_cooldown, ATTACK_COOLDOWN_KEY, CATEGORY_ABILITY};
+use crate::status_effect::{incoming_damage_multiplier, outgoing_damage_multiplier};
 
 #[table(name = enemy, public)]
 #[derive(Clone)]
@@ -11,15 +15,25 @@ pub struct Enemy {
     pub position_y: f32,
     pub velocity_x: f32,
     pub velocity_y: f32,
+    /// Motion hints for client interpolation (unit facing vector + animation id)
+    pub facing_x: f32,
+    pub facing_y: f32,
+    pub animation_state: u8,
+    pub motion_updated_at: Timestamp,
     pub health: f32,
     pub max_health: f32,
     pub enemy_type: String,
+    #[index(btree)]
     pub map_id: String,
     /// Estados: "Idle", "Alert", "Chasing", "ChasingThroughMap"
     pub state: String,
     pub patrol_center_x: f32,
     pub patrol_center_y: f32,
     pub patrol_radius: f32,
+    /// Ponto atual do passeio ocioso (escolhido dentro da região de patrulha)
+    pub wander_target_x: f32,
+    pub wander_target_y: f32,
+    pub wander_resume_at: Timestamp,
     pub detection_range: f32,
     pub leash_range: f32,
     pub target_player_id: Option<u32>,
@@ -52,6 +66,15 @@ pub struct Projectile {
     pub projectile_type: String,
     pub map_id: String,
     pub is_active: bool,
+    /// Arco de projéteis arremessados (ver `ballistics`); zero para tiros retos
+    pub height: f32,
+    pub velocity_z: f32,
+    pub gravity: f32,
+    pub aoe_radius: f32,
+    /// Projéteis em cadeia: saltos restantes, fator de dano por salto e alvos já atingidos
+    pub bounces_left: u32,
+    pub bounce_falloff: f32,
+    pub hit_ids: Vec<u32>,
 }
 
 // Combat event for client synchronization
@@ -67,6 +90,33 @@ pub struct CombatEvent {
     pub timestamp: u64,
 }
 
+/// Players leave combat this long after their last attack or hit taken
+pub const COMBAT_TIMEOUT_MS: u64 = 5000;
+
+/// Last time each player attacked or was hit (drives "in combat" checks)
+#[table(name = combat_state, public)]
+#[derive(Clone)]
+pub struct CombatState {
+    #[primary_key]
+    pub player_id: u32,
+    pub last_combat_at: Timestamp,
+}
+
+pub fn mark_in_combat(ctx: &ReducerContext, player_id: u32) {
+    let state = CombatState { player_id, last_combat_at: ctx.timestamp };
+    if ctx.db.combat_state().player_id().find(player_id).is_some() {
+        ctx.db.combat_state().player_id().update(state);
+    } else {
+        ctx.db.combat_state().insert(state);
+    }
+}
+
+pub fn is_in_combat(ctx: &ReducerContext, player_id: u32) -> bool {
+    ctx.db.combat_state().player_id().find(player_id)
+        .and_then(|s| ctx.timestamp.duration_since(s.last_combat_at))
+        .is_some_and(|elapsed| elapsed.as_millis() < COMBAT_TIMEOUT_MS as u128)
+}
+
 // Weapon configuration constants
 const SWORD_DAMAGE: f32 = 25.0;
 const AXE_DAMAGE: f32 = 40.0;
@@ -76,11 +126,20 @@ const AXE_RANGE: f32 = 60.0;
 const SWORD_CLEAVE_ANGLE: f32 = 90.0; // degrees
 const AXE_FRONTAL_ANGLE: f32 = 45.0; // degrees
 
+// Enemy types that end a dungeon run when defeated
+const BOSS_ENEMY_TYPES: &[&str] = &["DungeonBoss"];
+
+pub fn is_boss_type(enemy_type: &str) -> bool {
+    BOSS_ENEMY_TYPES.contains(&enemy_type)
+}
+
 // Projectile configuration constants
 const ARROW_SPEED: f32 = 400.0;
-const ARROW_MAX_RANGE: f32 = 300.0;
 const ARROW_TIME_TO_LIVE: f32 = 5.0;
 const PROJECTILE_COLLISION_RADIUS: f32 = 5.0;
+const BOMB_ITEM: &str = "bomb";
+const BOMB_PROJECTILE: &str = "Bomb";
+const STAFF_PROJECTILE: &str = "ChainBolt";
 
 #[reducer]
 pub fn execute_attack(
@@ -93,7 +152,7 @@ pub fn execute_attack(
     let identity = ctx.sender;
 
     // Validate player exists and owns this identity
-    let player = match ctx.db.player().id().find(&player_id) {
+    let player = match ctx.db.player().id().find(player_id) {
         Some(p) if p.identity == identity => p,
         Some(_) => {
             log::warn!("Player {} attack rejected: identity mismatch", player_id);
@@ -111,14 +170,52 @@ pub fn execute_attack(
         return Ok(());
     }
 
+    crate::bug_report::record_action(ctx, player_id, format!("execute_attack {}", weapon_type), false);
+
+    // Cooldown centralizado por identidade; dentro da janela de fila o ataque é
+    // enfileirado e executado pelo tick assim que o cooldown terminar
+    if let Some(remaining) = remaining_cooldown(ctx, identity, CATEGORY_ABILITY, ATTACK_COOLDOWN_KEY) {
+        if crate::ability_queue::try_queue_ability(ctx, &player, &weapon_type, direction_x, direction_y, remaining) {
+            log::info!("Player {} queued {} attack ({} ms remaining)", player_id, weapon_type, remaining.as_millis());
+        } else {
+            log::info!("Player {} attack rejected: on cooldown ({} ms remaining)", player_id, remaining.as_millis());
+        }
+        return Ok(());
+    }
+
+    start_cooldown(ctx, identity, CATEGORY_ABILITY, ATTACK_COOLDOWN_KEY, attack_cooldown_ms(&weapon_type));
+    perform_attack(ctx, player, &weapon_type, direction_x, direction_y)
+}
+
+/// Executes an attack that already passed ownership and cooldown validation.
+/// Shared by the `execute_attack` reducer and the ability queue tick.
+pub fn perform_attack(
+    ctx: &ReducerContext,
+    player: Player,
+    weapon_type: &str,
+    direction_x: f32,
+    direction_y: f32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let player_id = player.id;
+    mark_in_combat(ctx, player_id);
+
+    // Publica a direção e a animação do ataque para os clientes
+    let mut player = player;
+    face_towards(&mut player.facing_x, &mut player.facing_y, direction_x, direction_y);
+    player.animation_state = ANIM_ATTACK;
+    player.motion_updated_at = ctx.timestamp;
+    ctx.db.player().id().update(player.clone());
+
     log::info!("Player {} executed {} attack in direction ({}, {})",
                player_id, weapon_type, direction_x, direction_y);
 
     // Handle different weapon types
-    match weapon_type.as_str() {
+    match weapon_type {
         "Sword" => execute_sword_attack(ctx, player, direction_x, direction_y)?,
         "Axe" => execute_axe_attack(ctx, player, direction_x, direction_y)?,
         "Bow" => execute_bow_attack(ctx, player, direction_x, direction_y)?,
+        "Bomb" => execute_bomb_throw(ctx, player, direction_x, direction_y)?,
+        "Staff" => execute_staff_attack(ctx, player, direction_x, direction_y)?,
         _ => {
             log::warn!("Unknown weapon type: {}", weapon_type);
             return Ok(());
@@ -128,6 +225,25 @@ pub fn execute_attack(
     Ok(())
 }
 
+/// Point a facing vector along (dx, dy), keeping it unchanged for a zero vector
+fn face_towards(facing_x: &mut f32, facing_y: &mut f32, dx: f32, dy: f32) {
+    let length = (dx * dx + dy * dy).sqrt();
+    if length > 0.0 {
+        *facing_x = dx / length;
+        *facing_y = dy / length;
+    }
+}
+
+/// Refresh the motion hints of an enemy row after its velocity changed
+pub fn refresh_enemy_motion(enemy: &mut Enemy, now: Timestamp) {
+    let (facing_x, facing_y, animation_state) =
+        motion_hints(enemy.velocity_x, enemy.velocity_y, (enemy.facing_x, enemy.facing_y), false);
+    enemy.facing_x = facing_x;
+    enemy.facing_y = facing_y;
+    enemy.animation_state = animation_state;
+    enemy.motion_updated_at = now;
+}
+
 /// Execute sword cleave attack - wide area hitting multiple enemies
 /// Requirements 3.1: Wide cleave attacks that hit multiple enemies
 /// Requirements 7.3: Friendly fire prevention between players
@@ -298,12 +414,12 @@ fn execute_bow_attack(
 
         if updated_arrow.quantity <= 0 {
             // Remove item if no arrows left
-            ctx.db.inventory_item().id().delete(&arrow_item.id);
+            ctx.db.inventory_item().id().delete(arrow_item.id);
             log::info!("Player {} used last arrow", player.id);
         } else {
             // Update quantity
             let remaining = updated_arrow.quantity;
-            ctx.db.inventory_item().id().delete(&arrow_item.id);
+            ctx.db.inventory_item().id().delete(arrow_item.id);
             ctx.db.inventory_item().insert(updated_arrow);
             log::info!("Player {} has {} arrows remaining", player.id, remaining);
         }
@@ -331,6 +447,13 @@ fn execute_bow_attack(
         projectile_type: "Arrow".to_string(),
         map_id: player.current_map_id.clone(),
         is_active: true,
+        height: 0.0,
+        velocity_z: 0.0,
+        gravity: 0.0,
+        aoe_radius: 0.0,
+        bounces_left: 0,
+        bounce_falloff: 1.0,
+        hit_ids: Vec::new(),
     };
 
     ctx.db.projectile().insert(projectile.clone());
@@ -341,31 +464,95 @@ fn execute_bow_attack(
     Ok(())
 }
 
+/// Throw a bomb in an arc; (direction_x, direction_y) is the aim offset from the player,
+/// clamped to the bomb's max range
+fn execute_bomb_throw(
+    ctx: &ReducerContext,
+    player: Player,
+    direction_x: f32,
+    direction_y: f32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let def = crate::ballistics::lob_def(BOMB_PROJECTILE).ok_or("Bomb projectile is not defined")?;
+    if crate::inventory::remove_item_from_inventory(ctx, player.id, BOMB_ITEM, 1).is_err() {
+        log::info!("Player {} has no bombs to throw", player.id);
+        return Ok(());
+    }
+
+    let target = (player.position_x + direction_x, player.position_y + direction_y);
+    let projectile = crate::ballistics::launch_lob(def, player.id, &player.current_map_id, (player.position_x, player.position_y), target);
+    ctx.db.projectile().insert(projectile.clone());
+
+    log::info!("Player {} threw bomb {} towards ({}, {})", player.id, projectile.id, target.0, target.1);
+    Ok(())
+}
+
+/// Cast a chain bolt that bounces between nearby enemies
+fn execute_staff_attack(
+    ctx: &ReducerContext,
+    player: Player,
+    direction_x: f32,
+    direction_y: f32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let def = crate::ballistics::chain_def(STAFF_PROJECTILE).ok_or("Chain projectile is not defined")?;
+    let dir_length = (direction_x * direction_x + direction_y * direction_y).sqrt();
+    if dir_length == 0.0 {
+        log::warn!("Invalid direction vector for staff attack");
+        return Ok(());
+    }
+
+    let direction = (direction_x / dir_length, direction_y / dir_length);
+    let projectile = crate::ballistics::launch_chain(def, player.id, &player.current_map_id, (player.position_x, player.position_y), direction);
+    ctx.db.projectile().insert(projectile.clone());
+
+    log::info!("Player {} cast chain bolt {} with direction ({}, {})", player.id, projectile.id, direction.0, direction.1);
+    Ok(())
+}
+
 /// Apply damage to an enemy
 /// Requirements 3.5: Deal appropriate damage based on weapon type
 /// Requirements 7.3: Friendly fire prevention between players
-fn apply_damage_to_enemy(
+pub fn apply_damage_to_enemy(
     ctx: &ReducerContext,
     enemy_id: u32,
     damage: f32,
     attacker_id: u32,
     weapon_type: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Check if target is actually an enemy (enemy IDs >= 1000000)
-    if enemy_id < 1000000 {
+    // Buffs of the attacking player
+    let attacker_is_player = crate::character::attacking_player(ctx, attacker_id).is_some();
+    let damage = if attacker_is_player {
+        damage * outgoing_damage_multiplier(ctx, attacker_id) * crate::shadow_ban::damage_multiplier(ctx, attacker_id)
+    } else {
+        damage
+    };
+
+    // Check if target is actually a player (ids don't tell players and enemies apart)
+    if ctx.db.player().id().find(enemy_id).is_some() {
         // Target is a player - check if attacker is also a player (friendly fire prevention)
-        if attacker_id < 1000000 {
-            log::info!("Friendly fire prevented: player {} cannot damage player {}", attacker_id, enemy_id);
-            return Ok(());
+        if attacker_is_player {
+            if !crate::pvp::can_damage_player(ctx, attacker_id, enemy_id) {
+                log::info!("Friendly fire prevented: player {} cannot damage player {}", attacker_id, enemy_id);
+                return Ok(());
+            }
+            if crate::shadow_ban::is_player_shadow_banned(ctx, attacker_id) {
+                return Ok(());
+            }
+            return apply_damage_to_player_from_enemy(ctx, enemy_id, damage, attacker_id, weapon_type);
         }
 
         // Attacker is an enemy, target is a player - apply damage to player instead
-        return apply_damage_to_player_from_enemy(ctx, enemy_id, damage, attacker_id);
+        return apply_damage_to_player_from_enemy(ctx, enemy_id, damage, attacker_id, "Enemy Attack".to_string());
     }
 
     // Find and update enemy
-    if let Some(mut enemy) = ctx.db.enemy().id().find(&enemy_id) {
+    if let Some(mut enemy) = ctx.db.enemy().id().find(enemy_id) {
         enemy.health -= damage;
+        crate::run_report::record_damage(ctx, &enemy.map_id, attacker_id, damage);
+        if attacker_is_player {
+            crate::threat::add_threat(ctx, enemy_id, attacker_id, damage);
+        }
+        crate::damage_meter::record_hit(ctx, &enemy.map_id, attacker_id, enemy_id, damage, is_boss_type(&enemy.enemy_type));
+        crate::world_boss::on_world_boss_damaged(ctx, &mut enemy, attacker_id, damage);
 
         log::info!("Enemy {} took {} damage from {} ({}), health: {}/{}",
                    enemy_id, damage, attacker_id, weapon_type, enemy.health, enemy.max_health);
@@ -373,12 +560,33 @@ fn apply_damage_to_enemy(
         if enemy.health <= 0.0 {
             // Enemy is defeated
             log::info!("Enemy {} defeated by player {}", enemy_id, attacker_id);
-            ctx.db.enemy().id().delete(&enemy_id);
+            ctx.db.enemy().id().delete(enemy_id);
+            crate::threat::clear_enemy(ctx, enemy_id);
+            crate::run_report::record_enemy_defeated(ctx, &enemy.map_id, &enemy.enemy_type);
+            crate::world_boss::on_world_boss_defeated(ctx, enemy_id);
+            crate::challenge_tower::on_enemy_defeated(ctx, &enemy.map_id, enemy_id);
+            crate::tutorial::on_enemy_defeated(ctx, &enemy.map_id, enemy_id);
+            if is_boss_type(&enemy.enemy_type) {
+                crate::damage_meter::on_boss_defeated(ctx, &enemy.map_id);
+            }
+            if is_boss_type(&enemy.enemy_type) || enemy.enemy_type == crate::world_boss::WORLD_BOSS_ENEMY_TYPE {
+                crate::external_event::emit(ctx, crate::external_event::EVENT_BOSS_KILLED, &[
+                    ("enemy_type", enemy.enemy_type.clone()),
+                    ("map_id", enemy.map_id.clone()),
+                    ("killer_id", attacker_id.to_string()),
+                ]);
+            }
+            if ctx.db.player().id().find(attacker_id).is_some() {
+                crate::afk_detection::record_kill(ctx, attacker_id);
+                crate::crafting::roll_recipe_scroll_drop(ctx, attacker_id);
+                crate::bestiary::record_kill(ctx, attacker_id, &enemy.enemy_type);
+                crate::rested::grant_kill_xp(ctx, attacker_id, enemy.max_health);
+            }
 
-            // TODO: Handle loot drops and experience
+            // TODO: Handle loot drops
         } else {
             // Update enemy health
-            ctx.db.enemy().id().delete(&enemy_id);
+            ctx.db.enemy().id().delete(enemy_id);
             ctx.db.enemy().insert(enemy);
         }
 
@@ -397,7 +605,7 @@ fn apply_damage_to_enemy(
     Ok(())
 }
 
-/// Apply damage to a player from an enemy
+/// Apply damage to a player from an enemy (or another player when PvP is enabled)
 /// Requirements 8.6: Enemy damage dealing to players
 /// Requirements 9.2: Player damage application
 fn apply_damage_to_player_from_enemy(
@@ -405,34 +613,43 @@ fn apply_damage_to_player_from_enemy(
     player_id: u32,
     damage: f32,
     attacker_id: u32,
+    weapon_type: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Find the player
-    if let Some(mut player) = ctx.db.player().id().find(&player_id) {
+    if let Some(mut player) = ctx.db.player().id().find(player_id) {
         // Check if player is already downed
         if player.is_downed {
             log::warn!("Player {} is already downed, cannot take more damage", player_id);
             return Ok(());
         }
 
-        // Apply damage
+        // Apply damage (reduced by the target's buffs)
+        let damage = damage * incoming_damage_multiplier(ctx, player_id);
         player.health = (player.health - damage).max(0.0);
+        mark_in_combat(ctx, player_id);
+        crate::damage_meter::record_hit(ctx, &player.current_map_id, attacker_id, player_id, damage, false);
+        crate::death_recap::record_damage_taken(ctx, player_id, attacker_id, &weapon_type, damage);
 
         // Check if player is downed
         if player.health <= 0.0 {
             player.is_downed = true;
             log::info!("Player {} downed by enemy {}", player_id, attacker_id);
         }
+        refresh_player_motion(&mut player, ctx.timestamp);
 
         // Update player
-        ctx.db.player().id().delete(&player_id);
+        ctx.db.player().id().delete(player_id);
         ctx.db.player().insert(player.clone());
+        if player.is_downed {
+            crate::character::on_player_downed(ctx, &player, crate::character::attacking_player(ctx, attacker_id));
+        }
 
         // Record combat event
         let event = CombatEvent {
             id: generate_combat_event_id(),
             attacker_id,
             target_id: player_id,
-            weapon_type: "Enemy Attack".to_string(),
+            weapon_type,
             damage,
             timestamp: get_current_timestamp(),
         };
@@ -481,6 +698,10 @@ pub fn spawn_test_enemy(
         position_y,
         velocity_x: 0.0,
         velocity_y: 0.0,
+        facing_x: 0.0,
+        facing_y: 1.0,
+        animation_state: ANIM_IDLE,
+        motion_updated_at: ctx.timestamp,
         health: 50.0,
         max_health: 50.0,
         enemy_type: "test_enemy".to_string(),
@@ -490,6 +711,9 @@ pub fn spawn_test_enemy(
         patrol_center_x: position_x,
         patrol_center_y: position_y,
         patrol_radius: 100.0,
+        wander_target_x: position_x,
+        wander_target_y: position_y,
+        wander_resume_at: ctx.timestamp,
         detection_range: 120.0,
         leash_range: 200.0,
         target_player_id: None,
@@ -510,6 +734,17 @@ pub fn spawn_test_enemy(
     Ok(())
 }
 
+/// Id livre para inimigo gerado pelo servidor: sorteia na faixa alta e
+/// sorteia de novo se já estiver em uso
+pub fn allocate_enemy_id(ctx: &ReducerContext) -> u32 {
+    loop {
+        let enemy_id = ctx.rng().gen_range(1_000_000..u32::MAX);
+        if ctx.db.enemy().id().find(enemy_id).is_none() {
+            return enemy_id;
+        }
+    }
+}
+
 /// Generate unique enemy ID
 fn generate_enemy_id() -> u32 {
     use std::collections::hash_map::DefaultHasher;
@@ -539,6 +774,9 @@ pub fn spawn_enemy(
             "Goblin" => (30.0, 120.0, 10.0, 25.0, 80.0, 150.0),
             "Orc" => (80.0, 60.0, 25.0, 40.0, 120.0, 250.0),
             "Troll" => (150.0, 40.0, 40.0, 50.0, 100.0, 180.0),
+            "DungeonBoss" => (600.0, 50.0, 45.0, 60.0, 160.0, 400.0),
+            "WorldBoss" => (2000.0, 45.0, 60.0, 70.0, 200.0, 500.0),
+            "Mortar" => (40.0, 30.0, 30.0, 280.0, 260.0, 320.0),
             _ => (50.0, 75.0, 15.0, 30.0, 100.0, 200.0), // Default to TestEnemy
         };
 
@@ -548,6 +786,10 @@ pub fn spawn_enemy(
         position_y,
         velocity_x: 0.0,
         velocity_y: 0.0,
+        facing_x: 0.0,
+        facing_y: 1.0,
+        animation_state: ANIM_IDLE,
+        motion_updated_at: ctx.timestamp,
         health: max_health,
         max_health,
         enemy_type,
@@ -556,6 +798,9 @@ pub fn spawn_enemy(
         patrol_center_x: position_x,
         patrol_center_y: position_y,
         patrol_radius: 100.0,
+        wander_target_x: position_x,
+        wander_target_y: position_y,
+        wander_resume_at: ctx.timestamp,
         detection_range,
         leash_range,
         target_player_id: None,
@@ -585,8 +830,11 @@ pub fn remove_enemy(
 ) -> Result<(), Box<dyn std::error::Error>> {
     let _identity = ctx.sender;
 
-    if let Some(enemy) = ctx.db.enemy().id().find(&enemy_id) {
-        ctx.db.enemy().id().delete(&enemy_id);
+    if let Some(enemy) = ctx.db.enemy().id().find(enemy_id) {
+        ctx.db.enemy().id().delete(enemy_id);
+        crate::threat::clear_enemy(ctx, enemy_id);
+        crate::challenge_tower::on_enemy_removed(ctx, &enemy.map_id, enemy_id);
+        crate::tutorial::on_enemy_removed(ctx, &enemy.map_id, enemy_id);
         log::info!("Removed enemy {} from map {}", enemy_id, enemy.map_id);
     } else {
         log::warn!("Attempted to remove non-existent enemy {}", enemy_id);
@@ -595,6 +843,8 @@ pub fn remove_enemy(
     Ok(())
 }
 
+// Assinatura é a API do cliente: os campos vêm soltos
+#[allow(clippy::too_many_arguments)]
 #[reducer]
 pub fn update_enemy_ai(
     ctx: &ReducerContext,
@@ -608,12 +858,16 @@ pub fn update_enemy_ai(
     last_known_player_x: f32,
     last_known_player_y: f32,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    if let Some(mut enemy) = ctx.db.enemy().id().find(&enemy_id) {
+    if let Some(mut enemy) = ctx.db.enemy().id().find(enemy_id) {
         let mut target_map: Option<String> = None;
+        // O cliente decide se o inimigo persegue; quem ele persegue vem da tabela de ameaça
+        let target_player_id = target_player_id.map(|client_target| {
+            crate::threat::threat_target(ctx, &enemy).unwrap_or(client_target)
+        });
 
         // Se o inimigo tem um alvo, precisamos verificar em qual mapa o alvo está
         if let Some(pid) = target_player_id {
-            if let Some(player) = ctx.db.player().id().find(&pid) {
+            if let Some(player) = ctx.db.player().id().find(pid) {
                 target_map = Some(player.current_map_id.clone());
 
                 // Lógica de Transição de Estado: Chasing vs ChasingThroughMap
@@ -638,8 +892,9 @@ pub fn update_enemy_ai(
         enemy.target_map_id = target_map;
         enemy.last_known_player_x = last_known_player_x;
         enemy.last_known_player_y = last_known_player_y;
+        refresh_enemy_motion(&mut enemy, ctx.timestamp);
 
-        ctx.db.enemy().id().delete(&enemy_id);
+        ctx.db.enemy().id().delete(enemy_id);
         ctx.db.enemy().insert(enemy);
     }
     Ok(())
@@ -653,22 +908,24 @@ pub fn enemy_transition_map(
     spawn_x: f32,
     spawn_y: f32,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    if let Some(mut enemy) = ctx.db.enemy().id().find(&enemy_id) {
+    if let Some(mut enemy) = ctx.db.enemy().id().find(enemy_id) {
         // O inimigo só muda de mapa se estiver perseguindo alguém ou em alerta
         if enemy.state == "ChasingThroughMap" || enemy.state == "Alert" {
             log::info!("Enemy {} transitioned from {} to {}", enemy_id, enemy.map_id, destination_map_id);
-
-            enemy.map_id = destination_map_id;
+            let previous_map = std::mem::replace(&mut enemy.map_id, destination_map_id);
             enemy.position_x = spawn_x;
             enemy.position_y = spawn_y;
             enemy.velocity_x = 0.0;
             enemy.velocity_y = 0.0;
+            refresh_enemy_motion(&mut enemy, ctx.timestamp);
 
             // Ao entrar no novo mapa, ele entra em modo Alert para procurar o player
             enemy.state = "Alert".to_string();
 
-            ctx.db.enemy().id().delete(&enemy_id);
+            ctx.db.enemy().id().delete(enemy_id);
             ctx.db.enemy().insert(enemy);
+            crate::challenge_tower::on_enemy_removed(ctx, &previous_map, enemy_id);
+            crate::tutorial::on_enemy_removed(ctx, &previous_map, enemy_id);
         }
     }
     Ok(())
@@ -685,7 +942,7 @@ pub fn enemy_attack_player(
     let _identity = ctx.sender;
 
     // Validate enemy exists
-    let mut enemy = match ctx.db.enemy().id().find(&enemy_id) {
+    let mut enemy = match ctx.db.enemy().id().find(enemy_id) {
         Some(e) => e,
         None => {
             log::warn!("Enemy {} not found for attack", enemy_id);
@@ -694,7 +951,7 @@ pub fn enemy_attack_player(
     };
 
     // Validate player exists
-    let mut player = match ctx.db.player().id().find(&player_id) {
+    let mut player = match ctx.db.player().id().find(player_id) {
         Some(p) => p,
         None => {
             log::warn!("Player {} not found for enemy attack", player_id);
@@ -708,8 +965,10 @@ pub fn enemy_attack_player(
         return Ok(());
     }
 
-    // Apply damage to player
+    // Apply damage to player (reduced by the target's buffs)
+    let damage = damage * incoming_damage_multiplier(ctx, player_id);
     player.health -= damage;
+    mark_in_combat(ctx, player_id);
 
     log::info!("Enemy {} attacked player {} for {} damage, player health: {}/{}",
                enemy_id, player_id, damage, player.health, player.max_health);
@@ -720,15 +979,22 @@ pub fn enemy_attack_player(
         player.is_downed = true;
         log::info!("Player {} downed by enemy {}", player_id, enemy_id);
     }
+    refresh_player_motion(&mut player, ctx.timestamp);
 
     // Update enemy attack time
     enemy.last_attack_time = get_current_timestamp() as f64;
+    face_towards(&mut enemy.facing_x, &mut enemy.facing_y, player.position_x - enemy.position_x, player.position_y - enemy.position_y);
+    enemy.animation_state = ANIM_ATTACK;
+    enemy.motion_updated_at = ctx.timestamp;
 
     // Update both entities
-    ctx.db.player().id().delete(&player_id);
-    ctx.db.player().insert(player);
+    ctx.db.player().id().delete(player_id);
+    let player = ctx.db.player().insert(player);
+    if player.is_downed {
+        crate::character::on_player_downed(ctx, &player, None);
+    }
     
-    ctx.db.enemy().id().delete(&enemy_id);
+    ctx.db.enemy().id().delete(enemy_id);
     ctx.db.enemy().insert(enemy);
 
     // Record combat event
@@ -745,6 +1011,43 @@ pub fn enemy_attack_player(
     Ok(())
 }
 
+/// Artillery enemy (e.g. Mortar) lobs a shell at a point; damage happens on landing
+#[reducer]
+pub fn enemy_lob_attack(
+    ctx: &ReducerContext,
+    enemy_id: u32,
+    target_x: f32,
+    target_y: f32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut enemy = match ctx.db.enemy().id().find(enemy_id) {
+        Some(e) => e,
+        None => {
+            log::warn!("Enemy {} not found for lob attack", enemy_id);
+            return Ok(());
+        }
+    };
+    let Some(def) = crate::ballistics::enemy_lob_kind(&enemy.enemy_type).and_then(crate::ballistics::lob_def) else {
+        log::warn!("Enemy {} ({}) cannot lob projectiles", enemy_id, enemy.enemy_type);
+        return Ok(());
+    };
+    let now = get_current_timestamp() as f64;
+    if now - enemy.last_attack_time < enemy.attack_cooldown as f64 {
+        return Ok(());
+    }
+
+    let projectile = crate::ballistics::launch_lob(def, enemy_id, &enemy.map_id, (enemy.position_x, enemy.position_y), (target_x, target_y));
+    ctx.db.projectile().insert(projectile.clone());
+
+    enemy.last_attack_time = now;
+    face_towards(&mut enemy.facing_x, &mut enemy.facing_y, target_x - enemy.position_x, target_y - enemy.position_y);
+    enemy.animation_state = ANIM_ATTACK;
+    enemy.motion_updated_at = ctx.timestamp;
+    ctx.db.enemy().id().update(enemy);
+
+    log::info!("Enemy {} lobbed {} {} towards ({}, {})", enemy_id, def.kind, projectile.id, target_x, target_y);
+    Ok(())
+}
+
 #[reducer]
 pub fn create_projectile(
     ctx: &ReducerContext,
@@ -757,7 +1060,7 @@ pub fn create_projectile(
     let identity = ctx.sender;
 
     // Validate player exists and owns this identity
-    let player = match ctx.db.player().id().find(&player_id) {
+    let player = match ctx.db.player().id().find(player_id) {
         Some(p) if p.identity == identity => p,
         Some(_) => {
             log::warn!("Player {} projectile creation rejected: identity mismatch", player_id);
@@ -800,6 +1103,13 @@ pub fn create_projectile(
         projectile_type: "Arrow".to_string(),
         map_id: player.current_map_id.clone(),
         is_active: true,
+        height: 0.0,
+        velocity_z: 0.0,
+        gravity: 0.0,
+        aoe_radius: 0.0,
+        bounces_left: 0,
+        bounce_falloff: 1.0,
+        hit_ids: Vec::new(),
     };
 
     ctx.db.projectile().insert(projectile.clone());
@@ -820,7 +1130,7 @@ pub fn process_hit(
     let identity = ctx.sender;
 
     // Validate attacker exists and owns this identity
-    let _attacker = match ctx.db.player().id().find(&attacker_id) {
+    let _attacker = match ctx.db.player().id().find(attacker_id) {
         Some(p) if p.identity == identity => p,
         Some(_) => {
             log::warn!("Player {} hit processing rejected: identity mismatch", attacker_id);
@@ -841,29 +1151,44 @@ pub fn process_hit(
     Ok(())
 }
 
-/// Update all active projectiles (called periodically)
-/// Requirements 4.3: Projectile collision with enemies
-/// Requirements 4.4: Projectile collision with obstacles
+/// Passo manual dos projéteis (ferramentas de admin); o world tick já os avança
 #[reducer]
 pub fn update_projectiles(
     ctx: &ReducerContext,
     delta_time: f32,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    crate::admin::require_admin(ctx)?;
+    if crate::upgrade::is_frozen(ctx) {
+        return Ok(());
+    }
+    process_projectiles(ctx, delta_time)?;
+    Ok(())
+}
+
+/// Update all active projectiles (called every world tick)
+/// Requirements 4.3: Projectile collision with enemies
+/// Requirements 4.4: Projectile collision with obstacles
+/// Retorna os sub-passos executados, o custo no orçamento do tick
+pub fn process_projectiles(ctx: &ReducerContext, delta_time: f32) -> Result<u64, Box<dyn std::error::Error>> {
     let mut projectiles_to_remove = Vec::new();
+    let mut units = 0;
+    // Sub-passos de colisão: projéteis rápidos não atravessam alvos entre duas
+    // atualizações (menos sub-passos quando o tick está sob throttle)
+    let max_substeps = crate::tick_budget::max_projectile_substeps(ctx);
 
     // Get all active projectiles
     // Use iter() as we need to scan all projectiles
-    for projectile in ctx.db.projectile().iter() {
+    'projectiles: for projectile in ctx.db.projectile().iter() {
         if !projectile.is_active {
             continue;
         }
 
         let mut updated_projectile = projectile.clone();
-
-        // Update position
-        updated_projectile.position_x += updated_projectile.velocity_x * delta_time;
-        updated_projectile.position_y += updated_projectile.velocity_y * delta_time;
         updated_projectile.time_to_live -= delta_time;
+        let speed = (projectile.velocity_x * projectile.velocity_x + projectile.velocity_y * projectile.velocity_y).sqrt();
+        let substeps = crate::tick_budget::projectile_substeps(speed * delta_time, PROJECTILE_COLLISION_RADIUS * 2.0, max_substeps);
+        let step_time = delta_time / substeps as f32;
+        units += substeps as u64;
 
         // Check if projectile should be removed due to timeout
         if updated_projectile.time_to_live <= 0.0 {
@@ -873,49 +1198,110 @@ pub fn update_projectiles(
             continue;
         }
 
-        // Check collision with enemies
-        let mut hit_enemy = false;
-        // Optimization: only check enemies in the same map
-        for enemy in ctx.db.enemy().iter().filter(|e| e.map_id == updated_projectile.map_id) {
-            if check_projectile_enemy_collision(&updated_projectile, &enemy) {
+        // Arremessados voam por cima dos alvos e só causam dano ao pousar
+        if crate::ballistics::is_lobbed(&updated_projectile) {
+            for _ in 0..substeps {
+                if crate::ballistics::step_lob(&mut updated_projectile, step_time) {
+                    crate::ballistics::resolve_landing(ctx, &updated_projectile)?;
+                    projectiles_to_remove.push(updated_projectile.id);
+                    continue 'projectiles;
+                }
+            }
+            ctx.db.projectile().id().update(updated_projectile);
+            continue;
+        }
+
+        for _ in 0..substeps {
+            // Update position
+            updated_projectile.position_x += updated_projectile.velocity_x * step_time;
+            updated_projectile.position_y += updated_projectile.velocity_y * step_time;
+
+            // Check collision with enemies
+            // Optimization: only check enemies in the same map
+            // Projéteis em cadeia ignoram quem já atingiram
+            let hit = ctx.db.enemy().map_id().filter(&updated_projectile.map_id).find(|enemy| {
+                !updated_projectile.hit_ids.contains(&enemy.id)
+                    && check_projectile_enemy_collision(&updated_projectile, enemy)
+            });
+            if let Some(enemy) = hit {
                 // Apply damage to enemy
                 apply_damage_to_enemy(
                     ctx,
                     enemy.id,
                     updated_projectile.damage,
                     updated_projectile.owner_id,
-                    "Bow".to_string()
+                    projectile_weapon(&updated_projectile).to_string()
                 )?;
 
-                // Mark projectile for removal
-                updated_projectile.is_active = false;
-                projectiles_to_remove.push(updated_projectile.id);
-                hit_enemy = true;
-
                 log::info!("Projectile {} hit enemy {} for {} damage",
                           updated_projectile.id, enemy.id, updated_projectile.damage);
-                break;
+
+                // Salta para o próximo alvo ainda dentro deste passe de colisão
+                if crate::ballistics::try_chain(ctx, &mut updated_projectile, &enemy) {
+                    continue;
+                }
+
+                // Mark projectile for removal
+                projectiles_to_remove.push(updated_projectile.id);
+                continue 'projectiles;
             }
-        }
 
-        if hit_enemy {
-            continue;
+            // Collision with players: shields and parries can block or reflect
+            let struck = ctx.db.player().current_map_id().filter(&updated_projectile.map_id).find(|p| {
+                p.id != updated_projectile.owner_id
+                    && !p.is_downed
+                    && check_projectile_player_collision(&updated_projectile, p)
+                    && (crate::character::attacking_player(ctx, updated_projectile.owner_id).is_none()
+                        || crate::pvp::can_damage_player(ctx, updated_projectile.owner_id, p.id))
+            });
+            if let Some(target) = struck {
+                match crate::defense::deflection_for(ctx, &target, &updated_projectile) {
+                    Some(crate::defense::Deflection::Reflected) => {
+                        crate::defense::reflect(&mut updated_projectile, target.id);
+                        log::info!("Projectile {} reflected by player {}", updated_projectile.id, target.id);
+                    }
+                    Some(crate::defense::Deflection::Blocked) => {
+                        projectiles_to_remove.push(updated_projectile.id);
+                        log::info!("Projectile {} blocked by player {}", updated_projectile.id, target.id);
+                        continue 'projectiles;
+                    }
+                    None => {
+                        apply_damage_to_enemy(
+                            ctx,
+                            target.id,
+                            updated_projectile.damage,
+                            updated_projectile.owner_id,
+                            projectile_weapon(&updated_projectile).to_string()
+                        )?;
+                        projectiles_to_remove.push(updated_projectile.id);
+                        continue 'projectiles;
+                    }
+                }
+            }
         }
 
         // TODO: Check collision with obstacles/map boundaries
         // For now, assume no obstacles
 
         // Update projectile in database
-        ctx.db.projectile().id().delete(&updated_projectile.id);
+        ctx.db.projectile().id().delete(updated_projectile.id);
         ctx.db.projectile().insert(updated_projectile);
     }
 
     // Remove inactive projectiles
     for projectile_id in projectiles_to_remove {
-        ctx.db.projectile().id().delete(&projectile_id);
+        ctx.db.projectile().id().delete(projectile_id);
     }
 
-    Ok(())
+    Ok(units)
+}
+
+/// Weapon name recorded for projectile hits (combat events, death recaps)
+fn projectile_weapon(projectile: &Projectile) -> &str {
+    match projectile.projectile_type.as_str() {
+        "Arrow" => "Bow",
+        other => other,
+    }
 }
 
 /// Check collision between projectile and enemy
@@ -928,8 +1314,17 @@ fn check_projectile_enemy_collision(projectile: &Projectile, enemy: &Enemy) -> b
     distance <= PROJECTILE_COLLISION_RADIUS
 }
 
+/// Check collision between projectile and player
+fn check_projectile_player_collision(projectile: &Projectile, player: &Player) -> bool {
+    let dx = projectile.position_x - player.position_x;
+    let dy = projectile.position_y - player.position_y;
+    let distance = (dx * dx + dy * dy).sqrt();
+
+    distance <= PROJECTILE_COLLISION_RADIUS
+}
+
 /// Generate unique projectile ID
-fn generate_projectile_id() -> u32 {
+pub fn generate_projectile_id() -> u32 {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
@@ -961,6 +1356,7 @@ pub fn give_arrows_to_player(
     player_id: u32,
     quantity: i32,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    crate::upgrade::require_not_frozen(ctx)?;
     // Check if player already has arrows
     let existing_arrows: Vec<crate::inventory::InventoryItem> = ctx.db.inventory_item().iter()
         .filter(|item| item.player_id == player_id && item.item_id == "arrow")
@@ -969,8 +1365,8 @@ pub fn give_arrows_to_player(
     if let Some(arrow_item) = existing_arrows.first() {
         // Update quantity
         let mut updated_arrow = arrow_item.clone();
-        updated_arrow.quantity += quantity;
-        ctx.db.inventory_item().id().delete(&arrow_item.id);
+        updated_arrow.quantity = updated_arrow.quantity.checked_add(quantity).ok_or("Item stack is full")?;
+        ctx.db.inventory_item().id().delete(arrow_item.id);
         ctx.db.inventory_item().insert(updated_arrow);
     } else {
         // Create new arrow entry
@@ -981,6 +1377,7 @@ pub fn give_arrows_to_player(
             quantity,
             is_equipped: false,
             slot_type: "consumable".to_string(),
+            dye_color: None,
         };
         ctx.db.inventory_item().insert(new_arrow);
     }
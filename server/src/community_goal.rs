@@ -0,0 +1,191 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use crate::inventory::{count_item, remove_item_from_inventory};
+use crate::localization::{announce_localized, Message};
+use crate::map::{map_transition, set_tile_override, template_for_map, MapTransition};
+use crate::party::sender_player;
+use spacetimedb::{reducer, table, ReducerContext, SpacetimeType, Table, Timestamp};
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct GoalTile {
+    pub tile_x: u32,
+    pub tile_y: u32,
+    pub tile_id: u32,
+}
+
+/// Transição criada quando a meta é concluída (ex: a ponte reconstruída leva ao outro mapa)
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct GoalTransition {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub dest_map_id: String,
+    pub dest_x: f32,
+    pub dest_y: f32,
+}
+
+/// Meta comunitária: todos doam um item até o alvo (ex: 10.000 de madeira para
+/// reconstruir a ponte). O progresso é público; ao concluir, os tiles e
+/// transições configurados são aplicados ao mundo.
+#[table(name = community_goal, public)]
+#[derive(Clone)]
+pub struct CommunityGoal {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[unique]
+    pub name: String,
+    pub map_id: String,
+    pub item_id: String,
+    pub target_amount: u32,
+    pub contributed: u32,
+    pub tiles: Vec<GoalTile>,
+    pub transitions: Vec<GoalTransition>,
+    pub created_at: Timestamp,
+    pub completed_at: Option<Timestamp>,
+}
+
+/// Total doado por player em cada meta
+#[table(name = goal_contribution, public)]
+#[derive(Clone)]
+pub struct GoalContribution {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub goal_id: u64,
+    pub player_id: u32,
+    pub amount: u32,
+    pub last_contributed_at: Timestamp,
+}
+
+fn editable_goal(ctx: &ReducerContext, goal_id: u64) -> Result<CommunityGoal, String> {
+    let goal = ctx.db.community_goal().id().find(goal_id).ok_or("Goal not found")?;
+    if goal.completed_at.is_some() {
+        return Err("Goal already completed".to_string());
+    }
+    Ok(goal)
+}
+
+#[reducer]
+pub fn create_community_goal(ctx: &ReducerContext, name: String, map_id: String, item_id: String, target_amount: u32) -> Result<(), String> {
+    require_admin(ctx)?;
+    if target_amount == 0 {
+        return Err("Target amount must be positive".to_string());
+    }
+    template_for_map(ctx, &map_id).ok_or_else(|| format!("Map '{}' not found", map_id))?;
+    if ctx.db.community_goal().name().find(name.clone()).is_some() {
+        return Err("A goal with that name already exists".to_string());
+    }
+
+    record_audit(ctx, "community_goal", format!("Created '{}' ({} x{} in {})", name, item_id, target_amount, map_id));
+    ctx.db.community_goal().insert(CommunityGoal {
+        id: 0,
+        name,
+        map_id,
+        item_id,
+        target_amount,
+        contributed: 0,
+        tiles: Vec::new(),
+        transitions: Vec::new(),
+        created_at: ctx.timestamp,
+        completed_at: None,
+    });
+    Ok(())
+}
+
+/// Tile que muda quando a meta é concluída (ex: ponte sobre a água)
+#[reducer]
+pub fn add_goal_tile(ctx: &ReducerContext, goal_id: u64, tile_x: u32, tile_y: u32, tile_id: u32) -> Result<(), String> {
+    require_admin(ctx)?;
+    let mut goal = editable_goal(ctx, goal_id)?;
+    let template = template_for_map(ctx, &goal.map_id).ok_or_else(|| format!("Map '{}' not found", goal.map_id))?;
+    if tile_x >= template.width || tile_y >= template.height {
+        return Err("Tile out of bounds".to_string());
+    }
+    goal.tiles.retain(|t| t.tile_x != tile_x || t.tile_y != tile_y);
+    goal.tiles.push(GoalTile { tile_x, tile_y, tile_id });
+    ctx.db.community_goal().id().update(goal);
+    Ok(())
+}
+
+#[reducer]
+pub fn add_goal_transition(ctx: &ReducerContext, goal_id: u64, transition: GoalTransition) -> Result<(), String> {
+    require_admin(ctx)?;
+    let mut goal = editable_goal(ctx, goal_id)?;
+    template_for_map(ctx, &transition.dest_map_id).ok_or_else(|| format!("Map '{}' not found", transition.dest_map_id))?;
+    goal.transitions.push(transition);
+    ctx.db.community_goal().id().update(goal);
+    Ok(())
+}
+
+/// Doa itens do inventário para a meta. Só o que falta é consumido.
+#[reducer]
+pub fn contribute_to_goal(ctx: &ReducerContext, goal_id: u64, amount: u32) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    let mut goal = editable_goal(ctx, goal_id)?;
+    if amount == 0 {
+        return Err("Amount must be positive".to_string());
+    }
+    let accepted = amount.min(goal.target_amount - goal.contributed);
+    if count_item(ctx, player.id, &goal.item_id) < accepted as i32 {
+        return Err(format!("Not enough {}", goal.item_id));
+    }
+    remove_item_from_inventory(ctx, player.id, &goal.item_id, accepted as i32)?;
+
+    match ctx.db.goal_contribution().goal_id().filter(goal_id).find(|c| c.player_id == player.id) {
+        Some(mut contribution) => {
+            contribution.amount += accepted;
+            contribution.last_contributed_at = ctx.timestamp;
+            ctx.db.goal_contribution().id().update(contribution);
+        }
+        None => {
+            ctx.db.goal_contribution().insert(GoalContribution {
+                id: 0,
+                goal_id,
+                player_id: player.id,
+                amount: accepted,
+                last_contributed_at: ctx.timestamp,
+            });
+        }
+    }
+
+    goal.contributed += accepted;
+    log::info!("🪵 Player {} contributed {} {} to '{}' ({}/{})",
+               player.id, accepted, goal.item_id, goal.name, goal.contributed, goal.target_amount);
+    if goal.contributed >= goal.target_amount {
+        complete_goal(ctx, &mut goal)?;
+    }
+    ctx.db.community_goal().id().update(goal);
+    Ok(())
+}
+
+/// Aplica a mudança no mundo: tiles via WorldMutation e novas transições de mapa
+fn complete_goal(ctx: &ReducerContext, goal: &mut CommunityGoal) -> Result<(), String> {
+    goal.completed_at = Some(ctx.timestamp);
+    for tile in &goal.tiles {
+        set_tile_override(ctx, &goal.map_id, tile.tile_x, tile.tile_y, tile.tile_id)?;
+    }
+    let first_id = ctx.db.map_transition().iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    for (id, t) in (first_id..).zip(&goal.transitions) {
+        ctx.db.map_transition().insert(MapTransition {
+            id,
+            map_id: goal.map_id.clone(),
+            x: t.x,
+            y: t.y,
+            width: t.width,
+            height: t.height,
+            dest_map_id: t.dest_map_id.clone(),
+            dest_x: t.dest_x,
+            dest_y: t.dest_y,
+        });
+    }
+
+    let contributors = ctx.db.goal_contribution().goal_id().filter(goal.id).count();
+    announce_localized(ctx, "community_goal", Message::new("announce.community_goal.completed")
+        .param("goal", &goal.name)
+        .param("contributors", contributors));
+    Ok(())
+}
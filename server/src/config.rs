@@ -0,0 +1,61 @@
+use crate::admin::require_admin;
+use spacetimedb::{reducer, table, Identity, ReducerContext, Table, Timestamp};
+
+// Chaves de configuração conhecidas
+pub const ABILITY_QUEUE_WINDOW_MS: &str = "ability_queue_window_ms";
+pub const MIN_CLIENT_VERSION: &str = "min_client_version";
+pub const XP_RATE: &str = "xp_rate";
+
+/// Configuração do servidor ajustável em produção (chave -> valor)
+#[table(name = server_config, public)]
+#[derive(Clone)]
+pub struct ServerConfig {
+    #[primary_key]
+    pub key: String,
+    pub value: String,
+    pub updated_at: Timestamp,
+}
+
+pub fn get_config_string(ctx: &ReducerContext, key: &str, default: &str) -> String {
+    ctx.db.server_config().key().find(key.to_string())
+        .map(|c| c.value)
+        .unwrap_or_else(|| default.to_string())
+}
+
+pub fn get_config_u64(ctx: &ReducerContext, key: &str, default: u64) -> u64 {
+    ctx.db.server_config().key().find(key.to_string())
+        .and_then(|c| c.value.parse().ok())
+        .unwrap_or(default)
+}
+
+pub fn get_config_f32(ctx: &ReducerContext, key: &str, default: f32) -> f32 {
+    ctx.db.server_config().key().find(key.to_string())
+        .and_then(|c| c.value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Como `get_config_string`, mas respeitando a cohort da identidade em experimentos ativos
+pub fn get_player_config_string(ctx: &ReducerContext, identity: Identity, key: &str, default: &str) -> String {
+    crate::experiment::config_override(ctx, identity, key).unwrap_or_else(|| get_config_string(ctx, key, default))
+}
+
+pub fn get_player_config_f32(ctx: &ReducerContext, identity: Identity, key: &str, default: f32) -> f32 {
+    crate::experiment::config_override(ctx, identity, key)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| get_config_f32(ctx, key, default))
+}
+
+#[reducer]
+pub fn set_config(ctx: &ReducerContext, key: String, value: String) -> Result<(), String> {
+    require_admin(ctx)?;
+
+    let row = ServerConfig { key: key.clone(), value: value.clone(), updated_at: ctx.timestamp };
+    if ctx.db.server_config().key().find(key.clone()).is_some() {
+        ctx.db.server_config().key().update(row);
+    } else {
+        ctx.db.server_config().insert(row);
+    }
+
+    log::info!("⚙️ Config '{}' = '{}'", key, value);
+    Ok(())
+}
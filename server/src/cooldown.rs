@@ -0,0 +1,171 @@
+use crate::localization::Message;
+use spacetimedb::{reducer, table, Identity, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+// Categorias de cooldown suportadas pelo registro
+pub const CATEGORY_ABILITY: &str = "ability";
+pub const CATEGORY_CONSUMABLE: &str = "consumable";
+pub const CATEGORY_EMOTE: &str = "emote";
+pub const CATEGORY_TRANSITION: &str = "transition";
+pub const CATEGORY_PVP: &str = "pvp";
+pub const CATEGORY_SCENIC: &str = "scenic";
+
+// Ataques compartilham um único cooldown (trocar de arma não reseta)
+pub const ATTACK_COOLDOWN_KEY: &str = "attack";
+
+// Durações padrão (em milissegundos)
+pub const SWORD_COOLDOWN_MS: u64 = 500;
+pub const AXE_COOLDOWN_MS: u64 = 800;
+pub const BOW_COOLDOWN_MS: u64 = 600;
+pub const BOMB_COOLDOWN_MS: u64 = 1500;
+pub const STAFF_COOLDOWN_MS: u64 = 900;
+pub const CONSUMABLE_COOLDOWN_MS: u64 = 3000;
+pub const EMOTE_COOLDOWN_MS: u64 = 2000;
+pub const TRANSITION_COOLDOWN_MS: u64 = 1000;
+pub const PVP_FLAG_COOLDOWN_MS: u64 = 60_000;
+pub const SCENIC_MARKER_COOLDOWN_MS: u64 = 300_000;
+pub const PARRY_COOLDOWN_MS: u64 = 1500;
+pub const DASH_COOLDOWN_MS: u64 = 4000;
+pub const JUMP_COOLDOWN_MS: u64 = 800;
+pub const BULWARK_COOLDOWN_MS: u64 = 20_000;
+pub const TAUNT_COOLDOWN_MS: u64 = 8000;
+pub const SHOUT_COOLDOWN_MS: u64 = 12_000;
+pub const ALLY_HEAL_COOLDOWN_MS: u64 = 2500;
+pub const CLEANSE_COOLDOWN_MS: u64 = 8000;
+
+/// Cooldown ativo de uma ação para uma identidade. Fica na tabela (e não só no
+/// cliente) para que reconectar não zere nada.
+/// Clientes calculam o tempo restante a partir de `ready_at`; `duration_ms`
+/// permite desenhar o progresso sem depender do relógio local.
+#[table(name = cooldown, public)]
+#[derive(Clone)]
+pub struct Cooldown {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub category: String,   // "ability", "consumable", "emote", "transition", "pvp"
+    pub action_key: String, // Ex: "attack", "health_potion", "map_transition"
+    pub started_at: Timestamp,
+    pub ready_at: Timestamp,
+    pub duration_ms: u64,
+}
+
+fn find_cooldown(ctx: &ReducerContext, identity: Identity, category: &str, action_key: &str) -> Option<Cooldown> {
+    ctx.db.cooldown().identity().filter(&identity)
+        .find(|c| c.category == category && c.action_key == action_key)
+}
+
+/// Tempo restante do cooldown, ou `None` se a ação já está liberada.
+pub fn remaining_cooldown(ctx: &ReducerContext, identity: Identity, category: &str, action_key: &str) -> Option<Duration> {
+    find_cooldown(ctx, identity, category, action_key)
+        .and_then(|c| c.ready_at.duration_since(ctx.timestamp))
+        .filter(|d| !d.is_zero())
+}
+
+pub fn is_on_cooldown(ctx: &ReducerContext, identity: Identity, category: &str, action_key: &str) -> bool {
+    remaining_cooldown(ctx, identity, category, action_key).is_some()
+}
+
+/// Inicia (ou reinicia) o cooldown de uma ação.
+pub fn start_cooldown(ctx: &ReducerContext, identity: Identity, category: &str, action_key: &str, duration_ms: u64) {
+    let ready_at = ctx.timestamp + TimeDuration::from_duration(Duration::from_millis(duration_ms));
+
+    if let Some(mut existing) = find_cooldown(ctx, identity, category, action_key) {
+        existing.started_at = ctx.timestamp;
+        existing.ready_at = ready_at;
+        existing.duration_ms = duration_ms;
+        ctx.db.cooldown().id().update(existing);
+    } else {
+        ctx.db.cooldown().insert(Cooldown {
+            id: 0,
+            identity,
+            category: category.to_string(),
+            action_key: action_key.to_string(),
+            started_at: ctx.timestamp,
+            ready_at,
+            duration_ms,
+        });
+    }
+}
+
+/// Verifica o cooldown e, se liberado, já o inicia.
+/// Retorna `Err` com o tempo restante quando a ação ainda não pode ser usada.
+pub fn try_start_cooldown(
+    ctx: &ReducerContext,
+    identity: Identity,
+    category: &str,
+    action_key: &str,
+    duration_ms: u64,
+) -> Result<(), String> {
+    if let Some(remaining) = remaining_cooldown(ctx, identity, category, action_key) {
+        return Err(Message::new("error.on_cooldown")
+            .param("category", category)
+            .param("action", action_key)
+            .param("remaining_ms", remaining.as_millis())
+            .into());
+    }
+    start_cooldown(ctx, identity, category, action_key, duration_ms);
+    Ok(())
+}
+
+/// Remove os cooldowns já expirados de uma identidade
+pub fn clear_expired_cooldowns(ctx: &ReducerContext, identity: Identity) {
+    let expired: Vec<u64> = ctx.db.cooldown().identity().filter(&identity)
+        .filter(|c| c.ready_at <= ctx.timestamp)
+        .map(|c| c.id)
+        .collect();
+
+    for id in expired {
+        ctx.db.cooldown().id().delete(id);
+    }
+}
+
+/// Leva os cooldowns ativos para a nova identidade do player (reclaim), para
+/// que trocar de identidade não sirva para zerá-los. Vale o que terminar depois.
+pub fn transfer_cooldowns(ctx: &ReducerContext, from: Identity, to: Identity) {
+    let active: Vec<Cooldown> = ctx.db.cooldown().identity().filter(&from).collect();
+    for mut cooldown in active {
+        if cooldown.ready_at <= ctx.timestamp {
+            ctx.db.cooldown().id().delete(cooldown.id);
+            continue;
+        }
+        match find_cooldown(ctx, to, &cooldown.category, &cooldown.action_key) {
+            Some(existing) if existing.ready_at >= cooldown.ready_at => {
+                ctx.db.cooldown().id().delete(cooldown.id);
+            }
+            Some(existing) => {
+                ctx.db.cooldown().id().delete(existing.id);
+                cooldown.identity = to;
+                ctx.db.cooldown().id().update(cooldown);
+            }
+            None => {
+                cooldown.identity = to;
+                ctx.db.cooldown().id().update(cooldown);
+            }
+        }
+    }
+}
+
+pub fn attack_cooldown_ms(weapon_type: &str) -> u64 {
+    match weapon_type {
+        "Sword" => SWORD_COOLDOWN_MS,
+        "Axe" => AXE_COOLDOWN_MS,
+        "Bow" => BOW_COOLDOWN_MS,
+        "Bomb" => BOMB_COOLDOWN_MS,
+        "Staff" => STAFF_COOLDOWN_MS,
+        _ => SWORD_COOLDOWN_MS,
+    }
+}
+
+/// Emote simples com cooldown (o cliente anima a partir do log/evento)
+#[reducer]
+pub fn play_emote(ctx: &ReducerContext, emote_id: String) -> Result<(), String> {
+    if !crate::client_compat::client_supports(ctx, ctx.sender, crate::client_compat::FEATURE_EMOTES) {
+        return Err("Emotes require a newer client".to_string());
+    }
+    try_start_cooldown(ctx, ctx.sender, CATEGORY_EMOTE, &emote_id, EMOTE_COOLDOWN_MS)?;
+    log::info!("💬 {:?} played emote '{}'", ctx.sender, emote_id);
+    Ok(())
+}
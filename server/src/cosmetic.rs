@@ -0,0 +1,108 @@
+use crate::account_unlock::{has_account_unlock, record_account_unlock, UNLOCK_COSMETIC};
+use crate::inventory::{inventory_item, player_equipment, remove_item_from_inventory};
+use crate::party::sender_player;
+use spacetimedb::{reducer, table, ReducerContext, SpacetimeType, Table};
+
+/// Tinturas disponíveis e a cor (hex) que aplicam
+const DYES: &[(&str, &str)] = &[
+    ("dye_red", "#c0392b"),
+    ("dye_blue", "#2e86de"),
+    ("dye_green", "#27ae60"),
+    ("dye_black", "#1e1e1e"),
+    ("dye_white", "#f5f5f5"),
+];
+
+/// Tipos de slot que aceitam tintura
+const DYEABLE_SLOTS: &[&str] = &["weapon", "tool", "armor", "accessory", "bag"];
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct AppearanceSlot {
+    pub slot: String,
+    pub item_id: String,
+    pub dye_color: Option<String>,
+}
+
+/// Aparência visível do player (equipamento + cores), lida por todos os clientes
+#[table(name = player_appearance, public)]
+#[derive(Clone)]
+pub struct PlayerAppearance {
+    #[primary_key]
+    pub player_id: u32,
+    pub slots: Vec<AppearanceSlot>,
+}
+
+pub fn dye_color(dye_item_id: &str) -> Option<&'static str> {
+    DYES.iter().find(|(id, _)| *id == dye_item_id).map(|(_, color)| *color)
+}
+
+/// Recalcula a aparência a partir do equipamento atual
+pub fn refresh_appearance(ctx: &ReducerContext, player_id: u32) {
+    let Some(equipment) = ctx.db.player_equipment().player_id().find(player_id) else {
+        ctx.db.player_appearance().player_id().delete(player_id);
+        return;
+    };
+    let worn = [
+        ("main_hand", equipment.main_hand_weapon),
+        ("off_hand", equipment.off_hand_tool),
+        ("armor", equipment.armor),
+        ("accessory", equipment.accessory),
+        ("bag", equipment.bag),
+    ];
+    let slots: Vec<AppearanceSlot> = worn.into_iter()
+        .filter(|(_, item_id)| !item_id.is_empty())
+        .map(|(slot, item_id)| {
+            let dye_color = ctx.db.inventory_item().player_id().filter(player_id)
+                .find(|i| i.item_id == item_id && i.is_equipped)
+                .and_then(|i| i.dye_color);
+            AppearanceSlot { slot: slot.to_string(), item_id, dye_color }
+        })
+        .collect();
+
+    let appearance = PlayerAppearance { player_id, slots };
+    if ctx.db.player_appearance().player_id().find(player_id).is_some() {
+        ctx.db.player_appearance().player_id().update(appearance);
+    } else {
+        ctx.db.player_appearance().insert(appearance);
+    }
+}
+
+/// Grava a cor no item. Cor já desbloqueada pela conta sai de graça; as
+/// demais consomem uma tintura.
+#[reducer]
+pub fn dye_item(ctx: &ReducerContext, inventory_item_id: u32, dye_item_id: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    let mut item = ctx.db.inventory_item().id().find(inventory_item_id)
+        .filter(|i| i.player_id == player.id)
+        .ok_or("Item not found")?;
+    if !DYEABLE_SLOTS.contains(&item.slot_type.as_str()) {
+        return Err(format!("'{}' cannot be dyed", item.item_id));
+    }
+    let color = dye_color(&dye_item_id).ok_or_else(|| format!("'{}' is not a dye", dye_item_id))?;
+
+    if !has_account_unlock(ctx, player.identity, UNLOCK_COSMETIC, color) {
+        remove_item_from_inventory(ctx, player.id, &dye_item_id, 1)?;
+    }
+    item.dye_color = Some(color.to_string());
+    ctx.db.inventory_item().id().update(item);
+    refresh_appearance(ctx, player.id);
+    record_account_unlock(ctx, player.id, UNLOCK_COSMETIC, color);
+    log::info!("🎨 Player {} dyed item {} {}", player.id, inventory_item_id, color);
+    Ok(())
+}
+
+/// Remove a cor do item (a tintura não é devolvida)
+#[reducer]
+pub fn remove_dye(ctx: &ReducerContext, inventory_item_id: u32) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let mut item = ctx.db.inventory_item().id().find(inventory_item_id)
+        .filter(|i| i.player_id == player.id)
+        .ok_or("Item not found")?;
+    if item.dye_color.is_none() {
+        return Err("Item is not dyed".to_string());
+    }
+    item.dye_color = None;
+    ctx.db.inventory_item().id().update(item);
+    refresh_appearance(ctx, player.id);
+    Ok(())
+}
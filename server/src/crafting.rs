@@ -0,0 +1,261 @@
+use crate::inventory::{count_item, remove_item_from_inventory};
+use crate::loot_pity::{pity_chance, record_roll, LOOT_TABLE_RECIPE_SCROLL};
+use crate::party::sender_player;
+use crate::profession::{grant_profession_xp, material_cost, profession_level, roll_extra_yield, PROFESSION_ALCHEMY, PROFESSION_BLACKSMITHING, PROFESSION_COOKING};
+use crate::reputation::{require_reputation, FACTION_TAVERN_GUILD, FACTION_TOWN_GUARD, REP_FRIENDLY, REP_HONORED};
+use spacetimedb::rand::Rng;
+use spacetimedb::{reducer, table, ReducerContext, SpacetimeType, Table, Timestamp};
+
+/// Prefixo dos itens de pergaminho: "recipe_scroll_<recipe_id>"
+pub const RECIPE_SCROLL_PREFIX: &str = "recipe_scroll_";
+/// Chance de um inimigo derrotado deixar um pergaminho de receita
+const SCROLL_DROP_CHANCE: f64 = 0.05;
+const CRAFT_PROFESSION_XP: u64 = 15;
+
+// Chances de crítico/falha, ajustadas por nível da profissão, estação e buffs
+const BASE_CRIT_CHANCE: f32 = 0.05;
+const CRIT_PER_LEVEL: f32 = 0.003;
+const CRIT_PER_STATION_TIER: f32 = 0.05;
+const BASE_FAIL_CHANCE: f32 = 0.15;
+const FAIL_REDUCTION_PER_LEVEL: f32 = 0.005;
+const FAIL_REDUCTION_PER_STATION_TIER: f32 = 0.05;
+/// Fração dos materiais perdida em uma falha
+const FAIL_MATERIAL_LOSS: f32 = 0.5;
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub enum CraftOutcome {
+    Success,
+    CriticalSuccess,
+    Failure,
+}
+
+/// Resultado da última tentativa de crafting de cada player (feedback para o cliente)
+#[table(name = craft_result, public)]
+#[derive(Clone)]
+pub struct CraftResult {
+    #[primary_key]
+    pub player_id: u32,
+    pub recipe_id: String,
+    pub outcome: CraftOutcome,
+    pub quantity: i32,
+    pub crafted_at: Timestamp,
+}
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct ItemStack {
+    pub item_id: String,
+    pub quantity: i32,
+}
+
+/// Receitas de crafting (recriadas no init a partir de RECIPES)
+#[table(name = recipe, public)]
+#[derive(Clone)]
+pub struct Recipe {
+    #[primary_key]
+    pub id: String,
+    pub profession: String,
+    pub ingredients: Vec<ItemStack>,
+    pub output_item: String,
+    pub output_quantity: i32,
+    pub required_faction: String, // vazio = sem requisito
+    pub required_reputation: i32,
+    pub known_by_default: bool,
+}
+
+/// Receitas aprendidas por cada player (receitas padrão não precisam de linha)
+#[table(name = known_recipe, public)]
+#[derive(Clone)]
+pub struct KnownRecipe {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub recipe_id: String,
+    pub learned_at: Timestamp,
+}
+
+struct RecipeDef {
+    id: &'static str,
+    profession: &'static str,
+    ingredients: &'static [(&'static str, i32)],
+    output: (&'static str, i32),
+    reputation: Option<(&'static str, i32)>,
+    known_by_default: bool,
+}
+
+const RECIPES: &[RecipeDef] = &[
+    RecipeDef { id: "health_potion", profession: PROFESSION_ALCHEMY, ingredients: &[("fruit", 3)], output: ("health_potion", 1), reputation: None, known_by_default: true },
+    RecipeDef { id: "fruit_pie", profession: PROFESSION_COOKING, ingredients: &[("fruit", 4), ("wood", 1)], output: ("fruit_pie", 1), reputation: None, known_by_default: true },
+    RecipeDef { id: "ice_box", profession: PROFESSION_BLACKSMITHING, ingredients: &[("stone", 4), ("wood", 2)], output: ("ice_box", 1), reputation: None, known_by_default: true },
+    RecipeDef { id: "small_bag", profession: PROFESSION_BLACKSMITHING, ingredients: &[("wood", 6), ("stone_fragment", 2)], output: ("small_bag", 1), reputation: None, known_by_default: false },
+    RecipeDef { id: "blueprint_fence", profession: PROFESSION_BLACKSMITHING, ingredients: &[("wood", 4)], output: ("blueprint_fence", 1), reputation: None, known_by_default: true },
+    RecipeDef { id: "blueprint_bridge", profession: PROFESSION_BLACKSMITHING, ingredients: &[("wood", 10), ("stone", 2)], output: ("blueprint_bridge", 1), reputation: Some((FACTION_TOWN_GUARD, REP_FRIENDLY)), known_by_default: false },
+    RecipeDef { id: "portal_key_common", profession: PROFESSION_BLACKSMITHING, ingredients: &[("stone", 5), ("wood", 5)], output: ("portal_key_common", 1), reputation: Some((FACTION_TAVERN_GUILD, REP_FRIENDLY)), known_by_default: false },
+    RecipeDef { id: "mega_health_potion", profession: PROFESSION_ALCHEMY, ingredients: &[("health_potion", 2), ("fruit", 2)], output: ("mega_health_potion", 1), reputation: Some((FACTION_TAVERN_GUILD, REP_HONORED)), known_by_default: false },
+];
+
+pub fn seed_recipes(ctx: &ReducerContext) {
+    for recipe in ctx.db.recipe().iter() {
+        ctx.db.recipe().id().delete(recipe.id);
+    }
+    for def in RECIPES {
+        let (faction, required) = def.reputation.unwrap_or(("", 0));
+        ctx.db.recipe().insert(Recipe {
+            id: def.id.to_string(),
+            profession: def.profession.to_string(),
+            ingredients: def.ingredients.iter()
+                .map(|(item_id, quantity)| ItemStack { item_id: item_id.to_string(), quantity: *quantity })
+                .collect(),
+            output_item: def.output.0.to_string(),
+            output_quantity: def.output.1,
+            required_faction: faction.to_string(),
+            required_reputation: required,
+            known_by_default: def.known_by_default,
+        });
+    }
+}
+
+pub fn knows_recipe(ctx: &ReducerContext, player_id: u32, recipe: &Recipe) -> bool {
+    recipe.known_by_default
+        || ctx.db.known_recipe().player_id().filter(player_id).any(|k| k.recipe_id == recipe.id)
+}
+
+pub fn scroll_item_for(recipe_id: &str) -> String {
+    format!("{}{}", RECIPE_SCROLL_PREFIX, recipe_id)
+}
+
+/// Sorteia um pergaminho de receita para quem derrotou um inimigo.
+/// Cada falha aumenta um pouco a chance seguinte (pity) até o pergaminho cair.
+pub fn roll_recipe_scroll_drop(ctx: &ReducerContext, player_id: u32) {
+    let learnable: Vec<Recipe> = ctx.db.recipe().iter().filter(|r| !r.known_by_default).collect();
+    if learnable.is_empty() {
+        return;
+    }
+    let chance = pity_chance(ctx, player_id, LOOT_TABLE_RECIPE_SCROLL, SCROLL_DROP_CHANCE)
+        * crate::afk_detection::kill_returns(ctx, player_id) as f64;
+    let dropped = ctx.rng().gen_bool(chance);
+    record_roll(ctx, player_id, LOOT_TABLE_RECIPE_SCROLL, dropped);
+    if !dropped {
+        return;
+    }
+    let recipe = &learnable[ctx.rng().gen_range(0..learnable.len())];
+    let scroll = scroll_item_for(&recipe.id);
+    match crate::inventory::add_item_to_inventory(ctx, player_id, scroll.clone(), 1) {
+        Ok(()) => log::info!("📜 Player {} found {}", player_id, scroll),
+        Err(e) => log::warn!("Could not give {} to player {}: {}", scroll, player_id, e),
+    }
+}
+
+/// Consome um pergaminho e aprende a receita. A reputação é verificada aqui, no aprendizado.
+#[reducer]
+pub fn learn_recipe(ctx: &ReducerContext, recipe_id: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    let recipe = ctx.db.recipe().id().find(recipe_id.clone()).ok_or("Unknown recipe")?;
+    if knows_recipe(ctx, player.id, &recipe) {
+        return Err("Recipe already known".to_string());
+    }
+    require_reputation(ctx, player.id, &recipe.required_faction, recipe.required_reputation)?;
+
+    remove_item_from_inventory(ctx, player.id, &scroll_item_for(&recipe.id), 1)
+        .map_err(|_| format!("Requires a {} scroll", recipe.id))?;
+    ctx.db.known_recipe().insert(KnownRecipe {
+        id: 0,
+        player_id: player.id,
+        recipe_id: recipe.id.clone(),
+        learned_at: ctx.timestamp,
+    });
+
+    log::info!("📖 Player {} learned recipe {}", player.id, recipe.id);
+    Ok(())
+}
+
+/// Sorteia o resultado com o RNG determinístico do reducer
+fn roll_craft_outcome(ctx: &ReducerContext, player: &crate::Player, recipe: &Recipe) -> CraftOutcome {
+    let level = profession_level(ctx, player.id, &recipe.profession) as f32;
+    let station = crate::structure::crafting_station_tier(ctx, player) as f32;
+    let focus = crate::status_effect::crafting_focus_bonus(ctx, player.id);
+
+    let crit = (BASE_CRIT_CHANCE + level * CRIT_PER_LEVEL + station * CRIT_PER_STATION_TIER + focus).clamp(0.0, 1.0);
+    let fail = (BASE_FAIL_CHANCE - level * FAIL_REDUCTION_PER_LEVEL - station * FAIL_REDUCTION_PER_STATION_TIER).clamp(0.0, 1.0 - crit);
+
+    let roll: f32 = ctx.rng().gen();
+    if roll < crit {
+        CraftOutcome::CriticalSuccess
+    } else if roll < crit + fail {
+        CraftOutcome::Failure
+    } else {
+        CraftOutcome::Success
+    }
+}
+
+fn record_craft_result(ctx: &ReducerContext, player_id: u32, recipe_id: &str, outcome: CraftOutcome, quantity: i32) {
+    let result = CraftResult {
+        player_id,
+        recipe_id: recipe_id.to_string(),
+        outcome,
+        quantity,
+        crafted_at: ctx.timestamp,
+    };
+    if ctx.db.craft_result().player_id().find(player_id).is_some() {
+        ctx.db.craft_result().player_id().update(result);
+    } else {
+        ctx.db.craft_result().insert(result);
+    }
+}
+
+#[reducer]
+pub fn craft_item(ctx: &ReducerContext, recipe_id: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    if player.is_downed {
+        return Err("Cannot craft while downed".to_string());
+    }
+    let recipe = ctx.db.recipe().id().find(recipe_id.clone()).ok_or("Unknown recipe")?;
+    if !knows_recipe(ctx, player.id, &recipe) {
+        return Err("Recipe not known".to_string());
+    }
+
+    // Custo já com o perk "frugal" da profissão
+    let costs: Vec<ItemStack> = recipe.ingredients.iter()
+        .map(|i| ItemStack {
+            item_id: i.item_id.clone(),
+            quantity: material_cost(ctx, player.id, &recipe.profession, i.quantity),
+        })
+        .collect();
+    for ingredient in &costs {
+        if count_item(ctx, player.id, &ingredient.item_id) < ingredient.quantity {
+            return Err(format!("Missing {} x{}", ingredient.item_id, ingredient.quantity));
+        }
+    }
+
+    let outcome = roll_craft_outcome(ctx, &player, &recipe);
+    grant_profession_xp(ctx, player.id, &recipe.profession, CRAFT_PROFESSION_XP);
+
+    if outcome == CraftOutcome::Failure {
+        // Falha: perde parte dos materiais e não produz nada (retorna Ok para persistir a perda)
+        for ingredient in &costs {
+            let lost = ((ingredient.quantity as f32 * FAIL_MATERIAL_LOSS).ceil() as i32).max(1);
+            remove_item_from_inventory(ctx, player.id, &ingredient.item_id, lost)?;
+        }
+        record_craft_result(ctx, player.id, &recipe.id, CraftOutcome::Failure, 0);
+        log::info!("💥 Player {} failed to craft {}", player.id, recipe.output_item);
+        return Ok(());
+    }
+
+    for ingredient in &costs {
+        remove_item_from_inventory(ctx, player.id, &ingredient.item_id, ingredient.quantity)?;
+    }
+
+    let mut quantity = recipe.output_quantity + roll_extra_yield(ctx, player.id, &recipe.profession, recipe.output_quantity);
+    if outcome == CraftOutcome::CriticalSuccess {
+        quantity += recipe.output_quantity;
+    }
+    crate::inventory::add_item_to_inventory(ctx, player.id, recipe.output_item.clone(), quantity)
+        .map_err(|e| e.to_string())?;
+    record_craft_result(ctx, player.id, &recipe.id, outcome.clone(), quantity);
+
+    log::info!("⚒️ Player {} crafted {} x{} ({:?})", player.id, recipe.output_item, quantity, outcome);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::localization::Message;
+use spacetimedb::{table, ReducerContext, Table};
+
+pub const CURRENCY_GOLD: &str = "gold";
+/// Moeda de eventos (invasões, eventos sazonais)
+pub const CURRENCY_EVENT_TOKEN: &str = "event_token";
+/// Ganha por mentores enquanto agrupados com o aprendiz
+pub const CURRENCY_MENTOR_TOKEN: &str = "mentor_token";
+
+/// Origem de movimentos que só trocam moeda de mãos (custódia entre players);
+/// não entram no relatório de faucets e sinks
+pub const SOURCE_TRANSFER: &str = "transfer";
+
+/// Saldo de cada moeda por player
+#[table(name = player_currency, public)]
+#[derive(Clone)]
+pub struct PlayerCurrency {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub currency: String,
+    pub amount: u64,
+}
+
+fn find_balance(ctx: &ReducerContext, player_id: u32, currency: &str) -> Option<PlayerCurrency> {
+    ctx.db.player_currency().player_id().filter(player_id).find(|c| c.currency == currency)
+}
+
+pub fn balance(ctx: &ReducerContext, player_id: u32, currency: &str) -> u64 {
+    find_balance(ctx, player_id, currency).map(|c| c.amount).unwrap_or(0)
+}
+
+/// Credita o valor; `source` identifica a origem no relatório de economia
+pub fn add_currency(ctx: &ReducerContext, player_id: u32, currency: &str, amount: u64, source: &str) {
+    if source != SOURCE_TRANSFER {
+        crate::economy::record_flow(ctx, currency, source, true, amount);
+    }
+    match find_balance(ctx, player_id, currency) {
+        Some(mut row) => {
+            row.amount = row.amount.saturating_add(amount);
+            ctx.db.player_currency().id().update(row);
+        }
+        None => {
+            ctx.db.player_currency().insert(PlayerCurrency {
+                id: 0,
+                player_id,
+                currency: currency.to_string(),
+                amount,
+            });
+        }
+    }
+}
+
+/// Debita o valor; falha sem alterar nada se o saldo não cobrir
+pub fn spend_currency(ctx: &ReducerContext, player_id: u32, currency: &str, amount: u64, source: &str) -> Result<(), String> {
+    let mut row = find_balance(ctx, player_id, currency)
+        .filter(|c| c.amount >= amount)
+        .ok_or_else(|| String::from(Message::new("error.not_enough_currency").param("currency", currency)))?;
+    row.amount -= amount;
+    ctx.db.player_currency().id().update(row);
+    if source != SOURCE_TRANSFER {
+        crate::economy::record_flow(ctx, currency, source, false, amount);
+    }
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use crate::character::attacking_player;
+use crate::player;
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+/// Sem dano/cura no mapa por esse tempo, o encontro é encerrado
+pub const ENCOUNTER_LOCKOUT_SECS: u64 = 10;
+
+pub const ENCOUNTER_IN_PROGRESS: &str = "in_progress";
+pub const ENCOUNTER_CLEARED: &str = "cleared";
+pub const ENCOUNTER_WIPE: &str = "wipe";
+pub const ENCOUNTER_TIMEOUT: &str = "timeout";
+
+/// Um encontro de combate em um mapa. Começa no primeiro golpe (ou quando um
+/// boss é engajado) e termina com a morte do boss, um wipe ou o lockout.
+#[table(name = encounter, public)]
+#[derive(Clone)]
+pub struct Encounter {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub map_id: String,
+    pub boss: bool,
+    pub outcome: String,
+    pub started_at: Timestamp,
+    pub last_activity_at: Timestamp,
+    pub ended_at: Option<Timestamp>,
+}
+
+/// Resumo por player no encontro (o que o medidor de dano do cliente exibe)
+#[table(name = encounter_stat, public)]
+#[derive(Clone)]
+pub struct EncounterStat {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub encounter_id: u64,
+    pub player_id: u32,
+    pub damage_done: f32,
+    pub healing_done: f32,
+    pub damage_taken: f32,
+}
+
+pub fn open_encounter(ctx: &ReducerContext, map_id: &str) -> Option<Encounter> {
+    ctx.db.encounter().map_id().filter(map_id).find(|e| e.ended_at.is_none())
+}
+
+fn end_encounter(ctx: &ReducerContext, mut encounter: Encounter, outcome: &str) {
+    encounter.outcome = outcome.to_string();
+    encounter.ended_at = Some(ctx.timestamp);
+    log::info!("📈 Encounter {} in {} ended: {}", encounter.id, encounter.map_id, outcome);
+    ctx.db.encounter().id().update(encounter);
+}
+
+/// Encontro ativo do mapa, abrindo um novo se preciso. Engajar um boss
+/// durante um encontro comum inicia um encontro de boss separado.
+fn active_encounter(ctx: &ReducerContext, map_id: &str, boss: bool) -> Encounter {
+    if let Some(mut encounter) = open_encounter(ctx, map_id) {
+        if boss && !encounter.boss {
+            end_encounter(ctx, encounter, ENCOUNTER_CLEARED);
+        } else {
+            encounter.last_activity_at = ctx.timestamp;
+            ctx.db.encounter().id().update(encounter.clone());
+            return encounter;
+        }
+    }
+    ctx.db.encounter().insert(Encounter {
+        id: 0,
+        map_id: map_id.to_string(),
+        boss,
+        outcome: ENCOUNTER_IN_PROGRESS.to_string(),
+        started_at: ctx.timestamp,
+        last_activity_at: ctx.timestamp,
+        ended_at: None,
+    })
+}
+
+fn update_stat(ctx: &ReducerContext, encounter_id: u64, player_id: u32, apply: impl FnOnce(&mut EncounterStat)) {
+    match ctx.db.encounter_stat().encounter_id().filter(encounter_id).find(|s| s.player_id == player_id) {
+        Some(mut stat) => {
+            apply(&mut stat);
+            ctx.db.encounter_stat().id().update(stat);
+        }
+        None => {
+            let mut stat = EncounterStat { id: 0, encounter_id, player_id, damage_done: 0.0, healing_done: 0.0, damage_taken: 0.0 };
+            apply(&mut stat);
+            ctx.db.encounter_stat().insert(stat);
+        }
+    }
+}
+
+/// Golpe aplicado no mapa: conta dano causado (atacante player) e recebido (alvo player)
+pub fn record_hit(ctx: &ReducerContext, map_id: &str, attacker_id: u32, target_id: u32, amount: f32, boss: bool) {
+    crate::replay::record_replay_event(ctx, map_id, crate::replay::REPLAY_EVENT_HIT, attacker_id, target_id, amount);
+    let encounter = active_encounter(ctx, map_id, boss);
+    if let Some(player_id) = attacking_player(ctx, attacker_id) {
+        update_stat(ctx, encounter.id, player_id, |s| s.damage_done += amount);
+    }
+    if attacking_player(ctx, target_id).is_some() {
+        update_stat(ctx, encounter.id, target_id, |s| s.damage_taken += amount);
+    }
+}
+
+/// Cura efetiva; só conta dentro de um encontro já aberto
+pub fn record_healing(ctx: &ReducerContext, map_id: &str, player_id: u32, amount: f32) {
+    if amount <= 0.0 {
+        return;
+    }
+    if let Some(encounter) = open_encounter(ctx, map_id) {
+        update_stat(ctx, encounter.id, player_id, |s| s.healing_done += amount);
+    }
+}
+
+pub fn on_boss_defeated(ctx: &ReducerContext, map_id: &str) {
+    if let Some(encounter) = open_encounter(ctx, map_id).filter(|e| e.boss) {
+        end_encounter(ctx, encounter, ENCOUNTER_CLEARED);
+    }
+}
+
+/// Chamado depois que um player caiu: todos os players do mapa caídos = wipe
+pub fn on_player_downed(ctx: &ReducerContext, map_id: &str) {
+    let Some(encounter) = open_encounter(ctx, map_id) else { return };
+    if ctx.db.player().iter().filter(|p| p.current_map_id == map_id).all(|p| p.is_downed) {
+        end_encounter(ctx, encounter, ENCOUNTER_WIPE);
+    }
+}
+
+/// Checagem por tick: encerra encontros sem atividade dentro do lockout
+pub fn close_idle_encounters(ctx: &ReducerContext) {
+    let lockout = Duration::from_secs(ENCOUNTER_LOCKOUT_SECS);
+    let idle: Vec<Encounter> = ctx.db.encounter().iter()
+        .filter(|e| e.ended_at.is_none())
+        .filter(|e| ctx.timestamp.duration_since(e.last_activity_at).is_some_and(|d| d >= lockout))
+        .collect();
+    for encounter in idle {
+        end_encounter(ctx, encounter, ENCOUNTER_TIMEOUT);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::combat::enemy;
+use crate::player;
+use spacetimedb::{table, ReducerContext, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+/// Janela de dano guardada para o recap
+pub const RECAP_WINDOW_SECS: u64 = 10;
+/// Recaps mantidos por player (os mais antigos são descartados)
+const RECAPS_PER_PLAYER: usize = 5;
+
+/// Buffer curto de golpes recebidos por players, consumido pelo recap
+#[table(name = recent_damage)]
+#[derive(Clone)]
+pub struct RecentDamage {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub target_id: u32,
+    pub source_id: u32,
+    pub source_name: String,
+    pub ability: String,
+    pub amount: f32,
+    pub at: Timestamp,
+}
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct RecapEntry {
+    pub source_id: u32,
+    pub source_name: String,
+    pub ability: String,
+    pub amount: f32,
+    pub at: Timestamp,
+}
+
+/// O que derrubou o player: golpes dos últimos `RECAP_WINDOW_SECS` antes da queda
+#[table(name = death_recap, public)]
+#[derive(Clone)]
+pub struct DeathRecap {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub map_id: String,
+    pub killer_id: Option<u32>,
+    pub entries: Vec<RecapEntry>,
+    pub total_damage: f32,
+    pub downed_at: Timestamp,
+}
+
+fn source_name(ctx: &ReducerContext, source_id: u32) -> String {
+    match ctx.db.player().id().find(source_id) {
+        Some(source) => source.username_display,
+        None => ctx.db.enemy().id().find(source_id).map(|e| e.enemy_type).unwrap_or_else(|| "Unknown".to_string()),
+    }
+}
+
+fn within_window(ctx: &ReducerContext, at: Timestamp) -> bool {
+    ctx.timestamp.duration_since(at).is_none_or(|d| d <= Duration::from_secs(RECAP_WINDOW_SECS))
+}
+
+/// Registra um golpe recebido e descarta os que saíram da janela
+pub fn record_damage_taken(ctx: &ReducerContext, target_id: u32, source_id: u32, ability: &str, amount: f32) {
+    let stale: Vec<u64> = ctx.db.recent_damage().target_id().filter(target_id)
+        .filter(|d| !within_window(ctx, d.at))
+        .map(|d| d.id)
+        .collect();
+    for id in stale {
+        ctx.db.recent_damage().id().delete(id);
+    }
+
+    ctx.db.recent_damage().insert(RecentDamage {
+        id: 0,
+        target_id,
+        source_id,
+        source_name: source_name(ctx, source_id),
+        ability: ability.to_string(),
+        amount,
+        at: ctx.timestamp,
+    });
+}
+
+/// Fotografa o buffer no momento da queda e o limpa
+pub fn snapshot(ctx: &ReducerContext, player: &crate::Player, killer_id: Option<u32>) -> DeathRecap {
+    let mut hits: Vec<RecentDamage> = ctx.db.recent_damage().target_id().filter(player.id).collect();
+    for hit in &hits {
+        ctx.db.recent_damage().id().delete(hit.id);
+    }
+    hits.retain(|h| within_window(ctx, h.at));
+    hits.sort_by_key(|h| (h.at, h.id));
+
+    let entries: Vec<RecapEntry> = hits.into_iter()
+        .map(|h| RecapEntry { source_id: h.source_id, source_name: h.source_name, ability: h.ability, amount: h.amount, at: h.at })
+        .collect();
+    let recap = ctx.db.death_recap().insert(DeathRecap {
+        id: 0,
+        player_id: player.id,
+        map_id: player.current_map_id.clone(),
+        // Sem killer player, o último golpe recebido identifica quem derrubou
+        killer_id: killer_id.or_else(|| entries.last().map(|e| e.source_id)),
+        total_damage: entries.iter().map(|e| e.amount).sum(),
+        entries,
+        downed_at: ctx.timestamp,
+    });
+
+    let mut recaps: Vec<DeathRecap> = ctx.db.death_recap().player_id().filter(player.id).collect();
+    if recaps.len() > RECAPS_PER_PLAYER {
+        recaps.sort_by_key(|r| r.downed_at);
+        for recap in recaps.iter().take(recaps.len() - RECAPS_PER_PLAYER) {
+            ctx.db.death_recap().id().delete(recap.id);
+        }
+    }
+    recap
+}
@@ -0,0 +1,124 @@
+use crate::combat::Projectile;
+use crate::cooldown::{try_start_cooldown, BULWARK_COOLDOWN_MS, CATEGORY_ABILITY, PARRY_COOLDOWN_MS};
+use crate::status_effect::{apply_status_effect, EFFECT_UNSTOPPABLE};
+use crate::inventory::player_equipment;
+use crate::party::sender_player;
+use crate::Player;
+use spacetimedb::{reducer, table, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+/// Escudos que bloqueiam projéteis frontais
+const BLOCKING_SHIELDS: &[&str] = &["wooden_shield", "iron_shield", "mirror_shield"];
+/// Escudos que, além de bloquear, refletem o projétil
+const REFLECTING_SHIELDS: &[&str] = &["mirror_shield"];
+
+/// Janela do parry: qualquer projétil que chegue nela é refletido
+pub const PARRY_WINDOW_MS: u64 = 250;
+
+/// Bulwark: imunidade a deslocamento forçado por alguns segundos
+pub const BULWARK_DURATION_SECS: u64 = 4;
+
+/// Postura defensiva atual do player
+#[table(name = defensive_stance, public)]
+#[derive(Clone)]
+pub struct DefensiveStance {
+    #[primary_key]
+    pub player_id: u32,
+    pub blocking: bool,
+    pub parry_until: Option<Timestamp>,
+}
+
+pub enum Deflection {
+    /// O projétil é destruído sem causar dano
+    Blocked,
+    /// O projétil volta pelo caminho e passa a pertencer ao defensor
+    Reflected,
+}
+
+fn equipped_shield(ctx: &ReducerContext, player_id: u32) -> Option<String> {
+    ctx.db.player_equipment().player_id().find(player_id)
+        .map(|e| e.off_hand_tool)
+        .filter(|tool| BLOCKING_SHIELDS.contains(&tool.as_str()))
+}
+
+fn stance(ctx: &ReducerContext, player_id: u32) -> DefensiveStance {
+    ctx.db.defensive_stance().player_id().find(player_id)
+        .unwrap_or(DefensiveStance { player_id, blocking: false, parry_until: None })
+}
+
+fn save_stance(ctx: &ReducerContext, stance: DefensiveStance) {
+    if ctx.db.defensive_stance().player_id().find(stance.player_id).is_some() {
+        ctx.db.defensive_stance().player_id().update(stance);
+    } else {
+        ctx.db.defensive_stance().insert(stance);
+    }
+}
+
+/// Como o player reage a um projétil que o atingiu (None = recebe o golpe)
+pub fn deflection_for(ctx: &ReducerContext, player: &Player, projectile: &Projectile) -> Option<Deflection> {
+    let stance = ctx.db.defensive_stance().player_id().find(player.id)?;
+    if stance.parry_until.is_some_and(|t| t >= ctx.timestamp) {
+        return Some(Deflection::Reflected);
+    }
+    if !stance.blocking {
+        return None;
+    }
+    let shield = equipped_shield(ctx, player.id)?;
+    // O escudo só cobre a frente: o projétil precisa vir contra o facing
+    let facing_projectile = player.facing_x * projectile.velocity_x + player.facing_y * projectile.velocity_y < 0.0;
+    if !facing_projectile {
+        return None;
+    }
+    if REFLECTING_SHIELDS.contains(&shield.as_str()) {
+        Some(Deflection::Reflected)
+    } else {
+        Some(Deflection::Blocked)
+    }
+}
+
+/// Devolve o projétil pelo caminho de onde veio, agora do defensor
+pub fn reflect(projectile: &mut Projectile, deflector_id: u32) {
+    projectile.owner_id = deflector_id;
+    projectile.velocity_x = -projectile.velocity_x;
+    projectile.velocity_y = -projectile.velocity_y;
+}
+
+#[reducer]
+pub fn set_blocking(ctx: &ReducerContext, blocking: bool) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if blocking && equipped_shield(ctx, player.id).is_none() {
+        return Err("You need a shield in your off hand to block".to_string());
+    }
+    let mut stance = stance(ctx, player.id);
+    stance.blocking = blocking;
+    save_stance(ctx, stance);
+    Ok(())
+}
+
+#[reducer]
+pub fn parry(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if player.is_downed {
+        return Err("Cannot parry while downed".to_string());
+    }
+    try_start_cooldown(ctx, ctx.sender, CATEGORY_ABILITY, "parry", PARRY_COOLDOWN_MS)?;
+
+    let mut stance = stance(ctx, player.id);
+    stance.parry_until = Some(ctx.timestamp + TimeDuration::from_duration(Duration::from_millis(PARRY_WINDOW_MS)));
+    save_stance(ctx, stance);
+    Ok(())
+}
+
+#[reducer]
+pub fn bulwark(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if player.is_downed {
+        return Err("Cannot use bulwark while downed".to_string());
+    }
+    try_start_cooldown(ctx, ctx.sender, CATEGORY_ABILITY, "bulwark", BULWARK_COOLDOWN_MS)?;
+    apply_status_effect(
+        ctx, player.id, EFFECT_UNSTOPPABLE, 1.0, "ability:bulwark",
+        Some(Duration::from_secs(BULWARK_DURATION_SECS)),
+    );
+    Ok(())
+}
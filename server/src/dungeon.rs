@@ -0,0 +1,407 @@
+use crate::inventory::{count_item, remove_item_from_inventory};
+use crate::map::{create_map_instance, destroy_map_instance, get_spawn_point, relocate_player, template_for_map, MapTemplate, TILE_SIZE};
+use crate::party::{party_member_ids, require_party_leader, sender_player};
+use crate::player;
+use crate::run_report::{finish_run, start_run, RUN_ABANDONED, RUN_RESET};
+use crate::status_effect::{apply_status_effect, remove_effects_from_source, EFFECT_DAMAGE_BOOST, EFFECT_DAMAGE_REDUCTION};
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+/// Tier de uma instância aberta sem chave
+pub const BASE_DUNGEON_TIER: u32 = 1;
+
+/// Chaves de portal (craftadas): a qualidade define o tier da instância
+const PORTAL_KEY_TIERS: &[(&str, u32)] = &[
+    ("portal_key_common", 2),
+    ("portal_key_rare", 3),
+    ("portal_key_epic", 4),
+    ("portal_key_legendary", 5),
+];
+
+/// Tiles de santuário: (tile, buff concedido à instância inteira)
+const SHRINE_TILES: &[(u32, &str)] = &[
+    (48, SHRINE_BUFF_MIGHT),
+    (49, SHRINE_BUFF_WARDING),
+];
+pub const SHRINE_BUFF_MIGHT: &str = "shrine_might";
+pub const SHRINE_BUFF_WARDING: &str = "shrine_warding";
+const SHRINE_ACTIVATION_RANGE: f32 = TILE_SIZE * 1.5;
+
+/// Tile de checkpoint: ativado, vira o ponto de respawn da party na instância
+pub const CHECKPOINT_TILE: u32 = 50;
+const CHECKPOINT_ACTIVATION_RANGE: f32 = TILE_SIZE * 1.5;
+
+/// Revives permitidos por encontro dentro de uma instância (fora de combate não há limite)
+pub const BATTLE_RES_PER_ENCOUNTER: u32 = 1;
+
+/// Instância de dungeon de uma party. `map_key` é o `current_map_id` de quem está dentro.
+#[table(name = dungeon_instance, public)]
+#[derive(Clone)]
+pub struct DungeonInstance {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[unique]
+    pub map_key: String,
+    pub template_name: String,
+    #[index(btree)]
+    pub party_id: u64,
+    pub tier: u32,
+    pub portal_key_item_id: String, // vazio = aberta sem chave
+    // Para onde os players voltam ao fechar a instância
+    pub return_map_id: String,
+    pub return_x: f32,
+    pub return_y: f32,
+    pub created_at: Timestamp,
+    /// Buffs de santuário ativos: valem para todos dentro, inclusive quem entrar depois
+    pub instance_buffs: Vec<String>,
+    /// Último checkpoint ativado nesta run (limpo no reset)
+    pub active_checkpoint_id: Option<u64>,
+    /// Revives em combate usados no encontro `battle_res_encounter_id`
+    pub battle_res_encounter_id: u64,
+    pub battle_res_used: u32,
+}
+
+/// Santuário dentro de uma instância (gerado a partir dos tiles 48..=49 do template)
+#[table(name = dungeon_shrine, public)]
+#[derive(Clone)]
+pub struct DungeonShrine {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub dungeon_id: u64,
+    pub buff_id: String,
+    pub tile_x: u32,
+    pub tile_y: u32,
+    pub is_used: bool,
+}
+
+pub fn portal_key_tier(item_id: &str) -> Option<u32> {
+    PORTAL_KEY_TIERS.iter().find(|(key, _)| *key == item_id).map(|(_, tier)| *tier)
+}
+
+/// Checkpoint dentro de uma instância (gerado a partir do tile 50 do template)
+#[table(name = dungeon_checkpoint, public)]
+#[derive(Clone)]
+pub struct DungeonCheckpoint {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub dungeon_id: u64,
+    pub tile_x: u32,
+    pub tile_y: u32,
+}
+
+fn tile_center(tile_x: u32, tile_y: u32) -> (f32, f32) {
+    (tile_x as f32 * TILE_SIZE + TILE_SIZE / 2.0, tile_y as f32 * TILE_SIZE + TILE_SIZE / 2.0)
+}
+
+fn within_range(player: &crate::Player, tile_x: u32, tile_y: u32, range: f32) -> bool {
+    let (x, y) = tile_center(tile_x, tile_y);
+    ((player.position_x - x).powi(2) + (player.position_y - y).powi(2)).sqrt() <= range
+}
+
+/// Efeitos aplicados por um buff de instância: (efeito, magnitude)
+fn instance_buff_effect(buff_id: &str) -> Option<(&'static str, f32)> {
+    match buff_id {
+        SHRINE_BUFF_MIGHT => Some((EFFECT_DAMAGE_BOOST, 0.25)),
+        SHRINE_BUFF_WARDING => Some((EFFECT_DAMAGE_REDUCTION, 0.2)),
+        _ => None,
+    }
+}
+
+fn instance_source(map_key: &str) -> String {
+    format!("instance:{}", map_key)
+}
+
+fn apply_instance_buff(ctx: &ReducerContext, player_id: u32, dungeon: &DungeonInstance, buff_id: &str) {
+    if let Some((effect_id, magnitude)) = instance_buff_effect(buff_id) {
+        apply_status_effect(ctx, player_id, effect_id, magnitude, &instance_source(&dungeon.map_key), None);
+    }
+}
+
+/// Gera santuários e checkpoints a partir dos tiles do template
+fn spawn_instance_objects(ctx: &ReducerContext, dungeon_id: u64, template: &MapTemplate) {
+    for (index, tile_id) in template.tile_data.iter().enumerate() {
+        if *tile_id == CHECKPOINT_TILE {
+            ctx.db.dungeon_checkpoint().insert(DungeonCheckpoint {
+                id: 0,
+                dungeon_id,
+                tile_x: index as u32 % template.width,
+                tile_y: index as u32 / template.width,
+            });
+            continue;
+        }
+        let Some((_, buff_id)) = SHRINE_TILES.iter().find(|(t, _)| t == tile_id) else { continue };
+        ctx.db.dungeon_shrine().insert(DungeonShrine {
+            id: 0,
+            dungeon_id,
+            buff_id: buff_id.to_string(),
+            tile_x: index as u32 % template.width,
+            tile_y: index as u32 / template.width,
+            is_used: false,
+        });
+    }
+}
+
+/// Chamado quando um player troca de mapa: sai dos buffs da instância antiga
+/// e recebe os buffs já ativos na nova.
+pub fn on_player_changed_map(ctx: &ReducerContext, player_id: u32, old_map_id: &str, new_map_id: &str) {
+    if old_map_id == new_map_id {
+        return;
+    }
+    if dungeon_for_map(ctx, old_map_id).is_some() {
+        remove_effects_from_source(ctx, player_id, &instance_source(old_map_id));
+    }
+    if let Some(dungeon) = dungeon_for_map(ctx, new_map_id) {
+        for buff_id in &dungeon.instance_buffs {
+            apply_instance_buff(ctx, player_id, &dungeon, buff_id);
+        }
+    }
+}
+
+/// Conta um revive em combate na instância do mapa. Falha quando o encontro
+/// em andamento já usou todos; fora de dungeon ou de combate não conta nada.
+pub fn consume_battle_res(ctx: &ReducerContext, map_id: &str) -> Result<(), String> {
+    let Some(mut dungeon) = dungeon_for_map(ctx, map_id) else {
+        return Ok(());
+    };
+    let Some(encounter) = crate::damage_meter::open_encounter(ctx, map_id) else {
+        return Ok(());
+    };
+    if dungeon.battle_res_encounter_id != encounter.id {
+        dungeon.battle_res_encounter_id = encounter.id;
+        dungeon.battle_res_used = 0;
+    }
+    if dungeon.battle_res_used >= BATTLE_RES_PER_ENCOUNTER {
+        return Err("No battle resurrections left for this encounter".to_string());
+    }
+    dungeon.battle_res_used += 1;
+    log::info!("🕯️ Battle res {}/{} used in '{}'", dungeon.battle_res_used, BATTLE_RES_PER_ENCOUNTER, map_id);
+    ctx.db.dungeon_instance().id().update(dungeon);
+    Ok(())
+}
+
+pub fn dungeon_for_map(ctx: &ReducerContext, map_id: &str) -> Option<DungeonInstance> {
+    ctx.db.dungeon_instance().map_key().find(map_id.to_string())
+}
+
+/// Ponto de respawn dentro de uma instância: checkpoint ativo ou a entrada.
+/// `None` fora de instâncias de dungeon.
+pub fn respawn_point(ctx: &ReducerContext, map_id: &str) -> Option<(f32, f32)> {
+    let dungeon = dungeon_for_map(ctx, map_id)?;
+    let checkpoint = dungeon.active_checkpoint_id.and_then(|id| ctx.db.dungeon_checkpoint().id().find(id));
+    Some(match checkpoint {
+        Some(c) => tile_center(c.tile_x, c.tile_y),
+        None => get_spawn_point(ctx, map_id),
+    })
+}
+
+/// Cria a instância para a party do líder, consumindo a chave (opcional) do líder.
+/// Os membros no mesmo mapa do líder entram junto.
+#[reducer]
+pub fn create_party_dungeon(ctx: &ReducerContext, template_name: String, portal_key_item_id: Option<String>) -> Result<(), String> {
+    crate::upgrade::require_not_frozen(ctx)?;
+    open_party_dungeon(ctx, template_name, portal_key_item_id).map(|_| ())
+}
+
+pub fn open_party_dungeon(ctx: &ReducerContext, template_name: String, portal_key_item_id: Option<String>) -> Result<DungeonInstance, String> {
+    let (leader, party) = require_party_leader(ctx)?;
+
+    if ctx.db.dungeon_instance().party_id().filter(party.id).next().is_some() {
+        return Err("Your party already has an open dungeon".to_string());
+    }
+    let template = template_for_map(ctx, &template_name)
+        .ok_or_else(|| format!("Dungeon '{}' not found", template_name))?;
+
+    let tier = match &portal_key_item_id {
+        Some(key) => {
+            let tier = portal_key_tier(key).ok_or_else(|| format!("'{}' is not a portal key", key))?;
+            if count_item(ctx, leader.id, key) < 1 {
+                return Err(format!("You don't have a '{}'", key));
+            }
+            tier
+        }
+        None => BASE_DUNGEON_TIER,
+    };
+
+    let dungeon = ctx.db.dungeon_instance().insert(DungeonInstance {
+        id: 0,
+        map_key: String::new(),
+        template_name: template_name.clone(),
+        party_id: party.id,
+        tier,
+        portal_key_item_id: portal_key_item_id.clone().unwrap_or_default(),
+        return_map_id: leader.current_map_id.clone(),
+        return_x: leader.position_x,
+        return_y: leader.position_y,
+        created_at: ctx.timestamp,
+        instance_buffs: Vec::new(),
+        active_checkpoint_id: None,
+        battle_res_encounter_id: 0,
+        battle_res_used: 0,
+    });
+    let map_key = format!("{}@dungeon{}", template_name, dungeon.id);
+    create_map_instance(ctx, &map_key, &template_name)?;
+
+    // A chave só é consumida depois que a instância foi criada com sucesso
+    if let Some(key) = &portal_key_item_id {
+        remove_item_from_inventory(ctx, leader.id, key, 1)?;
+    }
+    ctx.db.dungeon_instance().id().update(DungeonInstance { map_key: map_key.clone(), ..dungeon });
+    spawn_instance_objects(ctx, dungeon.id, &template);
+    let dungeon = ctx.db.dungeon_instance().id().find(dungeon.id).ok_or("Dungeon not found")?;
+    start_run(ctx, &dungeon);
+
+    let (spawn_x, spawn_y) = get_spawn_point(ctx, &map_key);
+    for member_id in party_member_ids(ctx, party.id) {
+        let Some(member) = ctx.db.player().id().find(member_id) else { continue };
+        if member.current_map_id == leader.current_map_id {
+            relocate_player(ctx, &member, &map_key, spawn_x, spawn_y)?;
+        }
+    }
+
+    log::info!("🏰 Party {} opened '{}' (tier {})", party.id, map_key, tier);
+    Ok(dungeon)
+}
+
+/// Fecha a instância da party: quem estiver dentro volta ao ponto de entrada
+#[reducer]
+pub fn close_party_dungeon(ctx: &ReducerContext) -> Result<(), String> {
+    let (_, party) = require_party_leader(ctx)?;
+    let dungeon = ctx.db.dungeon_instance().party_id().filter(party.id).next()
+        .ok_or("Your party has no open dungeon")?;
+    close_dungeon(ctx, &dungeon)
+}
+
+fn clear_instance_objects(ctx: &ReducerContext, dungeon_id: u64) {
+    let shrines: Vec<u64> = ctx.db.dungeon_shrine().dungeon_id().filter(dungeon_id).map(|s| s.id).collect();
+    for id in shrines {
+        ctx.db.dungeon_shrine().id().delete(id);
+    }
+    let checkpoints: Vec<u64> = ctx.db.dungeon_checkpoint().dungeon_id().filter(dungeon_id).map(|c| c.id).collect();
+    for id in checkpoints {
+        ctx.db.dungeon_checkpoint().id().delete(id);
+    }
+}
+
+/// Reinicia a run: santuários, buffs, checkpoint e mutações voltam ao estado
+/// inicial e todos dentro voltam para a entrada.
+#[reducer]
+pub fn reset_party_dungeon(ctx: &ReducerContext) -> Result<(), String> {
+    let (_, party) = require_party_leader(ctx)?;
+    let dungeon = ctx.db.dungeon_instance().party_id().filter(party.id).next()
+        .ok_or("Your party has no open dungeon")?;
+    reset_dungeon(ctx, dungeon)
+}
+
+pub fn reset_dungeon(ctx: &ReducerContext, mut dungeon: DungeonInstance) -> Result<(), String> {
+    let template = template_for_map(ctx, &dungeon.map_key).ok_or("Dungeon template not found")?;
+    finish_run(ctx, dungeon.id, RUN_RESET);
+
+    let inside: Vec<_> = ctx.db.player().iter().filter(|p| p.current_map_id == dungeon.map_key).collect();
+    for p in &inside {
+        remove_effects_from_source(ctx, p.id, &instance_source(&dungeon.map_key));
+    }
+
+    // Recria a instância do mapa para descartar as mutações de tiles da run
+    destroy_map_instance(ctx, &dungeon.map_key);
+    create_map_instance(ctx, &dungeon.map_key, &template.name)?;
+
+    clear_instance_objects(ctx, dungeon.id);
+    spawn_instance_objects(ctx, dungeon.id, &template);
+    dungeon.instance_buffs.clear();
+    dungeon.active_checkpoint_id = None;
+    dungeon.battle_res_used = 0;
+    ctx.db.dungeon_instance().id().update(dungeon.clone());
+    crate::dungeon_gen::populate(ctx, &dungeon);
+    start_run(ctx, &dungeon);
+
+    let (spawn_x, spawn_y) = get_spawn_point(ctx, &dungeon.map_key);
+    for p in inside {
+        relocate_player(ctx, &p, &dungeon.map_key, spawn_x, spawn_y)?;
+    }
+
+    log::info!("🔄 Dungeon '{}' reset", dungeon.map_key);
+    Ok(())
+}
+
+pub fn close_dungeon(ctx: &ReducerContext, dungeon: &DungeonInstance) -> Result<(), String> {
+    let inside: Vec<_> = ctx.db.player().iter().filter(|p| p.current_map_id == dungeon.map_key).collect();
+    for p in inside {
+        relocate_player(ctx, &p, &dungeon.return_map_id, dungeon.return_x, dungeon.return_y)?;
+    }
+
+    finish_run(ctx, dungeon.id, RUN_ABANDONED);
+    clear_instance_objects(ctx, dungeon.id);
+    crate::dungeon_gen::on_dungeon_closed(ctx, dungeon);
+    destroy_map_instance(ctx, &dungeon.map_key);
+    ctx.db.dungeon_instance().id().delete(dungeon.id);
+    log::info!("🏰 Dungeon '{}' closed", dungeon.map_key);
+    Ok(())
+}
+
+/// Ativa um santuário próximo: o buff passa a valer para a instância inteira
+#[reducer]
+pub fn activate_shrine(ctx: &ReducerContext, shrine_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if player.is_downed {
+        return Err("Cannot activate shrines while downed".to_string());
+    }
+
+    let mut shrine = ctx.db.dungeon_shrine().id().find(shrine_id).ok_or("Shrine not found")?;
+    let mut dungeon = ctx.db.dungeon_instance().id().find(shrine.dungeon_id).ok_or("Dungeon not found")?;
+    if player.current_map_id != dungeon.map_key {
+        return Err("Shrine is in another instance".to_string());
+    }
+    if shrine.is_used {
+        return Err("Shrine has already been used".to_string());
+    }
+
+    if !within_range(&player, shrine.tile_x, shrine.tile_y, SHRINE_ACTIVATION_RANGE) {
+        return Err("Too far from the shrine".to_string());
+    }
+
+    shrine.is_used = true;
+    let buff_id = shrine.buff_id.clone();
+    ctx.db.dungeon_shrine().id().update(shrine);
+
+    if !dungeon.instance_buffs.contains(&buff_id) {
+        dungeon.instance_buffs.push(buff_id.clone());
+        ctx.db.dungeon_instance().id().update(dungeon.clone());
+    }
+
+    for p in ctx.db.player().iter().filter(|p| p.current_map_id == dungeon.map_key) {
+        apply_instance_buff(ctx, p.id, &dungeon, &buff_id);
+    }
+
+    log::info!("⛩️ Player {} activated shrine {} ('{}') in {}", player.id, shrine_id, buff_id, dungeon.map_key);
+    Ok(())
+}
+
+/// Ativa um checkpoint próximo: respawns da party nesta run passam a ocorrer nele
+#[reducer]
+pub fn activate_checkpoint(ctx: &ReducerContext, checkpoint_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if player.is_downed {
+        return Err("Cannot activate checkpoints while downed".to_string());
+    }
+
+    let checkpoint = ctx.db.dungeon_checkpoint().id().find(checkpoint_id).ok_or("Checkpoint not found")?;
+    let mut dungeon = ctx.db.dungeon_instance().id().find(checkpoint.dungeon_id).ok_or("Dungeon not found")?;
+    if player.current_map_id != dungeon.map_key {
+        return Err("Checkpoint is in another instance".to_string());
+    }
+    if !within_range(&player, checkpoint.tile_x, checkpoint.tile_y, CHECKPOINT_ACTIVATION_RANGE) {
+        return Err("Too far from the checkpoint".to_string());
+    }
+    if dungeon.active_checkpoint_id == Some(checkpoint_id) {
+        return Ok(());
+    }
+
+    dungeon.active_checkpoint_id = Some(checkpoint_id);
+    ctx.db.dungeon_instance().id().update(dungeon.clone());
+    log::info!("🚩 Player {} activated checkpoint {} in {}", player.id, checkpoint_id, dungeon.map_key);
+    Ok(())
+}
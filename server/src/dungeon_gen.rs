@@ -0,0 +1,408 @@
+use crate::combat::{allocate_enemy_id, enemy, spawn_enemy};
+use crate::dungeon::{dungeon_instance, open_party_dungeon, DungeonInstance};
+use crate::map::{map_template, MapTemplate, TILE_SIZE};
+use crate::party::sender_player;
+use crate::resource_registry::resource_registry;
+use crate::reward::{grant_reward_bundle, RewardBundle};
+use crate::room_prefab::is_prefab_validated;
+use spacetimedb::rand::Rng;
+use spacetimedb::{reducer, table, ReducerContext, SpacetimeType, Table};
+use std::collections::{HashMap, VecDeque};
+
+/// Tipo de recurso dos prefabs de sala no `resource_registry`
+pub const RESOURCE_TYPE_ROOM: &str = "room";
+
+pub const ROOM_ENTRANCE: &str = "entrance";
+pub const ROOM_COMBAT: &str = "combat";
+pub const ROOM_LOOT: &str = "loot";
+pub const ROOM_BOSS: &str = "boss";
+
+/// Todas as salas são quadradas deste tamanho (em tiles); as portas ficam no meio de cada lado
+pub const ROOM_SIZE: u32 = 16;
+const LAYOUT_GRID: i32 = 6;
+const TILE_FLOOR: u32 = 0;
+const TILE_WALL: u32 = 2;
+const TILE_SPAWN: u32 = 1;
+
+/// Marcadores nos prefabs; viram chão e são registrados no layout
+pub const TILE_ENEMY_MARKER: u32 = 51;
+pub const TILE_LOOT_MARKER: u32 = 52;
+pub const TILE_BOSS_MARKER: u32 = 53;
+
+const GENERATED_TEMPLATE_PREFIX: &str = "proc_";
+const BASE_ROOM_COUNT: u32 = 5;
+const CHEST_OPEN_RANGE: f32 = TILE_SIZE * 1.5;
+const CHEST_GOLD_PER_TIER: u64 = 40;
+/// Inimigos dos marcadores das salas de combate, por tier
+const SPAWNER_ENEMY_TYPES: &[&str] = &["Goblin", "Orc", "Mortar", "Troll"];
+
+/// Lados na ordem N, L, S, O
+const SIDES: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Prefab de sala lido do `resource_registry`. Formato do `data`:
+/// `kind=combat`, `doors=N,E,S,W` e `tiles=` com as linhas separadas por `/`
+/// (ids separados por vírgula), uma chave por linha.
+#[derive(Clone, Debug)]
+pub struct RoomPrefab {
+    pub key_id: String,
+    pub kind: String,
+    pub doors: [bool; 4],
+    pub tiles: Vec<u32>,
+}
+
+pub fn parse_room_prefab(key_id: &str, data: &str) -> Result<RoomPrefab, String> {
+    let mut kind = None;
+    let mut doors = [false; 4];
+    let mut tiles = Vec::new();
+    for line in data.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (key, value) = line.split_once('=').ok_or_else(|| format!("Invalid line '{}'", line))?;
+        match key.trim() {
+            "kind" => kind = Some(value.trim().to_string()),
+            "doors" => {
+                for side in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                    let index = ["N", "E", "S", "W"].iter().position(|s| *s == side)
+                        .ok_or_else(|| format!("Invalid door '{}'", side))?;
+                    doors[index] = true;
+                }
+            }
+            "tiles" => {
+                for row in value.split('/') {
+                    let parsed: Result<Vec<u32>, _> = row.split(',').map(|t| t.trim().parse::<u32>()).collect();
+                    let parsed = parsed.map_err(|_| format!("Invalid tile row '{}'", row))?;
+                    if parsed.len() != ROOM_SIZE as usize {
+                        return Err(format!("Rows must have {} tiles", ROOM_SIZE));
+                    }
+                    tiles.extend(parsed);
+                }
+            }
+            other => return Err(format!("Unknown key '{}'", other)),
+        }
+    }
+    let kind = kind.ok_or("Missing kind")?;
+    if ![ROOM_ENTRANCE, ROOM_COMBAT, ROOM_LOOT, ROOM_BOSS].contains(&kind.as_str()) {
+        return Err(format!("Unknown room kind '{}'", kind));
+    }
+    if tiles.len() != (ROOM_SIZE * ROOM_SIZE) as usize {
+        return Err(format!("Rooms must be {}x{} tiles", ROOM_SIZE, ROOM_SIZE));
+    }
+    Ok(RoomPrefab { key_id: key_id.to_string(), kind, doors, tiles })
+}
+
+/// Prefabs de sala validados disponíveis para o gerador, em ordem estável
+/// (o seed precisa escolher as mesmas salas)
+pub fn room_prefabs(ctx: &ReducerContext) -> Vec<RoomPrefab> {
+    let mut prefabs: Vec<RoomPrefab> = ctx.db.resource_registry().iter()
+        .filter(|r| r.resource_type == RESOURCE_TYPE_ROOM && is_prefab_validated(ctx, &r.key_id, &r.data))
+        .filter_map(|r| match parse_room_prefab(&r.key_id, &r.data) {
+            Ok(prefab) => Some(prefab),
+            Err(e) => {
+                log::warn!("⚠️ Room prefab '{}' ignored: {}", r.key_id, e);
+                None
+            }
+        })
+        .collect();
+    prefabs.sort_by(|a, b| a.key_id.cmp(&b.key_id));
+    prefabs
+}
+
+/// SplitMix64: o mesmo seed sempre gera o mesmo layout
+struct LayoutRng(u64);
+
+impl LayoutRng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct GeneratedRoom {
+    pub cell_x: i32,
+    pub cell_y: i32,
+    pub kind: String,
+    pub prefab_key: String,
+}
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct LayoutMarker {
+    pub marker_tile: u32,
+    pub tile_x: u32,
+    pub tile_y: u32,
+}
+
+/// Layout gerado de uma instância; os marcadores repovoam a instância no reset
+#[table(name = generated_layout, public)]
+#[derive(Clone)]
+pub struct GeneratedLayout {
+    #[primary_key]
+    pub dungeon_id: u64,
+    pub seed: u64,
+    pub template_name: String,
+    pub rooms: Vec<GeneratedRoom>,
+    pub markers: Vec<LayoutMarker>,
+}
+
+/// Baú de uma sala de loot: cada player da instância abre uma vez
+#[table(name = dungeon_chest, public)]
+#[derive(Clone)]
+pub struct DungeonChest {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub dungeon_id: u64,
+    pub position_x: f32,
+    pub position_y: f32,
+    pub opened_by: Vec<u32>,
+}
+
+type Cell = (i32, i32);
+
+struct Layout {
+    cells: Vec<Cell>,
+    links: Vec<(Cell, Cell)>,
+    kinds: HashMap<Cell, &'static str>,
+}
+
+/// Árvore de salas numa grade: cresce a partir da entrada; o chefe fica na
+/// sala mais distante e as salas de loot nas pontas restantes
+fn generate_layout(rng: &mut LayoutRng, room_count: usize) -> Layout {
+    let start = (LAYOUT_GRID / 2, LAYOUT_GRID / 2);
+    let mut cells = vec![start];
+    let mut links = Vec::new();
+    let room_count = room_count.min((LAYOUT_GRID * LAYOUT_GRID) as usize);
+    while cells.len() < room_count {
+        let from = cells[rng.below(cells.len())];
+        let (dx, dy) = SIDES[rng.below(SIDES.len())];
+        let to = (from.0 + dx, from.1 + dy);
+        if to.0 < 0 || to.1 < 0 || to.0 >= LAYOUT_GRID || to.1 >= LAYOUT_GRID || cells.contains(&to) {
+            continue;
+        }
+        cells.push(to);
+        links.push((from, to));
+    }
+
+    let neighbors = |cell: Cell| -> Vec<Cell> {
+        links.iter().filter_map(|&(a, b)| if a == cell { Some(b) } else if b == cell { Some(a) } else { None }).collect()
+    };
+    let mut depth: HashMap<Cell, u32> = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(cell) = queue.pop_front() {
+        for next in neighbors(cell) {
+            if !depth.contains_key(&next) {
+                depth.insert(next, depth[&cell] + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    let mut kinds: HashMap<Cell, &'static str> = cells.iter().map(|c| (*c, ROOM_COMBAT)).collect();
+    kinds.insert(start, ROOM_ENTRANCE);
+    if let Some(boss) = cells.iter().skip(1).max_by_key(|c| depth[c]) {
+        kinds.insert(*boss, ROOM_BOSS);
+    }
+    for cell in &cells {
+        if kinds[cell] == ROOM_COMBAT && neighbors(*cell).len() == 1 {
+            kinds.insert(*cell, ROOM_LOOT);
+        }
+    }
+    Layout { cells, links, kinds }
+}
+
+/// Monta o template do mapa a partir do layout e dos prefabs. Portas sem
+/// vizinho são fechadas com parede.
+fn assemble_template(ctx: &ReducerContext, seed: u64, tier: u32) -> Result<(MapTemplate, Vec<GeneratedRoom>, Vec<LayoutMarker>), String> {
+    let prefabs = room_prefabs(ctx);
+    let mut rng = LayoutRng(seed);
+    let Layout { cells, links, kinds } = generate_layout(&mut rng, (BASE_ROOM_COUNT + tier) as usize);
+
+    let min_x = cells.iter().map(|c| c.0).min().unwrap_or(0);
+    let min_y = cells.iter().map(|c| c.1).min().unwrap_or(0);
+    let cols = (cells.iter().map(|c| c.0).max().unwrap_or(0) - min_x + 1) as u32;
+    let rows = (cells.iter().map(|c| c.1).max().unwrap_or(0) - min_y + 1) as u32;
+    let width = cols * ROOM_SIZE;
+    let height = rows * ROOM_SIZE;
+    let mut tile_data = vec![TILE_WALL; (width * height) as usize];
+    let mut rooms = Vec::new();
+    let mut markers = Vec::new();
+    let mut spawn = (0.0, 0.0);
+
+    for cell in &cells {
+        let kind = kinds[cell];
+        let required: Vec<bool> = SIDES.iter()
+            .map(|(dx, dy)| {
+                let other = (cell.0 + dx, cell.1 + dy);
+                links.iter().any(|&l| l == (*cell, other) || l == (other, *cell))
+            })
+            .collect();
+        let candidates: Vec<&RoomPrefab> = prefabs.iter()
+            .filter(|p| p.kind == kind && (0..4).all(|side| !required[side] || p.doors[side]))
+            .collect();
+        if candidates.is_empty() {
+            return Err(format!("No '{}' room prefab with the required doors", kind));
+        }
+        let prefab = candidates[rng.below(candidates.len())];
+
+        let origin_x = (cell.0 - min_x) as u32 * ROOM_SIZE;
+        let origin_y = (cell.1 - min_y) as u32 * ROOM_SIZE;
+        for y in 0..ROOM_SIZE {
+            for x in 0..ROOM_SIZE {
+                let mut tile = prefab.tiles[(y * ROOM_SIZE + x) as usize];
+                let (tile_x, tile_y) = (origin_x + x, origin_y + y);
+                if [TILE_ENEMY_MARKER, TILE_LOOT_MARKER, TILE_BOSS_MARKER].contains(&tile) {
+                    markers.push(LayoutMarker { marker_tile: tile, tile_x, tile_y });
+                    tile = TILE_FLOOR;
+                }
+                tile_data[(tile_y * width + tile_x) as usize] = tile;
+            }
+        }
+        for (side, open) in required.iter().enumerate() {
+            if prefab.doors[side] && !open {
+                let (x, y) = door_tile(side);
+                tile_data[((origin_y + y) * width + origin_x + x) as usize] = TILE_WALL;
+            }
+        }
+        if kind == ROOM_ENTRANCE {
+            let (x, y) = (origin_x + ROOM_SIZE / 2, origin_y + ROOM_SIZE / 2);
+            tile_data[(y * width + x) as usize] = TILE_SPAWN;
+            spawn = (x as f32 * TILE_SIZE + TILE_SIZE / 2.0, y as f32 * TILE_SIZE + TILE_SIZE / 2.0);
+        }
+        rooms.push(GeneratedRoom { cell_x: cell.0, cell_y: cell.1, kind: kind.to_string(), prefab_key: prefab.key_id.clone() });
+    }
+
+    let template = MapTemplate {
+        name: format!("{}{:016x}", GENERATED_TEMPLATE_PREFIX, seed),
+        width,
+        height,
+        tile_data,
+        spawn_x: spawn.0,
+        spawn_y: spawn.1,
+        music_track_id: String::new(),
+        ambient_color: 0,
+        light_level: 0.3,
+        is_indoor: true,
+        mounts_allowed: false,
+        is_town: false,
+    };
+    Ok((template, rooms, markers))
+}
+
+/// Tile da porta de cada lado, relativo à sala
+pub fn door_tile(side: usize) -> (u32, u32) {
+    match side {
+        0 => (ROOM_SIZE / 2, 0),
+        1 => (ROOM_SIZE - 1, ROOM_SIZE / 2),
+        2 => (ROOM_SIZE / 2, ROOM_SIZE - 1),
+        _ => (0, ROOM_SIZE / 2),
+    }
+}
+
+fn marker_position(marker: &LayoutMarker) -> (f32, f32) {
+    (marker.tile_x as f32 * TILE_SIZE + TILE_SIZE / 2.0, marker.tile_y as f32 * TILE_SIZE + TILE_SIZE / 2.0)
+}
+
+/// Gera (ou reaproveita) o layout do seed e abre a instância para a party do líder.
+/// O seed é sorteado; escolher um (reproduzir um layout) é coisa de admin,
+/// senão players repetiriam o layout de baús mais fácil.
+#[reducer]
+pub fn create_procedural_dungeon(ctx: &ReducerContext, seed: Option<u64>, portal_key_item_id: Option<String>) -> Result<(), String> {
+    crate::upgrade::require_not_frozen(ctx)?;
+    if seed.is_some() {
+        crate::admin::require_admin(ctx)?;
+    }
+    let tier = portal_key_item_id.as_deref()
+        .map(|key| crate::dungeon::portal_key_tier(key).ok_or_else(|| format!("'{}' is not a portal key", key)))
+        .transpose()?
+        .unwrap_or(crate::dungeon::BASE_DUNGEON_TIER);
+    let seed = seed.unwrap_or_else(|| ctx.rng().gen());
+    let (template, rooms, markers) = assemble_template(ctx, seed, tier)?;
+    let template_name = template.name.clone();
+    if ctx.db.map_template().name().find(template_name.clone()).is_none() {
+        ctx.db.map_template().insert(template);
+    }
+
+    let dungeon = open_party_dungeon(ctx, template_name.clone(), portal_key_item_id)?;
+    ctx.db.generated_layout().insert(GeneratedLayout { dungeon_id: dungeon.id, seed, template_name, rooms, markers });
+    populate(ctx, &dungeon);
+    log::info!("🧩 Generated dungeon '{}' from seed {:016x}", dungeon.map_key, seed);
+    Ok(())
+}
+
+/// Coloca inimigos e baús nos marcadores do layout (na abertura e a cada reset)
+pub fn populate(ctx: &ReducerContext, dungeon: &DungeonInstance) {
+    let Some(layout) = ctx.db.generated_layout().dungeon_id().find(dungeon.id) else {
+        return;
+    };
+    clear_population(ctx, dungeon);
+
+    let spawner_type = SPAWNER_ENEMY_TYPES[(dungeon.tier as usize).saturating_sub(1).min(SPAWNER_ENEMY_TYPES.len() - 1)];
+    for marker in &layout.markers {
+        let (x, y) = marker_position(marker);
+        let enemy_type = match marker.marker_tile {
+            TILE_ENEMY_MARKER => spawner_type,
+            TILE_BOSS_MARKER => "DungeonBoss",
+            _ => {
+                ctx.db.dungeon_chest().insert(DungeonChest { id: 0, dungeon_id: dungeon.id, position_x: x, position_y: y, opened_by: Vec::new() });
+                continue;
+            }
+        };
+        let enemy_id = allocate_enemy_id(ctx);
+        if let Err(e) = spawn_enemy(ctx, enemy_id, x, y, dungeon.map_key.clone(), enemy_type.to_string()) {
+            log::warn!("Could not spawn {} in '{}': {}", enemy_type, dungeon.map_key, e);
+        }
+    }
+}
+
+fn clear_population(ctx: &ReducerContext, dungeon: &DungeonInstance) {
+    let enemies: Vec<u32> = ctx.db.enemy().map_id().filter(&dungeon.map_key).map(|e| e.id).collect();
+    for id in enemies {
+        ctx.db.enemy().id().delete(id);
+        crate::threat::clear_enemy(ctx, id);
+    }
+    let chests: Vec<u64> = ctx.db.dungeon_chest().dungeon_id().filter(dungeon.id).map(|c| c.id).collect();
+    for id in chests {
+        ctx.db.dungeon_chest().id().delete(id);
+    }
+}
+
+/// Instância fechada: remove o layout, os baús e o template gerado
+pub fn on_dungeon_closed(ctx: &ReducerContext, dungeon: &DungeonInstance) {
+    let Some(layout) = ctx.db.generated_layout().dungeon_id().find(dungeon.id) else {
+        return;
+    };
+    clear_population(ctx, dungeon);
+    ctx.db.generated_layout().dungeon_id().delete(dungeon.id);
+    let still_used = ctx.db.dungeon_instance().iter().any(|d| d.id != dungeon.id && d.template_name == layout.template_name);
+    if !still_used {
+        ctx.db.map_template().name().delete(layout.template_name);
+    }
+}
+
+#[reducer]
+pub fn open_dungeon_chest(ctx: &ReducerContext, chest_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    let mut chest = ctx.db.dungeon_chest().id().find(chest_id).ok_or("Chest not found")?;
+    let dungeon = ctx.db.dungeon_instance().id().find(chest.dungeon_id).ok_or("Dungeon not found")?;
+    let distance = ((player.position_x - chest.position_x).powi(2) + (player.position_y - chest.position_y).powi(2)).sqrt();
+    if player.current_map_id != dungeon.map_key || distance > CHEST_OPEN_RANGE {
+        return Err("Chest is out of range".to_string());
+    }
+    if chest.opened_by.contains(&player.id) {
+        return Err("You already opened this chest".to_string());
+    }
+
+    let bundle = RewardBundle::gold(CHEST_GOLD_PER_TIER * dungeon.tier as u64).with_item("health_potion", 1);
+    grant_reward_bundle(ctx, player.id, &bundle, "dungeon_chest")?;
+    chest.opened_by.push(player.id);
+    ctx.db.dungeon_chest().id().update(chest);
+    log::info!("🎁 Player {} opened chest {} in '{}'", player.id, chest_id, dungeon.map_key);
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+use std::collections::HashMap;
+
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// Cada criação (faucet) ou destruição (sink) de moeda, ainda não consolidada
+#[table(name = currency_ledger)]
+#[derive(Clone)]
+pub struct CurrencyLedger {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub currency: String,
+    pub source: String,
+    pub is_faucet: bool,
+    pub amount: u64,
+    pub at: Timestamp,
+}
+
+/// Totais diários de entrada e saída por moeda e origem.
+/// `key` = "day:currency:source", `day` = dias desde a época Unix (UTC).
+#[table(name = economy_daily, public)]
+#[derive(Clone)]
+pub struct EconomyDaily {
+    #[primary_key]
+    pub key: String,
+    #[index(btree)]
+    pub day: u32,
+    pub currency: String,
+    pub source: String,
+    pub faucet_total: u64,
+    pub sink_total: u64,
+    pub updated_at: Timestamp,
+}
+
+pub fn record_flow(ctx: &ReducerContext, currency: &str, source: &str, is_faucet: bool, amount: u64) {
+    if amount == 0 {
+        return;
+    }
+    ctx.db.currency_ledger().insert(CurrencyLedger {
+        id: 0,
+        currency: currency.to_string(),
+        source: source.to_string(),
+        is_faucet,
+        amount,
+        at: ctx.timestamp,
+    });
+}
+
+/// Consolida o ledger em `economy_daily` (rodado pela agregação periódica)
+pub fn rollup_currency_ledger(ctx: &ReducerContext) {
+    let mut totals: HashMap<(u32, String, String), (u64, u64)> = HashMap::new();
+    let entries: Vec<CurrencyLedger> = ctx.db.currency_ledger().iter().collect();
+    for entry in &entries {
+        let day = (entry.at.to_micros_since_unix_epoch() / MICROS_PER_DAY) as u32;
+        let total = totals.entry((day, entry.currency.clone(), entry.source.clone())).or_default();
+        if entry.is_faucet {
+            total.0 += entry.amount;
+        } else {
+            total.1 += entry.amount;
+        }
+        ctx.db.currency_ledger().id().delete(entry.id);
+    }
+
+    for ((day, currency, source), (faucet, sink)) in totals {
+        let key = format!("{}:{}:{}", day, currency, source);
+        match ctx.db.economy_daily().key().find(key.clone()) {
+            Some(mut row) => {
+                row.faucet_total += faucet;
+                row.sink_total += sink;
+                row.updated_at = ctx.timestamp;
+                ctx.db.economy_daily().key().update(row);
+            }
+            None => {
+                ctx.db.economy_daily().insert(EconomyDaily {
+                    key,
+                    day,
+                    currency,
+                    source,
+                    faucet_total: faucet,
+                    sink_total: sink,
+                    updated_at: ctx.timestamp,
+                });
+            }
+        }
+    }
+    if !entries.is_empty() {
+        log::info!("💰 Rolled up {} currency ledger entries", entries.len());
+    }
+}
@@ -0,0 +1,142 @@
+use crate::combat::{enemy, refresh_enemy_motion, Enemy};
+use crate::map::{
+    hot_map_ids, is_passable_step, map_instance, nearest_player_distance, random_walkable_point, template_for_map,
+    terrain_speed_multiplier, TILE_SIZE,
+};
+use crate::tick_budget::{current_tick, distant_ai_slowdown};
+use crate::tick::WORLD_TICK_MS;
+use spacetimedb::rand::Rng;
+use spacetimedb::{ReducerContext, TimeDuration};
+use std::time::Duration;
+
+// Passeio ocioso: inimigos andam devagar entre pontos caminháveis da região de patrulha
+const WANDER_SPEED_FACTOR: f32 = 0.4;
+const WANDER_ARRIVE_DISTANCE: f32 = 2.0;
+const WANDER_MIN_PAUSE_MS: u64 = 1000;
+const WANDER_MAX_PAUSE_MS: u64 = 3000;
+const WANDER_CANDIDATES: u32 = 2;
+// LOD da IA: fora do raio de ativação o inimigo atualiza a cada `AI_LOD_STRIDE`
+// ticks (multiplicado pelo throttle do tick) e sem comparar caminhos
+const AI_ACTIVATION_RADIUS: f32 = TILE_SIZE * 20.0;
+const AI_LOD_STRIDE: u64 = 10;
+
+/// Avança o passeio ocioso dos inimigos em estado "Idle" nas instâncias quentes.
+/// Os demais estados continuam sendo dirigidos por `update_enemy_ai`.
+/// Retorna quantos inimigos foram processados neste tick.
+pub fn process_enemy_wander(ctx: &ReducerContext) -> u64 {
+    let delta_time = WORLD_TICK_MS as f32 / 1000.0;
+    let stride = AI_LOD_STRIDE * distant_ai_slowdown(ctx);
+    let tick = current_tick(ctx);
+
+    let idle: Vec<Enemy> = hot_map_ids(ctx).iter()
+        .flat_map(|map_id| ctx.db.enemy().map_id().filter(map_id))
+        .filter(|e| e.is_active && e.state == "Idle")
+        .collect();
+
+    let mut processed = 0;
+    for mut enemy in idle {
+        let active = nearest_player_distance(ctx, &enemy.map_id, enemy.position_x, enemy.position_y)
+            .is_some_and(|d| d <= AI_ACTIVATION_RADIUS);
+        // Ids espalham os inimigos distantes entre os ticks do ciclo
+        if !active && !(tick + enemy.id as u64).is_multiple_of(stride) {
+            continue;
+        }
+        let step_time = if active { delta_time } else { delta_time * stride as f32 };
+
+        processed += 1;
+        if wander_step(ctx, &mut enemy, step_time, active) {
+            ctx.db.enemy().id().update(enemy);
+        }
+    }
+    processed
+}
+
+/// Instância reaquecida: inimigos esquecem perseguições antigas e voltam a
+/// passear a partir de onde pararam
+pub fn on_map_warmed(ctx: &ReducerContext, map_id: &str) {
+    let enemies: Vec<Enemy> = ctx.db.enemy().map_id().filter(map_id).filter(|e| e.is_active).collect();
+    for mut enemy in enemies {
+        enemy.state = "Idle".to_string();
+        enemy.target_player_id = None;
+        enemy.target_map_id = None;
+        enemy.velocity_x = 0.0;
+        enemy.velocity_y = 0.0;
+        enemy.wander_resume_at = ctx.timestamp;
+        pick_next_wander_target(ctx, &mut enemy, WANDER_CANDIDATES);
+        refresh_enemy_motion(&mut enemy, ctx.timestamp);
+        ctx.db.enemy().id().update(enemy);
+    }
+}
+
+/// Retorna `true` se o inimigo mudou e precisa ser gravado.
+/// `active` = há player por perto; sem ele o próximo ponto é sorteado sem comparar custos.
+fn wander_step(ctx: &ReducerContext, enemy: &mut Enemy, delta_time: f32, active: bool) -> bool {
+    let candidates = if active { WANDER_CANDIDATES } else { 1 };
+
+    // Pausado entre dois pontos
+    if enemy.wander_resume_at > ctx.timestamp {
+        return false;
+    }
+
+    let dx = enemy.wander_target_x - enemy.position_x;
+    let dy = enemy.wander_target_y - enemy.position_y;
+    let distance = (dx * dx + dy * dy).sqrt();
+
+    if distance <= WANDER_ARRIVE_DISTANCE {
+        // Chegou: escolhe o próximo ponto e pausa
+        pick_next_wander_target(ctx, enemy, candidates);
+        enemy.velocity_x = 0.0;
+        enemy.velocity_y = 0.0;
+        let pause_ms = ctx.rng().gen_range(WANDER_MIN_PAUSE_MS..WANDER_MAX_PAUSE_MS);
+        enemy.wander_resume_at = ctx.timestamp + TimeDuration::from_duration(Duration::from_millis(pause_ms));
+        refresh_enemy_motion(enemy, ctx.timestamp);
+        return true;
+    }
+
+    let speed = enemy.movement_speed * WANDER_SPEED_FACTOR
+        * terrain_speed_multiplier(ctx, &enemy.map_id, enemy.position_x, enemy.position_y);
+    let step = (speed * delta_time).min(distance);
+    let next_x = enemy.position_x + dx / distance * step;
+    let next_y = enemy.position_y + dy / distance * step;
+
+    let Some(template) = template_for_map(ctx, &enemy.map_id) else {
+        return false;
+    };
+    let instance_id = ctx.db.map_instance().key_id().find(enemy.map_id.clone()).map(|i| i.id);
+
+    if !is_passable_step(ctx, &template, instance_id, (enemy.position_x, enemy.position_y), (next_x, next_y), false) {
+        // Caminho bloqueado (ex.: mutação do mundo): desiste do ponto atual
+        pick_next_wander_target(ctx, enemy, candidates);
+        enemy.velocity_x = 0.0;
+        enemy.velocity_y = 0.0;
+        refresh_enemy_motion(enemy, ctx.timestamp);
+        return true;
+    }
+
+    enemy.position_x = next_x;
+    enemy.position_y = next_y;
+    enemy.velocity_x = dx / distance * speed;
+    enemy.velocity_y = dy / distance * speed;
+    refresh_enemy_motion(enemy, ctx.timestamp);
+    true
+}
+
+/// Custo de chegar ao ponto: distância ponderada pelo terreno do destino
+fn wander_cost(ctx: &ReducerContext, enemy: &Enemy, (x, y): (f32, f32)) -> f32 {
+    let distance = ((x - enemy.position_x).powi(2) + (y - enemy.position_y).powi(2)).sqrt();
+    distance / terrain_speed_multiplier(ctx, &enemy.map_id, x, y)
+}
+
+fn pick_next_wander_target(ctx: &ReducerContext, enemy: &mut Enemy, candidates: u32) {
+    // Entre os pontos sorteados, prefere o mais barato (evita atravessar lama à toa)
+    let target = (0..candidates)
+        .filter_map(|_| random_walkable_point(
+            ctx, &enemy.map_id, enemy.patrol_center_x, enemy.patrol_center_y, enemy.patrol_radius,
+        ))
+        .min_by(|a, b| wander_cost(ctx, enemy, *a).total_cmp(&wander_cost(ctx, enemy, *b)));
+
+    // Sem ponto livre na região: fica parado onde está
+    let (x, y) = target.unwrap_or((enemy.position_x, enemy.position_y));
+    enemy.wander_target_x = x;
+    enemy.wander_target_y = y;
+}
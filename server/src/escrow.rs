@@ -0,0 +1,262 @@
+use crate::crafting::ItemStack;
+use crate::currency::{add_currency, spend_currency, CURRENCY_GOLD, SOURCE_TRANSFER};
+use crate::inventory::{count_item, remove_item_from_inventory};
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+
+/// Itens e ouro retirados de um player e guardados até a troca ser
+/// concluída (`release`) ou desfeita (`refund` para o dono).
+/// `reference` identifica a troca, ex.: "work_order:12:payment".
+#[table(name = escrow_hold, public)]
+#[derive(Clone)]
+pub struct EscrowHold {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub reference: String,
+    /// Quem recebe a custódia de volta num reembolso
+    pub owner_id: u32,
+    pub items: Vec<ItemStack>,
+    pub gold: u64,
+    pub created_at: Timestamp,
+}
+
+/// Custódia sem itens nem ouro não deve existir
+fn has_goods(items: &[ItemStack], gold: u64) -> bool {
+    !items.is_empty() || gold > 0
+}
+
+/// Destino de uma custódia ao ser fechada com pagamento
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Payout {
+    /// Troca concluída: vai para a contraparte
+    Release(u32),
+    /// Troca desfeita: volta para o dono
+    Refund,
+}
+
+impl Payout {
+    fn recipient(self, hold: &EscrowHold) -> u32 {
+        match self {
+            Payout::Release(recipient_id) => recipient_id,
+            Payout::Refund => hold.owner_id,
+        }
+    }
+}
+
+/// Valida a entrada de uma custódia contra o que o player tem (`have`).
+/// Pilhas repetidas do mesmo item somam.
+fn check_hold(items: &[ItemStack], have: impl Fn(&str) -> i32) -> Result<(), String> {
+    for stack in items {
+        if stack.quantity <= 0 {
+            return Err("Item quantities must be positive".to_string());
+        }
+        let needed: i32 = items.iter().filter(|s| s.item_id == stack.item_id).map(|s| s.quantity).sum();
+        if have(&stack.item_id) < needed {
+            return Err(format!("Missing {} x{}", stack.item_id, needed));
+        }
+    }
+    Ok(())
+}
+
+/// Retira itens e ouro de `from_player_id` e abre uma custódia em nome de `owner_id`.
+/// Tudo é validado antes de mover qualquer coisa; sem nada a guardar, não abre linha.
+pub fn hold(
+    ctx: &ReducerContext,
+    from_player_id: u32,
+    owner_id: u32,
+    reference: &str,
+    items: &[ItemStack],
+    gold: u64,
+) -> Result<(), String> {
+    check_hold(items, |item_id| count_item(ctx, from_player_id, item_id))?;
+    if !has_goods(items, gold) {
+        return Ok(());
+    }
+    if gold > 0 {
+        spend_currency(ctx, from_player_id, CURRENCY_GOLD, gold, SOURCE_TRANSFER)?;
+    }
+    for stack in items {
+        remove_item_from_inventory(ctx, from_player_id, &stack.item_id, stack.quantity)?;
+    }
+
+    let hold = ctx.db.escrow_hold().insert(EscrowHold {
+        id: 0,
+        reference: reference.to_string(),
+        owner_id,
+        items: items.to_vec(),
+        gold,
+        created_at: ctx.timestamp,
+    });
+    log::info!("🔒 Escrow {} '{}' opened ({} items, {} gold)", hold.id, reference, hold.items.len(), gold);
+    Ok(())
+}
+
+/// Entrega o conteúdo das custódias de `reference` a `recipient_id` e as fecha
+pub fn release(ctx: &ReducerContext, reference: &str, recipient_id: u32) -> Result<(), String> {
+    for hold in holds_for(ctx, reference) {
+        pay_out(ctx, &hold, Payout::Release(recipient_id))?;
+    }
+    Ok(())
+}
+
+/// Devolve as custódias de `reference` aos respectivos donos
+pub fn refund(ctx: &ReducerContext, reference: &str) -> Result<(), String> {
+    for hold in holds_for(ctx, reference) {
+        pay_out(ctx, &hold, Payout::Refund)?;
+    }
+    Ok(())
+}
+
+/// Devolve as custódias de `reference` aos donos por correio. Para rotinas
+/// agendadas, onde um inventário cheio não pode desfazer a limpeza inteira.
+pub fn refund_by_mail(ctx: &ReducerContext, reference: &str, subject: &str) {
+    for hold in holds_for(ctx, reference) {
+        crate::mail::send_system_mail(ctx, hold.owner_id, subject, format!("Returned from '{}'", hold.reference), hold.items.clone(), hold.gold, SOURCE_TRANSFER);
+        ctx.db.escrow_hold().id().delete(hold.id);
+        log::info!("✉️ Escrow {} '{}' returned by mail to player {}", hold.id, hold.reference, hold.owner_id);
+    }
+}
+
+/// Fecha as custódias de `reference` sem pagar ninguém (dono apagado)
+pub fn discard(ctx: &ReducerContext, reference: &str) {
+    for hold in holds_for(ctx, reference) {
+        ctx.db.escrow_hold().id().delete(hold.id);
+        log::info!("🗑️ Escrow {} '{}' discarded", hold.id, hold.reference);
+    }
+}
+
+pub fn holds_for(ctx: &ReducerContext, reference: &str) -> Vec<EscrowHold> {
+    ctx.db.escrow_hold().reference().filter(reference).collect()
+}
+
+/// Inventário cheio faz a chamada falhar; o reducer desfaz a transação inteira
+fn pay_out(ctx: &ReducerContext, hold: &EscrowHold, payout: Payout) -> Result<(), String> {
+    let recipient_id = payout.recipient(hold);
+    for stack in &hold.items {
+        crate::inventory::add_item_to_inventory(ctx, recipient_id, stack.item_id.clone(), stack.quantity)
+            .map_err(|e| e.to_string())?;
+    }
+    if hold.gold > 0 {
+        add_currency(ctx, recipient_id, CURRENCY_GOLD, hold.gold, SOURCE_TRANSFER);
+    }
+    ctx.db.escrow_hold().id().delete(hold.id);
+    log::info!("🔓 Escrow {} '{}' paid out to player {}", hold.id, hold.reference, recipient_id);
+    Ok(())
+}
+
+/// Registra como custódia valores que já estavam fora do inventário
+/// (linhas anteriores a este serviço). Não debita ninguém.
+pub fn adopt(ctx: &ReducerContext, owner_id: u32, reference: &str, items: Vec<ItemStack>, gold: u64) {
+    if !has_goods(&items, gold) {
+        return;
+    }
+    ctx.db.escrow_hold().insert(EscrowHold {
+        id: 0,
+        reference: reference.to_string(),
+        owner_id,
+        items,
+        gold,
+        created_at: ctx.timestamp,
+    });
+}
+
+/// Invariantes que uma custódia viola; `live` diz se a troca dona dela ainda existe
+fn hold_violations(hold: &EscrowHold, live: bool) -> Vec<&'static str> {
+    let mut violations = Vec::new();
+    if !has_goods(&hold.items, hold.gold) {
+        violations.push("is empty");
+    }
+    if hold.items.iter().any(|s| s.quantity <= 0) {
+        violations.push("holds a non-positive stack");
+    }
+    if !live {
+        violations.push("has no live exchange");
+    }
+    violations
+}
+
+/// Invariantes verificados na limpeza periódica: toda custódia pertence a uma
+/// troca viva e não há custódia vazia. Quem abre custódias responde por
+/// `is_live(reference)`. Violações são logadas, nunca corrigidas às cegas,
+/// para não sumir com bens de ninguém.
+pub fn verify_escrow_invariants(ctx: &ReducerContext, is_live: impl Fn(&ReducerContext, &str) -> bool) {
+    for hold in ctx.db.escrow_hold().iter() {
+        for violation in hold_violations(&hold, is_live(ctx, &hold.reference)) {
+            log::warn!("⚠️ Escrow {} '{}' {} (owner {})", hold.id, hold.reference, violation, hold.owner_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stack(item_id: &str, quantity: i32) -> ItemStack {
+        ItemStack { item_id: item_id.to_string(), quantity }
+    }
+
+    fn escrow(items: Vec<ItemStack>, gold: u64) -> EscrowHold {
+        EscrowHold { id: 1, reference: "work_order:1:payment".to_string(), owner_id: 7, items, gold, created_at: Timestamp::UNIX_EPOCH }
+    }
+
+    #[test]
+    fn hold_rejects_non_positive_stacks() {
+        assert!(check_hold(&[stack("iron", 0)], |_| 10).is_err());
+        assert!(check_hold(&[stack("iron", -2)], |_| 10).is_err());
+    }
+
+    #[test]
+    fn hold_sums_repeated_stacks() {
+        let items = [stack("iron", 3), stack("iron", 3)];
+        assert_eq!(check_hold(&items, |_| 5), Err("Missing iron x6".to_string()));
+        assert!(check_hold(&items, |_| 6).is_ok());
+    }
+
+    #[test]
+    fn hold_checks_each_item_separately() {
+        let items = [stack("iron", 2), stack("wood", 4)];
+        let have = |item_id: &str| if item_id == "iron" { 2 } else { 3 };
+        assert_eq!(check_hold(&items, have), Err("Missing wood x4".to_string()));
+    }
+
+    #[test]
+    fn gold_only_hold_needs_no_items() {
+        assert!(check_hold(&[], |_| 0).is_ok());
+    }
+
+    #[test]
+    fn holds_and_adoptions_need_goods() {
+        assert!(!has_goods(&[], 0));
+        assert!(has_goods(&[], 1));
+        assert!(has_goods(&[stack("iron", 1)], 0));
+    }
+
+    #[test]
+    fn release_pays_the_counterparty_and_refund_the_owner() {
+        let hold = escrow(vec![stack("iron", 1)], 0);
+        assert_eq!(Payout::Release(9).recipient(&hold), 9);
+        assert_eq!(Payout::Refund.recipient(&hold), hold.owner_id);
+    }
+
+    #[test]
+    fn live_hold_with_goods_is_clean() {
+        assert!(hold_violations(&escrow(vec![stack("iron", 2)], 0), true).is_empty());
+        assert!(hold_violations(&escrow(Vec::new(), 50), true).is_empty());
+    }
+
+    #[test]
+    fn empty_hold_is_flagged() {
+        assert_eq!(hold_violations(&escrow(Vec::new(), 0), true), vec!["is empty"]);
+    }
+
+    #[test]
+    fn non_positive_stack_is_flagged() {
+        assert_eq!(hold_violations(&escrow(vec![stack("iron", 0)], 10), true), vec!["holds a non-positive stack"]);
+    }
+
+    #[test]
+    fn orphaned_hold_is_flagged() {
+        assert_eq!(hold_violations(&escrow(vec![stack("iron", 1)], 0), false), vec!["has no live exchange"]);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use spacetimedb::{reducer, table, Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Grupo de um experimento. `value = None` é o controle (usa a config normal).
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct ExperimentCohort {
+    pub name: String,
+    pub weight: u32,
+    pub value: Option<String>,
+}
+
+/// Experimento A/B sobre uma chave de `server_config`
+#[table(name = experiment, public)]
+#[derive(Clone)]
+pub struct Experiment {
+    #[primary_key]
+    pub name: String,
+    pub config_key: String,
+    pub cohorts: Vec<ExperimentCohort>,
+    pub active: bool,
+    pub created_at: Timestamp,
+    pub ended_at: Option<Timestamp>,
+}
+
+/// Cohort de cada identidade, gravado na primeira leitura: mudar os pesos
+/// depois não move quem já foi sorteado
+#[table(name = experiment_assignment)]
+#[derive(Clone)]
+pub struct ExperimentAssignment {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub experiment: String,
+    pub cohort: String,
+    pub assigned_at: Timestamp,
+}
+
+/// Resultado agregado por cohort. `key` = "experiment:cohort:metric".
+#[table(name = experiment_outcome, public)]
+#[derive(Clone)]
+pub struct ExperimentOutcome {
+    #[primary_key]
+    pub key: String,
+    #[index(btree)]
+    pub experiment: String,
+    pub cohort: String,
+    pub metric: String,
+    pub samples: u64,
+    pub total: f64,
+    pub updated_at: Timestamp,
+}
+
+/// Sorteio determinístico: a mesma identidade cai sempre no mesmo bucket
+fn pick_cohort(experiment: &Experiment, identity: Identity) -> Option<String> {
+    let total: u64 = experiment.cohorts.iter().map(|c| c.weight as u64).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut hasher = DefaultHasher::new();
+    experiment.name.hash(&mut hasher);
+    identity.to_hex().hash(&mut hasher);
+    let mut bucket = hasher.finish() % total;
+    for cohort in &experiment.cohorts {
+        if bucket < cohort.weight as u64 {
+            return Some(cohort.name.clone());
+        }
+        bucket -= cohort.weight as u64;
+    }
+    None
+}
+
+fn cohort_of(ctx: &ReducerContext, experiment: &Experiment, identity: Identity) -> Option<String> {
+    if let Some(assignment) = ctx.db.experiment_assignment().identity().filter(identity).find(|a| a.experiment == experiment.name) {
+        return Some(assignment.cohort);
+    }
+    let cohort = pick_cohort(experiment, identity)?;
+    ctx.db.experiment_assignment().insert(ExperimentAssignment {
+        id: 0,
+        identity,
+        experiment: experiment.name.clone(),
+        cohort: cohort.clone(),
+        assigned_at: ctx.timestamp,
+    });
+    record_outcome(ctx, &experiment.name, &cohort, "players", 1.0);
+    Some(cohort)
+}
+
+/// Reclaim do username: o player continua na mesma cohort com a nova
+/// identidade. A atribuição antiga vence a que a identidade nova já tiver.
+pub fn transfer_assignments(ctx: &ReducerContext, from: Identity, to: Identity) {
+    for mut assignment in ctx.db.experiment_assignment().identity().filter(from).collect::<Vec<_>>() {
+        if let Some(existing) = ctx.db.experiment_assignment().identity().filter(to).find(|a| a.experiment == assignment.experiment) {
+            ctx.db.experiment_assignment().id().delete(existing.id);
+        }
+        assignment.identity = to;
+        ctx.db.experiment_assignment().id().update(assignment);
+    }
+}
+
+/// Valor sobrescrito pela cohort da identidade num experimento ativo sobre `key`
+pub fn config_override(ctx: &ReducerContext, identity: Identity, key: &str) -> Option<String> {
+    let experiment = ctx.db.experiment().iter().find(|e| e.active && e.config_key == key)?;
+    let cohort = cohort_of(ctx, &experiment, identity)?;
+    experiment.cohorts.into_iter().find(|c| c.name == cohort)?.value
+}
+
+fn record_outcome(ctx: &ReducerContext, experiment: &str, cohort: &str, metric: &str, value: f64) {
+    let key = format!("{}:{}:{}", experiment, cohort, metric);
+    match ctx.db.experiment_outcome().key().find(key.clone()) {
+        Some(mut row) => {
+            row.samples += 1;
+            row.total += value;
+            row.updated_at = ctx.timestamp;
+            ctx.db.experiment_outcome().key().update(row);
+        }
+        None => {
+            ctx.db.experiment_outcome().insert(ExperimentOutcome {
+                key,
+                experiment: experiment.to_string(),
+                cohort: cohort.to_string(),
+                metric: metric.to_string(),
+                samples: 1,
+                total: value,
+                updated_at: ctx.timestamp,
+            });
+        }
+    }
+}
+
+/// Soma um resultado (ex: "xp_gained") na cohort de cada experimento ativo da identidade
+pub fn record_experiment_metric(ctx: &ReducerContext, identity: Identity, metric: &str, value: f64) {
+    let active: Vec<String> = ctx.db.experiment().iter().filter(|e| e.active).map(|e| e.name).collect();
+    for assignment in ctx.db.experiment_assignment().identity().filter(identity) {
+        if active.contains(&assignment.experiment) {
+            record_outcome(ctx, &assignment.experiment, &assignment.cohort, metric, value);
+        }
+    }
+}
+
+#[reducer]
+pub fn create_experiment(ctx: &ReducerContext, name: String, config_key: String, cohorts: Vec<ExperimentCohort>) -> Result<(), String> {
+    require_admin(ctx)?;
+    if ctx.db.experiment().name().find(name.clone()).is_some() {
+        return Err(format!("Experiment '{}' already exists", name));
+    }
+    if ctx.db.experiment().iter().any(|e| e.active && e.config_key == config_key) {
+        return Err(format!("'{}' is already under an active experiment", config_key));
+    }
+    if cohorts.len() < 2 || cohorts.iter().all(|c| c.weight == 0) {
+        return Err("An experiment needs at least two cohorts with weight".to_string());
+    }
+    if cohorts.iter().enumerate().any(|(i, c)| cohorts[..i].iter().any(|o| o.name == c.name)) {
+        return Err("Cohort names must be unique".to_string());
+    }
+
+    record_audit(ctx, "experiment", format!("Started '{}' on '{}' ({} cohorts)", name, config_key, cohorts.len()));
+    ctx.db.experiment().insert(Experiment {
+        name,
+        config_key,
+        cohorts,
+        active: true,
+        created_at: ctx.timestamp,
+        ended_at: None,
+    });
+    Ok(())
+}
+
+/// Encerra o experimento: as sobrescritas param, resultados e cohorts ficam para análise
+#[reducer]
+pub fn end_experiment(ctx: &ReducerContext, name: String) -> Result<(), String> {
+    require_admin(ctx)?;
+    let mut experiment = ctx.db.experiment().name().find(name.clone()).filter(|e| e.active).ok_or("Active experiment not found")?;
+    experiment.active = false;
+    experiment.ended_at = Some(ctx.timestamp);
+    ctx.db.experiment().name().update(experiment);
+    record_audit(ctx, "experiment", format!("Ended '{}'", name));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn experiment(cohorts: &[(&str, u32)]) -> Experiment {
+        Experiment {
+            name: "xp_rate".to_string(),
+            config_key: "xp_multiplier".to_string(),
+            cohorts: cohorts.iter().map(|(name, weight)| ExperimentCohort { name: name.to_string(), weight: *weight, value: None }).collect(),
+            active: true,
+            created_at: Timestamp::UNIX_EPOCH,
+            ended_at: None,
+        }
+    }
+
+    fn identity(seed: u16) -> Identity {
+        let mut bytes = [0u8; 32];
+        bytes[..2].copy_from_slice(&seed.to_le_bytes());
+        Identity::from_byte_array(bytes)
+    }
+
+    #[test]
+    fn no_weight_means_no_cohort() {
+        assert_eq!(pick_cohort(&experiment(&[]), identity(1)), None);
+        assert_eq!(pick_cohort(&experiment(&[("control", 0)]), identity(1)), None);
+    }
+
+    #[test]
+    fn same_identity_lands_in_the_same_cohort() {
+        let exp = experiment(&[("control", 50), ("boost", 50)]);
+        for seed in 0..32 {
+            assert_eq!(pick_cohort(&exp, identity(seed)), pick_cohort(&exp, identity(seed)));
+        }
+    }
+
+    #[test]
+    fn zero_weight_cohorts_are_never_picked() {
+        let exp = experiment(&[("control", 1), ("off", 0)]);
+        assert!((0..200).all(|seed| pick_cohort(&exp, identity(seed)).as_deref() == Some("control")));
+    }
+
+    #[test]
+    fn weights_split_identities() {
+        let exp = experiment(&[("control", 50), ("boost", 50)]);
+        let boosted = (0..1000).filter(|seed| pick_cohort(&exp, identity(*seed)).as_deref() == Some("boost")).count();
+        assert!((350..650).contains(&boosted), "{} of 1000 in boost", boosted);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::account_unlock::{record_account_unlock, UNLOCK_WAYPOINT};
+use crate::achievement::unlock_achievement;
+use crate::map::{map_template, template_for_map};
+use crate::progression::grant_xp;
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+
+pub const DISCOVERY_XP: u64 = 50;
+
+/// Distritos: conquista desbloqueada ao visitar todos os mapas listados
+const DISTRICTS: &[(&str, &[&str])] = &[
+    ("explore_tavern_district", &["tavern_outside", "tavern_inside"]),
+];
+const EXPLORE_ALL_MAPS: &str = "explore_all_maps";
+
+/// Mapas que cada player já visitou (XP de descoberta é concedida uma única vez)
+#[table(name = map_discovery, public)]
+#[derive(Clone)]
+pub struct MapDiscovery {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub map_id: String,
+    pub discovered_at: Timestamp,
+}
+
+pub fn has_discovered(ctx: &ReducerContext, player_id: u32, map_id: &str) -> bool {
+    ctx.db.map_discovery().player_id().filter(player_id).any(|d| d.map_id == map_id)
+}
+
+/// Registra a entrada do player no mapa; na primeira visita concede XP e
+/// avalia as conquistas de exploração.
+pub fn discover_map(ctx: &ReducerContext, player_id: u32, map_id: &str) {
+    // Instâncias separadas contam como o template de origem
+    let Some(template) = template_for_map(ctx, map_id) else { return };
+    let map_id = template.name.as_str();
+    // A ilha tutorial é privada de cada player novo: não é um destino do mundo
+    if map_id == crate::tutorial::TUTORIAL_TEMPLATE {
+        return;
+    }
+
+    if has_discovered(ctx, player_id, map_id) {
+        return;
+    }
+
+    ctx.db.map_discovery().insert(MapDiscovery {
+        id: 0,
+        player_id,
+        map_id: map_id.to_string(),
+        discovered_at: ctx.timestamp,
+    });
+    log::info!("🧭 Player {} discovered '{}'", player_id, map_id);
+    record_account_unlock(ctx, player_id, UNLOCK_WAYPOINT, map_id);
+    grant_xp(ctx, player_id, DISCOVERY_XP, "discovery");
+
+    check_exploration_achievements(ctx, player_id);
+}
+
+fn check_exploration_achievements(ctx: &ReducerContext, player_id: u32) {
+    let visited: Vec<String> = ctx.db.map_discovery().player_id().filter(player_id).map(|d| d.map_id).collect();
+
+    for (achievement_id, maps) in DISTRICTS {
+        if maps.iter().all(|m| visited.iter().any(|v| v == m)) {
+            unlock_achievement(ctx, player_id, achievement_id);
+        }
+    }
+
+    let all_maps_visited = ctx.db.map_template().iter()
+        .filter(|t| t.name != crate::tutorial::TUTORIAL_TEMPLATE)
+        .all(|t| visited.contains(&t.name));
+    if all_maps_visited {
+        unlock_achievement(ctx, player_id, EXPLORE_ALL_MAPS);
+    }
+}
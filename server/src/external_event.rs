@@ -0,0 +1,150 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use spacetimedb::{reducer, table, view, Identity, ReducerContext, SpacetimeType, Table, Timestamp, ViewContext};
+
+pub const EVENT_PLAYER_REGISTERED: &str = "player_registered";
+pub const EVENT_BOSS_KILLED: &str = "boss_killed";
+pub const EVENT_REPORT_FILED: &str = "report_filed";
+/// Todo `kind` emitido precisa estar aqui para chegar aos consumidores
+const EVENT_KINDS: &[&str] = &[EVENT_PLAYER_REGISTERED, EVENT_BOSS_KILLED, EVENT_REPORT_FILED];
+
+/// Sem nenhum consumidor registrado, o outbox guarda só os eventos mais recentes
+const MAX_UNCONSUMED_EVENTS: usize = 10_000;
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct EventField {
+    pub key: String,
+    pub value: String,
+}
+
+/// Outbox para consumidores fora do servidor (Discord, analytics).
+/// `seq` é crescente; cada consumidor confirma até onde já processou.
+/// Privada: consumidores leem pelo `my_external_events`.
+#[table(name = external_event)]
+#[derive(Clone)]
+pub struct ExternalEvent {
+    #[primary_key]
+    #[auto_inc]
+    pub seq: u64,
+    #[index(btree)]
+    pub kind: String,
+    pub fields: Vec<EventField>,
+    pub created_at: Timestamp,
+}
+
+/// Consumidor registrado e o último `seq` que ele confirmou. Uma identidade
+/// pode responder por vários consumidores (um cursor por nome).
+#[table(name = external_consumer)]
+#[derive(Clone)]
+pub struct ExternalConsumer {
+    #[primary_key]
+    pub name: String,
+    #[index(btree)]
+    pub identity: Identity,
+    pub acked_seq: u64,
+    pub acked_at: Option<Timestamp>,
+}
+
+/// Eventos ainda não confirmados pelos consumidores da identidade chamadora;
+/// para quem não é consumidor, nada
+#[view(name = my_external_events, public)]
+pub fn my_external_events(ctx: &ViewContext) -> Vec<ExternalEvent> {
+    let cursor = ctx.db.external_consumer().identity().filter(ctx.sender).map(|c| c.acked_seq).min();
+    let Some(acked) = cursor else { return Vec::new() };
+    let mut events: Vec<ExternalEvent> = EVENT_KINDS.iter()
+        .flat_map(|kind| ctx.db.external_event().kind().filter(*kind))
+        .filter(|e| e.seq > acked)
+        .collect();
+    events.sort_by_key(|e| e.seq);
+    events
+}
+
+pub fn emit(ctx: &ReducerContext, kind: &str, fields: &[(&str, String)]) {
+    let event = ctx.db.external_event().insert(ExternalEvent {
+        seq: 0,
+        kind: kind.to_string(),
+        fields: fields.iter().map(|(k, v)| EventField { key: k.to_string(), value: v.clone() }).collect(),
+        created_at: ctx.timestamp,
+    });
+    log::debug!("📤 External event {} '{}'", event.seq, kind);
+}
+
+/// Troca o nome do player nos eventos ainda guardados (exclusão de conta)
+pub fn anonymize_player(ctx: &ReducerContext, player_id: u32, anonymous: &str) {
+    let id = player_id.to_string();
+    let mentions = |e: &ExternalEvent| e.fields.iter().any(|f| f.key == "player_id" && f.value == id);
+    for mut event in ctx.db.external_event().iter().filter(mentions).collect::<Vec<_>>() {
+        for field in event.fields.iter_mut().filter(|f| f.key == "username") {
+            field.value = anonymous.to_string();
+        }
+        ctx.db.external_event().seq().update(event);
+    }
+}
+
+/// Remove o que todos os consumidores já confirmaram (chamado na limpeza periódica)
+pub fn prune_acknowledged_events(ctx: &ReducerContext) {
+    let min_acked = ctx.db.external_consumer().iter().map(|c| c.acked_seq).min();
+    match min_acked {
+        Some(acked) => {
+            let done: Vec<u64> = ctx.db.external_event().iter().filter(|e| e.seq <= acked).map(|e| e.seq).collect();
+            for seq in done {
+                ctx.db.external_event().seq().delete(seq);
+            }
+        }
+        None => {
+            let mut seqs: Vec<u64> = ctx.db.external_event().iter().map(|e| e.seq).collect();
+            if seqs.len() > MAX_UNCONSUMED_EVENTS {
+                seqs.sort_unstable();
+                for seq in &seqs[..seqs.len() - MAX_UNCONSUMED_EVENTS] {
+                    ctx.db.external_event().seq().delete(*seq);
+                }
+            }
+        }
+    }
+}
+
+#[reducer]
+pub fn register_external_consumer(ctx: &ReducerContext, name: String, identity: Identity) -> Result<(), String> {
+    require_admin(ctx)?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Consumer name cannot be empty".to_string());
+    }
+    if ctx.db.external_consumer().name().find(&name).is_some() {
+        return Err("Consumer already registered".to_string());
+    }
+    // Começa a partir do evento mais antigo ainda guardado
+    let oldest = ctx.db.external_event().iter().map(|e| e.seq).min().unwrap_or(1);
+    ctx.db.external_consumer().insert(ExternalConsumer {
+        name: name.clone(),
+        identity,
+        acked_seq: oldest.saturating_sub(1),
+        acked_at: None,
+    });
+    record_audit(ctx, "external_event", format!("Registered consumer '{}'", name));
+    Ok(())
+}
+
+#[reducer]
+pub fn remove_external_consumer(ctx: &ReducerContext, name: String) -> Result<(), String> {
+    require_admin(ctx)?;
+    ctx.db.external_consumer().name().find(&name).ok_or("Consumer not found")?;
+    ctx.db.external_consumer().name().delete(&name);
+    record_audit(ctx, "external_event", format!("Removed consumer '{}'", name));
+    Ok(())
+}
+
+/// Chamado pelo consumidor depois de encaminhar os eventos até `seq` (inclusive)
+#[reducer]
+pub fn ack_external_events(ctx: &ReducerContext, name: String, seq: u64) -> Result<(), String> {
+    let mut consumer = ctx.db.external_consumer().name().find(&name)
+        .filter(|c| c.identity == ctx.sender)
+        .ok_or("Not a registered consumer")?;
+    if seq < consumer.acked_seq {
+        return Err("Cannot move the cursor backwards".to_string());
+    }
+    consumer.acked_seq = seq;
+    consumer.acked_at = Some(ctx.timestamp);
+    ctx.db.external_consumer().name().update(consumer);
+    Ok(())
+}
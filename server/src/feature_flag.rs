@@ -0,0 +1,75 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use crate::localization::Message;
+use spacetimedb::{reducer, table, Identity, ReducerContext, Table, Timestamp};
+
+pub const PVP_ENABLED: &str = "pvp_enabled";
+pub const MARKET_ENABLED: &str = "market_enabled";
+pub const HARDCORE_ENABLED: &str = "hardcore_enabled";
+
+/// Flags conhecidas e seus valores padrão
+const DEFAULT_FLAGS: &[(&str, bool)] = &[
+    (PVP_ENABLED, false),
+    (MARKET_ENABLED, true),
+    (HARDCORE_ENABLED, false),
+];
+
+/// Chaves liga/desliga para sistemas arriscados, alteráveis sem redeploy
+#[table(name = feature_flag, public)]
+#[derive(Clone)]
+pub struct FeatureFlag {
+    #[primary_key]
+    pub name: String,
+    pub enabled: bool,
+    pub updated_by: Identity,
+    pub updated_at: Timestamp,
+}
+
+/// Cria as flags padrão que ainda não existem (não sobrescreve valores alterados)
+pub fn seed_feature_flags(ctx: &ReducerContext) {
+    for (name, enabled) in DEFAULT_FLAGS {
+        if ctx.db.feature_flag().name().find(name.to_string()).is_none() {
+            ctx.db.feature_flag().insert(FeatureFlag {
+                name: name.to_string(),
+                enabled: *enabled,
+                updated_by: ctx.sender,
+                updated_at: ctx.timestamp,
+            });
+        }
+    }
+}
+
+pub fn is_feature_enabled(ctx: &ReducerContext, name: &str) -> bool {
+    match ctx.db.feature_flag().name().find(name.to_string()) {
+        Some(flag) => flag.enabled,
+        None => DEFAULT_FLAGS.iter().find(|(n, _)| *n == name).map(|(_, d)| *d).unwrap_or(false),
+    }
+}
+
+/// Guard para o topo dos reducers: falha se a feature estiver desligada
+pub fn require_feature(ctx: &ReducerContext, name: &str) -> Result<(), String> {
+    if is_feature_enabled(ctx, name) {
+        Ok(())
+    } else {
+        Err(Message::new("error.feature_disabled").param("feature", name).into())
+    }
+}
+
+/// Liga/desliga uma flag (também usada por sistemas como eventos sazonais)
+pub fn set_feature(ctx: &ReducerContext, name: &str, enabled: bool) {
+    let flag = FeatureFlag { name: name.to_string(), enabled, updated_by: ctx.sender, updated_at: ctx.timestamp };
+    if ctx.db.feature_flag().name().find(name.to_string()).is_some() {
+        ctx.db.feature_flag().name().update(flag);
+    } else {
+        ctx.db.feature_flag().insert(flag);
+    }
+}
+
+#[reducer]
+pub fn set_feature_flag(ctx: &ReducerContext, name: String, enabled: bool) -> Result<(), String> {
+    require_admin(ctx)?;
+
+    set_feature(ctx, &name, enabled);
+    record_audit(ctx, "feature_flag", format!("{} set to {}", name, enabled));
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use crate::combat::{enemy, is_boss_type, refresh_enemy_motion};
+use crate::map::{is_passable_step, map_instance, template_for_map, TILE_SIZE};
+use crate::movement::refresh_player_motion;
+use crate::player;
+use crate::status_effect::is_immune_to_forced_movement;
+use spacetimedb::ReducerContext;
+
+/// Inimigos que nunca são deslocados (âncora de boss)
+const ANCHORED_ENEMY_TYPES: &[&str] = &["WorldBoss"];
+
+pub fn is_anchored_enemy(enemy_type: &str) -> bool {
+    is_boss_type(enemy_type) || ANCHORED_ENEMY_TYPES.contains(&enemy_type)
+}
+
+/// Ponto final do deslocamento, parando no primeiro tile bloqueado
+fn displaced_point(ctx: &ReducerContext, map_id: &str, from: (f32, f32), dir: (f32, f32), distance: f32) -> (f32, f32) {
+    let Some(template) = template_for_map(ctx, map_id) else { return from };
+    let instance_id = ctx.db.map_instance().key_id().find(map_id.to_string()).map(|i| i.id);
+
+    let step = TILE_SIZE / 2.0;
+    let steps = (distance / step).ceil() as u32;
+    let mut reached = from;
+    for i in 1..=steps {
+        let travelled = (step * i as f32).min(distance);
+        let next = (from.0 + dir.0 * travelled, from.1 + dir.1 * travelled);
+        if !is_passable_step(ctx, &template, instance_id, reached, next, false) {
+            break;
+        }
+        reached = next;
+    }
+    reached
+}
+
+/// Único ponto de entrada para knockback/puxão: respeita imunidades e âncoras.
+/// `direction` não precisa estar normalizada. Retorna `true` se o alvo se moveu.
+pub fn apply_forced_movement(ctx: &ReducerContext, target_id: u32, direction: (f32, f32), distance: f32) -> bool {
+    let length = (direction.0 * direction.0 + direction.1 * direction.1).sqrt();
+    if length <= f32::EPSILON || distance <= 0.0 {
+        return false;
+    }
+    let dir = (direction.0 / length, direction.1 / length);
+
+    if let Some(mut player) = ctx.db.player().id().find(target_id) {
+        if player.is_downed || is_immune_to_forced_movement(ctx, target_id) {
+            return false;
+        }
+        let (x, y) = displaced_point(ctx, &player.current_map_id, (player.position_x, player.position_y), dir, distance);
+        player.position_x = x;
+        player.position_y = y;
+        refresh_player_motion(&mut player, ctx.timestamp);
+        ctx.db.player().id().update(player);
+        return true;
+    }
+
+    if let Some(mut enemy) = ctx.db.enemy().id().find(target_id) {
+        if is_anchored_enemy(&enemy.enemy_type) {
+            return false;
+        }
+        let (x, y) = displaced_point(ctx, &enemy.map_id, (enemy.position_x, enemy.position_y), dir, distance);
+        enemy.position_x = x;
+        enemy.position_y = y;
+        refresh_enemy_motion(&mut enemy, ctx.timestamp);
+        ctx.db.enemy().id().update(enemy);
+        return true;
+    }
+    false
+}
+
+/// Empurra o alvo para longe de (from_x, from_y)
+pub fn knockback(ctx: &ReducerContext, target_id: u32, from_x: f32, from_y: f32, target_pos: (f32, f32), distance: f32) -> bool {
+    apply_forced_movement(ctx, target_id, (target_pos.0 - from_x, target_pos.1 - from_y), distance)
+}
@@ -0,0 +1,49 @@
+use crate::party::sender_player;
+use crate::player;
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+/// Lista de amigos (unidirecional: quem adiciona confia em quem foi adicionado)
+#[table(name = friend, public)]
+#[derive(Clone)]
+pub struct Friend {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub friend_id: u32,
+    pub added_at: Timestamp,
+}
+
+pub fn is_friend_of(ctx: &ReducerContext, player_id: u32, friend_id: u32) -> bool {
+    ctx.db.friend().player_id().filter(player_id).any(|f| f.friend_id == friend_id)
+}
+
+#[reducer]
+pub fn add_friend(ctx: &ReducerContext, friend_id: u32) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if player.id == friend_id {
+        return Err("Cannot add yourself".to_string());
+    }
+    if ctx.db.player().id().find(friend_id).is_none() {
+        return Err("Player not found".to_string());
+    }
+    if is_friend_of(ctx, player.id, friend_id) {
+        return Ok(());
+    }
+    ctx.db.friend().insert(Friend { id: 0, player_id: player.id, friend_id, added_at: ctx.timestamp });
+    Ok(())
+}
+
+#[reducer]
+pub fn remove_friend(ctx: &ReducerContext, friend_id: u32) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let rows: Vec<u64> = ctx.db.friend().player_id().filter(player.id)
+        .filter(|f| f.friend_id == friend_id)
+        .map(|f| f.id)
+        .collect();
+    for id in rows {
+        ctx.db.friend().id().delete(id);
+    }
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use crate::party::party_of;
+use crate::player;
+use spacetimedb::{table, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+/// Objeto colocado por um player fica reservado a ele por este tempo
+const PLACED_PROTECTION_SECS: u64 = 30 * 60;
+/// Nó de recurso começado por alguém fica reservado enquanto ele trabalha
+const HARVEST_TAG_SECS: u64 = 60;
+
+pub const TAG_PLACED: &str = "placed";
+pub const TAG_HARVESTING: &str = "harvesting";
+
+pub const TARGET_OBJECT: &str = "object";
+pub const TARGET_STRUCTURE: &str = "structure";
+
+/// Posse temporária de um alvo do mundo (`interactable_object` ou `structure`):
+/// só o dono (e a party dele) colhe, destrói ou mexe no alvo até `expires_at`
+#[table(name = ownership_tag, public)]
+#[derive(Clone)]
+pub struct OwnershipTag {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub target_id: u64,
+    pub target_kind: String,
+    #[index(btree)]
+    pub owner_id: u32,
+    pub reason: String,
+    pub expires_at: Timestamp,
+}
+
+fn find_tag(ctx: &ReducerContext, kind: &str, target_id: u64) -> Option<OwnershipTag> {
+    ctx.db.ownership_tag().target_id().filter(target_id).find(|t| t.target_kind == kind)
+}
+
+fn active_tag(ctx: &ReducerContext, kind: &str, target_id: u64) -> Option<OwnershipTag> {
+    find_tag(ctx, kind, target_id).filter(|t| t.expires_at > ctx.timestamp)
+}
+
+/// Falha se outro player (fora da party do dono) tem a posse do alvo
+pub fn ensure_can_interact(ctx: &ReducerContext, kind: &str, target_id: u64, player_id: u32) -> Result<(), String> {
+    let Some(tag) = active_tag(ctx, kind, target_id) else { return Ok(()) };
+    let same_party = party_of(ctx, player_id).is_some_and(|p| party_of(ctx, tag.owner_id) == Some(p));
+    if tag.owner_id == player_id || same_party {
+        return Ok(());
+    }
+    let owner = ctx.db.player().id().find(tag.owner_id).map(|p| p.username_display).unwrap_or_default();
+    let remaining = tag.expires_at.duration_since(ctx.timestamp).unwrap_or_default().as_secs();
+    Err(format!("Protected for {} ({}s left)", owner, remaining))
+}
+
+fn set_tag(ctx: &ReducerContext, kind: &str, target_id: u64, owner_id: u32, reason: &str, secs: u64) {
+    let expires_at = ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(secs));
+    match find_tag(ctx, kind, target_id) {
+        Some(mut tag) => {
+            tag.owner_id = owner_id;
+            tag.reason = reason.to_string();
+            tag.expires_at = expires_at;
+            ctx.db.ownership_tag().id().update(tag);
+        }
+        None => {
+            ctx.db.ownership_tag().insert(OwnershipTag {
+                id: 0,
+                target_id,
+                target_kind: kind.to_string(),
+                owner_id,
+                reason: reason.to_string(),
+                expires_at,
+            });
+        }
+    }
+}
+
+/// Alvo removido do mundo: a posse some junto
+pub fn clear_tag(ctx: &ReducerContext, kind: &str, target_id: u64) {
+    if let Some(tag) = find_tag(ctx, kind, target_id) {
+        ctx.db.ownership_tag().id().delete(tag.id);
+    }
+}
+
+pub fn on_object_placed(ctx: &ReducerContext, object_id: u32, owner_id: u32) {
+    set_tag(ctx, TARGET_OBJECT, object_id as u64, owner_id, TAG_PLACED, PLACED_PROTECTION_SECS);
+}
+
+pub fn on_structure_built(ctx: &ReducerContext, structure_id: u64, owner_id: u32) {
+    set_tag(ctx, TARGET_STRUCTURE, structure_id, owner_id, TAG_PLACED, PLACED_PROTECTION_SECS);
+}
+
+/// Depois de uma coleta: o nó fica com quem começou (sem encurtar a proteção
+/// de um objeto colocado); esgotado, a posse acaba
+pub fn on_object_harvested(ctx: &ReducerContext, object_id: u32, player_id: u32, depleted: bool) {
+    if depleted {
+        clear_tag(ctx, TARGET_OBJECT, object_id as u64);
+        return;
+    }
+    if active_tag(ctx, TARGET_OBJECT, object_id as u64).is_some_and(|t| t.reason == TAG_PLACED) {
+        return;
+    }
+    set_tag(ctx, TARGET_OBJECT, object_id as u64, player_id, TAG_HARVESTING, HARVEST_TAG_SECS);
+}
+
+pub fn prune_expired_tags(ctx: &ReducerContext) {
+    let expired: Vec<u64> = ctx.db.ownership_tag().iter().filter(|t| t.expires_at <= ctx.timestamp).map(|t| t.id).collect();
+    for id in expired {
+        ctx.db.ownership_tag().id().delete(id);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::party::sender_player;
+use crate::player;
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+pub const GUILD_RANK_LEADER: &str = "Leader";
+pub const GUILD_RANK_MEMBER: &str = "Member";
+
+#[table(name = guild, public)]
+#[derive(Clone)]
+pub struct Guild {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[unique]
+    pub name: String,
+    pub leader_id: u32,
+    pub created_at: Timestamp,
+}
+
+/// Um player pertence a no máximo uma guilda
+#[table(name = guild_member, public)]
+#[derive(Clone)]
+pub struct GuildMember {
+    #[primary_key]
+    pub player_id: u32,
+    #[index(btree)]
+    pub guild_id: u64,
+    pub rank: String,
+    pub joined_at: Timestamp,
+}
+
+#[table(name = guild_invite, public)]
+#[derive(Clone)]
+pub struct GuildInvite {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub guild_id: u64,
+    pub invited_at: Timestamp,
+}
+
+pub fn guild_of(ctx: &ReducerContext, player_id: u32) -> Option<u64> {
+    ctx.db.guild_member().player_id().find(player_id).map(|m| m.guild_id)
+}
+
+pub fn same_guild(ctx: &ReducerContext, a: u32, b: u32) -> bool {
+    matches!((guild_of(ctx, a), guild_of(ctx, b)), (Some(x), Some(y)) if x == y)
+}
+
+/// Guilda liderada pelo remetente (erro se não for líder)
+pub fn require_guild_leader(ctx: &ReducerContext) -> Result<(crate::Player, Guild), String> {
+    let leader = sender_player(ctx)?;
+    let guild = guild_of(ctx, leader.id)
+        .and_then(|id| ctx.db.guild().id().find(id))
+        .ok_or("You are not in a guild")?;
+    if guild.leader_id != leader.id {
+        return Err("Only the guild leader can do that".to_string());
+    }
+    Ok((leader, guild))
+}
+
+#[reducer]
+pub fn create_guild(ctx: &ReducerContext, name: String) -> Result<(), String> {
+    let leader = sender_player(ctx)?;
+    let name = name.trim().to_string();
+    if name.len() < 3 || name.len() > 24 {
+        return Err("Guild name must have 3 to 24 characters".to_string());
+    }
+    if guild_of(ctx, leader.id).is_some() {
+        return Err("Already in a guild".to_string());
+    }
+    if ctx.db.guild().name().find(name.clone()).is_some() {
+        return Err("Guild name already taken".to_string());
+    }
+
+    let guild = ctx.db.guild().insert(Guild { id: 0, name, leader_id: leader.id, created_at: ctx.timestamp });
+    ctx.db.guild_member().insert(GuildMember {
+        player_id: leader.id,
+        guild_id: guild.id,
+        rank: GUILD_RANK_LEADER.to_string(),
+        joined_at: ctx.timestamp,
+    });
+    log::info!("🛡️ Guild '{}' ({}) created by player {}", guild.name, guild.id, leader.id);
+    Ok(())
+}
+
+#[reducer]
+pub fn invite_to_guild(ctx: &ReducerContext, player_id: u32) -> Result<(), String> {
+    let (_, guild) = require_guild_leader(ctx)?;
+    if ctx.db.player().id().find(player_id).is_none() {
+        return Err("Player not found".to_string());
+    }
+    if guild_of(ctx, player_id).is_some() {
+        return Err("Player is already in a guild".to_string());
+    }
+    if ctx.db.guild_invite().player_id().filter(player_id).any(|i| i.guild_id == guild.id) {
+        return Ok(());
+    }
+
+    ctx.db.guild_invite().insert(GuildInvite { id: 0, player_id, guild_id: guild.id, invited_at: ctx.timestamp });
+    Ok(())
+}
+
+#[reducer]
+pub fn accept_guild_invite(ctx: &ReducerContext, guild_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let invite = ctx.db.guild_invite().player_id().filter(player.id)
+        .find(|i| i.guild_id == guild_id)
+        .ok_or("No invite from that guild")?;
+    ctx.db.guild_invite().id().delete(invite.id);
+
+    if guild_of(ctx, player.id).is_some() {
+        return Err("Already in a guild".to_string());
+    }
+    if ctx.db.guild().id().find(guild_id).is_none() {
+        return Err("Guild no longer exists".to_string());
+    }
+
+    ctx.db.guild_member().insert(GuildMember {
+        player_id: player.id,
+        guild_id,
+        rank: GUILD_RANK_MEMBER.to_string(),
+        joined_at: ctx.timestamp,
+    });
+    log::info!("🛡️ Player {} joined guild {}", player.id, guild_id);
+    Ok(())
+}
+
+#[reducer]
+pub fn leave_guild(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let guild_id = guild_of(ctx, player.id).ok_or("You are not in a guild")?;
+    remove_member(ctx, player.id, guild_id);
+    Ok(())
+}
+
+/// Tira o player da guilda, passando a liderança ou desfazendo a guilda vazia
+pub fn remove_member(ctx: &ReducerContext, player_id: u32, guild_id: u64) {
+    ctx.db.guild_member().player_id().delete(player_id);
+
+    let remaining: Vec<GuildMember> = ctx.db.guild_member().guild_id().filter(guild_id).collect();
+    match remaining.iter().min_by_key(|m| m.joined_at) {
+        None => {
+            ctx.db.guild().id().delete(guild_id);
+            let invites: Vec<u64> = ctx.db.guild_invite().iter().filter(|i| i.guild_id == guild_id).map(|i| i.id).collect();
+            for id in invites {
+                ctx.db.guild_invite().id().delete(id);
+            }
+            crate::alliance::on_guild_disbanded(ctx, guild_id);
+            log::info!("🛡️ Guild {} disbanded", guild_id);
+        }
+        Some(oldest) => {
+            if let Some(mut guild) = ctx.db.guild().id().find(guild_id) {
+                if guild.leader_id == player_id {
+                    // A liderança passa para o membro mais antigo
+                    guild.leader_id = oldest.player_id;
+                    ctx.db.guild().id().update(guild);
+                    let mut new_leader = oldest.clone();
+                    new_leader.rank = GUILD_RANK_LEADER.to_string();
+                    ctx.db.guild_member().player_id().update(new_leader);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use crate::alliance::alliance_member;
+use crate::guild::{guild, guild_of, require_guild_leader};
+use crate::localization::{announce_localized, Message};
+use spacetimedb::{reducer, table, ReducerContext, SpacetimeType, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+pub const WAR_DURATION_SECS: u64 = 3 * 24 * 60 * 60;
+/// Pontos por abate e por objetivo (derrubar o líder da guilda inimiga)
+const KILL_POINTS: u32 = 1;
+const LEADER_KILL_OBJECTIVE_POINTS: u32 = 5;
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub enum WarState {
+    Proposed,
+    Active,
+    Ended,
+}
+
+/// Guerra entre duas guildas: declarada por uma, aceita pela outra.
+/// Enquanto ativa, membros das duas guildas podem se atacar em qualquer lugar.
+#[table(name = guild_war, public)]
+#[derive(Clone)]
+pub struct GuildWar {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub attacker_guild_id: u64,
+    pub defender_guild_id: u64,
+    pub state: WarState,
+    pub declared_at: Timestamp,
+    pub ends_at: Option<Timestamp>,
+    // Placar
+    pub attacker_kills: u32,
+    pub defender_kills: u32,
+    pub attacker_objectives: u32,
+    pub defender_objectives: u32,
+    pub attacker_score: u32,
+    pub defender_score: u32,
+    /// Guilda que ofereceu paz (a outra aceita oferecendo também)
+    pub peace_offered_by: Option<u64>,
+    pub winner_guild_id: Option<u64>,
+    pub end_reason: String,
+}
+
+fn involves(war: &GuildWar, guild_id: u64) -> bool {
+    war.attacker_guild_id == guild_id || war.defender_guild_id == guild_id
+}
+
+fn open_war_between(ctx: &ReducerContext, a: u64, b: u64) -> Option<GuildWar> {
+    ctx.db.guild_war().iter()
+        .find(|w| w.state != WarState::Ended && involves(w, a) && involves(w, b))
+}
+
+fn active_war_between(ctx: &ReducerContext, a: u64, b: u64) -> Option<GuildWar> {
+    open_war_between(ctx, a, b).filter(|w| w.state == WarState::Active)
+}
+
+/// Membros de guildas em guerra ativa podem se atacar
+pub fn at_war(ctx: &ReducerContext, player_a: u32, player_b: u32) -> bool {
+    match (guild_of(ctx, player_a), guild_of(ctx, player_b)) {
+        (Some(a), Some(b)) if a != b => active_war_between(ctx, a, b).is_some(),
+        _ => false,
+    }
+}
+
+/// Guilda em guerra ativa com alguma guilda da aliança
+pub fn at_war_with_alliance(ctx: &ReducerContext, guild_id: u64, alliance_id: u64) -> bool {
+    ctx.db.alliance_member().alliance_id().filter(alliance_id)
+        .any(|m| active_war_between(ctx, guild_id, m.guild_id).is_some())
+}
+
+/// Abate entre guildas em guerra: pontua para a guilda do matador
+pub fn record_kill(ctx: &ReducerContext, killer_id: u32, victim_id: u32) {
+    let (Some(killer_guild), Some(victim_guild)) = (guild_of(ctx, killer_id), guild_of(ctx, victim_id)) else {
+        return;
+    };
+    if killer_guild == victim_guild {
+        return;
+    }
+    let Some(mut war) = active_war_between(ctx, killer_guild, victim_guild) else { return };
+
+    let leader_kill = ctx.db.guild().id().find(victim_guild).is_some_and(|g| g.leader_id == victim_id);
+    let objectives = if leader_kill { 1 } else { 0 };
+    if killer_guild == war.attacker_guild_id {
+        war.attacker_kills += 1;
+        war.attacker_objectives += objectives;
+    } else {
+        war.defender_kills += 1;
+        war.defender_objectives += objectives;
+    }
+    war.attacker_score = war.attacker_kills * KILL_POINTS + war.attacker_objectives * LEADER_KILL_OBJECTIVE_POINTS;
+    war.defender_score = war.defender_kills * KILL_POINTS + war.defender_objectives * LEADER_KILL_OBJECTIVE_POINTS;
+    ctx.db.guild_war().id().update(war);
+}
+
+fn end_war(ctx: &ReducerContext, mut war: GuildWar, winner: Option<u64>, reason: &str) {
+    war.state = WarState::Ended;
+    war.ends_at = Some(ctx.timestamp);
+    war.winner_guild_id = winner;
+    war.end_reason = reason.to_string();
+    let name = |id: u64| ctx.db.guild().id().find(id).map(|g| g.name).unwrap_or_default();
+    let message = match winner {
+        Some(id) => Message::new("announce.guild_war.won").param("winner", name(id)),
+        None => Message::new("announce.guild_war.ended"),
+    }
+    .param("attacker", name(war.attacker_guild_id))
+    .param("defender", name(war.defender_guild_id))
+    .param("reason", reason);
+    ctx.db.guild_war().id().update(war);
+    announce_localized(ctx, "guild_war", message);
+}
+
+#[reducer]
+pub fn declare_guild_war(ctx: &ReducerContext, target_guild_id: u64) -> Result<(), String> {
+    let (_, guild) = require_guild_leader(ctx)?;
+    if guild.id == target_guild_id {
+        return Err("Cannot declare war on your own guild".to_string());
+    }
+    ctx.db.guild().id().find(target_guild_id).ok_or("Guild not found")?;
+    if crate::alliance::same_alliance_guilds(ctx, guild.id, target_guild_id) {
+        return Err("Cannot declare war on an allied guild".to_string());
+    }
+    if open_war_between(ctx, guild.id, target_guild_id).is_some() {
+        return Err("There is already a war between these guilds".to_string());
+    }
+    ctx.db.guild_war().insert(GuildWar {
+        id: 0,
+        attacker_guild_id: guild.id,
+        defender_guild_id: target_guild_id,
+        state: WarState::Proposed,
+        declared_at: ctx.timestamp,
+        ends_at: None,
+        attacker_kills: 0,
+        defender_kills: 0,
+        attacker_objectives: 0,
+        defender_objectives: 0,
+        attacker_score: 0,
+        defender_score: 0,
+        peace_offered_by: None,
+        winner_guild_id: None,
+        end_reason: String::new(),
+    });
+    Ok(())
+}
+
+/// A guilda desafiada aceita: a guerra começa e dura WAR_DURATION_SECS
+#[reducer]
+pub fn accept_guild_war(ctx: &ReducerContext, war_id: u64) -> Result<(), String> {
+    let (_, guild) = require_guild_leader(ctx)?;
+    let mut war = ctx.db.guild_war().id().find(war_id).ok_or("War not found")?;
+    if war.state != WarState::Proposed || war.defender_guild_id != guild.id {
+        return Err("No war declaration to accept".to_string());
+    }
+    war.state = WarState::Active;
+    war.ends_at = Some(ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(WAR_DURATION_SECS)));
+    let attacker = war.attacker_guild_id;
+    ctx.db.guild_war().id().update(war);
+
+    let attacker_name = ctx.db.guild().id().find(attacker).map(|g| g.name).unwrap_or_default();
+    announce_localized(ctx, "guild_war", Message::new("announce.guild_war.declared")
+        .param("attacker", attacker_name)
+        .param("defender", &guild.name));
+    Ok(())
+}
+
+/// Recusa uma declaração ou retira a própria antes de ser aceita
+#[reducer]
+pub fn decline_guild_war(ctx: &ReducerContext, war_id: u64) -> Result<(), String> {
+    let (_, guild) = require_guild_leader(ctx)?;
+    let war = ctx.db.guild_war().id().find(war_id).ok_or("War not found")?;
+    if war.state != WarState::Proposed || !involves(&war, guild.id) {
+        return Err("No pending war declaration".to_string());
+    }
+    ctx.db.guild_war().id().delete(war_id);
+    Ok(())
+}
+
+#[reducer]
+pub fn surrender_guild_war(ctx: &ReducerContext, war_id: u64) -> Result<(), String> {
+    let (_, guild) = require_guild_leader(ctx)?;
+    let war = ctx.db.guild_war().id().find(war_id).ok_or("War not found")?;
+    if war.state != WarState::Active || !involves(&war, guild.id) {
+        return Err("Your guild is not in this war".to_string());
+    }
+    let winner = if war.attacker_guild_id == guild.id { war.defender_guild_id } else { war.attacker_guild_id };
+    end_war(ctx, war, Some(winner), "surrender");
+    Ok(())
+}
+
+/// Oferece paz; quando as duas guildas oferecem, a guerra termina sem vencedor
+#[reducer]
+pub fn offer_guild_peace(ctx: &ReducerContext, war_id: u64) -> Result<(), String> {
+    let (_, guild) = require_guild_leader(ctx)?;
+    let mut war = ctx.db.guild_war().id().find(war_id).ok_or("War not found")?;
+    if war.state != WarState::Active || !involves(&war, guild.id) {
+        return Err("Your guild is not in this war".to_string());
+    }
+    match war.peace_offered_by {
+        Some(offered_by) if offered_by != guild.id => end_war(ctx, war, None, "peace"),
+        Some(_) => return Err("Peace already offered".to_string()),
+        None => {
+            war.peace_offered_by = Some(guild.id);
+            ctx.db.guild_war().id().update(war);
+        }
+    }
+    Ok(())
+}
+
+/// Checagem periódica: encerra guerras cujo período acabou (vence quem pontuou mais)
+pub fn process_guild_wars(ctx: &ReducerContext) {
+    let expired: Vec<GuildWar> = ctx.db.guild_war().iter()
+        .filter(|w| w.state == WarState::Active && w.ends_at.is_some_and(|t| t <= ctx.timestamp))
+        .collect();
+    for war in expired {
+        let winner = match war.attacker_score.cmp(&war.defender_score) {
+            std::cmp::Ordering::Greater => Some(war.attacker_guild_id),
+            std::cmp::Ordering::Less => Some(war.defender_guild_id),
+            std::cmp::Ordering::Equal => None,
+        };
+        end_war(ctx, war, winner, "time");
+    }
+}
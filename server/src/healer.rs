@@ -0,0 +1,71 @@
+use crate::cooldown::{try_start_cooldown, ALLY_HEAL_COOLDOWN_MS, CATEGORY_ABILITY, CLEANSE_COOLDOWN_MS};
+use crate::map::{has_line_of_sight, TILE_SIZE};
+use crate::party::{party_of, sender_player};
+use crate::status_effect::{remove_effects_in_category, CLEANSABLE_CATEGORIES};
+use crate::{player, Player};
+use spacetimedb::{reducer, ReducerContext};
+
+pub const ALLY_HEAL_RANGE: f32 = TILE_SIZE * 10.0;
+pub const ALLY_HEAL_AMOUNT: f32 = 35.0;
+pub const CLEANSE_RANGE: f32 = TILE_SIZE * 10.0;
+
+/// Alvo válido de cura/purificação: o próprio conjurador ou alguém da party,
+/// de pé, no mesmo mapa, dentro do alcance e com linha de visão
+fn require_support_target(ctx: &ReducerContext, caster: &Player, target_id: u32, range: f32) -> Result<Player, String> {
+    if caster.is_downed {
+        return Err("Cannot cast while downed".to_string());
+    }
+    let target = ctx.db.player().id().find(target_id).ok_or("Player not found")?;
+    if target.id != caster.id {
+        let same_party = matches!((party_of(ctx, caster.id), party_of(ctx, target.id)), (Some(a), Some(b)) if a == b);
+        if !same_party {
+            return Err("Target is not in your party".to_string());
+        }
+    }
+    if target.is_downed {
+        return Err("Target is downed".to_string());
+    }
+    let distance = ((caster.position_x - target.position_x).powi(2) + (caster.position_y - target.position_y).powi(2)).sqrt();
+    if target.current_map_id != caster.current_map_id || distance > range {
+        return Err("Target is out of range".to_string());
+    }
+    if !has_line_of_sight(ctx, &caster.current_map_id, (caster.position_x, caster.position_y), (target.position_x, target.position_y)) {
+        return Err("Target is not in line of sight".to_string());
+    }
+    Ok(target)
+}
+
+/// Cura direcionada; a cura efetiva conta para quem curou no medidor de encontro
+#[reducer]
+pub fn heal_ally(ctx: &ReducerContext, target_id: u32) -> Result<(), String> {
+    let caster = sender_player(ctx)?;
+    let mut target = require_support_target(ctx, &caster, target_id, ALLY_HEAL_RANGE)?;
+    if target.health >= target.max_health {
+        return Err("Target is at full health".to_string());
+    }
+    try_start_cooldown(ctx, ctx.sender, CATEGORY_ABILITY, "ally_heal", ALLY_HEAL_COOLDOWN_MS)?;
+
+    let before = target.health;
+    target.health = (target.health + ALLY_HEAL_AMOUNT).min(target.max_health);
+    let healed = target.health - before;
+    ctx.db.player().id().update(target);
+    crate::damage_meter::record_healing(ctx, &caster.current_map_id, caster.id, healed);
+
+    log::info!("💚 Player {} healed player {} for {}", caster.id, target_id, healed);
+    Ok(())
+}
+
+/// Remove de um aliado os efeitos de uma categoria nociva (ex.: "impairment")
+#[reducer]
+pub fn cleanse_ally(ctx: &ReducerContext, target_id: u32, category: String) -> Result<(), String> {
+    if !CLEANSABLE_CATEGORIES.contains(&category.as_str()) {
+        return Err(format!("'{}' effects cannot be cleansed", category));
+    }
+    let caster = sender_player(ctx)?;
+    require_support_target(ctx, &caster, target_id, CLEANSE_RANGE)?;
+    try_start_cooldown(ctx, ctx.sender, CATEGORY_ABILITY, "cleanse", CLEANSE_COOLDOWN_MS)?;
+
+    let removed = remove_effects_in_category(ctx, target_id, &category);
+    log::info!("✨ Player {} cleansed {} {} effects from player {}", caster.id, removed, category, target_id);
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use crate::admin::require_admin;
+use crate::map::TILE_SIZE;
+use crate::player;
+use crate::rested::rested_state;
+use spacetimedb::{reducer, table, ReducerContext, ScheduleAt, Table, Timestamp};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Lado (em tiles) de cada célula do heatmap
+pub const HEATMAP_CHUNK_TILES: f32 = 8.0;
+/// Intervalo de amostragem da posição dos players online
+pub const HEATMAP_SAMPLE_SECS: u64 = 15;
+
+pub const HEATMAP_DEATHS: &str = "deaths";
+/// Segundos passados na célula (estimados pela amostragem)
+pub const HEATMAP_PRESENCE: &str = "presence";
+
+/// Pontos brutos ainda não agregados (mortes e amostras de presença)
+#[table(name = heatmap_point)]
+#[derive(Clone)]
+pub struct HeatmapPoint {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub kind: String,
+    pub map_id: String,
+    pub x: f32,
+    pub y: f32,
+    pub weight: u64,
+}
+
+/// Heatmap agregado por mapa em células de `HEATMAP_CHUNK_TILES` tiles.
+/// `key` = "map_id:kind:chunk_x:chunk_y".
+#[table(name = heatmap_cell, public)]
+#[derive(Clone)]
+pub struct HeatmapCell {
+    #[primary_key]
+    pub key: String,
+    #[index(btree)]
+    pub map_id: String,
+    pub kind: String,
+    pub chunk_x: u32,
+    pub chunk_y: u32,
+    pub value: u64,
+    pub updated_at: Timestamp,
+}
+
+#[table(name = heatmap_sample_schedule, scheduled(sample_heatmap_presence))]
+pub struct HeatmapSampleSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+pub fn ensure_heatmap_schedule(ctx: &ReducerContext) {
+    if ctx.db.heatmap_sample_schedule().count() == 0 {
+        ctx.db.heatmap_sample_schedule().insert(HeatmapSampleSchedule {
+            scheduled_id: 0,
+            scheduled_at: Duration::from_secs(HEATMAP_SAMPLE_SECS).into(),
+        });
+    }
+}
+
+fn push_point(ctx: &ReducerContext, kind: &str, map_id: &str, x: f32, y: f32, weight: u64) {
+    ctx.db.heatmap_point().insert(HeatmapPoint { id: 0, kind: kind.to_string(), map_id: map_id.to_string(), x, y, weight });
+}
+
+/// Chamado quando um player cai
+pub fn record_death(ctx: &ReducerContext, player: &crate::Player) {
+    push_point(ctx, HEATMAP_DEATHS, &player.current_map_id, player.position_x, player.position_y, 1);
+}
+
+/// Cada amostra de um player online vale o intervalo inteiro na posição atual
+#[reducer]
+pub fn sample_heatmap_presence(ctx: &ReducerContext, _schedule: HeatmapSampleSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("sample_heatmap_presence may only be invoked by the scheduler".to_string());
+    }
+    if crate::upgrade::is_frozen(ctx) {
+        return Ok(());
+    }
+    for player in ctx.db.player().iter() {
+        let logged_out = ctx.db.rested_state().player_id().find(player.id).is_some_and(|s| s.logged_out_at.is_some());
+        if !logged_out {
+            push_point(ctx, HEATMAP_PRESENCE, &player.current_map_id, player.position_x, player.position_y, HEATMAP_SAMPLE_SECS);
+        }
+    }
+    Ok(())
+}
+
+/// Reduz os pontos brutos às células (rodado pela agregação periódica)
+pub fn aggregate_heatmaps(ctx: &ReducerContext) {
+    let chunk_size = HEATMAP_CHUNK_TILES * TILE_SIZE;
+    let mut totals: HashMap<(String, String, u32, u32), u64> = HashMap::new();
+    let points: Vec<HeatmapPoint> = ctx.db.heatmap_point().iter().collect();
+    for point in &points {
+        let chunk_x = (point.x.max(0.0) / chunk_size) as u32;
+        let chunk_y = (point.y.max(0.0) / chunk_size) as u32;
+        *totals.entry((point.map_id.clone(), point.kind.clone(), chunk_x, chunk_y)).or_default() += point.weight;
+        ctx.db.heatmap_point().id().delete(point.id);
+    }
+
+    for ((map_id, kind, chunk_x, chunk_y), value) in totals {
+        let key = format!("{}:{}:{}:{}", map_id, kind, chunk_x, chunk_y);
+        match ctx.db.heatmap_cell().key().find(key.clone()) {
+            Some(mut cell) => {
+                cell.value += value;
+                cell.updated_at = ctx.timestamp;
+                ctx.db.heatmap_cell().key().update(cell);
+            }
+            None => {
+                ctx.db.heatmap_cell().insert(HeatmapCell { key, map_id, kind, chunk_x, chunk_y, value, updated_at: ctx.timestamp });
+            }
+        }
+    }
+    if !points.is_empty() {
+        log::info!("🌡️ Aggregated {} heatmap points", points.len());
+    }
+}
+
+/// Zera o heatmap de um mapa (ex: depois de redesenhá-lo)
+#[reducer]
+pub fn reset_heatmap(ctx: &ReducerContext, map_id: String) -> Result<(), String> {
+    require_admin(ctx)?;
+    let keys: Vec<String> = ctx.db.heatmap_cell().map_id().filter(&map_id).map(|c| c.key).collect();
+    for key in keys {
+        ctx.db.heatmap_cell().key().delete(key);
+    }
+    crate::audit::record_audit(ctx, "heatmap", format!("Reset heatmap of '{}'", map_id));
+    Ok(())
+}
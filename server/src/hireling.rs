@@ -0,0 +1,251 @@
+use crate::combat::{apply_damage_to_enemy, enemy, Enemy};
+use crate::crafting::ItemStack;
+use crate::currency::{spend_currency, CURRENCY_GOLD};
+use crate::inventory::{inventory_item, recompute_inventory_header};
+use crate::mail::send_system_mail;
+use crate::map::TILE_SIZE;
+use crate::party::{party_member_ids, party_of, sender_player};
+use crate::rested::is_logged_out;
+use crate::tick::WORLD_TICK_MS;
+use crate::{player, Player};
+use spacetimedb::{reducer, table, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+pub const HIRELING_HEALER: &str = "healer";
+pub const HIRELING_PORTER: &str = "porter";
+pub const HIRELING_FIGHTER: &str = "fighter";
+
+/// Companheiro NPC contratado por tempo. `power` é a cura ou o dano por ação;
+/// o carregador não age, só amplia o inventário do contratante.
+pub struct HirelingDef {
+    pub kind: &'static str,
+    pub fee_per_hour: u64,
+    pub action_interval_ms: u64,
+    pub power: f32,
+    pub range: f32,
+    pub extra_slots: u32,
+}
+
+pub const HIRELINGS: &[HirelingDef] = &[
+    HirelingDef { kind: HIRELING_HEALER, fee_per_hour: 150, action_interval_ms: 3000, power: 12.0, range: TILE_SIZE * 8.0, extra_slots: 0 },
+    HirelingDef { kind: HIRELING_PORTER, fee_per_hour: 80, action_interval_ms: 0, power: 0.0, range: 0.0, extra_slots: 6 },
+    HirelingDef { kind: HIRELING_FIGHTER, fee_per_hour: 200, action_interval_ms: 1500, power: 8.0, range: TILE_SIZE * 6.0, extra_slots: 0 },
+];
+
+pub const MAX_HIRE_HOURS: u32 = 4;
+const HIRELING_SPEED: f32 = 90.0;
+/// Distância que o companheiro mantém do contratante
+const FOLLOW_DISTANCE: f32 = TILE_SIZE * 2.0;
+/// Longe demais (ou em outro mapa): o companheiro reaparece ao lado do contratante
+const CATCH_UP_DISTANCE: f32 = TILE_SIZE * 20.0;
+
+/// Companheiro ativo; um por player, removido automaticamente ao expirar
+#[table(name = hireling, public)]
+#[derive(Clone)]
+pub struct Hireling {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[unique]
+    pub owner_id: u32,
+    pub kind: String,
+    pub map_id: String,
+    pub position_x: f32,
+    pub position_y: f32,
+    pub hired_at: Timestamp,
+    pub expires_at: Timestamp,
+    pub next_action_at: Timestamp,
+}
+
+pub fn hireling_def(kind: &str) -> Option<&'static HirelingDef> {
+    HIRELINGS.iter().find(|d| d.kind == kind)
+}
+
+/// Slots extras de inventário do carregador contratado
+pub fn hired_inventory_slots(ctx: &ReducerContext, player_id: u32) -> u32 {
+    ctx.db.hireling().owner_id().find(player_id)
+        .filter(|h| h.expires_at > ctx.timestamp)
+        .and_then(|h| hireling_def(&h.kind))
+        .map(|d| d.extra_slots)
+        .unwrap_or(0)
+}
+
+#[reducer]
+pub fn hire_hireling(ctx: &ReducerContext, kind: String, hours: u32) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    let def = hireling_def(&kind).ok_or("Unknown hireling")?;
+    if hours == 0 || hours > MAX_HIRE_HOURS {
+        return Err(format!("Hire duration must be between 1 and {} hours", MAX_HIRE_HOURS));
+    }
+    if ctx.db.hireling().owner_id().find(player.id).is_some() {
+        return Err("You already have a hireling".to_string());
+    }
+    spend_currency(ctx, player.id, CURRENCY_GOLD, def.fee_per_hour * hours as u64, "hireling")?;
+
+    ctx.db.hireling().insert(Hireling {
+        id: 0,
+        owner_id: player.id,
+        kind: kind.clone(),
+        map_id: player.current_map_id.clone(),
+        position_x: player.position_x,
+        position_y: player.position_y,
+        hired_at: ctx.timestamp,
+        expires_at: ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(hours as u64 * 3600)),
+        next_action_at: ctx.timestamp,
+    });
+    if def.extra_slots > 0 {
+        recompute_inventory_header(ctx, player.id);
+    }
+    log::info!("🧑‍🤝‍🧑 Player {} hired a {} for {} h", player.id, kind, hours);
+    Ok(())
+}
+
+/// Dispensa antes do fim do contrato (sem reembolso)
+#[reducer]
+pub fn dismiss_hireling(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let hireling = ctx.db.hireling().owner_id().find(player.id).ok_or("You have no hireling")?;
+    // Mesma regra da bolsa: tudo precisa caber sem os slots do carregador
+    let header = recompute_inventory_header(ctx, player.id);
+    if header.used_slots > header.max_slots - hired_inventory_slots(ctx, player.id) {
+        return Err("Empty the porter's slots before dismissing it".to_string());
+    }
+    remove_hireling(ctx, hireling);
+    Ok(())
+}
+
+fn remove_hireling(ctx: &ReducerContext, hireling: Hireling) {
+    ctx.db.hireling().id().delete(hireling.id);
+    if hireling_def(&hireling.kind).is_some_and(|d| d.extra_slots > 0) {
+        mail_porter_overflow(ctx, hireling.owner_id);
+    }
+    log::info!("👋 {} hireling of player {} left", hireling.kind, hireling.owner_id);
+}
+
+/// Contrato encerrado com os slots do carregador ocupados: as pilhas que não
+/// cabem mais no inventário vão para o correio
+fn mail_porter_overflow(ctx: &ReducerContext, owner_id: u32) {
+    let header = recompute_inventory_header(ctx, owner_id);
+    let excess = header.used_slots.saturating_sub(header.max_slots) as usize;
+    if excess == 0 {
+        return;
+    }
+    let mut loose: Vec<_> = ctx.db.inventory_item().player_id().filter(owner_id).filter(|i| !i.is_equipped).collect();
+    loose.sort_by_key(|i| i.id);
+    let overflow = loose.split_off(loose.len() - excess);
+    let mut attachments = Vec::new();
+    for item in overflow {
+        ctx.db.inventory_item().id().delete(item.id);
+        attachments.push(ItemStack { item_id: item.item_id, quantity: item.quantity });
+    }
+    recompute_inventory_header(ctx, owner_id);
+    send_system_mail(
+        ctx, owner_id, "Porter left",
+        "Your porter's contract ended and what no longer fit in your inventory was sent here.".to_string(),
+        attachments, 0, "hireling",
+    );
+}
+
+fn distance(x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
+    ((x1 - x2).powi(2) + (y1 - y2).powi(2)).sqrt()
+}
+
+/// Acompanha o contratante: anda até ficar a `FOLLOW_DISTANCE` dele, ou reaparece ao lado
+fn follow(hireling: &mut Hireling, owner: &Player, step_time: f32) {
+    let gap = distance(hireling.position_x, hireling.position_y, owner.position_x, owner.position_y);
+    if hireling.map_id != owner.current_map_id || gap > CATCH_UP_DISTANCE {
+        hireling.map_id = owner.current_map_id.clone();
+        hireling.position_x = owner.position_x;
+        hireling.position_y = owner.position_y;
+        return;
+    }
+    if gap <= FOLLOW_DISTANCE {
+        return;
+    }
+    let step = (HIRELING_SPEED * step_time).min(gap - FOLLOW_DISTANCE);
+    hireling.position_x += (owner.position_x - hireling.position_x) / gap * step;
+    hireling.position_y += (owner.position_y - hireling.position_y) / gap * step;
+}
+
+/// Curandeiro: cura o aliado de pé mais ferido (contratante ou party) no alcance
+fn heal_allies(ctx: &ReducerContext, hireling: &Hireling, def: &HirelingDef) -> bool {
+    let allies = party_of(ctx, hireling.owner_id)
+        .map(|party_id| party_member_ids(ctx, party_id))
+        .unwrap_or_else(|| vec![hireling.owner_id]);
+    let target = allies.into_iter()
+        .filter_map(|id| ctx.db.player().id().find(id))
+        .filter(|p| !p.is_downed && p.health < p.max_health && p.current_map_id == hireling.map_id
+            && distance(hireling.position_x, hireling.position_y, p.position_x, p.position_y) <= def.range)
+        .min_by(|a, b| (a.health / a.max_health).total_cmp(&(b.health / b.max_health)));
+    let Some(mut target) = target else {
+        return false;
+    };
+    let before = target.health;
+    target.health = (target.health + def.power).min(target.max_health);
+    let healed = target.health - before;
+    ctx.db.player().id().update(target);
+    crate::damage_meter::record_healing(ctx, &hireling.map_id, hireling.owner_id, healed);
+    true
+}
+
+/// Lutador: ataca o inimigo ativo mais próximo; o dano conta como do contratante
+fn attack_nearest(ctx: &ReducerContext, hireling: &Hireling, def: &HirelingDef) -> bool {
+    let distance_to = |e: &Enemy| distance(hireling.position_x, hireling.position_y, e.position_x, e.position_y);
+    let target = ctx.db.enemy().map_id().filter(&hireling.map_id)
+        .filter(|e| e.is_active && distance_to(e) <= def.range)
+        .min_by(|a, b| distance_to(a).total_cmp(&distance_to(b)));
+    let Some(target) = target else {
+        return false;
+    };
+    if let Err(e) = apply_damage_to_enemy(ctx, target.id, def.power, hireling.owner_id, "Hireling".to_string()) {
+        log::warn!("Hireling {} failed to attack enemy {}: {}", hireling.id, target.id, e);
+    }
+    true
+}
+
+/// Por tick: expira contratos, segue o contratante e executa a ação do tipo.
+/// Retorna quantos companheiros foram processados.
+/// Na reconexão: devolve ao contrato o tempo em que o contratante esteve fora
+pub fn resume_after_logout(ctx: &ReducerContext, owner_id: u32, offline: Duration) {
+    let Some(mut hireling) = ctx.db.hireling().owner_id().find(owner_id) else { return };
+    let offline = TimeDuration::from_duration(offline);
+    hireling.expires_at += offline;
+    hireling.next_action_at += offline;
+    ctx.db.hireling().id().update(hireling);
+}
+
+pub fn process_hirelings(ctx: &ReducerContext) -> u64 {
+    let step_time = WORLD_TICK_MS as f32 / 1000.0;
+    let hirelings: Vec<Hireling> = ctx.db.hireling().iter().collect();
+    let processed = hirelings.len() as u64;
+    for mut hireling in hirelings {
+        let owner = ctx.db.player().id().find(hireling.owner_id);
+        let (Some(owner), Some(def)) = (owner, hireling_def(&hireling.kind)) else {
+            remove_hireling(ctx, hireling);
+            continue;
+        };
+        // Contratante deslogado: o companheiro fica parado e o contrato não corre
+        if is_logged_out(ctx, owner.id) {
+            continue;
+        }
+        if hireling.expires_at <= ctx.timestamp {
+            remove_hireling(ctx, hireling);
+            continue;
+        }
+
+        follow(&mut hireling, &owner, step_time);
+        if def.action_interval_ms > 0 && hireling.next_action_at <= ctx.timestamp && !owner.is_downed {
+            let acted = match def.kind {
+                HIRELING_HEALER => heal_allies(ctx, &hireling, def),
+                HIRELING_FIGHTER => attack_nearest(ctx, &hireling, def),
+                _ => false,
+            };
+            if acted {
+                hireling.next_action_at = ctx.timestamp + TimeDuration::from_duration(Duration::from_millis(def.action_interval_ms));
+            }
+        }
+        ctx.db.hireling().id().update(hireling);
+    }
+    processed
+}
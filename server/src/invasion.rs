@@ -0,0 +1,267 @@
+use crate::admin::require_admin;
+use crate::combat::{allocate_enemy_id, enemy, refresh_enemy_motion, spawn_enemy, Enemy};
+use crate::currency::CURRENCY_EVENT_TOKEN;
+use crate::localization::{announce_localized, Message};
+use crate::reward::{grant_reward_bundle, RewardBundle};
+use crate::map::{is_passable_step, map_instance, map_template, random_spawn_point, template_for_map, terrain_speed_multiplier, TILE_SIZE};
+use crate::structure::{damage_structure, structure_at};
+use crate::player;
+use crate::reputation::{add_reputation, FACTION_TOWN_GUARD};
+use crate::tick::WORLD_TICK_MS;
+use crate::town::close_town_services;
+use spacetimedb::rand::Rng;
+use spacetimedb::{reducer, table, ReducerContext, ScheduleAt, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+pub const INVASION_INTERVAL_SECS: u64 = 2 * 60 * 60;
+const INVASION_DURATION_SECS: u64 = 15 * 60;
+const INVASION_WAVE_SIZE: u32 = 8;
+const INVASION_ENEMY_TYPES: &[&str] = &["Goblin", "Goblin", "Orc"];
+/// Distância mínima entre o ponto de spawn dos invasores e o objetivo
+const INVADER_SPAWN_MIN_DISTANCE: f32 = 120.0;
+const INVADER_SPAWN_RADIUS: f32 = 320.0;
+
+pub const INVADER_STATE: &str = "Invading";
+const OBJECTIVE_MAX_HEALTH: f32 = 1000.0;
+
+/// Recompensa de defesa bem-sucedida (para quem está na cidade)
+const INVASION_REWARD_TOKENS: u64 = 25;
+const INVASION_REWARD_REPUTATION: i32 = 250;
+/// Serviços fechados após uma defesa fracassada
+const FAILED_TOWN_CLOSURE_SECS: u64 = 30 * 60;
+
+pub const INVASION_ACTIVE: &str = "Active";
+pub const INVASION_SUCCEEDED: &str = "Succeeded";
+pub const INVASION_FAILED: &str = "Failed";
+
+/// Evento de invasão: invasores marcham até o objetivo da cidade
+#[table(name = invasion, public)]
+#[derive(Clone)]
+pub struct Invasion {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub map_id: String,
+    pub objective_x: f32,
+    pub objective_y: f32,
+    pub objective_health: f32,
+    pub objective_max_health: f32,
+    pub invader_ids: Vec<u32>,
+    pub state: String,
+    pub started_at: Timestamp,
+    pub ends_at: Timestamp,
+}
+
+#[table(name = invasion_schedule, scheduled(run_invasion_schedule))]
+pub struct InvasionSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+pub fn ensure_invasion_schedule(ctx: &ReducerContext) {
+    if ctx.db.invasion_schedule().count() == 0 {
+        ctx.db.invasion_schedule().insert(InvasionSchedule {
+            scheduled_id: 0,
+            scheduled_at: Duration::from_secs(INVASION_INTERVAL_SECS).into(),
+        });
+    }
+}
+
+fn has_active_invasion(ctx: &ReducerContext, map_id: &str) -> bool {
+    ctx.db.invasion().map_id().filter(map_id).any(|i| i.state == INVASION_ACTIVE)
+}
+
+#[reducer]
+pub fn run_invasion_schedule(ctx: &ReducerContext, _schedule: InvasionSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("run_invasion_schedule may only be invoked by the scheduler".to_string());
+    }
+    if crate::upgrade::is_frozen(ctx) {
+        return Ok(());
+    }
+
+    let towns: Vec<String> = ctx.db.map_template().iter()
+        .filter(|t| t.is_town && !has_active_invasion(ctx, &t.name))
+        .map(|t| t.name)
+        .collect();
+    if towns.is_empty() {
+        return Ok(());
+    }
+    let town = towns[ctx.rng().gen_range(0..towns.len())].clone();
+    start_invasion(ctx, &town)
+}
+
+/// Inicia uma invasão imediatamente (admin)
+#[reducer]
+pub fn start_invasion_now(ctx: &ReducerContext, map_id: String) -> Result<(), String> {
+    require_admin(ctx)?;
+    let template = template_for_map(ctx, &map_id).ok_or("Map not found")?;
+    if !template.is_town {
+        return Err(format!("'{}' is not a town", map_id));
+    }
+    start_invasion(ctx, &map_id)
+}
+
+fn start_invasion(ctx: &ReducerContext, map_id: &str) -> Result<(), String> {
+    if has_active_invasion(ctx, map_id) {
+        return Err(format!("'{}' is already under invasion", map_id));
+    }
+    let template = template_for_map(ctx, map_id).ok_or("Map not found")?;
+    // O objetivo fica no ponto de spawn da cidade
+    let (objective_x, objective_y) = (template.spawn_x, template.spawn_y);
+
+    let mut invader_ids = Vec::new();
+    for _ in 0..INVASION_WAVE_SIZE * 4 {
+        if invader_ids.len() as u32 >= INVASION_WAVE_SIZE {
+            break;
+        }
+        let Some((x, y)) = random_spawn_point(ctx, map_id, objective_x, objective_y, INVADER_SPAWN_RADIUS) else { continue };
+        if ((x - objective_x).powi(2) + (y - objective_y).powi(2)).sqrt() < INVADER_SPAWN_MIN_DISTANCE {
+            continue;
+        }
+
+        let enemy_type = INVASION_ENEMY_TYPES[ctx.rng().gen_range(0..INVASION_ENEMY_TYPES.len())];
+        let enemy_id = allocate_enemy_id(ctx);
+        spawn_enemy(ctx, enemy_id, x, y, map_id.to_string(), enemy_type.to_string()).map_err(|e| e.to_string())?;
+        if let Some(mut invader) = ctx.db.enemy().id().find(enemy_id) {
+            invader.state = INVADER_STATE.to_string();
+            ctx.db.enemy().id().update(invader);
+            invader_ids.push(enemy_id);
+        }
+    }
+
+    if invader_ids.is_empty() {
+        return Err(format!("No room to spawn invaders in '{}'", map_id));
+    }
+
+    ctx.db.invasion().insert(Invasion {
+        id: 0,
+        map_id: map_id.to_string(),
+        objective_x,
+        objective_y,
+        objective_health: OBJECTIVE_MAX_HEALTH,
+        objective_max_health: OBJECTIVE_MAX_HEALTH,
+        invader_ids,
+        state: INVASION_ACTIVE.to_string(),
+        started_at: ctx.timestamp,
+        ends_at: ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(INVASION_DURATION_SECS)),
+    });
+    announce_localized(ctx, "invasion", Message::new("announce.invasion.started").param("map", map_id));
+    Ok(())
+}
+
+/// Checagem por tick: invasores marcham e atacam o objetivo; resolve o evento
+pub fn process_invasions(ctx: &ReducerContext) {
+    let delta_time = WORLD_TICK_MS as f32 / 1000.0;
+
+    let active: Vec<Invasion> = ctx.db.invasion().iter().filter(|i| i.state == INVASION_ACTIVE).collect();
+    for mut invasion in active {
+        let alive: Vec<Enemy> = invasion.invader_ids.iter().filter_map(|id| ctx.db.enemy().id().find(*id)).collect();
+
+        for mut invader in alive.iter().cloned() {
+            // Inimigos que passaram a perseguir alguém saem da marcha
+            if invader.state != INVADER_STATE {
+                continue;
+            }
+            let damage = invader_step(ctx, &mut invader, &invasion, delta_time);
+            invasion.objective_health = (invasion.objective_health - damage).max(0.0);
+            ctx.db.enemy().id().update(invader);
+        }
+
+        if invasion.objective_health <= 0.0 || invasion.ends_at <= ctx.timestamp {
+            fail_invasion(ctx, invasion, &alive);
+        } else if alive.is_empty() {
+            succeed_invasion(ctx, invasion);
+        } else {
+            invasion.invader_ids = alive.iter().map(|e| e.id).collect();
+            ctx.db.invasion().id().update(invasion);
+        }
+    }
+}
+
+/// Move o invasor até o objetivo; retorna o dano causado ao objetivo neste tick
+fn invader_step(ctx: &ReducerContext, invader: &mut Enemy, invasion: &Invasion, delta_time: f32) -> f32 {
+    let dx = invasion.objective_x - invader.position_x;
+    let dy = invasion.objective_y - invader.position_y;
+    let distance = (dx * dx + dy * dy).sqrt();
+
+    if distance <= invader.attack_range {
+        invader.velocity_x = 0.0;
+        invader.velocity_y = 0.0;
+        invader.state_timer -= delta_time;
+        refresh_enemy_motion(invader, ctx.timestamp);
+        if invader.state_timer <= 0.0 {
+            invader.state_timer = invader.attack_cooldown;
+            return invader.attack_damage;
+        }
+        return 0.0;
+    }
+
+    let speed = invader.movement_speed * terrain_speed_multiplier(ctx, &invader.map_id, invader.position_x, invader.position_y);
+    let step = (speed * delta_time).min(distance);
+    let next_x = invader.position_x + dx / distance * step;
+    let next_y = invader.position_y + dy / distance * step;
+
+    let walkable = template_for_map(ctx, &invader.map_id).is_some_and(|template| {
+        let instance_id = ctx.db.map_instance().key_id().find(invader.map_id.clone()).map(|i| i.id);
+        is_passable_step(ctx, &template, instance_id, (invader.position_x, invader.position_y), (next_x, next_y), false)
+    });
+
+    if walkable {
+        invader.position_x = next_x;
+        invader.position_y = next_y;
+        invader.velocity_x = dx / distance * speed;
+        invader.velocity_y = dy / distance * speed;
+    } else {
+        invader.velocity_x = 0.0;
+        invader.velocity_y = 0.0;
+        // Caminho bloqueado por barricada/portão: ataca a estrutura
+        let blocking = structure_at(ctx, &invader.map_id, (next_x / TILE_SIZE) as u32, (next_y / TILE_SIZE) as u32)
+            .filter(|s| !s.is_destroyed);
+        if let Some(structure) = blocking {
+            invader.state_timer -= delta_time;
+            if invader.state_timer <= 0.0 {
+                invader.state_timer = invader.attack_cooldown;
+                damage_structure(ctx, structure.id, invader.attack_damage, "invasion");
+            }
+        }
+    }
+    refresh_enemy_motion(invader, ctx.timestamp);
+    0.0
+}
+
+fn succeed_invasion(ctx: &ReducerContext, mut invasion: Invasion) {
+    let defenders: Vec<u32> = ctx.db.player().iter()
+        .filter(|p| p.current_map_id == invasion.map_id)
+        .map(|p| p.id)
+        .collect();
+    let reward = RewardBundle::default().with_currency(CURRENCY_EVENT_TOKEN, INVASION_REWARD_TOKENS);
+    for player_id in &defenders {
+        if let Err(e) = grant_reward_bundle(ctx, *player_id, &reward, "invasion") {
+            log::warn!("Invasion reward for player {} failed: {}", player_id, e);
+        }
+        add_reputation(ctx, *player_id, FACTION_TOWN_GUARD, INVASION_REWARD_REPUTATION);
+    }
+
+    invasion.state = INVASION_SUCCEEDED.to_string();
+    invasion.invader_ids.clear();
+    announce_localized(ctx, "invasion", Message::new("announce.invasion.defended")
+        .param("map", &invasion.map_id)
+        .param("defenders", defenders.len()));
+    ctx.db.invasion().id().update(invasion);
+}
+
+fn fail_invasion(ctx: &ReducerContext, mut invasion: Invasion, alive: &[Enemy]) {
+    for invader in alive {
+        ctx.db.enemy().id().delete(invader.id);
+    }
+    close_town_services(ctx, &invasion.map_id, Duration::from_secs(FAILED_TOWN_CLOSURE_SECS), "invasion");
+
+    invasion.state = INVASION_FAILED.to_string();
+    invasion.invader_ids.clear();
+    announce_localized(ctx, "invasion", Message::new("announce.invasion.fallen").param("map", &invasion.map_id));
+    ctx.db.invasion().id().update(invasion);
+}
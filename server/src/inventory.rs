@@ -1,16 +1,25 @@
 use spacetimedb::{table, reducer, ReducerContext, Table};
 use crate::{player};
 
+const GATHER_PROFESSION_XP: u64 = 5;
+
+// Encumbrance: above the threshold, every extra weight unit slows the player down
+pub const ENCUMBRANCE_THRESHOLD: f32 = 100.0;
+const ENCUMBRANCE_SLOW_PER_WEIGHT: f32 = 0.005;
+const MAX_ENCUMBRANCE_SLOW: f32 = 0.6;
+
 #[table(name = inventory_item, public)]
 #[derive(Clone)]
 pub struct InventoryItem {
     #[primary_key]
     pub id: u32,
+    #[index(btree)]
     pub player_id: u32,
     pub item_id: String,
     pub quantity: i32,
     pub is_equipped: bool,
     pub slot_type: String, // "weapon", "tool", "consumable", etc.
+    pub dye_color: Option<String>, // cosmetic color (hex), set by dye_item
 }
 
 #[table(name = player_equipment, public)]
@@ -22,6 +31,22 @@ pub struct PlayerEquipment {
     pub off_hand_tool: String,
     pub armor: String,
     pub accessory: String,
+    pub bag: String,
+}
+
+// Inventory capacity: base slots plus the equipped bag
+pub const BASE_INVENTORY_SLOTS: u32 = 20;
+
+/// Capacity summary of a player's inventory, recomputed whenever it changes
+#[table(name = inventory_header, public)]
+#[derive(Clone)]
+pub struct InventoryHeader {
+    #[primary_key]
+    pub player_id: u32,
+    pub base_slots: u32,
+    pub bag_slots: u32,
+    pub max_slots: u32,
+    pub used_slots: u32,
 }
 
 // Interactable objects in the world
@@ -71,20 +96,23 @@ pub fn add_item_to_inventory(
     if let Some(existing_item) = existing_items.first() {
         // Update quantity
         let mut updated_item = existing_item.clone();
-        updated_item.quantity += quantity;
-        ctx.db.inventory_item().id().delete(&existing_item.id);
+        updated_item.quantity = updated_item.quantity.checked_add(quantity).ok_or("Item stack is full")?;
+        ctx.db.inventory_item().id().delete(existing_item.id);
         ctx.db.inventory_item().insert(updated_item);
     } else {
+        ensure_free_slot(ctx, player_id, &item_id)?;
         // Create new inventory entry
         let new_item = InventoryItem {
-            id: generate_inventory_id(),
+            id: generate_inventory_id(ctx),
             player_id,
             item_id: item_id.clone(),
             quantity,
             is_equipped: false,
             slot_type: get_item_slot_type(&item_id),
+            dye_color: None,
         };
         ctx.db.inventory_item().insert(new_item);
+        recompute_inventory_header(ctx, player_id);
     }
     
     log::info!("Added {} x{} to player {}'s inventory", item_id, quantity, player_id);
@@ -109,7 +137,7 @@ pub fn equip_item(
     
     if let Some(item) = items.first() {
         // Get or create player equipment
-    let equipment = ctx.db.player_equipment().player_id().find(&player_id);
+    let equipment = ctx.db.player_equipment().player_id().find(player_id);
         let mut equipment = if let Some(eq) = equipment {
             eq
         } else {
@@ -119,6 +147,7 @@ pub fn equip_item(
                 off_hand_tool: String::new(),
                 armor: String::new(),
                 accessory: String::new(),
+                bag: String::new(),
             }
         };
         
@@ -150,20 +179,30 @@ pub fn equip_item(
                 }
                 equipment.accessory = item_id.clone();
             },
+            "bag" => {
+                // Swapping bags: the old one must be emptied first
+                if !equipment.bag.is_empty() {
+                    unequip_item_internal(ctx, player_id, &equipment.bag)?;
+                    equipment.bag = String::new();
+                }
+                equipment.bag = item_id.clone();
+            },
             _ => {
                 return Err("Item cannot be equipped".into());
             }
         }
         
         // Update equipment table
-        ctx.db.player_equipment().player_id().delete(&player_id);
+        ctx.db.player_equipment().player_id().delete(player_id);
         ctx.db.player_equipment().insert(equipment);
         
         // Mark item as equipped
         let mut updated_item = item.clone();
         updated_item.is_equipped = true;
-        ctx.db.inventory_item().id().delete(&item.id);
+        ctx.db.inventory_item().id().delete(item.id);
         ctx.db.inventory_item().insert(updated_item);
+        recompute_inventory_header(ctx, player_id);
+        crate::cosmetic::refresh_appearance(ctx, player_id);
         
         log::info!("Player {} equipped {}", player_id, item_id);
     } else {
@@ -186,7 +225,7 @@ pub fn unequip_item(
 
 fn unequip_item_internal(ctx: &ReducerContext, player_id: u32, item_id: &str) -> Result<(), Box<dyn std::error::Error>> {
     // Get player equipment
-    if let Some(mut equipment) = ctx.db.player_equipment().player_id().find(&player_id) {
+    if let Some(mut equipment) = ctx.db.player_equipment().player_id().find(player_id) {
         // Remove from appropriate slot
         if equipment.main_hand_weapon == item_id {
             equipment.main_hand_weapon = String::new();
@@ -196,12 +235,19 @@ fn unequip_item_internal(ctx: &ReducerContext, player_id: u32, item_id: &str) ->
             equipment.armor = String::new();
         } else if equipment.accessory == item_id {
             equipment.accessory = String::new();
+        } else if equipment.bag == item_id {
+            // The unequipped bag needs a base slot and everything else must fit without it
+            let header = recompute_inventory_header(ctx, player_id);
+            if header.used_slots + 1 > header.base_slots {
+                return Err("Empty the bag before unequipping it".into());
+            }
+            equipment.bag = String::new();
         } else {
             return Err("Item not equipped".into());
         }
         
         // Update equipment table
-        ctx.db.player_equipment().player_id().delete(&player_id);
+        ctx.db.player_equipment().player_id().delete(player_id);
         ctx.db.player_equipment().insert(equipment);
         
         // Mark item as not equipped
@@ -212,9 +258,11 @@ fn unequip_item_internal(ctx: &ReducerContext, player_id: u32, item_id: &str) ->
         if let Some(item) = items.first() {
             let mut updated_item = item.clone();
             updated_item.is_equipped = false;
-            ctx.db.inventory_item().id().delete(&item.id);
+            ctx.db.inventory_item().id().delete(item.id);
             ctx.db.inventory_item().insert(updated_item);
         }
+        recompute_inventory_header(ctx, player_id);
+        crate::cosmetic::refresh_appearance(ctx, player_id);
         
         log::info!("Player {} unequipped {}", player_id, item_id);
     }
@@ -232,6 +280,7 @@ pub fn pickup_item(
     _position_y: f32,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let _identity = ctx.sender;
+    crate::upgrade::require_not_frozen(ctx)?;
     
     // Requirements 5.2: Add items to available inventory space
     // Requirements 5.5: Prevent picking up when inventory is full
@@ -248,17 +297,21 @@ pub fn execute_contextual_action(
     object_id: u32,
     action_type: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let _identity = ctx.sender;
+    let actor = ctx.db.player().identity().find(ctx.sender).ok_or("Player not found")?;
+    if actor.id != player_id {
+        return Err("Cannot act for another player".into());
+    }
+    crate::upgrade::require_not_frozen(ctx)?;
     
     // Requirements 6.4: Execute appropriate interactions
     // Requirements 6.5: Server validates all contextual actions
     // Requirements 6.6: Handle item generation and object state changes
     
     // Get the interactable object
-    let object = ctx.db.interactable_object().id().find(&object_id).ok_or("Object not found")?;
+    let object = ctx.db.interactable_object().id().find(object_id).ok_or("Object not found")?;
     
     // Get player position for range validation
-    let player = ctx.db.player().id().find(&player_id).ok_or("Player not found")?;
+    let player = actor;
     
     // Validate interaction range
     let distance = ((object.position_x - player.position_x).powi(2) + 
@@ -268,6 +321,7 @@ pub fn execute_contextual_action(
     if distance > max_range {
         return Err("Player too far from object".into());
     }
+    crate::grief_protection::ensure_can_interact(ctx, crate::grief_protection::TARGET_OBJECT, object_id as u64, player_id)?;
     
     // Validate action requirements
     let requirements = get_action_requirements(&object.object_type, &action_type);
@@ -283,6 +337,8 @@ pub fn execute_contextual_action(
         ("rock", "break") => execute_rock_break(ctx, player_id, object_id)?,
         _ => return Err("Invalid action for object type".into()),
     }
+    let depleted = ctx.db.interactable_object().id().find(object_id).is_none_or(|o| o.is_destroyed || (o.health <= 0 && o.resource_count <= 0));
+    crate::grief_protection::on_object_harvested(ctx, object_id, player_id, depleted);
     
     log::info!("Player {} executed action {} on object {}", player_id, action_type, object_id);
     
@@ -291,7 +347,7 @@ pub fn execute_contextual_action(
 
 // Tree interaction implementations
 fn execute_tree_shake(ctx: &ReducerContext, player_id: u32, object_id: u32) -> Result<(), Box<dyn std::error::Error>> {
-    let mut object = ctx.db.interactable_object().id().find(&object_id).ok_or("Object not found")?;
+    let mut object = ctx.db.interactable_object().id().find(object_id).ok_or("Object not found")?;
     
     if object.resource_count <= 0 {
         return Err("No fruit to shake".into());
@@ -301,18 +357,18 @@ fn execute_tree_shake(ctx: &ReducerContext, player_id: u32, object_id: u32) -> R
     object.resource_count -= 1;
     
     // Update object state
-    ctx.db.interactable_object().id().delete(&object_id);
+    ctx.db.interactable_object().id().delete(object_id);
     ctx.db.interactable_object().insert(object);
     
     // Generate fruit item
-    add_item_to_inventory_internal(ctx, player_id, "fruit".to_string(), 1)?;
+    add_gathered_item(ctx, player_id, "fruit".to_string(), 1)?;
     
     log::info!("Player {} shook fruit from tree {}", player_id, object_id);
     Ok(())
 }
 
 fn execute_tree_cut(ctx: &ReducerContext, player_id: u32, object_id: u32) -> Result<(), Box<dyn std::error::Error>> {
-    let mut object = ctx.db.interactable_object().id().find(&object_id).ok_or("Object not found")?;
+    let mut object = ctx.db.interactable_object().id().find(object_id).ok_or("Object not found")?;
     
     if object.health <= 0 {
         return Err("Tree already cut down".into());
@@ -322,11 +378,11 @@ fn execute_tree_cut(ctx: &ReducerContext, player_id: u32, object_id: u32) -> Res
     object.health -= 1;
     
     // Generate wood
-    add_item_to_inventory_internal(ctx, player_id, "wood".to_string(), 1)?;
+    add_gathered_item(ctx, player_id, "wood".to_string(), 1)?;
     
     // If tree is fully cut down, give extra wood
     if object.health <= 0 {
-        add_item_to_inventory_internal(ctx, player_id, "wood".to_string(), 2)?;
+        add_gathered_item(ctx, player_id, "wood".to_string(), 2)?;
         object.is_destroyed = true;
         log::info!("Player {} cut down tree {} completely", player_id, object_id);
     } else {
@@ -334,7 +390,7 @@ fn execute_tree_cut(ctx: &ReducerContext, player_id: u32, object_id: u32) -> Res
     }
     
     // Update object state
-    ctx.db.interactable_object().id().delete(&object_id);
+    ctx.db.interactable_object().id().delete(object_id);
     ctx.db.interactable_object().insert(object);
     
     Ok(())
@@ -342,7 +398,7 @@ fn execute_tree_cut(ctx: &ReducerContext, player_id: u32, object_id: u32) -> Res
 
 // Rock interaction implementations
 fn execute_rock_pickup(ctx: &ReducerContext, player_id: u32, object_id: u32) -> Result<(), Box<dyn std::error::Error>> {
-    let mut object = ctx.db.interactable_object().id().find(&object_id).ok_or("Object not found")?;
+    let mut object = ctx.db.interactable_object().id().find(object_id).ok_or("Object not found")?;
     
     if object.is_destroyed {
         return Err("Rock already picked up".into());
@@ -352,18 +408,18 @@ fn execute_rock_pickup(ctx: &ReducerContext, player_id: u32, object_id: u32) ->
     object.is_destroyed = true;
     
     // Update object state
-    ctx.db.interactable_object().id().delete(&object_id);
+    ctx.db.interactable_object().id().delete(object_id);
     ctx.db.interactable_object().insert(object);
     
     // Generate stone item
-    add_item_to_inventory_internal(ctx, player_id, "stone".to_string(), 1)?;
+    add_gathered_item(ctx, player_id, "stone".to_string(), 1)?;
     
     log::info!("Player {} picked up rock {}", player_id, object_id);
     Ok(())
 }
 
 fn execute_rock_break(ctx: &ReducerContext, player_id: u32, object_id: u32) -> Result<(), Box<dyn std::error::Error>> {
-    let mut object = ctx.db.interactable_object().id().find(&object_id).ok_or("Object not found")?;
+    let mut object = ctx.db.interactable_object().id().find(object_id).ok_or("Object not found")?;
     
     if object.health <= 0 {
         return Err("Rock already broken".into());
@@ -373,11 +429,11 @@ fn execute_rock_break(ctx: &ReducerContext, player_id: u32, object_id: u32) -> R
     object.health -= 1;
     
     // Generate stone fragment
-    add_item_to_inventory_internal(ctx, player_id, "stone_fragment".to_string(), 1)?;
+    add_gathered_item(ctx, player_id, "stone_fragment".to_string(), 1)?;
     
     // If rock is fully broken, give extra stone
     if object.health <= 0 {
-        add_item_to_inventory_internal(ctx, player_id, "stone".to_string(), 1)?;
+        add_gathered_item(ctx, player_id, "stone".to_string(), 1)?;
         object.is_destroyed = true;
         log::info!("Player {} broke rock {} completely", player_id, object_id);
     } else {
@@ -385,7 +441,7 @@ fn execute_rock_break(ctx: &ReducerContext, player_id: u32, object_id: u32) -> R
     }
     
     // Update object state
-    ctx.db.interactable_object().id().delete(&object_id);
+    ctx.db.interactable_object().id().delete(object_id);
     ctx.db.interactable_object().insert(object);
     
     Ok(())
@@ -397,7 +453,7 @@ fn validate_action_requirements(ctx: &ReducerContext, player_id: u32, requiremen
         match requirement.requirement_type.as_str() {
             "equipped_weapon" => {
                 if requirement.must_be_equipped {
-                    if let Some(eq) = ctx.db.player_equipment().player_id().find(&player_id) {
+                    if let Some(eq) = ctx.db.player_equipment().player_id().find(player_id) {
                         if eq.main_hand_weapon != requirement.item_id && eq.off_hand_tool != requirement.item_id {
                             return Ok(false);
                         }
@@ -460,6 +516,18 @@ fn get_interaction_range(object_type: &str) -> f32 {
     }
 }
 
+// Gathering yield, including the profession "bountiful" perk
+fn add_gathered_item(ctx: &ReducerContext, player_id: u32, item_id: String, quantity: i32) -> Result<(), Box<dyn std::error::Error>> {
+    let bonus = match crate::profession::gathering_profession(&item_id) {
+        Some(profession) => {
+            crate::profession::grant_profession_xp(ctx, player_id, profession, GATHER_PROFESSION_XP);
+            crate::profession::roll_extra_yield(ctx, player_id, profession, quantity)
+        }
+        None => 0,
+    };
+    add_item_to_inventory_internal(ctx, player_id, item_id, quantity + bonus)
+}
+
 // Internal helper to add items without context
 fn add_item_to_inventory_internal(ctx: &ReducerContext, player_id: u32, item_id: String, quantity: i32) -> Result<(), Box<dyn std::error::Error>> {
     // Check if item already exists in inventory
@@ -470,25 +538,114 @@ fn add_item_to_inventory_internal(ctx: &ReducerContext, player_id: u32, item_id:
     if let Some(existing_item) = existing_items.first() {
         // Update quantity
         let mut updated_item = existing_item.clone();
-        updated_item.quantity += quantity;
-        ctx.db.inventory_item().id().delete(&existing_item.id);
+        updated_item.quantity = updated_item.quantity.checked_add(quantity).ok_or("Item stack is full")?;
+        ctx.db.inventory_item().id().delete(existing_item.id);
         ctx.db.inventory_item().insert(updated_item);
     } else {
+        ensure_free_slot(ctx, player_id, &item_id)?;
         // Create new inventory entry
         let new_item = InventoryItem {
-            id: generate_inventory_id(),
+            id: generate_inventory_id(ctx),
             player_id,
             item_id: item_id.clone(),
             quantity,
             is_equipped: false,
             slot_type: get_item_slot_type(&item_id),
+            dye_color: None,
         };
         ctx.db.inventory_item().insert(new_item);
+        recompute_inventory_header(ctx, player_id);
     }
     
     Ok(())
 }
 
+/// Slots granted by each bag item
+pub fn bag_capacity(item_id: &str) -> u32 {
+    match item_id {
+        "small_bag" => 4,
+        "large_bag" => 8,
+        _ => 0,
+    }
+}
+
+/// Recomputes the capacity header: loose (unequipped) stacks use one slot each
+pub fn recompute_inventory_header(ctx: &ReducerContext, player_id: u32) -> InventoryHeader {
+    let bag_slots = ctx.db.player_equipment().player_id().find(player_id)
+        .map(|eq| bag_capacity(&eq.bag))
+        .unwrap_or(0)
+        + crate::hireling::hired_inventory_slots(ctx, player_id);
+    let used_slots = ctx.db.inventory_item().player_id().filter(player_id)
+        .filter(|item| !item.is_equipped)
+        .count() as u32;
+    let header = InventoryHeader {
+        player_id,
+        base_slots: BASE_INVENTORY_SLOTS,
+        bag_slots,
+        max_slots: BASE_INVENTORY_SLOTS + bag_slots,
+        used_slots,
+    };
+    if ctx.db.inventory_header().player_id().find(player_id).is_some() {
+        ctx.db.inventory_header().player_id().update(header.clone());
+    } else {
+        ctx.db.inventory_header().insert(header.clone());
+    }
+    header
+}
+
+// A new stack needs a free slot; loose bags only fit in base slots (bags can't hold bags)
+fn ensure_free_slot(ctx: &ReducerContext, player_id: u32, item_id: &str) -> Result<(), String> {
+    let header = recompute_inventory_header(ctx, player_id);
+    if header.used_slots >= header.max_slots {
+        return Err("Inventory is full".to_string());
+    }
+    if get_item_slot_type(item_id) == "bag" && header.used_slots >= header.base_slots {
+        return Err("Bags cannot be stored inside other bags".to_string());
+    }
+    Ok(())
+}
+
+/// Total quantity of an item held by the player
+pub fn count_item(ctx: &ReducerContext, player_id: u32, item_id: &str) -> i32 {
+    ctx.db.inventory_item().player_id().filter(player_id)
+        .filter(|item| item.item_id == item_id)
+        .map(|item| item.quantity)
+        .sum()
+}
+
+/// Removes `quantity` of an item across its stacks (loose stacks first),
+/// deleting each row that reaches zero
+pub fn remove_item_from_inventory(ctx: &ReducerContext, player_id: u32, item_id: &str, quantity: i32) -> Result<(), String> {
+    let mut stacks: Vec<InventoryItem> = ctx.db.inventory_item().player_id().filter(player_id)
+        .filter(|item| item.item_id == item_id)
+        .collect();
+    if stacks.iter().map(|item| item.quantity as i64).sum::<i64>() < quantity as i64 {
+        return Err(format!("Not enough '{}' in inventory", item_id));
+    }
+    stacks.sort_by_key(|item| item.is_equipped);
+
+    let mut remaining = quantity;
+    let mut emptied = false;
+    for mut stack in stacks {
+        if remaining <= 0 {
+            break;
+        }
+        let taken = stack.quantity.min(remaining);
+        remaining -= taken;
+        if taken == stack.quantity {
+            ctx.db.inventory_item().id().delete(stack.id);
+            emptied = true;
+        } else {
+            stack.quantity -= taken;
+            ctx.db.inventory_item().id().update(stack);
+        }
+    }
+    if emptied {
+        recompute_inventory_header(ctx, player_id);
+    }
+    Ok(())
+}
+
 // Reducer to create interactable objects (for testing/setup)
 #[reducer]
 pub fn create_interactable_object(
@@ -518,6 +675,9 @@ pub fn create_interactable_object(
     };
     
     ctx.db.interactable_object().insert(object.clone());
+    if let Some(player) = ctx.db.player().identity().find(ctx.sender) {
+        crate::grief_protection::on_object_placed(ctx, object.id, player.id);
+    }
     log::info!("Created interactable object: {:?}", object.id);
     
     Ok(())
@@ -542,16 +702,60 @@ fn get_item_slot_type(item_id: &str) -> String {
         "arrow" => "ammunition".to_string(),
         "wood" | "stone" | "stone_fragment" => "material".to_string(),
         "fruit" => "consumable".to_string(),
+        "small_bag" | "large_bag" => "bag".to_string(),
         _ => "misc".to_string(),
     }
 }
 
-// Simple ID generation for inventory items
-fn generate_inventory_id() -> u32 {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-    
-    let mut hasher = DefaultHasher::new();
-    std::time::SystemTime::now().hash(&mut hasher);
-    (hasher.finish() % u32::MAX as u64) as u32
+/// Soulbound items stay with the character that got them (no shared bank)
+pub fn is_soulbound(item_id: &str) -> bool {
+    matches!(item_id, "caravan_cargo")
+}
+
+// Weight per unit of each item (encumbrance)
+pub fn item_weight(item_id: &str) -> f32 {
+    match item_id {
+        "sword" | "axe" | "bow" => 5.0,
+        "pickaxe" => 6.0,
+        "arrow" => 0.1,
+        "wood" => 2.0,
+        "stone" => 3.0,
+        "stone_fragment" => 1.0,
+        "fruit" | "fruit_pie" | "rotten_fruit" | "stale_pie" | "raw_fish" => 0.5,
+        "ice_box" => 4.0,
+        "small_bag" | "large_bag" => 1.0,
+        item if item.starts_with("dye_") => 0.1,
+        "health_potion" | "mega_health_potion" => 0.5,
+        // Caravan cargo already slows its carrier through a status effect
+        "caravan_cargo" => 0.0,
+        _ => 1.0,
+    }
+}
+
+pub fn carried_weight(ctx: &ReducerContext, player_id: u32) -> f32 {
+    ctx.db.inventory_item().player_id().filter(player_id)
+        .map(|item| item_weight(&item.item_id) * item.quantity.max(0) as f32)
+        .sum()
+}
+
+/// Movement speed multiplier from carried weight (1.0 = not encumbered)
+pub fn encumbrance_multiplier(ctx: &ReducerContext, player_id: u32) -> f32 {
+    let excess = carried_weight(ctx, player_id) - ENCUMBRANCE_THRESHOLD;
+    if excess <= 0.0 {
+        return 1.0;
+    }
+    1.0 - (excess * ENCUMBRANCE_SLOW_PER_WEIGHT).min(MAX_ENCUMBRANCE_SLOW)
+}
+
+// Random ID generation for inventory items (the reducer RNG is deterministic and
+// available inside the module, unlike the system clock)
+fn generate_inventory_id(ctx: &ReducerContext) -> u32 {
+    use spacetimedb::rand::Rng;
+
+    loop {
+        let id = ctx.rng().gen::<u32>();
+        if ctx.db.inventory_item().id().find(id).is_none() {
+            return id;
+        }
+    }
 }
\ No newline at end of file
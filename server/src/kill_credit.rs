@@ -0,0 +1,82 @@
+use crate::achievement::unlock_achievement;
+use crate::character::attacking_player;
+use crate::death_recap::DeathRecap;
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+/// Dano de outro player nesta janela antes da queda vale assistência
+/// (precisa caber na janela do death recap, de onde os golpes vêm)
+pub const ASSIST_WINDOW_SECS: u64 = 10;
+
+pub const ACHIEVEMENT_FIRST_BLOOD: &str = "first_blood";
+pub const ACHIEVEMENT_TEAM_PLAYER: &str = "team_player";
+const TEAM_PLAYER_ASSISTS: u32 = 25;
+
+/// Quem recebe crédito por uma queda: o golpe final e as assistências
+#[derive(Clone, Debug, Default)]
+pub struct KillCredit {
+    pub killer_id: Option<u32>,
+    pub weapon: String,
+    pub assist_ids: Vec<u32>,
+}
+
+/// Totais de golpes finais e assistências por player
+#[table(name = combat_credit, public)]
+#[derive(Clone)]
+pub struct CombatCredit {
+    #[primary_key]
+    pub player_id: u32,
+    pub killing_blows: u32,
+    pub assists: u32,
+    pub updated_at: Timestamp,
+}
+
+/// Atribui a queda a partir do recap: `killer_id` é o player do golpe final
+pub fn credit_for(ctx: &ReducerContext, recap: &DeathRecap, killer_id: Option<u32>) -> KillCredit {
+    let weapon = killer_id
+        .and_then(|k| recap.entries.iter().rev().find(|e| e.source_id == k))
+        .map(|e| e.ability.clone())
+        .unwrap_or_default();
+
+    let window = Duration::from_secs(ASSIST_WINDOW_SECS);
+    let mut assist_ids: Vec<u32> = Vec::new();
+    for entry in &recap.entries {
+        let recent = recap.downed_at.duration_since(entry.at).is_none_or(|d| d <= window);
+        if recent
+            && Some(entry.source_id) != killer_id
+            && entry.source_id != recap.player_id
+            && attacking_player(ctx, entry.source_id).is_some()
+            && !assist_ids.contains(&entry.source_id)
+        {
+            assist_ids.push(entry.source_id);
+        }
+    }
+    KillCredit { killer_id, weapon, assist_ids }
+}
+
+fn bump(ctx: &ReducerContext, player_id: u32, apply: impl FnOnce(&mut CombatCredit)) -> CombatCredit {
+    let existing = ctx.db.combat_credit().player_id().find(player_id);
+    let mut credit = existing.clone().unwrap_or(CombatCredit { player_id, killing_blows: 0, assists: 0, updated_at: ctx.timestamp });
+    apply(&mut credit);
+    credit.updated_at = ctx.timestamp;
+    if existing.is_some() {
+        ctx.db.combat_credit().player_id().update(credit.clone());
+    } else {
+        ctx.db.combat_credit().insert(credit.clone());
+    }
+    credit
+}
+
+/// Soma o crédito aos totais e às conquistas ligadas a eles
+pub fn record_credit(ctx: &ReducerContext, credit: &KillCredit) {
+    if let Some(killer_id) = credit.killer_id {
+        bump(ctx, killer_id, |c| c.killing_blows += 1);
+        unlock_achievement(ctx, killer_id, ACHIEVEMENT_FIRST_BLOOD);
+    }
+    for assist_id in &credit.assist_ids {
+        let totals = bump(ctx, *assist_id, |c| c.assists += 1);
+        if totals.assists >= TEAM_PLAYER_ASSISTS {
+            unlock_achievement(ctx, *assist_id, ACHIEVEMENT_TEAM_PLAYER);
+        }
+    }
+}
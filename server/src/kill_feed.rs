@@ -0,0 +1,59 @@
+use crate::kill_credit::KillCredit;
+use crate::map::map_instance;
+use crate::player;
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+
+/// Linhas mantidas por mapa; as mais antigas saem ao entrar uma nova
+pub const KILL_FEED_SIZE: usize = 20;
+
+/// Feed de abates entre players por mapa (arenas, zonas PvP) para a UI competitiva
+#[table(name = kill_feed, public)]
+#[derive(Clone)]
+pub struct KillFeedEntry {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub map_id: String,
+    pub killer_id: u32,
+    pub killer_name: String,
+    pub victim_id: u32,
+    pub victim_name: String,
+    pub weapon: String,
+    pub assist_ids: Vec<u32>,
+    pub at: Timestamp,
+}
+
+/// Chamado pelo caminho de queda compartilhado quando um player derrubou outro
+pub fn record_kill(ctx: &ReducerContext, victim: &crate::Player, killer_id: u32, credit: &KillCredit) {
+    ctx.db.kill_feed().insert(KillFeedEntry {
+        id: 0,
+        map_id: victim.current_map_id.clone(),
+        killer_id,
+        killer_name: ctx.db.player().id().find(killer_id).map(|p| p.username_display).unwrap_or_default(),
+        victim_id: victim.id,
+        victim_name: victim.username_display.clone(),
+        weapon: credit.weapon.clone(),
+        assist_ids: credit.assist_ids.clone(),
+        at: ctx.timestamp,
+    });
+
+    let mut feed: Vec<KillFeedEntry> = ctx.db.kill_feed().map_id().filter(&victim.current_map_id).collect();
+    if feed.len() > KILL_FEED_SIZE {
+        feed.sort_by_key(|e| e.id);
+        for entry in feed.iter().take(feed.len() - KILL_FEED_SIZE) {
+            ctx.db.kill_feed().id().delete(entry.id);
+        }
+    }
+}
+
+/// Remove feeds de instâncias que já não existem (arenas encerradas)
+pub fn prune_kill_feeds(ctx: &ReducerContext) {
+    let orphan: Vec<u64> = ctx.db.kill_feed().iter()
+        .filter(|e| ctx.db.map_instance().key_id().find(e.map_id.clone()).is_none())
+        .map(|e| e.id)
+        .collect();
+    for id in orphan {
+        ctx.db.kill_feed().id().delete(id);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::alliance::{alliance, alliance_member};
+use crate::guild::guild_member;
+use crate::guild_war::{guild_war, GuildWar, WarState};
+use crate::player;
+use crate::run_report::{run_report, RunReport, RUN_CLEARED};
+use crate::season::current_season;
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+
+/// Posições mantidas por dungeon/tier
+pub const SPEEDRUN_LEADERBOARD_SIZE: usize = 10;
+
+/// Melhores tempos por dungeon e tier em uma temporada.
+/// Linhas de temporadas encerradas não são mais recalculadas (arquivo).
+#[table(name = speedrun_entry, public)]
+#[derive(Clone)]
+pub struct SpeedrunEntry {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub season_id: u64,
+    pub template_name: String,
+    pub tier: u32,
+    pub rank: u32,
+    pub run_id: u64,
+    pub clear_time_ms: u64,
+    pub member_ids: Vec<u32>,
+    pub member_names: Vec<String>,
+    pub achieved_at: Timestamp,
+}
+
+/// Recria o ranking da temporada atual a partir dos run reports concluídos
+pub fn refresh_speedrun_leaderboard(ctx: &ReducerContext) {
+    let season = current_season(ctx);
+
+    let stale: Vec<u64> = ctx.db.speedrun_entry().season_id().filter(season.id).map(|e| e.id).collect();
+    for id in stale {
+        ctx.db.speedrun_entry().id().delete(id);
+    }
+
+    let mut cleared: Vec<RunReport> = ctx.db.run_report().iter()
+        .filter(|r| r.outcome == RUN_CLEARED && r.clear_time_ms.is_some())
+        .filter(|r| r.finished_at.is_some_and(|t| t >= season.started_at))
+        .collect();
+    cleared.sort_by(|a, b| {
+        (&a.template_name, a.tier, a.clear_time_ms, a.id).cmp(&(&b.template_name, b.tier, b.clear_time_ms, b.id))
+    });
+
+    let mut group: Option<(String, u32)> = None;
+    let mut rank = 0;
+    for run in cleared {
+        let key = (run.template_name.clone(), run.tier);
+        if group.as_ref() != Some(&key) {
+            group = Some(key);
+            rank = 0;
+        }
+        if rank as usize >= SPEEDRUN_LEADERBOARD_SIZE {
+            continue;
+        }
+        rank += 1;
+
+        let member_names = run.member_ids.iter()
+            .map(|id| ctx.db.player().id().find(*id).map(|p| p.username_display).unwrap_or_default())
+            .collect();
+
+        ctx.db.speedrun_entry().insert(SpeedrunEntry {
+            id: 0,
+            season_id: season.id,
+            template_name: run.template_name,
+            tier: run.tier,
+            rank,
+            run_id: run.id,
+            clear_time_ms: run.clear_time_ms.unwrap_or_default(),
+            member_ids: run.member_ids,
+            member_names,
+            achieved_at: run.finished_at.unwrap_or(run.started_at),
+        });
+    }
+}
+
+/// Ranking de alianças: vitórias em guerra das guildas membro, depois pontuação
+/// acumulada em guerras e número de membros. Recalculado a cada agregação.
+#[table(name = alliance_standing, public)]
+#[derive(Clone)]
+pub struct AllianceStanding {
+    #[primary_key]
+    pub alliance_id: u64,
+    pub name: String,
+    pub rank: u32,
+    pub guild_count: u32,
+    pub member_count: u32,
+    pub war_wins: u32,
+    pub war_score: u32,
+    pub updated_at: Timestamp,
+}
+
+pub fn refresh_alliance_leaderboard(ctx: &ReducerContext) {
+    let stale: Vec<u64> = ctx.db.alliance_standing().iter().map(|s| s.alliance_id).collect();
+    for id in stale {
+        ctx.db.alliance_standing().alliance_id().delete(id);
+    }
+
+    let wars: Vec<GuildWar> = ctx.db.guild_war().iter().filter(|w| w.state == WarState::Ended).collect();
+    let mut standings: Vec<AllianceStanding> = ctx.db.alliance().iter().map(|alliance| {
+        let guild_ids: Vec<u64> = ctx.db.alliance_member().alliance_id().filter(alliance.id).map(|m| m.guild_id).collect();
+        let member_count = guild_ids.iter().map(|g| ctx.db.guild_member().guild_id().filter(*g).count() as u32).sum();
+        let war_wins = wars.iter().filter(|w| w.winner_guild_id.is_some_and(|g| guild_ids.contains(&g))).count() as u32;
+        let war_score = wars.iter().map(|w| {
+            let attacker = if guild_ids.contains(&w.attacker_guild_id) { w.attacker_score } else { 0 };
+            let defender = if guild_ids.contains(&w.defender_guild_id) { w.defender_score } else { 0 };
+            attacker + defender
+        }).sum();
+        AllianceStanding {
+            alliance_id: alliance.id,
+            name: alliance.name,
+            rank: 0,
+            guild_count: guild_ids.len() as u32,
+            member_count,
+            war_wins,
+            war_score,
+            updated_at: ctx.timestamp,
+        }
+    }).collect();
+    standings.sort_by_key(|s| (std::cmp::Reverse((s.war_wins, s.war_score, s.member_count)), s.alliance_id));
+
+    for (i, mut standing) in standings.into_iter().enumerate() {
+        standing.rank = i as u32 + 1;
+        ctx.db.alliance_standing().insert(standing);
+    }
+}
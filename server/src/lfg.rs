@@ -0,0 +1,207 @@
+use crate::party::{form_party, join_party, party, party_member_ids, party_of, sender_player, MAX_PARTY_SIZE};
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+pub const ROLE_TANK: &str = "tank";
+pub const ROLE_HEALER: &str = "healer";
+pub const ROLE_DPS: &str = "dps";
+const ROLES: [&str; 3] = [ROLE_TANK, ROLE_HEALER, ROLE_DPS];
+
+pub const LISTING_DURATION_SECS: u64 = 30 * 60;
+const MAX_DESCRIPTION_LENGTH: usize = 140;
+
+/// Anúncio de grupo procurando gente para uma atividade (template de dungeon, quest...).
+/// `roles_needed` lista uma entrada por vaga; cada aceite consome a vaga do papel.
+#[table(name = group_listing, public)]
+#[derive(Clone)]
+pub struct GroupListing {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[unique]
+    pub leader_id: u32,
+    pub activity: String,
+    pub description: String,
+    pub roles_needed: Vec<String>,
+    pub created_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+#[table(name = group_application, public)]
+#[derive(Clone)]
+pub struct GroupApplication {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub listing_id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub role: String,
+    pub applied_at: Timestamp,
+}
+
+pub fn delete_listing(ctx: &ReducerContext, listing_id: u64) {
+    let applications: Vec<u64> = ctx.db.group_application().listing_id().filter(listing_id).map(|a| a.id).collect();
+    for id in applications {
+        ctx.db.group_application().id().delete(id);
+    }
+    ctx.db.group_listing().id().delete(listing_id);
+}
+
+fn open_slots(ctx: &ReducerContext, leader_id: u32) -> usize {
+    let members = party_of(ctx, leader_id).map(|p| party_member_ids(ctx, p).len()).unwrap_or(1);
+    MAX_PARTY_SIZE.saturating_sub(members)
+}
+
+/// Remove anúncios vencidos (chamado pela limpeza periódica)
+pub fn expire_group_listings(ctx: &ReducerContext) {
+    let expired: Vec<u64> = ctx.db.group_listing().iter()
+        .filter(|l| l.expires_at <= ctx.timestamp)
+        .map(|l| l.id)
+        .collect();
+    for id in expired {
+        delete_listing(ctx, id);
+        log::info!("📋 Group listing {} expired", id);
+    }
+}
+
+#[reducer]
+pub fn create_group_listing(ctx: &ReducerContext, activity: String, description: String, roles_needed: Vec<String>) -> Result<(), String> {
+    let leader = sender_player(ctx)?;
+    let activity = activity.trim().to_string();
+    if activity.is_empty() {
+        return Err("Activity cannot be empty".to_string());
+    }
+    let description = description.trim().to_string();
+    if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+        return Err(format!("Description must have at most {} characters", MAX_DESCRIPTION_LENGTH));
+    }
+    if roles_needed.is_empty() || roles_needed.iter().any(|r| !ROLES.contains(&r.as_str())) {
+        return Err("Roles must be tank, healer or dps".to_string());
+    }
+    if let Some(party_id) = party_of(ctx, leader.id) {
+        if ctx.db.party().id().find(party_id).is_some_and(|p| p.leader_id != leader.id) {
+            return Err("Only the party leader can list the group".to_string());
+        }
+    }
+    if roles_needed.len() > open_slots(ctx, leader.id) {
+        return Err("Not enough free party slots for those roles".to_string());
+    }
+    if let Some(existing) = ctx.db.group_listing().leader_id().find(leader.id) {
+        delete_listing(ctx, existing.id);
+    }
+
+    let listing = ctx.db.group_listing().insert(GroupListing {
+        id: 0,
+        leader_id: leader.id,
+        activity,
+        description,
+        roles_needed,
+        created_at: ctx.timestamp,
+        expires_at: ctx.timestamp + Duration::from_secs(LISTING_DURATION_SECS),
+    });
+    log::info!("📋 Player {} listed group {} for {}", leader.id, listing.id, listing.activity);
+    Ok(())
+}
+
+#[reducer]
+pub fn cancel_group_listing(ctx: &ReducerContext) -> Result<(), String> {
+    let leader = sender_player(ctx)?;
+    let listing = ctx.db.group_listing().leader_id().find(leader.id).ok_or("You have no group listing")?;
+    delete_listing(ctx, listing.id);
+    Ok(())
+}
+
+#[reducer]
+pub fn apply_to_group_listing(ctx: &ReducerContext, listing_id: u64, role: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let listing = ctx.db.group_listing().id().find(listing_id).ok_or("Listing not found")?;
+    if listing.expires_at <= ctx.timestamp {
+        return Err("Listing has expired".to_string());
+    }
+    if listing.leader_id == player.id {
+        return Err("Cannot apply to your own listing".to_string());
+    }
+    if party_of(ctx, player.id).is_some() {
+        return Err("Leave your party before applying".to_string());
+    }
+    if !listing.roles_needed.contains(&role) {
+        return Err("That role is not needed".to_string());
+    }
+    if ctx.db.group_application().player_id().filter(player.id).any(|a| a.listing_id == listing_id) {
+        return Err("Already applied to that listing".to_string());
+    }
+
+    ctx.db.group_application().insert(GroupApplication {
+        id: 0,
+        listing_id,
+        player_id: player.id,
+        role,
+        applied_at: ctx.timestamp,
+    });
+    Ok(())
+}
+
+#[reducer]
+pub fn withdraw_group_application(ctx: &ReducerContext, application_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let application = ctx.db.group_application().id().find(application_id).ok_or("Application not found")?;
+    if application.player_id != player.id {
+        return Err("Not your application".to_string());
+    }
+    ctx.db.group_application().id().delete(application_id);
+    Ok(())
+}
+
+#[reducer]
+pub fn decline_group_application(ctx: &ReducerContext, application_id: u64) -> Result<(), String> {
+    let leader = sender_player(ctx)?;
+    let application = ctx.db.group_application().id().find(application_id).ok_or("Application not found")?;
+    let listing = ctx.db.group_listing().id().find(application.listing_id).ok_or("Listing not found")?;
+    if listing.leader_id != leader.id {
+        return Err("Only the listing owner can do that".to_string());
+    }
+    ctx.db.group_application().id().delete(application_id);
+    Ok(())
+}
+
+/// Aceita o candidato: forma a party do líder se ainda não existir, ou estende a atual
+#[reducer]
+pub fn accept_group_application(ctx: &ReducerContext, application_id: u64) -> Result<(), String> {
+    let leader = sender_player(ctx)?;
+    let application = ctx.db.group_application().id().find(application_id).ok_or("Application not found")?;
+    let mut listing = ctx.db.group_listing().id().find(application.listing_id).ok_or("Listing not found")?;
+    if listing.leader_id != leader.id {
+        return Err("Only the listing owner can do that".to_string());
+    }
+    let Some(slot) = listing.roles_needed.iter().position(|r| *r == application.role) else {
+        return Err("That role is already filled".to_string());
+    };
+
+    let party_id = match party_of(ctx, leader.id) {
+        Some(party_id) => {
+            if ctx.db.party().id().find(party_id).is_some_and(|p| p.leader_id != leader.id) {
+                return Err("Only the party leader can accept applicants".to_string());
+            }
+            party_id
+        }
+        None => form_party(ctx, leader.id).id,
+    };
+    join_party(ctx, application.player_id, party_id)?;
+
+    // O candidato não precisa mais das outras candidaturas
+    let stale: Vec<u64> = ctx.db.group_application().player_id().filter(application.player_id).map(|a| a.id).collect();
+    for id in stale {
+        ctx.db.group_application().id().delete(id);
+    }
+
+    listing.roles_needed.remove(slot);
+    if listing.roles_needed.is_empty() || open_slots(ctx, leader.id) == 0 {
+        delete_listing(ctx, listing.id);
+        log::info!("📋 Group listing {} filled", listing.id);
+    } else {
+        ctx.db.group_listing().id().update(listing);
+    }
+    Ok(())
+}
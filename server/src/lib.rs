@@ -10,6 +10,101 @@ pub mod combat;
 pub mod character;
 pub mod inventory;
 pub mod resource_registry;
+pub mod cooldown;
+pub mod admin;
+pub mod config;
+pub mod tick;
+pub mod ability_queue;
+pub mod enemy_ai;
+pub mod audit;
+pub mod sanitation;
+pub mod client_compat;
+pub mod feature_flag;
+pub mod world_validation;
+pub mod teleporter;
+pub mod transport;
+pub mod minimap;
+pub mod progression;
+pub mod achievement;
+pub mod exploration;
+pub mod party;
+pub mod dungeon;
+pub mod status_effect;
+pub mod run_report;
+pub mod aggregation;
+pub mod season;
+pub mod leaderboard;
+pub mod announcement;
+pub mod world_boss;
+pub mod currency;
+pub mod town;
+pub mod invasion;
+pub mod structure;
+pub mod guild;
+pub mod friend;
+pub mod caravan;
+pub mod reputation;
+pub mod vendor;
+pub mod crafting;
+pub mod work_order;
+pub mod profession;
+pub mod perishable;
+pub mod loadout;
+pub mod cosmetic;
+pub mod pvp;
+pub mod arena;
+pub mod mail;
+pub mod rating;
+pub mod tournament;
+pub mod guild_war;
+pub mod alliance;
+pub mod chat;
+pub mod lfg;
+pub mod mentor;
+pub mod world_first;
+pub mod seasonal_event;
+pub mod scenic;
+pub mod bestiary;
+pub mod damage_meter;
+pub mod death_recap;
+pub mod kill_feed;
+pub mod kill_credit;
+pub mod defense;
+pub mod forced_movement;
+pub mod metric;
+pub mod tick_budget;
+pub mod reward;
+pub mod escrow;
+pub mod rested;
+pub mod local_event;
+pub mod npc_bark;
+pub mod name_policy;
+pub mod localization;
+pub mod external_event;
+pub mod privacy;
+pub mod ballistics;
+pub mod aura;
+pub mod threat;
+pub mod healer;
+pub mod loot_pity;
+pub mod afk_detection;
+pub mod community_goal;
+pub mod hireling;
+pub mod challenge_tower;
+pub mod dungeon_gen;
+pub mod room_prefab;
+pub mod story_flag;
+pub mod account_unlock;
+pub mod shared_bank;
+pub mod bug_report;
+pub mod heatmap;
+pub mod economy;
+pub mod experiment;
+pub mod replay;
+pub mod shadow_ban;
+pub mod grief_protection;
+pub mod tutorial;
+pub mod upgrade;
 
 #[table(name = player, public)]
 #[derive(Clone)]
@@ -26,6 +121,12 @@ pub struct Player {
     pub position_y: f32,
     pub velocity_x: f32,
     pub velocity_y: f32,
+    /// Motion hints for client interpolation (unit facing vector + animation id)
+    pub facing_x: f32,
+    pub facing_y: f32,
+    pub animation_state: u8,
+    pub motion_updated_at: Timestamp,
+    #[index(btree)]
     pub current_map_id: String,
 
     pub health: f32,
@@ -35,6 +136,22 @@ pub struct Player {
     pub last_transition_time: Timestamp,
 }
 
+/// Data de criação da conta (players anteriores a esta tabela não têm linha)
+#[table(name = player_registration, public)]
+#[derive(Clone)]
+pub struct PlayerRegistration {
+    #[primary_key]
+    pub player_id: u32,
+    pub registered_at: Timestamp,
+}
+
+/// Idade da conta; contas sem registro são anteriores à tabela e contam como antigas
+pub fn account_age(ctx: &ReducerContext, player_id: u32) -> std::time::Duration {
+    ctx.db.player_registration().player_id().find(player_id)
+        .and_then(|r| ctx.timestamp.duration_since(r.registered_at))
+        .unwrap_or(std::time::Duration::MAX)
+}
+
 // ============================================================================
 // LIFECYCLE HANDLERS
 // ============================================================================
@@ -48,6 +165,7 @@ pub fn on_connect(ctx: &ReducerContext) {
     let map_to_init = if let Some(player) = ctx.db.player().iter().find(|p| p.identity == ctx.sender) {
         log::info!("👤 Existing player reconnected: {}, Map: {}",
                    player.username_display, player.current_map_id);
+        rested::accrue_on_reconnect(ctx, &player);
         player.current_map_id.clone()
     } else {
         log::info!("🆕 New client connected. Preparing starting_area.");
@@ -62,6 +180,20 @@ pub fn on_connect(ctx: &ReducerContext) {
         log::info!("🔧 DB Empty: Auto-initializing map transitions...");
         map::init_map_transitions(ctx);
     }
+
+    // Republish sem limpar o banco não roda o init: garante o tick
+    tick::ensure_world_tick(ctx);
+    localization::ensure_localized_strings(ctx);
+    sanitation::ensure_sanitation_schedule(ctx);
+    aggregation::ensure_aggregation_schedule(ctx);
+    heatmap::ensure_heatmap_schedule(ctx);
+    world_boss::ensure_world_boss_schedule(ctx);
+    invasion::ensure_invasion_schedule(ctx);
+    perishable::ensure_perish_schedule(ctx);
+    rating::ensure_rating_decay_schedule(ctx);
+    seasonal_event::ensure_seasonal_event_schedule(ctx);
+    npc_bark::ensure_npc_bark_schedule(ctx);
+    work_order::adopt_legacy_escrow(ctx);
 }
 
 /// Called when a client disconnects from the database
@@ -74,6 +206,9 @@ pub fn on_disconnect(ctx: &ReducerContext) {
         log::info!("👋 Player {} ({}) disconnected from map: {}",
                    player.id, player.username_display, player.current_map_id);
 
+        cooldown::clear_expired_cooldowns(ctx, player.identity);
+        rested::record_logout(ctx, &player);
+
         // Note: We don't delete the player on disconnect
         // Players persist across sessions
     }
@@ -86,24 +221,28 @@ pub fn on_disconnect(ctx: &ReducerContext) {
 pub fn register_player(ctx: &ReducerContext, username_display: String) -> Result<(), String> {
     let identity = ctx.sender;
 
-    // Se já existe por identidade, só atualiza o mapa e retorna
-    if let Some(p) = ctx.db.player().iter().find(|p| p.identity == identity) {
-        let _ = map::update_map_state(ctx, &p.current_map_id); // Garante sync
+    // Se já existe por identidade, não há nada a fazer
+    if ctx.db.player().identity().find(identity).is_some() {
         return Ok(());
     }
 
     let display = username_display.trim().to_string();
     let canonical = display.to_lowercase();
 
-    if display.is_empty() || canonical.len() < 3 || canonical.len() > 16 {
-        return Err("Invalid username length".into());
-    }
-
     // Lógica de Reclaim (Recuperar usuário antigo)
     if let Some(existing_player) = ctx.db.player().iter().find(|p| p.username_canonical == canonical) {
         let mut p = existing_player.clone();
-        ctx.db.player().id().delete(&p.id);
-
+        let previous_map = p.current_map_id.clone();
+        ctx.db.player().id().delete(p.id);
+
+        // Cooldowns, banco, desbloqueios e cohorts da conta só seguem para a nova
+        // identidade se o dono autorizou; quem só reivindica o nome não leva a conta junto
+        if account_unlock::take_account_transfer(ctx, p.identity, identity) {
+            cooldown::transfer_cooldowns(ctx, p.identity, identity);
+            shared_bank::transfer_shared_bank(ctx, p.identity, identity);
+            account_unlock::transfer_account_unlocks(ctx, p.identity, identity);
+            experiment::transfer_assignments(ctx, p.identity, identity);
+        }
         p.identity = identity;
 
         // Lógica de Reclaim (Recuperar usuário antigo)
@@ -131,15 +270,15 @@ pub fn register_player(ctx: &ReducerContext, username_display: String) -> Result
             p.position_y = spawn_y;
         }
 
+        // Avisa os mapas se o reclaim mudou o player de lugar
+        map::move_map_population(ctx, Some(&previous_map), Some(&p.current_map_id));
         ctx.db.player().insert(p);
 
-        // --- NOVO: Avisa o mapa que chegou gente ---
-        let _ = map::update_map_state(ctx, STARTING_MAP);
-
         return Ok(());
     }
 
-    // Novo Player
+    // Novo Player: nome passa pela política de nomes reservados
+    let (display, canonical) = name_policy::validate_username(ctx, &display)?;
     let (spawn_x, spawn_y) = map::get_spawn_point(ctx, STARTING_MAP);
     let new_player = Player {
         id: generate_player_id(ctx),
@@ -150,6 +289,10 @@ pub fn register_player(ctx: &ReducerContext, username_display: String) -> Result
         position_y: spawn_y,
         velocity_x: 0.0,
         velocity_y: 0.0,
+        facing_x: 0.0,
+        facing_y: 1.0,
+        animation_state: movement::ANIM_IDLE,
+        motion_updated_at: ctx.timestamp,
         current_map_id: STARTING_MAP.to_string(),
         health: 100.0,
         max_health: 100.0,
@@ -158,8 +301,16 @@ pub fn register_player(ctx: &ReducerContext, username_display: String) -> Result
         last_transition_time: ctx.timestamp,
     };
 
-    ctx.db.player().insert(new_player);
-    let _ = map::update_map_state(ctx, STARTING_MAP);
+    let new_player = ctx.db.player().insert(new_player);
+    ctx.db.player_registration().insert(PlayerRegistration { player_id: new_player.id, registered_at: ctx.timestamp });
+    map::move_map_population(ctx, None, Some(STARTING_MAP));
+    external_event::emit(ctx, external_event::EVENT_PLAYER_REGISTERED, &[
+        ("player_id", new_player.id.to_string()),
+        ("username", new_player.username_display.clone()),
+    ]);
+    account_unlock::inherit_account_unlocks(ctx, new_player.id);
+    exploration::discover_map(ctx, new_player.id, STARTING_MAP);
+    tutorial::start_tutorial(ctx, new_player.id);
 
     Ok(())
 }
@@ -221,7 +372,7 @@ fn generate_player_id(ctx: &ReducerContext) -> u32 {
     let mut disambiguation = 0u32;
     loop {
         let final_id = base_hash.wrapping_add(disambiguation);
-        if ctx.db.player().id().find(&final_id).is_none() {
+        if ctx.db.player().id().find(final_id).is_none() {
             return final_id;
         }
         disambiguation += 1;
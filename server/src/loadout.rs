@@ -0,0 +1,170 @@
+use crate::combat::is_in_combat;
+use crate::inventory::{count_item, equip_item, player_equipment, unequip_item};
+use crate::party::sender_player;
+use spacetimedb::{reducer, table, ReducerContext, SpacetimeType, Table, Timestamp};
+
+pub const HOTBAR_SLOTS: u8 = 8;
+const MAX_LOADOUTS: usize = 5;
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct HotbarAssignment {
+    pub slot: u8,
+    pub item_id: String,
+}
+
+/// Atalhos da barra rápida de cada player
+#[table(name = hotbar_slot, public)]
+#[derive(Clone)]
+pub struct HotbarSlot {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub slot: u8,
+    pub item_id: String,
+}
+
+/// Conjunto nomeado de equipamento + barra rápida (slots vazios = "")
+#[table(name = loadout, public)]
+#[derive(Clone)]
+pub struct Loadout {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub name: String,
+    pub main_hand_weapon: String,
+    pub off_hand_tool: String,
+    pub armor: String,
+    pub accessory: String,
+    pub hotbar: Vec<HotbarAssignment>,
+    pub saved_at: Timestamp,
+}
+
+fn set_hotbar_slot(ctx: &ReducerContext, player_id: u32, slot: u8, item_id: String) {
+    let existing = ctx.db.hotbar_slot().player_id().filter(player_id).find(|h| h.slot == slot);
+    match (existing, item_id.is_empty()) {
+        (Some(row), true) => {
+            ctx.db.hotbar_slot().id().delete(row.id);
+        }
+        (Some(mut row), false) => {
+            row.item_id = item_id;
+            ctx.db.hotbar_slot().id().update(row);
+        }
+        (None, false) => {
+            ctx.db.hotbar_slot().insert(HotbarSlot { id: 0, player_id, slot, item_id });
+        }
+        (None, true) => {}
+    }
+}
+
+#[reducer]
+pub fn assign_hotbar_slot(ctx: &ReducerContext, slot: u8, item_id: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if slot >= HOTBAR_SLOTS {
+        return Err(format!("Hotbar slot must be below {}", HOTBAR_SLOTS));
+    }
+    if !item_id.is_empty() && count_item(ctx, player.id, &item_id) <= 0 {
+        return Err(format!("You don't own '{}'", item_id));
+    }
+    set_hotbar_slot(ctx, player.id, slot, item_id);
+    Ok(())
+}
+
+/// Salva (ou sobrescreve) o equipamento e a barra rápida atuais com um nome
+#[reducer]
+pub fn save_loadout(ctx: &ReducerContext, name: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Loadout name cannot be empty".to_string());
+    }
+
+    let equipment = ctx.db.player_equipment().player_id().find(player.id);
+    let hotbar: Vec<HotbarAssignment> = ctx.db.hotbar_slot().player_id().filter(player.id)
+        .map(|h| HotbarAssignment { slot: h.slot, item_id: h.item_id })
+        .collect();
+    let existing = ctx.db.loadout().player_id().filter(player.id).find(|l| l.name == name);
+    if existing.is_none() && ctx.db.loadout().player_id().filter(player.id).count() >= MAX_LOADOUTS {
+        return Err(format!("At most {} loadouts can be saved", MAX_LOADOUTS));
+    }
+
+    let loadout = Loadout {
+        id: existing.as_ref().map(|l| l.id).unwrap_or(0),
+        player_id: player.id,
+        name: name.clone(),
+        main_hand_weapon: equipment.as_ref().map(|e| e.main_hand_weapon.clone()).unwrap_or_default(),
+        off_hand_tool: equipment.as_ref().map(|e| e.off_hand_tool.clone()).unwrap_or_default(),
+        armor: equipment.as_ref().map(|e| e.armor.clone()).unwrap_or_default(),
+        accessory: equipment.as_ref().map(|e| e.accessory.clone()).unwrap_or_default(),
+        hotbar,
+        saved_at: ctx.timestamp,
+    };
+    if existing.is_some() {
+        ctx.db.loadout().id().update(loadout);
+    } else {
+        ctx.db.loadout().insert(loadout);
+    }
+    log::info!("🎒 Player {} saved loadout '{}'", player.id, name);
+    Ok(())
+}
+
+/// Aplica um loadout de uma vez: qualquer falha desfaz a transação inteira
+#[reducer]
+pub fn apply_loadout(ctx: &ReducerContext, name: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if is_in_combat(ctx, player.id) {
+        return Err("Cannot change loadout while in combat".to_string());
+    }
+    let loadout = ctx.db.loadout().player_id().filter(player.id)
+        .find(|l| l.name == name)
+        .ok_or("Loadout not found")?;
+
+    let slots = [&loadout.main_hand_weapon, &loadout.off_hand_tool, &loadout.armor, &loadout.accessory];
+    for item_id in slots.iter().filter(|i| !i.is_empty()) {
+        if count_item(ctx, player.id, item_id) <= 0 {
+            return Err(format!("You no longer own '{}'", item_id));
+        }
+    }
+    for assignment in &loadout.hotbar {
+        if count_item(ctx, player.id, &assignment.item_id) <= 0 {
+            return Err(format!("You no longer own '{}'", assignment.item_id));
+        }
+    }
+
+    // Tira o que não faz parte do loadout e equipa o resto
+    if let Some(current) = ctx.db.player_equipment().player_id().find(player.id) {
+        let current_slots = [current.main_hand_weapon, current.off_hand_tool, current.armor, current.accessory];
+        for (worn, wanted) in current_slots.iter().zip(slots) {
+            if !worn.is_empty() && worn != wanted {
+                unequip_item(ctx, player.id, worn.clone()).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    for item_id in slots.into_iter().filter(|i| !i.is_empty()) {
+        equip_item(ctx, player.id, item_id.clone()).map_err(|e| e.to_string())?;
+    }
+
+    for slot in 0..HOTBAR_SLOTS {
+        let item_id = loadout.hotbar.iter()
+            .find(|a| a.slot == slot)
+            .map(|a| a.item_id.clone())
+            .unwrap_or_default();
+        set_hotbar_slot(ctx, player.id, slot, item_id);
+    }
+
+    log::info!("🎒 Player {} applied loadout '{}'", player.id, name);
+    Ok(())
+}
+
+#[reducer]
+pub fn delete_loadout(ctx: &ReducerContext, name: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let loadout = ctx.db.loadout().player_id().filter(player.id)
+        .find(|l| l.name == name)
+        .ok_or("Loadout not found")?;
+    ctx.db.loadout().id().delete(loadout.id);
+    Ok(())
+}
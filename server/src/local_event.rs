@@ -0,0 +1,52 @@
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+
+/// Eventos mantidos por mapa (os mais antigos são descartados)
+const MAX_EVENTS_PER_MAP: usize = 30;
+
+pub const LOCAL_EVENT_BARK: &str = "bark";
+
+/// Fluxo de eventos locais de um mapa (falas de NPC, avisos da área);
+/// clientes assinam filtrando pelo próprio `map_id`
+#[table(name = local_event, public)]
+#[derive(Clone)]
+pub struct LocalEvent {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub map_id: String,
+    pub kind: String,
+    pub source_name: String,
+    pub message: String,
+    pub position_x: f32,
+    pub position_y: f32,
+    pub created_at: Timestamp,
+}
+
+pub fn post_local_event(
+    ctx: &ReducerContext,
+    map_id: &str,
+    kind: &str,
+    source_name: &str,
+    message: String,
+    (position_x, position_y): (f32, f32),
+) {
+    ctx.db.local_event().insert(LocalEvent {
+        id: 0,
+        map_id: map_id.to_string(),
+        kind: kind.to_string(),
+        source_name: source_name.to_string(),
+        message,
+        position_x,
+        position_y,
+        created_at: ctx.timestamp,
+    });
+
+    let mut events: Vec<LocalEvent> = ctx.db.local_event().map_id().filter(map_id).collect();
+    if events.len() > MAX_EVENTS_PER_MAP {
+        events.sort_by_key(|e| e.id);
+        for old in &events[..events.len() - MAX_EVENTS_PER_MAP] {
+            ctx.db.local_event().id().delete(old.id);
+        }
+    }
+}
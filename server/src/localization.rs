@@ -0,0 +1,191 @@
+use crate::announcement::announce;
+use spacetimedb::{reducer, table, Identity, ReducerContext, SpacetimeType, Table};
+use std::fmt;
+
+pub const DEFAULT_LOCALE: &str = "en";
+pub const SUPPORTED_LOCALES: &[&str] = &["en", "pt-BR"];
+
+/// Textos das mensagens por locale; `{chave}` é substituído pelo parâmetro de mesmo nome
+const STRINGS: &[(&str, &str, &str)] = &[
+    ("error.player_not_found", "en", "Player not found"),
+    ("error.player_not_found", "pt-BR", "Jogador não encontrado"),
+    ("error.admin_required", "en", "Admin permission required"),
+    ("error.admin_required", "pt-BR", "Permissão de administrador necessária"),
+    ("error.not_enough_currency", "en", "Not enough {currency}"),
+    ("error.not_enough_currency", "pt-BR", "{currency} insuficiente"),
+    ("error.on_cooldown", "en", "{category} '{action}' on cooldown ({remaining_ms} ms remaining)"),
+    ("error.on_cooldown", "pt-BR", "{category} '{action}' em recarga (faltam {remaining_ms} ms)"),
+    ("error.feature_disabled", "en", "Feature '{feature}' is currently disabled"),
+    ("error.feature_disabled", "pt-BR", "O recurso '{feature}' está desativado no momento"),
+    ("announce.world_boss.appeared", "en", "A world boss has appeared in {map}!"),
+    ("announce.world_boss.appeared", "pt-BR", "Um chefe mundial apareceu em {map}!"),
+    ("announce.world_boss.defeated", "en", "The world boss in {map} has been defeated! {heroes} heroes rewarded."),
+    ("announce.world_boss.defeated", "pt-BR", "O chefe mundial em {map} foi derrotado! {heroes} heróis recompensados."),
+    ("announce.invasion.started", "en", "{map} is under attack! Defend the town!"),
+    ("announce.invasion.started", "pt-BR", "{map} está sob ataque! Defendam a cidade!"),
+    ("announce.invasion.defended", "en", "{map} has been defended! {defenders} defenders rewarded."),
+    ("announce.invasion.defended", "pt-BR", "{map} foi defendida! {defenders} defensores recompensados."),
+    ("announce.invasion.fallen", "en", "{map} has fallen to the invaders. Town services are closed."),
+    ("announce.invasion.fallen", "pt-BR", "{map} caiu para os invasores. Os serviços da cidade estão fechados."),
+    ("announce.guild_war.declared", "en", "{attacker} and {defender} are now at war!"),
+    ("announce.guild_war.declared", "pt-BR", "{attacker} e {defender} estão em guerra!"),
+    ("announce.guild_war.ended", "en", "The war between {attacker} and {defender} ended ({reason})"),
+    ("announce.guild_war.ended", "pt-BR", "A guerra entre {attacker} e {defender} terminou ({reason})"),
+    ("announce.guild_war.won", "en", "The war between {attacker} and {defender} ended ({reason}): {winner} wins"),
+    ("announce.guild_war.won", "pt-BR", "A guerra entre {attacker} e {defender} terminou ({reason}): vitória de {winner}"),
+    ("announce.world_first", "en", "World first! {players} {description}"),
+    ("announce.world_first", "pt-BR", "Primeiro do mundo! {players} {description}"),
+    ("announce.seasonal_event.started", "en", "{event} has begun!"),
+    ("announce.seasonal_event.started", "pt-BR", "{event} começou!"),
+    ("announce.seasonal_event.ended", "en", "{event} has ended. See you next time!"),
+    ("announce.seasonal_event.ended", "pt-BR", "{event} terminou. Até a próxima!"),
+    ("announce.tournament.won", "en", "Player {player} won the tournament '{tournament}'!"),
+    ("announce.tournament.won", "pt-BR", "O jogador {player} venceu o torneio '{tournament}'!"),
+    ("announce.community_goal.completed", "en", "'{goal}' is complete! Thanks to {contributors} contributors."),
+    ("announce.community_goal.completed", "pt-BR", "'{goal}' foi concluída! Obrigado aos {contributors} contribuidores."),
+];
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct MessageParam {
+    pub key: String,
+    pub value: String,
+}
+
+/// Id de mensagem + parâmetros. Como erro de reducer vira
+/// `id{chave=valor,...}` (valores com `%`, `,`, `=` e `}` escapados)
+/// para o cliente renderizar no idioma do player.
+#[derive(Clone, Debug)]
+pub struct Message {
+    pub id: &'static str,
+    pub params: Vec<MessageParam>,
+}
+
+impl Message {
+    pub fn new(id: &'static str) -> Self {
+        Message { id, params: Vec::new() }
+    }
+
+    pub fn param(mut self, key: &str, value: impl ToString) -> Self {
+        self.params.push(MessageParam { key: key.to_string(), value: value.to_string() });
+        self
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('%', "%25").replace(',', "%2C").replace('=', "%3D").replace('}', "%7D")
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)?;
+        if !self.params.is_empty() {
+            let params: Vec<String> = self.params.iter().map(|p| format!("{}={}", p.key, escape(&p.value))).collect();
+            write!(f, "{{{}}}", params.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Message> for String {
+    fn from(message: Message) -> String {
+        message.to_string()
+    }
+}
+
+/// Textos traduzidos (recriados a partir de STRINGS no init e no connect
+/// quando o módulo traz textos novos)
+#[table(name = localized_string, public)]
+#[derive(Clone)]
+pub struct LocalizedString {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub message_id: String,
+    pub locale: String,
+    pub text: String,
+}
+
+/// Idioma escolhido por cada cliente
+#[table(name = player_locale, public)]
+#[derive(Clone)]
+pub struct PlayerLocale {
+    #[primary_key]
+    pub identity: Identity,
+    pub locale: String,
+}
+
+/// Id e parâmetros de um anúncio, para o cliente traduzir o texto em inglês
+#[table(name = announcement_localization, public)]
+#[derive(Clone)]
+pub struct AnnouncementLocalization {
+    #[primary_key]
+    pub announcement_id: u64,
+    pub message_id: String,
+    pub params: Vec<MessageParam>,
+}
+
+pub fn seed_localized_strings(ctx: &ReducerContext) {
+    for row in ctx.db.localized_string().iter() {
+        ctx.db.localized_string().id().delete(row.id);
+    }
+    for (message_id, locale, text) in STRINGS {
+        ctx.db.localized_string().insert(LocalizedString {
+            id: 0,
+            message_id: message_id.to_string(),
+            locale: locale.to_string(),
+            text: text.to_string(),
+        });
+    }
+}
+
+/// Ressemeia se o publish trouxe textos que a tabela ainda não tem
+pub fn ensure_localized_strings(ctx: &ReducerContext) {
+    if ctx.db.localized_string().count() != STRINGS.len() as u64 {
+        seed_localized_strings(ctx);
+    }
+}
+
+/// Texto embutido no módulo, para quando a tabela ainda não tem a mensagem
+/// (só é semeada no init; mensagens novas chegam com o publish)
+fn compiled_text(message_id: &str, locale: &str) -> Option<&'static str> {
+    let find = |locale: &str| STRINGS.iter().find(|(id, l, _)| *id == message_id && *l == locale).map(|(_, _, text)| *text);
+    find(locale).or_else(|| find(DEFAULT_LOCALE))
+}
+
+/// Texto da mensagem no idioma pedido (cai para o padrão, depois para o
+/// texto embutido e, por fim, para o id)
+pub fn render(ctx: &ReducerContext, message: &Message, locale: &str) -> String {
+    let candidates: Vec<LocalizedString> = ctx.db.localized_string().message_id().filter(message.id).collect();
+    let template = candidates.iter().find(|s| s.locale == locale)
+        .or_else(|| candidates.iter().find(|s| s.locale == DEFAULT_LOCALE))
+        .map(|s| s.text.clone())
+        .or_else(|| compiled_text(message.id, locale).map(str::to_string))
+        .unwrap_or_else(|| message.id.to_string());
+    message.params.iter().fold(template, |text, p| text.replace(&format!("{{{}}}", p.key), &p.value))
+}
+
+/// Anúncio com texto padrão (clientes antigos) e id + parâmetros para tradução
+pub fn announce_localized(ctx: &ReducerContext, category: &str, message: Message) {
+    let text = render(ctx, &message, DEFAULT_LOCALE);
+    let announcement_id = announce(ctx, category, text);
+    ctx.db.announcement_localization().insert(AnnouncementLocalization {
+        announcement_id,
+        message_id: message.id.to_string(),
+        params: message.params,
+    });
+}
+
+#[reducer]
+pub fn set_locale(ctx: &ReducerContext, locale: String) -> Result<(), String> {
+    if !SUPPORTED_LOCALES.contains(&locale.as_str()) {
+        return Err(format!("Unsupported locale '{}'", locale));
+    }
+    let row = PlayerLocale { identity: ctx.sender, locale };
+    if ctx.db.player_locale().identity().find(ctx.sender).is_some() {
+        ctx.db.player_locale().identity().update(row);
+    } else {
+        ctx.db.player_locale().insert(row);
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use spacetimedb::{table, ReducerContext, SpacetimeType, Table};
+
+/// Tabela de loot do pergaminho de receita (drop raro de inimigos)
+pub const LOOT_TABLE_RECIPE_SCROLL: &str = "recipe_scroll";
+
+/// Chance extra por tentativa sem drop raro, e o teto da chance final
+const PITY_STEP: f64 = 0.01;
+const PITY_MAX_CHANCE: f64 = 0.5;
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct PityCounter {
+    pub loot_table: String,
+    pub misses: u32,
+}
+
+/// Azar acumulado do player por tabela de loot: uma linha por player, só com
+/// as tabelas em que ele está sem drop raro
+#[table(name = loot_pity)]
+#[derive(Clone)]
+pub struct LootPity {
+    #[primary_key]
+    pub player_id: u32,
+    pub counters: Vec<PityCounter>,
+}
+
+fn misses(ctx: &ReducerContext, player_id: u32, loot_table: &str) -> u32 {
+    ctx.db.loot_pity().player_id().find(player_id)
+        .and_then(|p| p.counters.into_iter().find(|c| c.loot_table == loot_table))
+        .map(|c| c.misses)
+        .unwrap_or(0)
+}
+
+/// Chance do drop raro já com o bônus de azar acumulado
+pub fn pity_chance(ctx: &ReducerContext, player_id: u32, loot_table: &str, base_chance: f64) -> f64 {
+    (base_chance + misses(ctx, player_id, loot_table) as f64 * PITY_STEP).min(PITY_MAX_CHANCE.max(base_chance))
+}
+
+/// Registra o resultado da rolagem: falha soma azar, drop raro zera a tabela
+pub fn record_roll(ctx: &ReducerContext, player_id: u32, loot_table: &str, dropped: bool) {
+    let existing = ctx.db.loot_pity().player_id().find(player_id);
+    let mut pity = existing.clone().unwrap_or(LootPity { player_id, counters: Vec::new() });
+
+    if dropped {
+        pity.counters.retain(|c| c.loot_table != loot_table);
+    } else {
+        match pity.counters.iter_mut().find(|c| c.loot_table == loot_table) {
+            Some(counter) => counter.misses += 1,
+            None => pity.counters.push(PityCounter { loot_table: loot_table.to_string(), misses: 1 }),
+        }
+    }
+
+    match (existing.is_some(), pity.counters.is_empty()) {
+        (true, true) => {
+            ctx.db.loot_pity().player_id().delete(player_id);
+        }
+        (true, false) => {
+            ctx.db.loot_pity().player_id().update(pity);
+        }
+        (false, false) => {
+            ctx.db.loot_pity().insert(pity);
+        }
+        (false, true) => {}
+    }
+}
@@ -0,0 +1,88 @@
+use crate::crafting::ItemStack;
+use crate::currency::{add_currency, CURRENCY_GOLD};
+use crate::party::sender_player;
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+pub const SYSTEM_SENDER: &str = "System";
+
+/// Correspondência de um player, com anexos (itens e ouro) a resgatar
+#[table(name = mail, public)]
+#[derive(Clone)]
+pub struct Mail {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub recipient_id: u32,
+    pub sender_name: String,
+    pub subject: String,
+    pub body: String,
+    pub attachments: Vec<ItemStack>,
+    pub gold: u64,
+    /// Origem do ouro anexado, repassada à economia no resgate
+    pub source: String,
+    pub sent_at: Timestamp,
+    pub claimed: bool,
+}
+
+/// Envia uma mensagem do sistema (recompensas, avisos). `source` é a origem
+/// do ouro anexado, a mesma que `add_currency` registraria na entrega direta.
+pub fn send_system_mail(
+    ctx: &ReducerContext,
+    recipient_id: u32,
+    subject: &str,
+    body: String,
+    attachments: Vec<ItemStack>,
+    gold: u64,
+    source: &str,
+) {
+    ctx.db.mail().insert(Mail {
+        id: 0,
+        recipient_id,
+        sender_name: SYSTEM_SENDER.to_string(),
+        subject: subject.to_string(),
+        body,
+        claimed: attachments.is_empty() && gold == 0,
+        attachments,
+        gold,
+        source: source.to_string(),
+        sent_at: ctx.timestamp,
+    });
+    log::info!("✉️ Mail '{}' sent to player {}", subject, recipient_id);
+}
+
+/// Resgata os anexos de uma mensagem
+#[reducer]
+pub fn claim_mail(ctx: &ReducerContext, mail_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    let mut mail = ctx.db.mail().id().find(mail_id)
+        .filter(|m| m.recipient_id == player.id)
+        .ok_or("Mail not found")?;
+    if mail.claimed {
+        return Err("Attachments already claimed".to_string());
+    }
+
+    for stack in &mail.attachments {
+        crate::inventory::add_item_to_inventory(ctx, player.id, stack.item_id.clone(), stack.quantity)
+            .map_err(|e| e.to_string())?;
+    }
+    add_currency(ctx, player.id, CURRENCY_GOLD, mail.gold, &mail.source);
+    mail.claimed = true;
+    ctx.db.mail().id().update(mail);
+    Ok(())
+}
+
+/// Apaga uma mensagem (anexos não resgatados são perdidos)
+#[reducer]
+pub fn delete_mail(ctx: &ReducerContext, mail_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let mail = ctx.db.mail().id().find(mail_id)
+        .filter(|m| m.recipient_id == player.id)
+        .ok_or("Mail not found")?;
+    if !mail.claimed {
+        return Err("Claim the attachments before deleting".to_string());
+    }
+    ctx.db.mail().id().delete(mail_id);
+    Ok(())
+}
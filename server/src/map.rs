@@ -1,7 +1,9 @@
-use crate::player;
+use crate::cooldown::{CATEGORY_TRANSITION, TRANSITION_COOLDOWN_MS};
+use crate::{player, Player};
 use include_dir::{include_dir, Dir};
-use spacetimedb::{reducer, table, ReducerContext, Table};
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
@@ -11,6 +13,29 @@ pub const TILE_SIZE: f32 = TILE_SIZE_PX as f32;
 
 const SPAWN_TILE: u32 = 1;
 
+/// Tiles que bloqueiam movimento (paredes, água, árvores, móveis, estruturas)
+pub const BLOCKED_TILES: &[u32] = &[2, 5, 8, 10, 11, 12, 13, 14, 15, 16, 17, 18, 60, 61, 63, 65, 67];
+const TRANSITION_COOLDOWN_KEY: &str = "map_transition";
+
+pub const TILE_ROAD: u32 = 6;
+pub const TILE_MUD: u32 = 7;
+/// Custo de movimento por tile (1.0 = normal; maior = mais lento)
+const TILE_MOVEMENT_COSTS: &[(u32, f32)] = &[(TILE_ROAD, 0.85), (TILE_MUD, 1.6)];
+
+/// Beiradas de mão única: só se entra andando no sentido da queda (dx, dy)
+pub const TILE_LEDGE_SOUTH: u32 = 24;
+pub const TILE_LEDGE_NORTH: u32 = 25;
+pub const TILE_LEDGE_EAST: u32 = 26;
+pub const TILE_LEDGE_WEST: u32 = 27;
+const LEDGE_TILES: &[(u32, (i32, i32))] = &[
+    (TILE_LEDGE_SOUTH, (0, 1)),
+    (TILE_LEDGE_NORTH, (0, -1)),
+    (TILE_LEDGE_EAST, (1, 0)),
+    (TILE_LEDGE_WEST, (-1, 0)),
+];
+/// Obstáculos baixos: bloqueiam a caminhada, mas não um pulo
+const LOW_OBSTACLE_TILES: &[u32] = &[crate::structure::TILE_FENCE];
+
 static MAPS_DIR: Dir = include_dir!("src/maps");
 
 #[table(name = map_template, public)]
@@ -22,12 +47,73 @@ pub struct MapTemplate {
     pub tile_data: Vec<u32>,
     pub spawn_x: f32,
     pub spawn_y: f32,
+    // Metadados de ambiente (arquivo <mapa>.meta ao lado do CSV)
+    pub music_track_id: String,
+    pub ambient_color: u32, // RGBA
+    pub light_level: f32,   // 0.0 (escuro) .. 1.0 (dia claro)
+    pub is_indoor: bool,
+    pub mounts_allowed: bool,
+    pub is_town: bool,
+}
+
+/// Valores lidos do arquivo `.meta` de um mapa
+pub struct MapEnvironment {
+    pub music_track_id: String,
+    pub ambient_color: u32,
+    pub light_level: f32,
+    pub is_indoor: bool,
+    pub mounts_allowed: bool,
+    pub is_town: bool,
+}
+
+impl Default for MapEnvironment {
+    fn default() -> Self {
+        MapEnvironment {
+            music_track_id: String::new(),
+            ambient_color: 0xFFFFFFFF,
+            light_level: 1.0,
+            is_indoor: false,
+            mounts_allowed: true,
+            is_town: false,
+        }
+    }
+}
+
+/// Lê linhas `chave = valor` (`#` comenta). Chaves desconhecidas ou valores
+/// inválidos são ignorados com aviso, mantendo o padrão.
+pub fn parse_map_environment(template_name: &str, content: &str) -> MapEnvironment {
+    let mut env = MapEnvironment::default();
+
+    for line in content.lines().map(|l| l.trim()) {
+        if line.is_empty() || line.starts_with('#') { continue; }
+        let Some((key, value)) = line.split_once('=') else {
+            log::warn!("⚠️ {}.meta: linha ignorada '{}'", template_name, line);
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim().trim_matches('"'));
+
+        let ok = match key {
+            "music" => { env.music_track_id = value.to_string(); true },
+            "ambient_color" => u32::from_str_radix(value.trim_start_matches('#'), 16).map(|c| env.ambient_color = c).is_ok(),
+            "light_level" => f32::from_str(value).map(|l| env.light_level = l.clamp(0.0, 1.0)).is_ok(),
+            "indoor" => bool::from_str(value).map(|b| env.is_indoor = b).is_ok(),
+            "mounts_allowed" => bool::from_str(value).map(|b| env.mounts_allowed = b).is_ok(),
+            "town" => bool::from_str(value).map(|b| env.is_town = b).is_ok(),
+            _ => false,
+        };
+        if !ok {
+            log::warn!("⚠️ {}.meta: '{}' inválido ou desconhecido", template_name, line);
+        }
+    }
+
+    env
 }
 
 #[table(name = world_mutation, public)]
 pub struct WorldMutation {
     #[primary_key]
     pub id: u64,
+    #[index(btree)]
     pub instance_id: u32,
     pub x: u32,
     pub y: u32,
@@ -46,6 +132,14 @@ pub struct MapInstance {
     pub template_name: String,
 }
 
+/// Desde quando uma instância está fria (sem players); usado para pausar timers
+#[table(name = map_cold_since)]
+pub struct MapColdSince {
+    #[primary_key]
+    pub key_id: String,
+    pub since: Timestamp,
+}
+
 #[table(name = map_transition, public)]
 #[derive(Clone)]
 pub struct MapTransition {
@@ -109,15 +203,46 @@ pub fn init(ctx: &ReducerContext) {
             continue;
         }
 
+        let meta_path = file.path().with_extension("meta");
+        let env = MAPS_DIR.get_file(&meta_path)
+            .and_then(|f| f.contents_utf8())
+            .map(|content| parse_map_environment(&template_name, content))
+            .unwrap_or_default();
+
         ctx.db.map_template().insert(MapTemplate {
             name: template_name.clone(),
             width, height, tile_data, spawn_x, spawn_y,
+            music_track_id: env.music_track_id,
+            ambient_color: env.ambient_color,
+            light_level: env.light_level,
+            is_indoor: env.is_indoor,
+            mounts_allowed: env.mounts_allowed,
+            is_town: env.is_town,
         });
 
         log::info!("✅ Mapa carregado: '{}' | Spawn: ({}, {})", template_name, spawn_x, spawn_y);
     }
 
     init_map_transitions(ctx);
+    crate::teleporter::rebuild_teleporter_pads(ctx);
+    crate::minimap::rebuild_minimaps(ctx);
+
+    crate::admin::bootstrap_admin(ctx);
+    crate::feature_flag::seed_feature_flags(ctx);
+    crate::name_policy::seed_reserved_names(ctx);
+    crate::localization::seed_localized_strings(ctx);
+    crate::crafting::seed_recipes(ctx);
+    crate::tick::ensure_world_tick(ctx);
+    crate::sanitation::ensure_sanitation_schedule(ctx);
+    crate::aggregation::ensure_aggregation_schedule(ctx);
+    crate::heatmap::ensure_heatmap_schedule(ctx);
+    crate::world_boss::ensure_world_boss_schedule(ctx);
+    crate::invasion::ensure_invasion_schedule(ctx);
+    crate::perishable::ensure_perish_schedule(ctx);
+    crate::rating::ensure_rating_decay_schedule(ctx);
+    crate::seasonal_event::ensure_seasonal_event_schedule(ctx);
+    crate::npc_bark::ensure_npc_bark_schedule(ctx);
+    crate::work_order::adopt_legacy_escrow(ctx);
 }
 
 #[reducer]
@@ -163,6 +288,8 @@ pub fn replace_all_templates(ctx: &ReducerContext, new_templates: Vec<MapTemplat
     for t in new_templates {
         ctx.db.map_template().insert(t);
     }
+    crate::teleporter::rebuild_teleporter_pads(ctx);
+    crate::minimap::rebuild_minimaps(ctx);
     log::info!("REDEPLOY: {} templates carregados do zero.", ctx.db.map_template().count());
     Ok(())
 }
@@ -197,8 +324,269 @@ pub fn get_or_create_map_instance(ctx: &ReducerContext, key_id: &str) -> Option<
     }
 }
 
+/// Cria uma instância separada de um template (ex: dungeon de party).
+/// A chave é usada como `current_map_id` dos players dentro dela.
+pub fn create_map_instance(ctx: &ReducerContext, key_id: &str, template_name: &str) -> Result<MapInstance, String> {
+    if ctx.db.map_template().name().find(template_name.to_string()).is_none() {
+        return Err(format!("Map template '{}' not found", template_name));
+    }
+    if ctx.db.map_instance().key_id().find(key_id.to_string()).is_some() {
+        return Err(format!("Map instance '{}' already exists", key_id));
+    }
+
+    let instance = ctx.db.map_instance().insert(MapInstance {
+        id: generate_map_instance_id(key_id),
+        key_id: key_id.to_string(),
+        state: "Hot".to_string(),
+        player_count: 0,
+        template_name: template_name.to_string(),
+    });
+    log::info!("✨ Instância '{}' criada a partir de '{}'.", key_id, template_name);
+    Ok(instance)
+}
+
+/// Remove a instância e as mutações de tiles ligadas a ela
+pub fn destroy_map_instance(ctx: &ReducerContext, key_id: &str) {
+    if let Some(instance) = ctx.db.map_instance().key_id().find(key_id.to_string()) {
+        let mutations: Vec<u64> = ctx.db.world_mutation().instance_id().filter(instance.id).map(|m| m.id).collect();
+        for id in mutations {
+            ctx.db.world_mutation().id().delete(id);
+        }
+        ctx.db.map_instance().id().delete(instance.id);
+        log::info!("🗑️ Instância '{}' removida.", key_id);
+    }
+}
+
+fn world_mutation_id(instance_id: u32, template: &MapTemplate, tile_x: u32, tile_y: u32) -> u64 {
+    ((instance_id as u64) << 32) | (tile_y * template.width + tile_x) as u64
+}
+
+/// Sobrescreve um tile da instância do mapa (uma mutação por tile)
+pub fn set_tile_override(ctx: &ReducerContext, map_id: &str, tile_x: u32, tile_y: u32, tile_id: u32) -> Result<(), String> {
+    let instance = get_or_create_map_instance(ctx, map_id).ok_or_else(|| format!("Map '{}' not found", map_id))?;
+    let template = template_for_map(ctx, map_id).ok_or_else(|| format!("Map '{}' not found", map_id))?;
+    if tile_x >= template.width || tile_y >= template.height {
+        return Err("Tile out of bounds".to_string());
+    }
+
+    let mutation = WorldMutation {
+        id: world_mutation_id(instance.id, &template, tile_x, tile_y),
+        instance_id: instance.id,
+        x: tile_x,
+        y: tile_y,
+        new_tile_id: tile_id,
+    };
+    if ctx.db.world_mutation().id().find(mutation.id).is_some() {
+        ctx.db.world_mutation().id().update(mutation);
+    } else {
+        ctx.db.world_mutation().insert(mutation);
+    }
+    Ok(())
+}
+
+/// Remove a mutação do tile, voltando ao tile do template
+pub fn clear_tile_override(ctx: &ReducerContext, map_id: &str, tile_x: u32, tile_y: u32) {
+    let Some(instance) = ctx.db.map_instance().key_id().find(map_id.to_string()) else { return };
+    let Some(template) = template_for_map(ctx, map_id) else { return };
+    ctx.db.world_mutation().id().delete(world_mutation_id(instance.id, &template, tile_x, tile_y));
+}
+
+/// Template de um mapa: instâncias separadas apontam para o template de origem
+pub fn template_for_map(ctx: &ReducerContext, map_id: &str) -> Option<MapTemplate> {
+    let template_name = ctx.db.map_instance().key_id().find(map_id.to_string())
+        .map(|i| i.template_name)
+        .unwrap_or_else(|| map_id.to_string());
+    ctx.db.map_template().name().find(template_name)
+}
+
+pub fn is_blocking_tile(tile_id: u32) -> bool {
+    BLOCKED_TILES.contains(&tile_id)
+}
+
+/// Tile efetivo em (tile_x, tile_y): mutações do mundo sobrescrevem o template
+pub fn tile_at(ctx: &ReducerContext, template: &MapTemplate, instance_id: Option<u32>, tile_x: u32, tile_y: u32) -> Option<u32> {
+    if tile_x >= template.width || tile_y >= template.height {
+        return None;
+    }
+
+    if let Some(instance_id) = instance_id {
+        if let Some(mutation) = ctx.db.world_mutation().instance_id().filter(instance_id)
+            .find(|m| m.x == tile_x && m.y == tile_y)
+        {
+            return Some(mutation.new_tile_id);
+        }
+    }
+
+    template.tile_data.get((tile_y * template.width + tile_x) as usize).copied()
+}
+
+pub fn tile_movement_cost(tile_id: u32) -> f32 {
+    TILE_MOVEMENT_COSTS.iter().find(|(t, _)| *t == tile_id).map(|(_, c)| *c).unwrap_or(1.0)
+}
+
+/// Multiplicador de velocidade do terreno na posição (inverso do custo do tile)
+pub fn terrain_speed_multiplier(ctx: &ReducerContext, map_id: &str, x: f32, y: f32) -> f32 {
+    if x < 0.0 || y < 0.0 {
+        return 1.0;
+    }
+    let Some(template) = template_for_map(ctx, map_id) else { return 1.0 };
+    let instance_id = ctx.db.map_instance().key_id().find(map_id.to_string()).map(|i| i.id);
+    tile_at(ctx, &template, instance_id, (x / TILE_SIZE) as u32, (y / TILE_SIZE) as u32)
+        .map(|tile_id| 1.0 / tile_movement_cost(tile_id))
+        .unwrap_or(1.0)
+}
+
+pub fn ledge_direction(tile_id: u32) -> Option<(i32, i32)> {
+    LEDGE_TILES.iter().find(|(t, _)| *t == tile_id).map(|(_, d)| *d)
+}
+
+/// Passagem de um tile para o vizinho no caminho (tiles em coordenadas de grid)
+fn tile_step_allowed(from: (i32, i32), from_id: u32, to: (i32, i32), to_id: u32, airborne: bool) -> bool {
+    if is_blocking_tile(to_id) && !(airborne && LOW_OBSTACLE_TILES.contains(&to_id)) {
+        return false;
+    }
+    match ledge_direction(to_id) {
+        // Andar ao longo da mesma beirada é permitido; subir por ela não
+        Some((ldx, ldy)) => from_id == to_id || (to.0 - from.0) * ldx + (to.1 - from.1) * ldy > 0,
+        None => true,
+    }
+}
+
+/// Valida o deslocamento (from -> to) tile a tile: colisão, beiradas de mão
+/// única e, no ar, obstáculos baixos ignorados
+pub fn is_passable_step(
+    ctx: &ReducerContext,
+    template: &MapTemplate,
+    instance_id: Option<u32>,
+    from: (f32, f32),
+    to: (f32, f32),
+    airborne: bool,
+) -> bool {
+    if to.0 < 0.0 || to.1 < 0.0 {
+        return false;
+    }
+    let tile_of = |x: f32, y: f32| ((x / TILE_SIZE).floor() as i32, (y / TILE_SIZE).floor() as i32);
+    let id_of = |(tx, ty): (i32, i32)| {
+        if tx < 0 || ty < 0 { None } else { tile_at(ctx, template, instance_id, tx as u32, ty as u32) }
+    };
+
+    let distance = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+    let samples = ((distance / (TILE_SIZE / 2.0)).ceil() as u32).max(1);
+    let mut prev = tile_of(from.0, from.1);
+    let mut prev_id = id_of(prev).unwrap_or(0);
+    for i in 1..=samples {
+        let t = i as f32 / samples as f32;
+        let tile = tile_of(from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+        if tile == prev {
+            continue;
+        }
+        let Some(tile_id) = id_of(tile) else { return false };
+        if !tile_step_allowed(prev, prev_id, tile, tile_id, airborne) {
+            return false;
+        }
+        prev = tile;
+        prev_id = tile_id;
+    }
+    true
+}
+
+/// Linha de visão entre dois pontos do mapa: tiles bloqueantes cortam a visão,
+/// obstáculos baixos (cercas) não
+pub fn has_line_of_sight(ctx: &ReducerContext, map_id: &str, from: (f32, f32), to: (f32, f32)) -> bool {
+    let Some(template) = template_for_map(ctx, map_id) else {
+        return false;
+    };
+    let instance_id = ctx.db.map_instance().key_id().find(map_id.to_string()).map(|i| i.id);
+    let distance = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+    let samples = ((distance / (TILE_SIZE / 2.0)).ceil() as u32).max(1);
+    (1..samples).all(|i| {
+        let t = i as f32 / samples as f32;
+        let (x, y) = (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+        if x < 0.0 || y < 0.0 {
+            return false;
+        }
+        tile_at(ctx, &template, instance_id, (x / TILE_SIZE) as u32, (y / TILE_SIZE) as u32)
+            .is_some_and(|id| !is_blocking_tile(id) || LOW_OBSTACLE_TILES.contains(&id))
+    })
+}
+
+/// Verifica a camada de colisão na posição em pixels (fora do mapa = bloqueado)
+pub fn is_walkable_position(ctx: &ReducerContext, template: &MapTemplate, instance_id: Option<u32>, x: f32, y: f32) -> bool {
+    if x < 0.0 || y < 0.0 {
+        return false;
+    }
+    let tile_x = (x / TILE_SIZE) as u32;
+    let tile_y = (y / TILE_SIZE) as u32;
+
+    match tile_at(ctx, template, instance_id, tile_x, tile_y) {
+        Some(tile_id) => !is_blocking_tile(tile_id),
+        None => false,
+    }
+}
+
+/// Sorteia o centro de um tile caminhável dentro do círculo (center, radius).
+/// Retorna `None` se nenhuma tentativa achar um tile livre.
+pub fn random_walkable_point(
+    ctx: &ReducerContext,
+    map_id: &str,
+    center_x: f32,
+    center_y: f32,
+    radius: f32,
+) -> Option<(f32, f32)> {
+    use spacetimedb::rand::Rng;
+    const MAX_ATTEMPTS: u32 = 12;
+
+    let template = template_for_map(ctx, map_id)?;
+    let instance_id = ctx.db.map_instance().key_id().find(map_id.to_string()).map(|i| i.id);
+
+    let mut rng = ctx.rng();
+    for _ in 0..MAX_ATTEMPTS {
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let distance = radius * rng.gen_range(0.0f32..1.0).sqrt();
+        let x = center_x + angle.cos() * distance;
+        let y = center_y + angle.sin() * distance;
+
+        if !is_walkable_position(ctx, &template, instance_id, x, y) {
+            continue;
+        }
+
+        // Alinha ao centro do tile sorteado
+        let tile_x = (x / TILE_SIZE).floor();
+        let tile_y = (y / TILE_SIZE).floor();
+        return Some((tile_x * TILE_SIZE + TILE_SIZE / 2.0, tile_y * TILE_SIZE + TILE_SIZE / 2.0));
+    }
+
+    None
+}
+
+/// Distância mínima entre um inimigo recém-criado e o player mais próximo
+pub const SPAWN_CLEARANCE: f32 = TILE_SIZE * 6.0;
+
+/// Distância até o player mais próximo no mapa (índice por `current_map_id`)
+pub fn nearest_player_distance(ctx: &ReducerContext, map_id: &str, x: f32, y: f32) -> Option<f32> {
+    ctx.db.player().current_map_id().filter(map_id)
+        .map(|p| ((p.position_x - x).powi(2) + (p.position_y - y).powi(2)).sqrt())
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+/// Ponto caminhável sem nenhum player dentro de `SPAWN_CLEARANCE`.
+/// `None` significa que o spawn deve esperar a área ficar livre.
+pub fn random_spawn_point(
+    ctx: &ReducerContext,
+    map_id: &str,
+    center_x: f32,
+    center_y: f32,
+    radius: f32,
+) -> Option<(f32, f32)> {
+    const MAX_ATTEMPTS: u32 = 4;
+
+    (0..MAX_ATTEMPTS)
+        .filter_map(|_| random_walkable_point(ctx, map_id, center_x, center_y, radius))
+        .find(|&(x, y)| nearest_player_distance(ctx, map_id, x, y).is_none_or(|d| d >= SPAWN_CLEARANCE))
+}
+
 pub fn get_map_bounds_from_db(ctx: &ReducerContext, map_id: &str) -> (f32, f32, f32, f32) {
-    if let Some(template) = ctx.db.map_template().name().find(map_id.to_string()) {
+    if let Some(template) = template_for_map(ctx, map_id) {
         let w = (template.width * 8) as f32;
         let h = (template.height * 8) as f32;
         return (0.0, w, 0.0, h);
@@ -211,7 +599,7 @@ pub fn get_map_bounds_from_db(ctx: &ReducerContext, map_id: &str) -> (f32, f32,
 #[reducer]
 pub fn spawn_player_at_map(ctx: &ReducerContext, player_id: u32, map_id: String) -> Result<(), String> {
     let identity = ctx.sender;
-    let player = ctx.db.player().id().find(&player_id).ok_or("Player not found")?;
+    let player = ctx.db.player().id().find(player_id).ok_or("Player not found")?;
 
     if player.identity != identity { return Err("Unauthorized".to_string()); }
 
@@ -233,9 +621,12 @@ pub fn spawn_player_at_map(ctx: &ReducerContext, player_id: u32, map_id: String)
     updated_player.position_y = spawn_y;
     updated_player.velocity_x = 0.0;
     updated_player.velocity_y = 0.0;
+    crate::movement::refresh_player_motion(&mut updated_player, ctx.timestamp);
 
     ctx.db.player().id().update(updated_player);
-    update_map_state(ctx, &final_map_id)?;
+    crate::dungeon::on_player_changed_map(ctx, player_id, &player.current_map_id, &final_map_id);
+    move_map_population(ctx, Some(&player.current_map_id), Some(&final_map_id));
+    crate::exploration::discover_map(ctx, player_id, &final_map_id);
 
     Ok(())
 }
@@ -247,12 +638,12 @@ fn generate_map_instance_id(key_id: &str) -> u32 {
 }
 
 fn count_players_in_map(ctx: &ReducerContext, key_id: &str) -> u32 {
-    ctx.db.player().iter().filter(|p| p.current_map_id == key_id).count() as u32
+    ctx.db.player().current_map_id().filter(key_id).count() as u32
 }
 
 pub fn get_spawn_point(ctx: &ReducerContext, map_id: &str) -> (f32, f32) {
     // 1. Tenta o mapa solicitado
-    if let Some(template) = ctx.db.map_template().name().find(map_id.to_string()) {
+    if let Some(template) = template_for_map(ctx, map_id) {
         return (template.spawn_x, template.spawn_y);
     }
 
@@ -267,19 +658,125 @@ pub fn get_spawn_point(ctx: &ReducerContext, map_id: &str) -> (f32, f32) {
     panic!("❌ ERRO CRÍTICO: Nem o mapa '{}' nem o STARTING_MAP '{}' existem!", map_id, STARTING_MAP);
 }
 
+/// Recontagem completa de uma instância; os caminhos quentes usam
+/// `move_map_population` e a reconciliação periódica corrige desvios
 pub fn update_map_state(ctx: &ReducerContext, key_id: &str) -> Result<(), String> {
     // Só atualiza se a instância existir (is_some)
-    if let Some(mut map_instance) = get_or_create_map_instance(ctx, key_id) {
+    if let Some(map_instance) = get_or_create_map_instance(ctx, key_id) {
         let player_count = count_players_in_map(ctx, key_id);
-        map_instance.player_count = player_count;
-        map_instance.state = if player_count > 0 { "Hot".to_string() } else { "Cold".to_string() };
-        ctx.db.map_instance().id().update(map_instance);
+        set_map_population(ctx, map_instance, player_count);
     }
     Ok(())
 }
 
+fn set_map_population(ctx: &ReducerContext, mut map_instance: MapInstance, player_count: u32) {
+    let key_id = map_instance.key_id.clone();
+    map_instance.player_count = player_count;
+    map_instance.state = if player_count > 0 { "Hot".to_string() } else { "Cold".to_string() };
+    ctx.db.map_instance().id().update(map_instance);
+    track_heat(ctx, &key_id, player_count > 0);
+}
+
+fn adjust_map_population(ctx: &ReducerContext, key_id: &str, delta: i32) {
+    if let Some(map_instance) = get_or_create_map_instance(ctx, key_id) {
+        let player_count = map_instance.player_count.saturating_add_signed(delta);
+        set_map_population(ctx, map_instance, player_count);
+    }
+}
+
+/// Atualiza as contagens incrementalmente quando um player entra (`from` vazio),
+/// sai (`to` vazio) ou troca de mapa
+pub fn move_map_population(ctx: &ReducerContext, from: Option<&str>, to: Option<&str>) {
+    if from == to {
+        return;
+    }
+    if let Some(from) = from {
+        adjust_map_population(ctx, from, -1);
+    }
+    if let Some(to) = to {
+        adjust_map_population(ctx, to, 1);
+    }
+}
+
+/// Reconciliação de baixa frequência: uma única varredura dos players
+/// corrige contagens que tenham desviado
+pub fn reconcile_map_populations(ctx: &ReducerContext) {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for player in ctx.db.player().iter() {
+        *counts.entry(player.current_map_id).or_default() += 1;
+    }
+
+    for instance in ctx.db.map_instance().iter() {
+        let actual = counts.get(&instance.key_id).copied().unwrap_or(0);
+        if instance.player_count != actual {
+            log::warn!("🧮 Population drift in '{}': {} tracked, {} actual", instance.key_id, instance.player_count, actual);
+            set_map_population(ctx, instance, actual);
+        }
+    }
+}
+
+/// Só instâncias "Hot" rodam spawners e IA; sem instância o mapa conta como frio
+pub fn is_map_hot(ctx: &ReducerContext, key_id: &str) -> bool {
+    ctx.db.map_instance().key_id().find(key_id.to_string()).is_some_and(|i| i.state == "Hot")
+}
+
+/// Chaves das instâncias quentes (para os processadores do tick)
+pub fn hot_map_ids(ctx: &ReducerContext) -> Vec<String> {
+    ctx.db.map_instance().iter().filter(|i| i.state == "Hot").map(|i| i.key_id).collect()
+}
+
+/// Registra quando a instância esfria e, ao reaquecer, avisa os sistemas
+/// pausados com o tempo que ela ficou parada
+fn track_heat(ctx: &ReducerContext, key_id: &str, hot: bool) {
+    let cold_since = ctx.db.map_cold_since().key_id().find(key_id.to_string());
+    match (hot, cold_since) {
+        (false, None) => {
+            ctx.db.map_cold_since().insert(MapColdSince { key_id: key_id.to_string(), since: ctx.timestamp });
+        }
+        (true, Some(cold)) => {
+            ctx.db.map_cold_since().key_id().delete(key_id.to_string());
+            let paused = ctx.timestamp.duration_since(cold.since).unwrap_or_default();
+            crate::seasonal_event::on_map_warmed(ctx, key_id, paused);
+            crate::enemy_ai::on_map_warmed(ctx, key_id);
+            log::info!("🔥 Instância '{}' reaquecida após {} s", key_id, paused.as_secs());
+        }
+        _ => {}
+    }
+}
+
+/// Move o player para outro mapa (ou posição), zerando a velocidade e
+/// atualizando a contagem dos dois mapas. Retorna `false` se o destino não existe.
+pub fn relocate_player(ctx: &ReducerContext, player: &Player, dest_map_id: &str, dest_x: f32, dest_y: f32) -> Result<bool, String> {
+    if get_or_create_map_instance(ctx, dest_map_id).is_none() {
+        return Ok(false);
+    }
+
+    let old_map = player.current_map_id.clone();
+    let mut updated_player = player.clone();
+
+    updated_player.current_map_id = dest_map_id.to_string();
+    updated_player.position_x = dest_x;
+    updated_player.position_y = dest_y;
+    updated_player.velocity_x = 0.0;
+    updated_player.velocity_y = 0.0;
+    crate::movement::refresh_player_motion(&mut updated_player, ctx.timestamp);
+
+    ctx.db.player().id().update(updated_player);
+    crate::cooldown::start_cooldown(
+        ctx, player.identity, CATEGORY_TRANSITION, TRANSITION_COOLDOWN_KEY, TRANSITION_COOLDOWN_MS,
+    );
+
+    crate::dungeon::on_player_changed_map(ctx, player.id, &old_map, dest_map_id);
+
+    if old_map != dest_map_id {
+        move_map_population(ctx, Some(&old_map), Some(dest_map_id));
+        crate::exploration::discover_map(ctx, player.id, dest_map_id);
+    }
+    Ok(true)
+}
+
 pub fn check_map_transition(ctx: &ReducerContext, player_id: u32) -> Result<(), String> {
-    let player = ctx.db.player().id().find(&player_id).ok_or("Player not found")?;
+    let player = ctx.db.player().id().find(player_id).ok_or("Player not found")?;
 
     let transitions: Vec<MapTransition> = ctx.db.map_transition().iter()
         .filter(|t| t.map_id == player.current_map_id)
@@ -289,21 +786,19 @@ pub fn check_map_transition(ctx: &ReducerContext, player_id: u32) -> Result<(),
         if player.position_x >= t.x && player.position_x <= (t.x + t.width) &&
             player.position_y >= t.y && player.position_y <= (t.y + t.height)
         {
-            // Valida destino antes de mover
-            if get_or_create_map_instance(ctx, &t.dest_map_id).is_some() {
-                let old_map = player.current_map_id.clone();
-                let mut updated_player = player.clone();
-
-                updated_player.current_map_id = t.dest_map_id.clone();
-                updated_player.position_x = t.dest_x;
-                updated_player.position_y = t.dest_y;
-                updated_player.velocity_x = 0.0;
-                updated_player.velocity_y = 0.0;
+            // Passagens trancadas pela história do player
+            if !crate::story_flag::transition_allowed(ctx, player.id, t.id) {
+                continue;
+            }
 
-                ctx.db.player().id().update(updated_player);
+            // Evita ping-pong entre portas logo após uma transição
+            if crate::cooldown::is_on_cooldown(ctx, player.identity, CATEGORY_TRANSITION, TRANSITION_COOLDOWN_KEY) {
+                break;
+            }
 
-                update_map_state(ctx, &old_map)?;
-                update_map_state(ctx, &t.dest_map_id)?;
+            // Valida destino antes de mover
+            if relocate_player(ctx, &player, &t.dest_map_id, t.dest_x, t.dest_y)? {
+                crate::bug_report::record_action(ctx, player.id, format!("transition {} -> {}", t.map_id, t.dest_map_id), false);
                 break;
             }
         }
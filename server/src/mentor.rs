@@ -0,0 +1,162 @@
+use crate::currency::{add_currency, CURRENCY_MENTOR_TOKEN};
+use crate::party::{party_of, sender_player};
+use crate::progression::get_progress;
+use crate::{account_age, player};
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+use std::time::Duration;
+
+pub const MENTOR_MIN_LEVEL: u32 = 30;
+pub const MENTOR_MIN_ACCOUNT_AGE_SECS: u64 = 7 * 24 * 60 * 60;
+/// Nível máximo para começar uma mentoria
+pub const APPRENTICE_MAX_START_LEVEL: u32 = 10;
+/// Ao alcançar este nível o aprendiz se forma e a mentoria termina
+pub const APPRENTICE_GRADUATION_LEVEL: u32 = 20;
+
+/// XP extra do aprendiz quando agrupado com o mentor
+pub const APPRENTICE_XP_BONUS: f32 = 0.5;
+/// XP do aprendiz (agrupado) por token do mentor
+pub const MENTOR_TOKEN_XP: u64 = 100;
+pub const GRADUATION_TOKENS: u64 = 25;
+
+/// Vínculo ativo. Cada mentor tem no máximo um aprendiz e vice-versa.
+#[table(name = mentorship, public)]
+#[derive(Clone)]
+pub struct Mentorship {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[unique]
+    pub mentor_id: u32,
+    #[unique]
+    pub apprentice_id: u32,
+    pub started_at: Timestamp,
+    /// XP agrupado ainda não convertido em token
+    pub pending_xp: u64,
+    pub tokens_earned: u64,
+}
+
+#[table(name = mentor_offer, public)]
+#[derive(Clone)]
+pub struct MentorOffer {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub apprentice_id: u32,
+    pub mentor_id: u32,
+    pub offered_at: Timestamp,
+}
+
+fn grouped(ctx: &ReducerContext, a: u32, b: u32) -> bool {
+    matches!((party_of(ctx, a), party_of(ctx, b)), (Some(x), Some(y)) if x == y)
+}
+
+/// Bônus de XP do aprendiz; o mentor acumula tokens pelo XP ganho junto
+pub fn apply_apprentice_bonus(ctx: &ReducerContext, player_id: u32, amount: u64) -> u64 {
+    let Some(mut link) = ctx.db.mentorship().apprentice_id().find(player_id) else {
+        return amount;
+    };
+    if !grouped(ctx, link.mentor_id, player_id) {
+        return amount;
+    }
+
+    let boosted = amount + (amount as f32 * APPRENTICE_XP_BONUS) as u64;
+    link.pending_xp += boosted;
+    let tokens = link.pending_xp / MENTOR_TOKEN_XP;
+    if tokens > 0 {
+        link.pending_xp %= MENTOR_TOKEN_XP;
+        link.tokens_earned += tokens;
+        add_currency(ctx, link.mentor_id, CURRENCY_MENTOR_TOKEN, tokens, "mentor");
+    }
+    ctx.db.mentorship().id().update(link);
+    boosted
+}
+
+/// Formatura: encerra a mentoria e paga o bônus ao mentor
+pub fn on_apprentice_level_up(ctx: &ReducerContext, player_id: u32, level: u32) {
+    if level < APPRENTICE_GRADUATION_LEVEL {
+        return;
+    }
+    let Some(link) = ctx.db.mentorship().apprentice_id().find(player_id) else { return };
+    add_currency(ctx, link.mentor_id, CURRENCY_MENTOR_TOKEN, GRADUATION_TOKENS, "mentor_graduation");
+    ctx.db.mentorship().id().delete(link.id);
+    log::info!("🎓 Apprentice {} graduated under mentor {} ({} tokens earned)",
+        player_id, link.mentor_id, link.tokens_earned + GRADUATION_TOKENS);
+}
+
+#[reducer]
+pub fn offer_mentorship(ctx: &ReducerContext, apprentice_id: u32) -> Result<(), String> {
+    let mentor = sender_player(ctx)?;
+    if mentor.id == apprentice_id {
+        return Err("Cannot mentor yourself".to_string());
+    }
+    ctx.db.player().id().find(apprentice_id).ok_or("Player not found")?;
+    if get_progress(ctx, mentor.id).level < MENTOR_MIN_LEVEL {
+        return Err(format!("Mentors must be level {} or higher", MENTOR_MIN_LEVEL));
+    }
+    if account_age(ctx, mentor.id) < Duration::from_secs(MENTOR_MIN_ACCOUNT_AGE_SECS) {
+        return Err("Your account is too new to mentor".to_string());
+    }
+    if ctx.db.mentorship().mentor_id().find(mentor.id).is_some() {
+        return Err("You already have an apprentice".to_string());
+    }
+    if get_progress(ctx, apprentice_id).level > APPRENTICE_MAX_START_LEVEL {
+        return Err(format!("Apprentices must be level {} or lower", APPRENTICE_MAX_START_LEVEL));
+    }
+    if ctx.db.mentorship().apprentice_id().find(apprentice_id).is_some() {
+        return Err("That player already has a mentor".to_string());
+    }
+    if ctx.db.mentor_offer().apprentice_id().filter(apprentice_id).any(|o| o.mentor_id == mentor.id) {
+        return Ok(());
+    }
+
+    ctx.db.mentor_offer().insert(MentorOffer { id: 0, apprentice_id, mentor_id: mentor.id, offered_at: ctx.timestamp });
+    Ok(())
+}
+
+#[reducer]
+pub fn accept_mentorship(ctx: &ReducerContext, mentor_id: u32) -> Result<(), String> {
+    let apprentice = sender_player(ctx)?;
+    let offer = ctx.db.mentor_offer().apprentice_id().filter(apprentice.id)
+        .find(|o| o.mentor_id == mentor_id)
+        .ok_or("No mentorship offer from that player")?;
+    ctx.db.mentor_offer().id().delete(offer.id);
+
+    // Revalida: as condições podem ter mudado desde a oferta
+    if ctx.db.mentorship().mentor_id().find(mentor_id).is_some() {
+        return Err("That mentor already has an apprentice".to_string());
+    }
+    if ctx.db.mentorship().apprentice_id().find(apprentice.id).is_some() {
+        return Err("You already have a mentor".to_string());
+    }
+    if get_progress(ctx, apprentice.id).level > APPRENTICE_MAX_START_LEVEL {
+        return Err(format!("Apprentices must be level {} or lower", APPRENTICE_MAX_START_LEVEL));
+    }
+
+    let offers: Vec<u64> = ctx.db.mentor_offer().apprentice_id().filter(apprentice.id).map(|o| o.id).collect();
+    for id in offers {
+        ctx.db.mentor_offer().id().delete(id);
+    }
+    ctx.db.mentorship().insert(Mentorship {
+        id: 0,
+        mentor_id,
+        apprentice_id: apprentice.id,
+        started_at: ctx.timestamp,
+        pending_xp: 0,
+        tokens_earned: 0,
+    });
+    log::info!("🎓 Player {} is now mentoring player {}", mentor_id, apprentice.id);
+    Ok(())
+}
+
+/// Qualquer um dos dois pode encerrar a mentoria
+#[reducer]
+pub fn end_mentorship(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let link = ctx.db.mentorship().mentor_id().find(player.id)
+        .or_else(|| ctx.db.mentorship().apprentice_id().find(player.id))
+        .ok_or("You are not in a mentorship")?;
+    ctx.db.mentorship().id().delete(link.id);
+    log::info!("🎓 Mentorship between {} and {} ended", link.mentor_id, link.apprentice_id);
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+
+/// Máximo de eventos guardados por métrica (os mais antigos são descartados)
+const MAX_EVENTS_PER_METRIC: usize = 200;
+
+/// Eventos de métricas operacionais (degradação, contadores periódicos)
+#[table(name = metric_event, public)]
+#[derive(Clone)]
+pub struct MetricEvent {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub name: String,
+    pub value: f64,
+    pub detail: String,
+    pub recorded_at: Timestamp,
+}
+
+pub fn record_metric(ctx: &ReducerContext, name: &str, value: f64, detail: String) {
+    ctx.db.metric_event().insert(MetricEvent {
+        id: 0,
+        name: name.to_string(),
+        value,
+        detail,
+        recorded_at: ctx.timestamp,
+    });
+
+    let mut events: Vec<MetricEvent> = ctx.db.metric_event().name().filter(name).collect();
+    if events.len() > MAX_EVENTS_PER_METRIC {
+        events.sort_by_key(|e| e.id);
+        for old in &events[..events.len() - MAX_EVENTS_PER_METRIC] {
+            ctx.db.metric_event().id().delete(old.id);
+        }
+    }
+}
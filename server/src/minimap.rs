@@ -0,0 +1,80 @@
+use crate::map::{map_template, MapTemplate};
+use spacetimedb::{table, ReducerContext, Table};
+
+/// Quantos tiles (por lado) viram um pixel do minimapa
+pub const MINIMAP_TILES_PER_PIXEL: u32 = 2;
+
+/// Grade de cores reduzida de cada template, para minimapa / mapa-múndi
+#[table(name = map_minimap, public)]
+#[derive(Clone)]
+pub struct MapMinimap {
+    #[primary_key]
+    pub template_name: String,
+    pub width: u32,
+    pub height: u32,
+    pub tiles_per_pixel: u32,
+    pub colors: Vec<u32>, // RGBA, linha a linha
+}
+
+/// Cor representativa de cada tile (RGBA)
+pub fn tile_color(tile_id: u32) -> u32 {
+    match tile_id {
+        0 | 9 => 0x5A9E4BFF,         // grama / flores
+        1 | 3 | 4 => 0xC8A165FF,     // spawn, portas
+        2 | 5 | 16 => 0x6B4A2FFF,    // paredes
+        6 => 0xD9C08CFF,             // caminho
+        8 | 10 => 0x3B6FB6FF,        // água
+        11 | 12 | 14 | 23 => 0x8C6239FF, // móveis
+        13 | 15 => 0x9E3B2EFF,       // telhado
+        17 | 18 => 0x2F6B2AFF,       // árvores
+        40..=47 => 0xB266FFFF,       // teleportes
+        _ => 0x000000FF,
+    }
+}
+
+/// Reduz o template escolhendo a cor mais frequente de cada bloco
+pub fn build_minimap(template: &MapTemplate) -> MapMinimap {
+    let step = MINIMAP_TILES_PER_PIXEL;
+    let width = template.width.div_ceil(step);
+    let height = template.height.div_ceil(step);
+    let mut colors = Vec::with_capacity((width * height) as usize);
+
+    for py in 0..height {
+        for px in 0..width {
+            let mut counts: Vec<(u32, u32)> = Vec::new();
+            for ty in (py * step)..((py + 1) * step).min(template.height) {
+                for tx in (px * step)..((px + 1) * step).min(template.width) {
+                    let Some(tile_id) = template.tile_data.get((ty * template.width + tx) as usize) else { continue };
+                    let color = tile_color(*tile_id);
+                    match counts.iter_mut().find(|(c, _)| *c == color) {
+                        Some((_, n)) => *n += 1,
+                        None => counts.push((color, 1)),
+                    }
+                }
+            }
+            let dominant = counts.iter().max_by_key(|(_, n)| *n).map(|(c, _)| *c).unwrap_or(0x000000FF);
+            colors.push(dominant);
+        }
+    }
+
+    MapMinimap {
+        template_name: template.name.clone(),
+        width,
+        height,
+        tiles_per_pixel: step,
+        colors,
+    }
+}
+
+/// Regera os minimapas de todos os templates (init e `replace_all_templates`)
+pub fn rebuild_minimaps(ctx: &ReducerContext) {
+    for minimap in ctx.db.map_minimap().iter() {
+        ctx.db.map_minimap().template_name().delete(minimap.template_name);
+    }
+
+    for template in ctx.db.map_template().iter() {
+        ctx.db.map_minimap().insert(build_minimap(&template));
+    }
+
+    log::info!("🗺️ Minimapas gerados: {}", ctx.db.map_minimap().count());
+}
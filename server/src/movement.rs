@@ -1,10 +1,64 @@
-use crate::map::{map_instance, map_template, TILE_SIZE};
-use crate::player;
-use spacetimedb::{reducer, ReducerContext, Table};
+use crate::map::{is_passable_step, map_instance, map_template, TILE_SIZE};
+use crate::{player, Player};
+use spacetimedb::{reducer, table, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
 
 const MAX_MOVEMENT_SPEED: f32 = 50.0; // pixels per second
 const MAX_POSITION_DELTA: f32 = 50.0; // per update
 
+// Animation state ids published for client interpolation
+pub const ANIM_IDLE: u8 = 0;
+pub const ANIM_WALK: u8 = 1;
+pub const ANIM_ATTACK: u8 = 2;
+pub const ANIM_DOWNED: u8 = 3;
+
+const MOVING_SPEED_EPSILON: f32 = 0.01;
+
+/// Duração do arco do pulo; no ar, obstáculos baixos não bloqueiam
+pub const JUMP_DURATION_MS: u64 = 450;
+pub const DASH_DISTANCE_TILES: f32 = 4.0;
+
+/// Pulo em andamento (o cliente desenha o arco entre `started_at` e `lands_at`)
+#[table(name = player_jump, public)]
+#[derive(Clone)]
+pub struct PlayerJump {
+    #[primary_key]
+    pub player_id: u32,
+    pub started_at: Timestamp,
+    pub lands_at: Timestamp,
+}
+
+pub fn is_airborne(ctx: &ReducerContext, player_id: u32) -> bool {
+    ctx.db.player_jump().player_id().find(player_id).is_some_and(|j| j.lands_at > ctx.timestamp)
+}
+
+#[reducer]
+pub fn jump(ctx: &ReducerContext) -> Result<(), String> {
+    let player = ctx.db.player().identity().find(ctx.sender).ok_or("Player not found")?;
+    if player.is_downed {
+        return Err("Cannot jump while downed".to_string());
+    }
+    if is_airborne(ctx, player.id) {
+        return Ok(());
+    }
+    // Sem intervalo entre pulos, emendar um no outro deixaria o player sempre no ar
+    crate::cooldown::try_start_cooldown(
+        ctx, ctx.sender, crate::cooldown::CATEGORY_ABILITY, "jump", crate::cooldown::JUMP_COOLDOWN_MS,
+    )?;
+
+    let jump = PlayerJump {
+        player_id: player.id,
+        started_at: ctx.timestamp,
+        lands_at: ctx.timestamp + TimeDuration::from_duration(Duration::from_millis(JUMP_DURATION_MS)),
+    };
+    if ctx.db.player_jump().player_id().find(player.id).is_some() {
+        ctx.db.player_jump().player_id().update(jump);
+    } else {
+        ctx.db.player_jump().insert(jump);
+    }
+    Ok(())
+}
+
 #[reducer]
 pub fn update_player_position(
     ctx: &ReducerContext,
@@ -17,7 +71,7 @@ pub fn update_player_position(
 ) -> Result<(), String> {
     let identity = ctx.sender;
 
-    let player = ctx.db.player().id().find(&player_id)
+    let player = ctx.db.player().id().find(player_id)
         .ok_or_else(|| "Player not found".to_string())?;
 
     if player.identity != identity {
@@ -36,7 +90,7 @@ pub fn update_player_position(
 
     // 2. Busca o template (CSV) vinculado a esta instância para saber o tamanho real
     let template = ctx.db.map_template().name().find(instance.template_name)
-        .ok_or_else(|| "❌ FATAL: Template não encontrado para o cálculo de limites")?;
+        .ok_or("❌ FATAL: Template não encontrado para o cálculo de limites")?;
 
     // 3. Define os limites do mundo baseados no CSV (Tiles * 8px)
     let max_x = template.width as f32 * TILE_SIZE;
@@ -45,16 +99,37 @@ pub fn update_player_position(
 
     // 4. Validações de movimento usando os novos limites numéricos
     let validated_position = validate_movement_bounds(new_x, new_y, min_x, max_x, min_y, max_y);
-    let validated_velocity = validate_movement_speed(velocity_x, velocity_y);
+    // Efeitos (ex: carga pesada), o peso carregado e o terreno (lama, estrada) ajustam os limites de velocidade e deslocamento
+    let speed_multiplier = crate::status_effect::movement_speed_multiplier(ctx, player_id)
+        * crate::inventory::encumbrance_multiplier(ctx, player_id)
+        * crate::map::terrain_speed_multiplier(ctx, &player.current_map_id, player.position_x, player.position_y);
+    let validated_velocity = validate_movement_speed(velocity_x, velocity_y, MAX_MOVEMENT_SPEED * speed_multiplier);
 
     // Evita teleporte (valida se o movimento é fisicamente possível entre frames)
     let (final_x, final_y) = validate_position_delta(
         player.position_x,
         player.position_y,
         validated_position.0,
-        validated_position.1
+        validated_position.1,
+        MAX_POSITION_DELTA * speed_multiplier,
     );
 
+    // Colisão: paredes, beiradas de mão única e (fora de um pulo) obstáculos baixos
+    let airborne = is_airborne(ctx, player_id);
+    let (final_x, final_y, validated_velocity) = if is_passable_step(
+        ctx, &template, Some(instance.id), (player.position_x, player.position_y), (final_x, final_y), airborne,
+    ) {
+        (final_x, final_y, validated_velocity)
+    } else {
+        (player.position_x, player.position_y, (0.0, 0.0))
+    };
+
+    if (final_x, final_y) != (new_x, new_y) {
+        crate::bug_report::record_action(ctx, player_id, format!(
+            "update_player_position corrected ({:.0}, {:.0}) -> ({:.0}, {:.0})", new_x, new_y, final_x, final_y,
+        ), true);
+    }
+
     // 5. Atualização atômica do estado do player
     let mut updated_player = player.clone();
     updated_player.position_x = final_x;
@@ -62,6 +137,7 @@ pub fn update_player_position(
     updated_player.velocity_x = validated_velocity.0;
     updated_player.velocity_y = validated_velocity.1;
     updated_player.last_input_sequence = input_sequence;
+    refresh_player_motion(&mut updated_player, ctx.timestamp);
 
     ctx.db.player().id().update(updated_player);
 
@@ -71,6 +147,54 @@ pub fn update_player_position(
     Ok(())
 }
 
+/// Dash: desloca o player instantaneamente até `DASH_DISTANCE_TILES` na direção.
+/// O caminho é validado tile a tile e para no último ponto livre (parede, beirada
+/// contra o sentido ou fora do mapa). `input_sequence` marca o dash no fluxo de
+/// movimento: atualizações anteriores a ele são descartadas, então o salto não
+/// passa pela checagem de teleporte.
+#[reducer]
+pub fn dash(ctx: &ReducerContext, direction_x: f32, direction_y: f32, input_sequence: u32) -> Result<(), String> {
+    let player = ctx.db.player().identity().find(ctx.sender).ok_or("Player not found")?;
+    if player.is_downed {
+        return Err("Cannot dash while downed".to_string());
+    }
+    let length = (direction_x * direction_x + direction_y * direction_y).sqrt();
+    if length <= MOVING_SPEED_EPSILON {
+        return Err("Invalid dash direction".to_string());
+    }
+    let template = crate::map::template_for_map(ctx, &player.current_map_id).ok_or("Map not found")?;
+    let instance_id = ctx.db.map_instance().key_id().find(player.current_map_id.clone()).map(|i| i.id);
+    crate::cooldown::try_start_cooldown(
+        ctx, ctx.sender, crate::cooldown::CATEGORY_ABILITY, "dash", crate::cooldown::DASH_COOLDOWN_MS,
+    )?;
+
+    let (dir_x, dir_y) = (direction_x / length, direction_y / length);
+    let step = TILE_SIZE / 2.0;
+    let steps = (DASH_DISTANCE_TILES * TILE_SIZE / step) as u32;
+    let mut reached = (player.position_x, player.position_y);
+    for i in 1..=steps {
+        let next = (player.position_x + dir_x * step * i as f32, player.position_y + dir_y * step * i as f32);
+        if !is_passable_step(ctx, &template, instance_id, reached, next, false) {
+            break;
+        }
+        reached = next;
+    }
+
+    let mut updated_player = player.clone();
+    updated_player.position_x = reached.0;
+    updated_player.position_y = reached.1;
+    updated_player.velocity_x = 0.0;
+    updated_player.velocity_y = 0.0;
+    updated_player.facing_x = dir_x;
+    updated_player.facing_y = dir_y;
+    updated_player.last_input_sequence = updated_player.last_input_sequence.max(input_sequence);
+    refresh_player_motion(&mut updated_player, ctx.timestamp);
+    ctx.db.player().id().update(updated_player);
+
+    log::info!("💨 Player {} dashed to ({:.1}, {:.1})", player.id, reached.0, reached.1);
+    crate::map::check_map_transition(ctx, player.id)
+}
+
 /// Validate movement bounds to prevent players from going out of map
 /// Requirements 1.5: Server validates all movement inputs
 fn validate_movement_bounds(x: f32, y: f32, min_x: f32, max_x: f32, min_y: f32, max_y: f32) -> (f32, f32) {
@@ -86,18 +210,18 @@ fn validate_movement_bounds(x: f32, y: f32, min_x: f32, max_x: f32, min_y: f32,
 
 /// Validate movement speed to prevent speed hacking
 /// Requirements 1.5: Server validates all movement inputs
-fn validate_movement_speed(velocity_x: f32, velocity_y: f32) -> (f32, f32) {
+fn validate_movement_speed(velocity_x: f32, velocity_y: f32, max_speed: f32) -> (f32, f32) {
     let speed = (velocity_x * velocity_x + velocity_y * velocity_y).sqrt();
     
-    if speed > MAX_MOVEMENT_SPEED {
+    if speed > max_speed {
         // Normalize to maximum allowed speed
-        let scale = MAX_MOVEMENT_SPEED / speed;
+        let scale = max_speed / speed;
         let validated_x = velocity_x * scale;
         let validated_y = velocity_y * scale;
         
         log::warn!(
             "Speed validation: reduced from {:.1} to {:.1} (max: {:.1})", 
-            speed, MAX_MOVEMENT_SPEED, MAX_MOVEMENT_SPEED
+            speed, max_speed, max_speed
         );
         
         (validated_x, validated_y)
@@ -108,20 +232,20 @@ fn validate_movement_speed(velocity_x: f32, velocity_y: f32) -> (f32, f32) {
 
 /// Validate position delta to prevent teleporting
 /// Requirements 1.5: Server validates all movement inputs
-fn validate_position_delta(old_x: f32, old_y: f32, new_x: f32, new_y: f32) -> (f32, f32) {
+fn validate_position_delta(old_x: f32, old_y: f32, new_x: f32, new_y: f32, max_delta: f32) -> (f32, f32) {
     let delta_x = new_x - old_x;
     let delta_y = new_y - old_y;
     let delta_distance = (delta_x * delta_x + delta_y * delta_y).sqrt();
     
-    if delta_distance > MAX_POSITION_DELTA {
+    if delta_distance > max_delta {
         // Limit movement to maximum allowed delta
-        let scale = MAX_POSITION_DELTA / delta_distance;
+        let scale = max_delta / delta_distance;
         let validated_x = old_x + (delta_x * scale);
         let validated_y = old_y + (delta_y * scale);
         
         log::warn!(
             "Position delta validation: limited movement from {:.1} to {:.1} pixels", 
-            delta_distance, MAX_POSITION_DELTA
+            delta_distance, max_delta
         );
         
         (validated_x, validated_y)
@@ -130,6 +254,38 @@ fn validate_position_delta(old_x: f32, old_y: f32, new_x: f32, new_y: f32) -> (f
     }
 }
 
+/// Compute facing and animation state from the authoritative velocity.
+/// Facing keeps its previous value while the entity is standing still.
+pub fn motion_hints(velocity_x: f32, velocity_y: f32, previous_facing: (f32, f32), is_downed: bool) -> (f32, f32, u8) {
+    let speed = (velocity_x * velocity_x + velocity_y * velocity_y).sqrt();
+
+    let (facing_x, facing_y) = if speed > MOVING_SPEED_EPSILON {
+        (velocity_x / speed, velocity_y / speed)
+    } else {
+        previous_facing
+    };
+
+    let animation_state = if is_downed {
+        ANIM_DOWNED
+    } else if speed > MOVING_SPEED_EPSILON {
+        ANIM_WALK
+    } else {
+        ANIM_IDLE
+    };
+
+    (facing_x, facing_y, animation_state)
+}
+
+/// Refresh the motion hints of a player row after its velocity or downed state changed
+pub fn refresh_player_motion(player: &mut Player, now: Timestamp) {
+    let (facing_x, facing_y, animation_state) =
+        motion_hints(player.velocity_x, player.velocity_y, (player.facing_x, player.facing_y), player.is_downed);
+    player.facing_x = facing_x;
+    player.facing_y = facing_y;
+    player.animation_state = animation_state;
+    player.motion_updated_at = now;
+}
+
 /// Force position correction for a player (admin/debug function)
 /// Requirements 1.7: Position reconciliation system
 #[reducer]
@@ -142,7 +298,7 @@ pub fn force_player_position(
     let identity = ctx.sender;
     
     // Find the player
-    if let Some(player) = ctx.db.player().id().find(&player_id) {
+    if let Some(player) = ctx.db.player().id().find(player_id) {
         // Verify the player belongs to the sender (or add admin check here)
         if player.identity != identity {
             return Err("Unauthorized position correction".to_string());
@@ -157,9 +313,10 @@ pub fn force_player_position(
         updated_player.position_y = validated_position.1;
         updated_player.velocity_x = 0.0;
         updated_player.velocity_y = 0.0;
+        refresh_player_motion(&mut updated_player, ctx.timestamp);
         
         // Delete old and insert updated
-        ctx.db.player().id().delete(&player_id);
+        ctx.db.player().id().delete(player_id);
         ctx.db.player().insert(updated_player);
         
         log::info!("🔧 Force corrected player {} position to ({:.1}, {:.1})", player_id, validated_position.0, validated_position.1);
@@ -174,7 +331,7 @@ pub fn get_player_position(
     player_id: u32,
 ) -> Result<(), String> {
     // Find the player
-    if let Some(player) = ctx.db.player().id().find(&player_id) {
+    if let Some(player) = ctx.db.player().id().find(player_id) {
         log::info!(
             "📍 Player {} position: ({:.1}, {:.1}), velocity: ({:.1}, {:.1}), map: {}", 
             player_id, player.position_x, player.position_y,
@@ -0,0 +1,182 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use crate::party::sender_player;
+use crate::player;
+use spacetimedb::{reducer, table, ReducerContext, SpacetimeType, Table, Timestamp};
+
+const MIN_USERNAME_LEN: usize = 3;
+const MAX_USERNAME_LEN: usize = 16;
+
+/// Nomes de equipe e do sistema que ninguém pode usar nem imitar
+const DEFAULT_RESERVED: &[(&str, ReservedMatch, &str)] = &[
+    ("admin", ReservedMatch::Contains, "staff"),
+    ("moderator", ReservedMatch::Contains, "staff"),
+    ("gamemaster", ReservedMatch::Contains, "staff"),
+    ("gm", ReservedMatch::Exact, "staff"),
+    ("support", ReservedMatch::Contains, "staff"),
+    ("system", ReservedMatch::Exact, "system"),
+    ("server", ReservedMatch::Exact, "system"),
+];
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum ReservedMatch {
+    /// O nome inteiro (normalizado) é igual ao padrão
+    Exact,
+    /// O padrão aparece em qualquer parte do nome normalizado
+    Contains,
+}
+
+/// Nomes reservados ou proibidos (equipe, ofensas, imitações)
+#[table(name = reserved_name)]
+#[derive(Clone)]
+pub struct ReservedName {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub pattern: String,
+    pub match_kind: ReservedMatch,
+    pub reason: String,
+    pub added_at: Timestamp,
+}
+
+/// Trocas de nome disponíveis (ganhas por renomeação forçada)
+#[table(name = rename_token, public)]
+#[derive(Clone)]
+pub struct RenameToken {
+    #[primary_key]
+    pub player_id: u32,
+    pub tokens: u32,
+}
+
+pub fn seed_reserved_names(ctx: &ReducerContext) {
+    for (pattern, match_kind, reason) in DEFAULT_RESERVED {
+        if !ctx.db.reserved_name().iter().any(|r| r.pattern == *pattern) {
+            ctx.db.reserved_name().insert(ReservedName {
+                id: 0,
+                pattern: pattern.to_string(),
+                match_kind: *match_kind,
+                reason: reason.to_string(),
+                added_at: ctx.timestamp,
+            });
+        }
+    }
+}
+
+/// Forma usada na comparação: minúsculas, sem separadores e com
+/// substituições comuns desfeitas (ex.: "Adm1n_" -> "admin")
+fn normalize(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            '0' => Some('o'),
+            '1' | '!' | '|' => Some('i'),
+            '3' => Some('e'),
+            '4' | '@' => Some('a'),
+            '5' | '$' => Some('s'),
+            '7' => Some('t'),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Valida um nome escolhido pelo player. Retorna (exibição, canônico).
+pub fn validate_username(ctx: &ReducerContext, username_display: &str) -> Result<(String, String), String> {
+    let display = username_display.trim().to_string();
+    let canonical = display.to_lowercase();
+    if display.is_empty() || canonical.len() < MIN_USERNAME_LEN || canonical.len() > MAX_USERNAME_LEN {
+        return Err("Invalid username length".to_string());
+    }
+
+    let normalized = normalize(&display);
+    let blocked = ctx.db.reserved_name().iter().any(|r| match r.match_kind {
+        ReservedMatch::Exact => normalized == r.pattern,
+        ReservedMatch::Contains => normalized.contains(&r.pattern),
+    });
+    if blocked {
+        return Err("That name is not allowed".to_string());
+    }
+    Ok((display, canonical))
+}
+
+fn add_rename_token(ctx: &ReducerContext, player_id: u32) {
+    match ctx.db.rename_token().player_id().find(player_id) {
+        Some(mut row) => {
+            row.tokens += 1;
+            ctx.db.rename_token().player_id().update(row);
+        }
+        None => {
+            ctx.db.rename_token().insert(RenameToken { player_id, tokens: 1 });
+        }
+    }
+}
+
+fn apply_rename(ctx: &ReducerContext, player_id: u32, display: String, canonical: String) -> Result<(), String> {
+    if ctx.db.player().username_canonical().find(&canonical).is_some_and(|p| p.id != player_id) {
+        return Err("Username already taken".to_string());
+    }
+    let mut player = ctx.db.player().id().find(player_id).ok_or("Player not found")?;
+    player.username_display = display;
+    player.username_canonical = canonical;
+    ctx.db.player().id().update(player);
+    Ok(())
+}
+
+/// Troca o próprio nome gastando uma ficha de renomeação
+#[reducer]
+pub fn rename_player(ctx: &ReducerContext, username_display: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let mut token = ctx.db.rename_token().player_id().find(player.id)
+        .filter(|t| t.tokens > 0)
+        .ok_or("You need a rename token")?;
+    let (display, canonical) = validate_username(ctx, &username_display)?;
+
+    apply_rename(ctx, player.id, display.clone(), canonical)?;
+    token.tokens -= 1;
+    ctx.db.rename_token().player_id().update(token);
+    log::info!("🏷️ Player {} renamed '{}' -> '{}'", player.id, player.username_display, display);
+    Ok(())
+}
+
+#[reducer]
+pub fn add_reserved_name(ctx: &ReducerContext, pattern: String, match_kind: ReservedMatch, reason: String) -> Result<(), String> {
+    require_admin(ctx)?;
+    let pattern = normalize(&pattern);
+    if pattern.is_empty() {
+        return Err("Pattern cannot be empty".to_string());
+    }
+    if ctx.db.reserved_name().iter().any(|r| r.pattern == pattern && r.match_kind == match_kind) {
+        return Err("Pattern already reserved".to_string());
+    }
+    ctx.db.reserved_name().insert(ReservedName {
+        id: 0,
+        pattern: pattern.clone(),
+        match_kind,
+        reason: reason.clone(),
+        added_at: ctx.timestamp,
+    });
+    record_audit(ctx, "name_policy", format!("Reserved '{}' ({:?}): {}", pattern, match_kind, reason));
+    Ok(())
+}
+
+#[reducer]
+pub fn remove_reserved_name(ctx: &ReducerContext, reserved_id: u64) -> Result<(), String> {
+    require_admin(ctx)?;
+    let reserved = ctx.db.reserved_name().id().find(reserved_id).ok_or("Reserved name not found")?;
+    ctx.db.reserved_name().id().delete(reserved_id);
+    record_audit(ctx, "name_policy", format!("Released '{}'", reserved.pattern));
+    Ok(())
+}
+
+/// Troca o nome de um infrator por um provisório e dá uma renomeação grátis
+#[reducer]
+pub fn force_rename(ctx: &ReducerContext, player_id: u32, reason: String) -> Result<(), String> {
+    require_admin(ctx)?;
+    let player = ctx.db.player().id().find(player_id).ok_or("Player not found")?;
+    let placeholder = format!("Renamed{}", player_id);
+
+    apply_rename(ctx, player_id, placeholder.clone(), placeholder.to_lowercase())?;
+    add_rename_token(ctx, player_id);
+    record_audit(ctx, "name_policy", format!("Force-renamed player {} '{}': {}", player_id, player.username_display, reason));
+    Ok(())
+}
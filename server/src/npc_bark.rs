@@ -0,0 +1,176 @@
+use crate::admin::require_admin;
+use crate::local_event::{post_local_event, LOCAL_EVENT_BARK};
+use crate::map::{is_map_hot, template_for_map};
+use crate::town::town_services_open;
+use crate::vendor::{vendor, Vendor};
+use spacetimedb::rand::Rng;
+use spacetimedb::{reducer, table, ReducerContext, ScheduleAt, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+pub const BARK_INTERVAL_SECS: u64 = 15;
+// Cada NPC fala no máximo uma vez nesta janela (sorteada)
+const BARK_MIN_GAP_SECS: u64 = 60;
+const BARK_MAX_GAP_SECS: u64 = 180;
+/// No máximo uma fala por mapa a cada execução
+const BARKS_PER_MAP_PER_RUN: usize = 1;
+const SECS_PER_HOUR: i64 = 60 * 60;
+
+/// Falas padrão dos NPCs de cidade: (texto, hora inicial, hora final) em UTC.
+/// Hora inicial igual à final = o dia todo.
+const DEFAULT_BARKS: &[(&str, u8, u8)] = &[
+    ("Fresh goods, come and see!", 0, 0),
+    ("Mind the roads after dark, traveler.", 18, 6),
+    ("Early start today. Best prices before noon!", 6, 12),
+    ("The tavern's warm tonight, if you need a bed.", 18, 2),
+    ("Heard the caravans took the long road again.", 0, 0),
+];
+const CLOSED_BARK: &str = "Shutters are down until the town recovers. Stay safe out there.";
+
+/// Fala ambiente personalizada de um NPC (vendedor), ativa nas horas dadas
+#[table(name = npc_bark_line, public)]
+#[derive(Clone)]
+pub struct NpcBarkLine {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub vendor_id: u64,
+    pub text: String,
+    pub start_hour: u8,
+    pub end_hour: u8,
+}
+
+/// Próxima fala permitida de cada NPC
+#[table(name = npc_bark_cooldown)]
+pub struct NpcBarkCooldown {
+    #[primary_key]
+    pub vendor_id: u64,
+    pub next_bark_at: Timestamp,
+}
+
+#[table(name = npc_bark_schedule, scheduled(run_npc_barks))]
+pub struct NpcBarkSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+pub fn ensure_npc_bark_schedule(ctx: &ReducerContext) {
+    if ctx.db.npc_bark_schedule().count() == 0 {
+        ctx.db.npc_bark_schedule().insert(NpcBarkSchedule {
+            scheduled_id: 0,
+            scheduled_at: Duration::from_secs(BARK_INTERVAL_SECS).into(),
+        });
+    }
+}
+
+fn active_at(start_hour: u8, end_hour: u8, hour: u8) -> bool {
+    match start_hour.cmp(&end_hour) {
+        std::cmp::Ordering::Equal => true,
+        std::cmp::Ordering::Less => hour >= start_hour && hour < end_hour,
+        // Janela que atravessa a meia-noite
+        std::cmp::Ordering::Greater => hour >= start_hour || hour < end_hour,
+    }
+}
+
+fn pick_line(ctx: &ReducerContext, npc: &Vendor, hour: u8) -> Option<String> {
+    if !town_services_open(ctx, &npc.map_id) {
+        return Some(CLOSED_BARK.to_string());
+    }
+
+    let custom: Vec<NpcBarkLine> = ctx.db.npc_bark_line().vendor_id().filter(npc.id).collect();
+    let lines: Vec<String> = if custom.is_empty() {
+        DEFAULT_BARKS.iter()
+            .filter(|(_, start, end)| active_at(*start, *end, hour))
+            .map(|(text, _, _)| text.to_string())
+            .collect()
+    } else {
+        custom.into_iter()
+            .filter(|l| active_at(l.start_hour, l.end_hour, hour))
+            .map(|l| l.text)
+            .collect()
+    };
+    if lines.is_empty() {
+        return None;
+    }
+    Some(lines[ctx.rng().gen_range(0..lines.len())].clone())
+}
+
+/// NPCs de cidades com players presentes soltam falas ambiente no fluxo local do mapa
+#[reducer]
+pub fn run_npc_barks(ctx: &ReducerContext, _schedule: NpcBarkSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("run_npc_barks may only be invoked by the scheduler".to_string());
+    }
+    if crate::upgrade::is_frozen(ctx) {
+        return Ok(());
+    }
+
+    let hour = (ctx.timestamp.to_micros_since_unix_epoch() / 1_000_000 / SECS_PER_HOUR).rem_euclid(24) as u8;
+    let mut barks_per_map: Vec<(String, usize)> = Vec::new();
+
+    for npc in ctx.db.vendor().iter() {
+        if !template_for_map(ctx, &npc.map_id).is_some_and(|t| t.is_town) || !is_map_hot(ctx, &npc.map_id) {
+            continue;
+        }
+        let posted = barks_per_map.iter().find(|(m, _)| *m == npc.map_id).map(|(_, n)| *n).unwrap_or(0);
+        if posted >= BARKS_PER_MAP_PER_RUN {
+            continue;
+        }
+        let cooldown = ctx.db.npc_bark_cooldown().vendor_id().find(npc.id);
+        if cooldown.as_ref().is_some_and(|c| c.next_bark_at > ctx.timestamp) {
+            continue;
+        }
+        let Some(line) = pick_line(ctx, &npc, hour) else { continue };
+
+        post_local_event(ctx, &npc.map_id, LOCAL_EVENT_BARK, &npc.name, line, (npc.position_x, npc.position_y));
+
+        let gap = ctx.rng().gen_range(BARK_MIN_GAP_SECS..=BARK_MAX_GAP_SECS);
+        let next = NpcBarkCooldown {
+            vendor_id: npc.id,
+            next_bark_at: ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(gap)),
+        };
+        if cooldown.is_some() {
+            ctx.db.npc_bark_cooldown().vendor_id().update(next);
+        } else {
+            ctx.db.npc_bark_cooldown().insert(next);
+        }
+        match barks_per_map.iter_mut().find(|(m, _)| *m == npc.map_id) {
+            Some((_, n)) => *n += 1,
+            None => barks_per_map.push((npc.map_id.clone(), 1)),
+        }
+    }
+    Ok(())
+}
+
+pub fn on_vendor_removed(ctx: &ReducerContext, vendor_id: u64) {
+    let lines: Vec<u64> = ctx.db.npc_bark_line().vendor_id().filter(vendor_id).map(|l| l.id).collect();
+    for id in lines {
+        ctx.db.npc_bark_line().id().delete(id);
+    }
+    ctx.db.npc_bark_cooldown().vendor_id().delete(vendor_id);
+}
+
+#[reducer]
+pub fn add_npc_bark_line(ctx: &ReducerContext, vendor_id: u64, text: String, start_hour: u8, end_hour: u8) -> Result<(), String> {
+    require_admin(ctx)?;
+    ctx.db.vendor().id().find(vendor_id).ok_or("Vendor not found")?;
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("Bark text cannot be empty".to_string());
+    }
+    if start_hour > 23 || end_hour > 23 {
+        return Err("Hours must be between 0 and 23".to_string());
+    }
+    ctx.db.npc_bark_line().insert(NpcBarkLine { id: 0, vendor_id, text, start_hour, end_hour });
+    Ok(())
+}
+
+#[reducer]
+pub fn remove_npc_bark_line(ctx: &ReducerContext, line_id: u64) -> Result<(), String> {
+    require_admin(ctx)?;
+    ctx.db.npc_bark_line().id().find(line_id).ok_or("Bark line not found")?;
+    ctx.db.npc_bark_line().id().delete(line_id);
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use crate::localization::Message;
+use crate::{player, Player};
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+pub const MAX_PARTY_SIZE: usize = 5;
+
+#[table(name = party, public)]
+#[derive(Clone)]
+pub struct Party {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub leader_id: u32,
+    pub created_at: Timestamp,
+}
+
+/// Um player pertence a no máximo uma party
+#[table(name = party_member, public)]
+#[derive(Clone)]
+pub struct PartyMember {
+    #[primary_key]
+    pub player_id: u32,
+    #[index(btree)]
+    pub party_id: u64,
+    pub joined_at: Timestamp,
+}
+
+#[table(name = party_invite, public)]
+#[derive(Clone)]
+pub struct PartyInvite {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub party_id: u64,
+    pub invited_at: Timestamp,
+}
+
+pub fn sender_player(ctx: &ReducerContext) -> Result<Player, String> {
+    ctx.db.player().identity().find(ctx.sender).ok_or_else(|| Message::new("error.player_not_found").into())
+}
+
+pub fn party_of(ctx: &ReducerContext, player_id: u32) -> Option<u64> {
+    ctx.db.party_member().player_id().find(player_id).map(|m| m.party_id)
+}
+
+pub fn party_member_ids(ctx: &ReducerContext, party_id: u64) -> Vec<u32> {
+    ctx.db.party_member().party_id().filter(party_id).map(|m| m.player_id).collect()
+}
+
+/// Party liderada pelo remetente (erro se não for líder)
+pub fn require_party_leader(ctx: &ReducerContext) -> Result<(Player, Party), String> {
+    let leader = sender_player(ctx)?;
+    let party = party_of(ctx, leader.id)
+        .and_then(|id| ctx.db.party().id().find(id))
+        .ok_or("You are not in a party")?;
+    if party.leader_id != leader.id {
+        return Err("Only the party leader can do that".to_string());
+    }
+    Ok((leader, party))
+}
+
+/// Cria uma party com `leader_id` como único membro (quem chama garante que ele está sem party)
+pub fn form_party(ctx: &ReducerContext, leader_id: u32) -> Party {
+    let party = ctx.db.party().insert(Party { id: 0, leader_id, created_at: ctx.timestamp });
+    ctx.db.party_member().insert(PartyMember { player_id: leader_id, party_id: party.id, joined_at: ctx.timestamp });
+    log::info!("👥 Party {} created by player {}", party.id, leader_id);
+    party
+}
+
+/// Adiciona um player sem party a uma party existente, respeitando o limite
+pub fn join_party(ctx: &ReducerContext, player_id: u32, party_id: u64) -> Result<(), String> {
+    if party_of(ctx, player_id).is_some() {
+        return Err("Already in a party".to_string());
+    }
+    if ctx.db.party().id().find(party_id).is_none() {
+        return Err("Party no longer exists".to_string());
+    }
+    if party_member_ids(ctx, party_id).len() >= MAX_PARTY_SIZE {
+        return Err("Party is full".to_string());
+    }
+
+    ctx.db.party_member().insert(PartyMember { player_id, party_id, joined_at: ctx.timestamp });
+    log::info!("👥 Player {} joined party {}", player_id, party_id);
+    Ok(())
+}
+
+#[reducer]
+pub fn create_party(ctx: &ReducerContext) -> Result<(), String> {
+    let leader = sender_player(ctx)?;
+    if party_of(ctx, leader.id).is_some() {
+        return Err("Already in a party".to_string());
+    }
+
+    form_party(ctx, leader.id);
+    Ok(())
+}
+
+#[reducer]
+pub fn invite_to_party(ctx: &ReducerContext, player_id: u32) -> Result<(), String> {
+    let (_, party) = require_party_leader(ctx)?;
+    if ctx.db.player().id().find(player_id).is_none() {
+        return Err("Player not found".to_string());
+    }
+    if party_of(ctx, player_id).is_some() {
+        return Err("Player is already in a party".to_string());
+    }
+    if ctx.db.party_invite().player_id().filter(player_id).any(|i| i.party_id == party.id) {
+        return Ok(());
+    }
+
+    ctx.db.party_invite().insert(PartyInvite { id: 0, player_id, party_id: party.id, invited_at: ctx.timestamp });
+    Ok(())
+}
+
+#[reducer]
+pub fn accept_party_invite(ctx: &ReducerContext, party_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let invite = ctx.db.party_invite().player_id().filter(player.id)
+        .find(|i| i.party_id == party_id)
+        .ok_or("No invite from that party")?;
+    ctx.db.party_invite().id().delete(invite.id);
+
+    join_party(ctx, player.id, party_id)
+}
+
+#[reducer]
+pub fn leave_party(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let party_id = party_of(ctx, player.id).ok_or("You are not in a party")?;
+    remove_member(ctx, player.id, party_id);
+    Ok(())
+}
+
+/// Tira o player do grupo, passando a liderança ou desfazendo o grupo vazio
+pub fn remove_member(ctx: &ReducerContext, player_id: u32, party_id: u64) {
+    ctx.db.party_member().player_id().delete(player_id);
+
+    let remaining = party_member_ids(ctx, party_id);
+    match remaining.first() {
+        None => {
+            ctx.db.party().id().delete(party_id);
+            let invites: Vec<u64> = ctx.db.party_invite().iter().filter(|i| i.party_id == party_id).map(|i| i.id).collect();
+            for id in invites {
+                ctx.db.party_invite().id().delete(id);
+            }
+            log::info!("👥 Party {} disbanded", party_id);
+        }
+        Some(&next_leader) => {
+            if let Some(mut party) = ctx.db.party().id().find(party_id) {
+                if party.leader_id == player_id {
+                    party.leader_id = next_leader;
+                    ctx.db.party().id().update(party);
+                }
+            }
+        }
+    }
+}
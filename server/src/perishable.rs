@@ -0,0 +1,129 @@
+use crate::inventory::{count_item, inventory_item, InventoryItem};
+use spacetimedb::{reducer, table, ReducerContext, ScheduleAt, Table, Timestamp};
+use std::time::Duration;
+
+pub const PERISH_INTERVAL_SECS: u64 = 60;
+
+/// Item que preserva a comida carregada junto (decadência na taxa normal)
+pub const PRESERVATION_CONTAINER_ITEM: &str = "ice_box";
+/// Sem recipiente de preservação a comida estraga mais rápido
+const UNPRESERVED_DECAY_MULTIPLIER: f32 = 2.0;
+
+struct PerishableDef {
+    item_id: &'static str,
+    /// Validade (preservado) em segundos
+    shelf_life_secs: u64,
+    /// Item em que se transforma ao estragar (None = removido)
+    spoils_into: Option<&'static str>,
+}
+
+// Comida cozida dura mais que os ingredientes crus
+const PERISHABLES: &[PerishableDef] = &[
+    PerishableDef { item_id: "fruit", shelf_life_secs: 2 * 60 * 60, spoils_into: Some("rotten_fruit") },
+    PerishableDef { item_id: "fruit_pie", shelf_life_secs: 8 * 60 * 60, spoils_into: Some("stale_pie") },
+    PerishableDef { item_id: "raw_fish", shelf_life_secs: 60 * 60, spoils_into: None },
+    PerishableDef { item_id: "stale_pie", shelf_life_secs: 4 * 60 * 60, spoils_into: None },
+];
+
+/// Frescor de uma pilha perecível do inventário (1.0 = fresco, 0.0 = estragado).
+/// Pilhas mescladas mantêm o frescor da mais antiga.
+#[table(name = item_freshness, public)]
+#[derive(Clone)]
+pub struct ItemFreshness {
+    #[primary_key]
+    pub inventory_item_id: u32,
+    pub freshness: f32,
+    pub updated_at: Timestamp,
+}
+
+#[table(name = perish_schedule, scheduled(process_perishables))]
+pub struct PerishSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+pub fn ensure_perish_schedule(ctx: &ReducerContext) {
+    if ctx.db.perish_schedule().count() == 0 {
+        ctx.db.perish_schedule().insert(PerishSchedule {
+            scheduled_id: 0,
+            scheduled_at: Duration::from_secs(PERISH_INTERVAL_SECS).into(),
+        });
+    }
+}
+
+fn perishable_def(item_id: &str) -> Option<&'static PerishableDef> {
+    PERISHABLES.iter().find(|d| d.item_id == item_id)
+}
+
+pub fn is_perishable(item_id: &str) -> bool {
+    perishable_def(item_id).is_some()
+}
+
+#[reducer]
+pub fn process_perishables(ctx: &ReducerContext, _schedule: PerishSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("process_perishables may only be invoked by the scheduler".to_string());
+    }
+    if crate::upgrade::is_frozen(ctx) {
+        return Ok(());
+    }
+
+    let stacks: Vec<InventoryItem> = ctx.db.inventory_item().iter()
+        .filter(|item| is_perishable(&item.item_id))
+        .collect();
+    for stack in stacks {
+        decay_stack(ctx, stack);
+    }
+
+    // Remove frescor de pilhas que não existem mais
+    let orphaned: Vec<u32> = ctx.db.item_freshness().iter()
+        .filter(|f| ctx.db.inventory_item().id().find(f.inventory_item_id).is_none())
+        .map(|f| f.inventory_item_id)
+        .collect();
+    for id in orphaned {
+        ctx.db.item_freshness().inventory_item_id().delete(id);
+    }
+    Ok(())
+}
+
+fn decay_stack(ctx: &ReducerContext, stack: InventoryItem) {
+    let Some(def) = perishable_def(&stack.item_id) else { return };
+    let Some(mut freshness) = ctx.db.item_freshness().inventory_item_id().find(stack.id) else {
+        // Pilha nova: começa fresca a partir de agora
+        ctx.db.item_freshness().insert(ItemFreshness {
+            inventory_item_id: stack.id,
+            freshness: 1.0,
+            updated_at: ctx.timestamp,
+        });
+        return;
+    };
+
+    let elapsed = ctx.timestamp.duration_since(freshness.updated_at).unwrap_or_default().as_secs_f32();
+    let rate = if count_item(ctx, stack.player_id, PRESERVATION_CONTAINER_ITEM) > 0 {
+        1.0
+    } else {
+        UNPRESERVED_DECAY_MULTIPLIER
+    };
+    freshness.freshness -= elapsed * rate / def.shelf_life_secs as f32;
+    freshness.updated_at = ctx.timestamp;
+
+    if freshness.freshness > 0.0 {
+        ctx.db.item_freshness().inventory_item_id().update(freshness);
+        return;
+    }
+
+    // Estragou: remove a pilha e, se houver, entrega a versão rebaixada
+    ctx.db.item_freshness().inventory_item_id().delete(stack.id);
+    ctx.db.inventory_item().id().delete(stack.id);
+    match def.spoils_into {
+        Some(spoiled) => {
+            if let Err(e) = crate::inventory::add_item_to_inventory(ctx, stack.player_id, spoiled.to_string(), stack.quantity) {
+                log::warn!("Could not downgrade {} for player {}: {}", stack.item_id, stack.player_id, e);
+            }
+            log::info!("🤢 {} x{} of player {} spoiled into {}", stack.item_id, stack.quantity, stack.player_id, spoiled);
+        }
+        None => log::info!("🤢 {} x{} of player {} spoiled", stack.item_id, stack.quantity, stack.player_id),
+    }
+}
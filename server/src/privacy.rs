@@ -0,0 +1,434 @@
+use crate::ability_queue::queued_ability;
+use crate::account_unlock::{account_transfer, account_unlock};
+use crate::achievement::player_achievement;
+use crate::admin::require_admin;
+use crate::afk_detection::{afk_review, farming_session};
+use crate::arena::match_spectator;
+use crate::audit::{audit_log, record_audit};
+use crate::bestiary::bestiary_entry;
+use crate::bug_report::{action_trail, bug_report};
+use crate::caravan::caravan_delivery;
+use crate::chat::chat_message;
+use crate::client_compat::{client_error_event, client_info};
+use crate::challenge_tower::{tower_ladder_entry, tower_progress};
+use crate::combat::combat_state;
+use crate::community_goal::goal_contribution;
+use crate::cooldown::{cooldown, try_start_cooldown};
+use crate::cosmetic::player_appearance;
+use crate::crafting::{craft_result, known_recipe};
+use crate::currency::player_currency;
+use crate::damage_meter::encounter_stat;
+use crate::death_recap::{death_recap, recent_damage};
+use crate::defense::defensive_stance;
+use crate::escrow::escrow_hold;
+use crate::experiment::experiment_assignment;
+use crate::exploration::map_discovery;
+use crate::friend::friend;
+use crate::grief_protection::ownership_tag;
+use crate::guild::{guild_invite, guild_member};
+use crate::hireling::hireling;
+use crate::inventory::{inventory_header, inventory_item, player_equipment};
+use crate::kill_credit::combat_credit;
+use crate::kill_feed::kill_feed;
+use crate::leaderboard::speedrun_entry;
+use crate::lfg::{group_application, group_listing};
+use crate::loadout::{hotbar_slot, loadout};
+use crate::loot_pity::loot_pity;
+use crate::localization::player_locale;
+use crate::mail::mail;
+use crate::mentor::{mentor_offer, mentorship};
+use crate::movement::player_jump;
+use crate::name_policy::rename_token;
+use crate::party::{party_invite, party_member, sender_player};
+use crate::perishable::item_freshness;
+use crate::profession::profession_skill;
+use crate::progression::player_progress;
+use crate::pvp::pvp_flag;
+use crate::rating::{arena_rating, season_rating};
+use crate::reputation::player_reputation;
+use crate::rested::rested_state;
+use crate::reward::login_streak;
+use crate::run_report::run_member_stat;
+use crate::scenic::{scenic_marker, scenic_vote};
+use crate::shadow_ban::{shadow_ban, shadow_chat_message};
+use crate::shared_bank::shared_bank_slot;
+use crate::status_effect::status_effect;
+use crate::story_flag::story_flag;
+use crate::structure::{structure, structure_access};
+use crate::teleporter::teleport_channel;
+use crate::threat::{enemy_taunt, threat_entry};
+use crate::tutorial::tutorial_run;
+use crate::world_boss::world_boss_contribution;
+use crate::world_first::{player_title, world_first};
+use crate::{player, player_registration, Player};
+use spacetimedb::sats::satn::Satn;
+use spacetimedb::{reducer, table, view, Identity, ReducerContext, SpacetimeType, Table, Timestamp, ViewContext};
+use std::time::Duration;
+
+/// Nome que substitui o do player em registros compartilhados (chat, feed, rankings)
+pub const ERASED_PLAYER_NAME: &str = "Deleted player";
+const EXPORT_COOLDOWN_MS: u64 = 60 * 60 * 1000;
+const EXPORT_TTL_SECS: u64 = 60 * 60;
+const CATEGORY_PRIVACY: &str = "privacy";
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct ExportSection {
+    pub table: String,
+    /// Cada linha em SATN, o mesmo formato do `spacetime sql`
+    pub rows: Vec<String>,
+}
+
+/// Resposta do `export_my_data`. Uma por identidade, lida só pela própria via
+/// `my_data_export`; a linha some após `expires_at`.
+#[table(name = data_export)]
+#[derive(Clone)]
+pub struct DataExport {
+    #[primary_key]
+    pub identity: Identity,
+    pub requested_at: Timestamp,
+    pub expires_at: Timestamp,
+    pub sections: Vec<ExportSection>,
+}
+
+/// O export do chamador; ninguém mais enxerga a linha
+#[view(name = my_data_export, public)]
+pub fn my_data_export(ctx: &ViewContext) -> Option<DataExport> {
+    ctx.db.data_export().identity().find(ctx.sender)
+}
+
+/// Pedido de exclusão de conta aguardando aprovação de um admin
+#[table(name = erasure_request, public)]
+#[derive(Clone)]
+pub struct ErasureRequest {
+    #[primary_key]
+    pub player_id: u32,
+    pub identity: Identity,
+    pub requested_at: Timestamp,
+}
+
+fn section<T: Satn>(table: &str, rows: impl Iterator<Item = T>) -> ExportSection {
+    ExportSection {
+        table: table.to_string(),
+        rows: rows.map(|row| row.to_satn()).collect(),
+    }
+}
+
+/// Todas as linhas ligadas ao player, agrupadas por tabela
+fn collect_player_data(ctx: &ReducerContext, player: &Player) -> Vec<ExportSection> {
+    let id = player.id;
+    let identity = player.identity;
+    let item_ids: Vec<u32> = ctx.db.inventory_item().iter().filter(|i| i.player_id == id).map(|i| i.id).collect();
+
+    let sections = vec![
+        section("player", std::iter::once(player.clone())),
+        section("player_registration", ctx.db.player_registration().player_id().find(id).into_iter()),
+        section("player_progress", ctx.db.player_progress().iter().filter(|r| r.player_id == id)),
+        section("player_currency", ctx.db.player_currency().iter().filter(|r| r.player_id == id)),
+        section("inventory_item", ctx.db.inventory_item().iter().filter(|r| r.player_id == id)),
+        section("item_freshness", ctx.db.item_freshness().iter().filter(|r| item_ids.contains(&r.inventory_item_id))),
+        section("inventory_header", ctx.db.inventory_header().iter().filter(|r| r.player_id == id)),
+        section("player_equipment", ctx.db.player_equipment().iter().filter(|r| r.player_id == id)),
+        section("hotbar_slot", ctx.db.hotbar_slot().iter().filter(|r| r.player_id == id)),
+        section("loadout", ctx.db.loadout().iter().filter(|r| r.player_id == id)),
+        section("player_appearance", ctx.db.player_appearance().iter().filter(|r| r.player_id == id)),
+        section("player_title", ctx.db.player_title().iter().filter(|r| r.player_id == id)),
+        section("player_achievement", ctx.db.player_achievement().iter().filter(|r| r.player_id == id)),
+        section("player_reputation", ctx.db.player_reputation().iter().filter(|r| r.player_id == id)),
+        section("profession_skill", ctx.db.profession_skill().iter().filter(|r| r.player_id == id)),
+        section("known_recipe", ctx.db.known_recipe().iter().filter(|r| r.player_id == id)),
+        section("craft_result", ctx.db.craft_result().iter().filter(|r| r.player_id == id)),
+        section("bestiary_entry", ctx.db.bestiary_entry().iter().filter(|r| r.player_id == id)),
+        section("map_discovery", ctx.db.map_discovery().iter().filter(|r| r.player_id == id)),
+        section("status_effect", ctx.db.status_effect().iter().filter(|r| r.player_id == id)),
+        section("rested_state", ctx.db.rested_state().iter().filter(|r| r.player_id == id)),
+        section("login_streak", ctx.db.login_streak().iter().filter(|r| r.player_id == id)),
+        section("rename_token", ctx.db.rename_token().iter().filter(|r| r.player_id == id)),
+        section("loot_pity", ctx.db.loot_pity().player_id().find(id).into_iter()),
+        section("farming_session", ctx.db.farming_session().player_id().find(id).into_iter()),
+        section("afk_review", ctx.db.afk_review().player_id().filter(id)),
+        section("goal_contribution", ctx.db.goal_contribution().iter().filter(|r| r.player_id == id)),
+        section("hireling", ctx.db.hireling().owner_id().find(id).into_iter()),
+        section("tower_progress", ctx.db.tower_progress().player_id().find(id).into_iter()),
+        section("tower_ladder_entry", ctx.db.tower_ladder_entry().iter().filter(|r| r.player_id == id)),
+        section("story_flag", ctx.db.story_flag().player_id().filter(id)),
+        section("account_unlock", ctx.db.account_unlock().identity().filter(identity)),
+        section("account_transfer", ctx.db.account_transfer().from_identity().find(identity).into_iter()),
+        section("shared_bank_slot", ctx.db.shared_bank_slot().identity().filter(identity)),
+        section("action_trail", ctx.db.action_trail().player_id().filter(id)),
+        section("bug_report", ctx.db.bug_report().reporter_id().filter(id)),
+        section("experiment_assignment", ctx.db.experiment_assignment().identity().filter(identity)),
+        section("player_locale", ctx.db.player_locale().iter().filter(|r| r.identity == identity)),
+        section("client_info", ctx.db.client_info().iter().filter(|r| r.identity == identity)),
+        section("client_error_event", ctx.db.client_error_event().iter().filter(|r| r.identity == identity)),
+        section("cooldown", ctx.db.cooldown().iter().filter(|r| r.identity == identity)),
+        section("queued_ability", ctx.db.queued_ability().iter().filter(|r| r.player_id == id)),
+        section("combat_state", ctx.db.combat_state().iter().filter(|r| r.player_id == id)),
+        section("combat_credit", ctx.db.combat_credit().iter().filter(|r| r.player_id == id)),
+        section("defensive_stance", ctx.db.defensive_stance().iter().filter(|r| r.player_id == id)),
+        section("player_jump", ctx.db.player_jump().iter().filter(|r| r.player_id == id)),
+        section("recent_damage", ctx.db.recent_damage().iter().filter(|r| r.target_id == id)),
+        section("death_recap", ctx.db.death_recap().iter().filter(|r| r.player_id == id)),
+        section("encounter_stat", ctx.db.encounter_stat().iter().filter(|r| r.player_id == id)),
+        section("run_member_stat", ctx.db.run_member_stat().iter().filter(|r| r.player_id == id)),
+        section("pvp_flag", ctx.db.pvp_flag().iter().filter(|r| r.player_id == id)),
+        section("arena_rating", ctx.db.arena_rating().iter().filter(|r| r.player_id == id)),
+        section("season_rating", ctx.db.season_rating().iter().filter(|r| r.player_id == id)),
+        section("match_spectator", ctx.db.match_spectator().iter().filter(|r| r.player_id == id)),
+        section("world_boss_contribution", ctx.db.world_boss_contribution().iter().filter(|r| r.player_id == id)),
+        section("caravan_delivery", ctx.db.caravan_delivery().iter().filter(|r| r.player_id == id)),
+        section("teleport_channel", ctx.db.teleport_channel().iter().filter(|r| r.player_id == id)),
+        section("guild_member", ctx.db.guild_member().iter().filter(|r| r.player_id == id)),
+        section("guild_invite", ctx.db.guild_invite().iter().filter(|r| r.player_id == id)),
+        section("party_member", ctx.db.party_member().iter().filter(|r| r.player_id == id)),
+        section("party_invite", ctx.db.party_invite().iter().filter(|r| r.player_id == id)),
+        section("friend", ctx.db.friend().iter().filter(|r| r.player_id == id || r.friend_id == id)),
+        section("mentorship", ctx.db.mentorship().iter().filter(|r| r.mentor_id == id || r.apprentice_id == id)),
+        section("mentor_offer", ctx.db.mentor_offer().iter().filter(|r| r.mentor_id == id || r.apprentice_id == id)),
+        section("group_listing", ctx.db.group_listing().iter().filter(|r| r.leader_id == id)),
+        section("group_application", ctx.db.group_application().iter().filter(|r| r.player_id == id)),
+        section("mail", ctx.db.mail().iter().filter(|r| r.recipient_id == id)),
+        section("escrow_hold", ctx.db.escrow_hold().iter().filter(|r| r.owner_id == id)),
+        section("structure", ctx.db.structure().iter().filter(|r| r.owner_id == Some(id))),
+        section("structure_access", ctx.db.structure_access().iter().filter(|r| r.player_id == id)),
+        section("scenic_marker", ctx.db.scenic_marker().iter().filter(|r| r.owner_id == id)),
+        section("scenic_vote", ctx.db.scenic_vote().iter().filter(|r| r.voter_id == id)),
+        section("chat_message", ctx.db.chat_message().iter().filter(|r| r.sender_id == id)),
+        section("shadow_chat_message", ctx.db.shadow_chat_message().sender_id().filter(id)),
+        section("ownership_tag", ctx.db.ownership_tag().owner_id().filter(id)),
+        section("tutorial_run", ctx.db.tutorial_run().player_id().find(id).into_iter()),
+    ];
+    sections.into_iter().filter(|s| !s.rows.is_empty()).collect()
+}
+
+/// Gera (ou substitui) o export de dados do player chamador
+#[reducer]
+pub fn export_my_data(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    try_start_cooldown(ctx, ctx.sender, CATEGORY_PRIVACY, "export", EXPORT_COOLDOWN_MS)?;
+
+    let export = DataExport {
+        identity: ctx.sender,
+        requested_at: ctx.timestamp,
+        expires_at: ctx.timestamp + Duration::from_secs(EXPORT_TTL_SECS),
+        sections: collect_player_data(ctx, &player),
+    };
+    let rows: usize = export.sections.iter().map(|s| s.rows.len()).sum();
+    if ctx.db.data_export().identity().find(ctx.sender).is_some() {
+        ctx.db.data_export().identity().update(export);
+    } else {
+        ctx.db.data_export().insert(export);
+    }
+    log::info!("📦 Player {} exported {} rows of personal data", player.id, rows);
+    Ok(())
+}
+
+/// Remove exports vencidos (chamado pela limpeza periódica)
+pub fn prune_expired_exports(ctx: &ReducerContext) {
+    let expired: Vec<Identity> = ctx.db.data_export().iter()
+        .filter(|e| e.expires_at <= ctx.timestamp)
+        .map(|e| e.identity)
+        .collect();
+    for identity in expired {
+        ctx.db.data_export().identity().delete(identity);
+    }
+}
+
+/// O player pede a exclusão da própria conta; um admin precisa aprovar
+#[reducer]
+pub fn request_account_erasure(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if ctx.db.erasure_request().player_id().find(player.id).is_some() {
+        return Err("Account erasure already requested".to_string());
+    }
+    ctx.db.erasure_request().insert(ErasureRequest {
+        player_id: player.id,
+        identity: player.identity,
+        requested_at: ctx.timestamp,
+    });
+    log::info!("🗑️ Player {} requested account erasure", player.id);
+    Ok(())
+}
+
+#[reducer]
+pub fn cancel_account_erasure(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if !ctx.db.erasure_request().player_id().delete(player.id) {
+        return Err("No pending account erasure".to_string());
+    }
+    log::info!("🗑️ Player {} cancelled account erasure", player.id);
+    Ok(())
+}
+
+/// Aprova um pedido pendente e apaga a conta
+#[reducer]
+pub fn approve_account_erasure(ctx: &ReducerContext, player_id: u32) -> Result<(), String> {
+    require_admin(ctx)?;
+    let request = ctx.db.erasure_request().player_id().find(player_id).ok_or("No pending erasure for that player")?;
+    let player = ctx.db.player().id().find(player_id).ok_or("Player not found")?;
+    if player.identity != request.identity {
+        return Err("Erasure request does not match the player's identity".to_string());
+    }
+
+    erase_player(ctx, &player);
+    ctx.db.erasure_request().player_id().delete(player_id);
+    record_audit(ctx, "privacy", format!("Erased account of player {}", player_id));
+    Ok(())
+}
+
+/// Apaga uma lista de linhas pela chave primária
+macro_rules! purge {
+    ($ctx:expr, $table:ident, $pk:ident, |$row:ident| $cond:expr) => {{
+        let keys: Vec<_> = $ctx.db.$table().iter().filter(|$row| $cond).map(|$row| $row.$pk).collect();
+        for key in keys {
+            $ctx.db.$table().$pk().delete(key);
+        }
+    }};
+}
+
+/// Apaga os dados pessoais e anonimiza os registros compartilhados.
+/// Históricos coletivos (torneios, relatórios de run, encomendas) mantêm só o
+/// id numérico, que deixa de apontar para alguém depois que a linha `player` some.
+fn erase_player(ctx: &ReducerContext, player: &Player) {
+    let id = player.id;
+    let identity = player.identity;
+
+    // Vínculos com outros players: liderança passa adiante, pendências são desfeitas
+    if let Some(guild_id) = crate::guild::guild_of(ctx, id) {
+        crate::guild::remove_member(ctx, id, guild_id);
+    }
+    if let Some(party_id) = crate::party::party_of(ctx, id) {
+        crate::party::remove_member(ctx, id, party_id);
+    }
+    if let Some(listing) = ctx.db.group_listing().leader_id().find(id) {
+        crate::lfg::delete_listing(ctx, listing.id);
+    }
+    crate::work_order::on_player_erased(ctx, id);
+    crate::tutorial::on_player_erased(ctx, id);
+    for owned in ctx.db.structure().iter().filter(|s| s.owner_id == Some(id)).collect::<Vec<_>>() {
+        crate::structure::remove_structure(ctx, &owned);
+    }
+    let markers: Vec<u64> = ctx.db.scenic_marker().iter().filter(|m| m.owner_id == id).map(|m| m.id).collect();
+    purge!(ctx, scenic_vote, id, |v| v.voter_id == id || markers.contains(&v.marker_id));
+    purge!(ctx, scenic_marker, id, |m| m.owner_id == id);
+
+    // Dados pessoais
+    let item_ids: Vec<u32> = ctx.db.inventory_item().iter().filter(|i| i.player_id == id).map(|i| i.id).collect();
+    purge!(ctx, item_freshness, inventory_item_id, |f| item_ids.contains(&f.inventory_item_id));
+    purge!(ctx, inventory_item, id, |r| r.player_id == id);
+    purge!(ctx, inventory_header, player_id, |r| r.player_id == id);
+    purge!(ctx, player_equipment, player_id, |r| r.player_id == id);
+    purge!(ctx, escrow_hold, id, |r| r.owner_id == id);
+    purge!(ctx, player_registration, player_id, |r| r.player_id == id);
+    purge!(ctx, player_progress, player_id, |r| r.player_id == id);
+    purge!(ctx, player_currency, id, |r| r.player_id == id);
+    purge!(ctx, hotbar_slot, id, |r| r.player_id == id);
+    purge!(ctx, loadout, id, |r| r.player_id == id);
+    purge!(ctx, player_appearance, player_id, |r| r.player_id == id);
+    purge!(ctx, player_title, id, |r| r.player_id == id);
+    purge!(ctx, player_achievement, id, |r| r.player_id == id);
+    purge!(ctx, player_reputation, id, |r| r.player_id == id);
+    purge!(ctx, profession_skill, id, |r| r.player_id == id);
+    purge!(ctx, known_recipe, id, |r| r.player_id == id);
+    purge!(ctx, craft_result, player_id, |r| r.player_id == id);
+    purge!(ctx, bestiary_entry, id, |r| r.player_id == id);
+    purge!(ctx, map_discovery, id, |r| r.player_id == id);
+    purge!(ctx, status_effect, id, |r| r.player_id == id);
+    purge!(ctx, rested_state, player_id, |r| r.player_id == id);
+    purge!(ctx, login_streak, player_id, |r| r.player_id == id);
+    purge!(ctx, rename_token, player_id, |r| r.player_id == id);
+    purge!(ctx, loot_pity, player_id, |r| r.player_id == id);
+    purge!(ctx, farming_session, player_id, |r| r.player_id == id);
+    purge!(ctx, afk_review, id, |r| r.player_id == id);
+    purge!(ctx, goal_contribution, id, |r| r.player_id == id);
+    purge!(ctx, hireling, id, |r| r.owner_id == id);
+    purge!(ctx, tower_progress, player_id, |r| r.player_id == id);
+    purge!(ctx, tower_ladder_entry, id, |r| r.player_id == id);
+    purge!(ctx, story_flag, id, |r| r.player_id == id);
+    purge!(ctx, account_unlock, id, |r| r.identity == identity);
+    purge!(ctx, account_transfer, from_identity, |r| r.from_identity == identity || r.to_identity == identity);
+    purge!(ctx, shared_bank_slot, id, |r| r.identity == identity);
+    purge!(ctx, action_trail, id, |r| r.player_id == id);
+    purge!(ctx, bug_report, id, |r| r.reporter_id == id);
+    purge!(ctx, experiment_assignment, id, |r| r.identity == identity);
+    purge!(ctx, shadow_chat_message, id, |r| r.sender_id == id);
+    purge!(ctx, shadow_ban, identity, |r| r.identity == identity);
+    purge!(ctx, ownership_tag, id, |r| r.owner_id == id);
+    purge!(ctx, player_locale, identity, |r| r.identity == identity);
+    purge!(ctx, client_info, identity, |r| r.identity == identity);
+    purge!(ctx, client_error_event, id, |r| r.identity == identity);
+    purge!(ctx, cooldown, id, |r| r.identity == identity);
+    purge!(ctx, data_export, identity, |r| r.identity == identity);
+    purge!(ctx, queued_ability, player_id, |r| r.player_id == id);
+    purge!(ctx, combat_state, player_id, |r| r.player_id == id);
+    purge!(ctx, combat_credit, player_id, |r| r.player_id == id);
+    purge!(ctx, defensive_stance, player_id, |r| r.player_id == id);
+    purge!(ctx, player_jump, player_id, |r| r.player_id == id);
+    purge!(ctx, recent_damage, id, |r| r.target_id == id);
+    purge!(ctx, death_recap, id, |r| r.player_id == id);
+    purge!(ctx, encounter_stat, id, |r| r.player_id == id);
+    purge!(ctx, run_member_stat, id, |r| r.player_id == id);
+    purge!(ctx, pvp_flag, player_id, |r| r.player_id == id);
+    purge!(ctx, arena_rating, player_id, |r| r.player_id == id);
+    purge!(ctx, season_rating, id, |r| r.player_id == id);
+    purge!(ctx, match_spectator, player_id, |r| r.player_id == id);
+    purge!(ctx, world_boss_contribution, id, |r| r.player_id == id);
+    purge!(ctx, caravan_delivery, player_id, |r| r.player_id == id);
+    purge!(ctx, teleport_channel, player_id, |r| r.player_id == id);
+    purge!(ctx, guild_invite, id, |r| r.player_id == id);
+    purge!(ctx, party_invite, id, |r| r.player_id == id);
+    purge!(ctx, group_application, id, |r| r.player_id == id);
+    purge!(ctx, friend, id, |r| r.player_id == id || r.friend_id == id);
+    purge!(ctx, mentorship, id, |r| r.mentor_id == id || r.apprentice_id == id);
+    purge!(ctx, mentor_offer, id, |r| r.mentor_id == id || r.apprentice_id == id);
+    purge!(ctx, mail, id, |r| r.recipient_id == id);
+    purge!(ctx, structure_access, id, |r| r.player_id == id);
+    purge!(ctx, threat_entry, id, |r| r.player_id == id);
+    purge!(ctx, enemy_taunt, enemy_id, |r| r.player_id == id);
+
+    anonymize_references(ctx, player);
+
+    crate::map::move_map_population(ctx, Some(&player.current_map_id), None);
+    ctx.db.player().id().delete(id);
+    log::info!("🗑️ Player {} erased", id);
+}
+
+/// Registros de outros players e do servidor ficam, mas sem o nome ou a identidade
+fn anonymize_references(ctx: &ReducerContext, player: &Player) {
+    let id = player.id;
+    let anonymous = ERASED_PLAYER_NAME.to_string();
+
+    for mut message in ctx.db.chat_message().iter().filter(|m| m.sender_id == id).collect::<Vec<_>>() {
+        message.sender_name = anonymous.clone();
+        ctx.db.chat_message().id().update(message);
+    }
+    for mut entry in ctx.db.kill_feed().iter().filter(|k| k.killer_id == id || k.victim_id == id).collect::<Vec<_>>() {
+        if entry.killer_id == id {
+            entry.killer_name = anonymous.clone();
+        }
+        if entry.victim_id == id {
+            entry.victim_name = anonymous.clone();
+        }
+        ctx.db.kill_feed().id().update(entry);
+    }
+    for mut entry in ctx.db.speedrun_entry().iter().filter(|s| s.member_ids.contains(&id)).collect::<Vec<_>>() {
+        for (member_id, name) in entry.member_ids.iter().zip(entry.member_names.iter_mut()) {
+            if *member_id == id {
+                *name = anonymous.clone();
+            }
+        }
+        ctx.db.speedrun_entry().id().update(entry);
+    }
+    for mut first in ctx.db.world_first().iter().filter(|w| w.player_ids.contains(&id)).collect::<Vec<_>>() {
+        for (player_id, name) in first.player_ids.iter().zip(first.player_names.iter_mut()) {
+            if *player_id == id {
+                *name = anonymous.clone();
+            }
+        }
+        ctx.db.world_first().id().update(first);
+    }
+    for mut entry in ctx.db.audit_log().iter().filter(|a| a.actor == player.identity).collect::<Vec<_>>() {
+        entry.actor = Identity::ZERO;
+        ctx.db.audit_log().id().update(entry);
+    }
+    crate::external_event::anonymize_player(ctx, id, &anonymous);
+}
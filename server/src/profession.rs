@@ -0,0 +1,134 @@
+use crate::party::sender_player;
+use spacetimedb::rand::Rng;
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+pub const PROFESSION_BLACKSMITHING: &str = "blacksmithing";
+pub const PROFESSION_COOKING: &str = "cooking";
+pub const PROFESSION_ALCHEMY: &str = "alchemy";
+pub const PROFESSIONS: &[&str] = &[PROFESSION_BLACKSMITHING, PROFESSION_COOKING, PROFESSION_ALCHEMY];
+
+/// Especializações disponíveis em qualquer profissão
+pub const SPEC_BOUNTIFUL: &str = "bountiful"; // chance de produção extra
+pub const SPEC_FRUGAL: &str = "frugal"; // reduz o custo de materiais
+
+pub const MAX_PROFESSION_LEVEL: u32 = 50;
+/// Nível mínimo para escolher uma especialização
+pub const SPECIALIZATION_LEVEL: u32 = 10;
+/// A partir deste nível os perks da especialização ficam mais fortes
+pub const MASTERY_LEVEL: u32 = 25;
+const XP_PER_LEVEL: u64 = 100;
+
+const BOUNTIFUL_CHANCE: f64 = 0.2;
+const BOUNTIFUL_MASTERY_CHANCE: f64 = 0.35;
+const FRUGAL_REDUCTION: f32 = 0.25;
+const FRUGAL_MASTERY_REDUCTION: f32 = 0.4;
+
+/// Nível e especialização de um player em uma profissão
+#[table(name = profession_skill, public)]
+#[derive(Clone)]
+pub struct ProfessionSkill {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub profession: String,
+    pub xp: u64,
+    pub level: u32,
+    pub specialization: Option<String>,
+    pub updated_at: Timestamp,
+}
+
+pub fn profession_level_for_xp(xp: u64) -> u32 {
+    ((xp / XP_PER_LEVEL) as u32 + 1).min(MAX_PROFESSION_LEVEL)
+}
+
+pub fn profession_skill(ctx: &ReducerContext, player_id: u32, profession: &str) -> Option<ProfessionSkill> {
+    ctx.db.profession_skill().player_id().filter(player_id).find(|s| s.profession == profession)
+}
+
+pub fn profession_level(ctx: &ReducerContext, player_id: u32, profession: &str) -> u32 {
+    profession_skill(ctx, player_id, profession).map(|s| s.level).unwrap_or(1)
+}
+
+/// Profissão que se beneficia da coleta de um recurso
+pub fn gathering_profession(item_id: &str) -> Option<&'static str> {
+    match item_id {
+        "fruit" => Some(PROFESSION_COOKING),
+        "wood" | "stone" | "stone_fragment" => Some(PROFESSION_BLACKSMITHING),
+        _ => None,
+    }
+}
+
+pub fn grant_profession_xp(ctx: &ReducerContext, player_id: u32, profession: &str, amount: u64) {
+    let mut skill = profession_skill(ctx, player_id, profession).unwrap_or(ProfessionSkill {
+        id: 0,
+        player_id,
+        profession: profession.to_string(),
+        xp: 0,
+        level: 1,
+        specialization: None,
+        updated_at: ctx.timestamp,
+    });
+    let old_level = skill.level;
+    skill.xp = skill.xp.saturating_add(amount);
+    skill.level = profession_level_for_xp(skill.xp);
+    skill.updated_at = ctx.timestamp;
+    let level = skill.level;
+
+    if skill.id == 0 {
+        ctx.db.profession_skill().insert(skill);
+    } else {
+        ctx.db.profession_skill().id().update(skill);
+    }
+    if level > old_level {
+        log::info!("🔨 Player {} reached {} level {}", player_id, profession, level);
+    }
+}
+
+fn active_specialization(ctx: &ReducerContext, player_id: u32, profession: &str, spec: &str) -> Option<ProfessionSkill> {
+    profession_skill(ctx, player_id, profession)
+        .filter(|s| s.specialization.as_deref() == Some(spec))
+}
+
+/// Rola o perk "bountiful": retorna a quantidade extra produzida
+pub fn roll_extra_yield(ctx: &ReducerContext, player_id: u32, profession: &str, base_quantity: i32) -> i32 {
+    let Some(skill) = active_specialization(ctx, player_id, profession, SPEC_BOUNTIFUL) else {
+        return 0;
+    };
+    let chance = if skill.level >= MASTERY_LEVEL { BOUNTIFUL_MASTERY_CHANCE } else { BOUNTIFUL_CHANCE };
+    if ctx.rng().gen_bool(chance) { base_quantity.max(1) } else { 0 }
+}
+
+/// Aplica o perk "frugal" a um ingrediente (nunca abaixo de 1)
+pub fn material_cost(ctx: &ReducerContext, player_id: u32, profession: &str, quantity: i32) -> i32 {
+    let Some(skill) = active_specialization(ctx, player_id, profession, SPEC_FRUGAL) else {
+        return quantity;
+    };
+    let reduction = if skill.level >= MASTERY_LEVEL { FRUGAL_MASTERY_REDUCTION } else { FRUGAL_REDUCTION };
+    ((quantity as f32 * (1.0 - reduction)).ceil() as i32).clamp(1, quantity.max(1))
+}
+
+/// Escolhe (uma única vez) a especialização de uma profissão
+#[reducer]
+pub fn choose_specialization(ctx: &ReducerContext, profession: String, specialization: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if !PROFESSIONS.contains(&profession.as_str()) {
+        return Err(format!("Unknown profession '{}'", profession));
+    }
+    if specialization != SPEC_BOUNTIFUL && specialization != SPEC_FRUGAL {
+        return Err(format!("Unknown specialization '{}'", specialization));
+    }
+    let mut skill = profession_skill(ctx, player.id, &profession)
+        .filter(|s| s.level >= SPECIALIZATION_LEVEL)
+        .ok_or_else(|| format!("Requires {} level {}", profession, SPECIALIZATION_LEVEL))?;
+    if skill.specialization.is_some() {
+        return Err("Specialization already chosen".to_string());
+    }
+
+    skill.specialization = Some(specialization.clone());
+    skill.updated_at = ctx.timestamp;
+    ctx.db.profession_skill().id().update(skill);
+    log::info!("🔨 Player {} specialized in {} ({})", player.id, profession, specialization);
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use crate::config::{get_player_config_f32, XP_RATE};
+use crate::player;
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+
+pub const MAX_LEVEL: u32 = 50;
+
+/// XP e nível de cada player
+#[table(name = player_progress, public)]
+#[derive(Clone)]
+pub struct PlayerProgress {
+    #[primary_key]
+    pub player_id: u32,
+    pub xp: u64,
+    pub level: u32,
+    pub updated_at: Timestamp,
+}
+
+/// XP total necessária para alcançar `level` (nível 1 = 0 XP)
+pub fn xp_for_level(level: u32) -> u64 {
+    let l = level.saturating_sub(1) as u64;
+    50 * l * (l + 1)
+}
+
+pub fn level_for_xp(xp: u64) -> u32 {
+    let mut level = 1;
+    while level < MAX_LEVEL && xp >= xp_for_level(level + 1) {
+        level += 1;
+    }
+    level
+}
+
+pub fn get_progress(ctx: &ReducerContext, player_id: u32) -> PlayerProgress {
+    ctx.db.player_progress().player_id().find(player_id).unwrap_or(PlayerProgress {
+        player_id,
+        xp: 0,
+        level: 1,
+        updated_at: ctx.timestamp,
+    })
+}
+
+/// Concede XP e recalcula o nível. Retorna o progresso atualizado.
+pub fn grant_xp(ctx: &ReducerContext, player_id: u32, amount: u64, source: &str) -> PlayerProgress {
+    let existing = ctx.db.player_progress().player_id().find(player_id);
+    let mut progress = existing.clone().unwrap_or_else(|| get_progress(ctx, player_id));
+
+    let amount = crate::mentor::apply_apprentice_bonus(ctx, player_id, amount);
+    let identity = ctx.db.player().id().find(player_id).map(|p| p.identity);
+    let amount = match identity {
+        Some(identity) => (amount as f64 * get_player_config_f32(ctx, identity, XP_RATE, 1.0).max(0.0) as f64) as u64,
+        None => amount,
+    };
+    let old_level = progress.level;
+    progress.xp = progress.xp.saturating_add(amount);
+    progress.level = level_for_xp(progress.xp);
+    progress.updated_at = ctx.timestamp;
+
+    if existing.is_some() {
+        ctx.db.player_progress().player_id().update(progress.clone());
+    } else {
+        ctx.db.player_progress().insert(progress.clone());
+    }
+
+    log::info!("⭐ Player {} +{} XP ({}) -> {} XP, level {}", player_id, amount, source, progress.xp, progress.level);
+    if let Some(identity) = identity {
+        crate::experiment::record_experiment_metric(ctx, identity, "xp_gained", amount as f64);
+        if progress.level > old_level {
+            crate::experiment::record_experiment_metric(ctx, identity, "levels_gained", (progress.level - old_level) as f64);
+        }
+    }
+    if progress.level > old_level {
+        log::info!("🎉 Player {} reached level {}", player_id, progress.level);
+        crate::mentor::on_apprentice_level_up(ctx, player_id, progress.level);
+        if progress.level == MAX_LEVEL {
+            crate::world_first::record_world_first(
+                ctx, crate::world_first::FIRST_LEVEL_CAP,
+                format!("reached level {}", MAX_LEVEL), "the Ascended", &[player_id],
+            );
+        }
+    }
+    progress
+}
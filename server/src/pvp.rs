@@ -0,0 +1,81 @@
+use crate::cooldown::{try_start_cooldown, CATEGORY_PVP, PVP_FLAG_COOLDOWN_MS};
+use crate::feature_flag::{is_feature_enabled, PVP_ENABLED};
+use crate::map::template_for_map;
+use crate::party::sender_player;
+use crate::player;
+use spacetimedb::{reducer, table, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+/// Tempo entre o toggle e a mudança valer (evita ligar/desligar no meio de uma luta)
+pub const PVP_FLAG_GRACE_SECS: u64 = 10;
+
+/// Flag de PvP opcional de cada player. A mudança pedida fica em `pending_flag`
+/// até `effective_at`, e é resolvida na leitura.
+#[table(name = pvp_flag, public)]
+#[derive(Clone)]
+pub struct PvpFlag {
+    #[primary_key]
+    pub player_id: u32,
+    pub flagged: bool,
+    pub pending_flag: Option<bool>,
+    pub effective_at: Timestamp,
+}
+
+pub fn is_pvp_flagged(ctx: &ReducerContext, player_id: u32) -> bool {
+    match ctx.db.pvp_flag().player_id().find(player_id) {
+        Some(flag) => match flag.pending_flag {
+            Some(pending) if flag.effective_at <= ctx.timestamp => pending,
+            _ => flag.flagged,
+        },
+        None => false,
+    }
+}
+
+/// Cidades são zonas seguras para a flag de PvP
+pub fn is_safe_zone(ctx: &ReducerContext, map_id: &str) -> bool {
+    template_for_map(ctx, map_id).is_some_and(|t| t.is_town)
+}
+
+/// Regra usada pela função de dano compartilhada para ataques entre players
+pub fn can_damage_player(ctx: &ReducerContext, attacker_id: u32, target_id: u32) -> bool {
+    if crate::arena::are_opponents(ctx, attacker_id, target_id) {
+        return true;
+    }
+    // Pacto de não agressão entre guildas aliadas
+    if crate::alliance::are_allied(ctx, attacker_id, target_id) {
+        return false;
+    }
+    if is_feature_enabled(ctx, PVP_ENABLED)
+        || crate::guild_war::at_war(ctx, attacker_id, target_id)
+    {
+        return true;
+    }
+    let Some(target) = ctx.db.player().id().find(target_id) else {
+        return false;
+    };
+    !is_safe_zone(ctx, &target.current_map_id)
+        && is_pvp_flagged(ctx, attacker_id)
+        && is_pvp_flagged(ctx, target_id)
+}
+
+#[reducer]
+pub fn toggle_pvp_flag(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    try_start_cooldown(ctx, ctx.sender, CATEGORY_PVP, "pvp_flag", PVP_FLAG_COOLDOWN_MS)?;
+
+    let current = is_pvp_flagged(ctx, player.id);
+    let flag = PvpFlag {
+        player_id: player.id,
+        flagged: current,
+        pending_flag: Some(!current),
+        effective_at: ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(PVP_FLAG_GRACE_SECS)),
+    };
+    if ctx.db.pvp_flag().player_id().find(player.id).is_some() {
+        ctx.db.pvp_flag().player_id().update(flag);
+    } else {
+        ctx.db.pvp_flag().insert(flag);
+    }
+
+    log::info!("⚔️ Player {} PvP flag -> {} in {}s", player.id, !current, PVP_FLAG_GRACE_SECS);
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use crate::crafting::ItemStack;
+use crate::mail::send_system_mail;
+use crate::season::Season;
+use spacetimedb::{reducer, table, ReducerContext, ScheduleAt, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+pub const STARTING_RATING: i32 = 1500;
+/// Partidas de colocação: o rating se move mais rápido até o player ser posicionado
+pub const PLACEMENT_MATCHES: u32 = 5;
+/// Partidas de colocação ao começar uma nova temporada
+const SEASON_PLACEMENT_MATCHES: u32 = 2;
+const K_FACTOR: f32 = 32.0;
+const PLACEMENT_K_FACTOR: f32 = 64.0;
+
+pub const RATING_DECAY_INTERVAL_SECS: u64 = 7 * 24 * 60 * 60;
+/// Inatividade a partir da qual o rating decai
+const DECAY_INACTIVITY_SECS: u64 = 14 * 24 * 60 * 60;
+const DECAY_AMOUNT: i32 = 25;
+/// O decaimento nunca leva o rating abaixo deste valor
+const DECAY_FLOOR: i32 = STARTING_RATING;
+
+/// Recompensas de fim de temporada: (rating mínimo, ouro, item)
+const SEASON_REWARDS: &[(i32, u64, &str)] = &[
+    (2000, 1000, "arena_trophy_gold"),
+    (1750, 500, "arena_trophy_silver"),
+    (1500, 200, "arena_trophy_bronze"),
+];
+
+/// Rating de arena ranqueada de cada player na temporada atual
+#[table(name = arena_rating, public)]
+#[derive(Clone)]
+pub struct ArenaRating {
+    #[primary_key]
+    pub player_id: u32,
+    pub rating: i32,
+    pub peak_rating: i32,
+    pub matches_played: u32,
+    pub placement_remaining: u32,
+    pub last_match_at: Option<Timestamp>,
+}
+
+/// Arquivo do rating final de cada player por temporada
+#[table(name = season_rating, public)]
+#[derive(Clone)]
+pub struct SeasonRating {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub season_id: u64,
+    pub player_id: u32,
+    pub rating: i32,
+    pub peak_rating: i32,
+    pub matches_played: u32,
+    pub rank: u32,
+}
+
+#[table(name = rating_decay_schedule, scheduled(process_rating_decay))]
+pub struct RatingDecaySchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+pub fn ensure_rating_decay_schedule(ctx: &ReducerContext) {
+    if ctx.db.rating_decay_schedule().count() == 0 {
+        ctx.db.rating_decay_schedule().insert(RatingDecaySchedule {
+            scheduled_id: 0,
+            scheduled_at: Duration::from_secs(RATING_DECAY_INTERVAL_SECS).into(),
+        });
+    }
+}
+
+pub fn is_placed(rating: &ArenaRating) -> bool {
+    rating.placement_remaining == 0
+}
+
+fn get_rating(ctx: &ReducerContext, player_id: u32) -> ArenaRating {
+    ctx.db.arena_rating().player_id().find(player_id).unwrap_or(ArenaRating {
+        player_id,
+        rating: STARTING_RATING,
+        peak_rating: STARTING_RATING,
+        matches_played: 0,
+        placement_remaining: PLACEMENT_MATCHES,
+        last_match_at: None,
+    })
+}
+
+fn save_rating(ctx: &ReducerContext, rating: ArenaRating) {
+    if ctx.db.arena_rating().player_id().find(rating.player_id).is_some() {
+        ctx.db.arena_rating().player_id().update(rating);
+    } else {
+        ctx.db.arena_rating().insert(rating);
+    }
+}
+
+fn average_rating(ctx: &ReducerContext, player_ids: &[u32]) -> f32 {
+    if player_ids.is_empty() {
+        return STARTING_RATING as f32;
+    }
+    player_ids.iter().map(|id| get_rating(ctx, *id).rating as f32).sum::<f32>() / player_ids.len() as f32
+}
+
+/// Atualiza os ratings (Elo por média do time) de uma partida ranqueada.
+/// `score_a` = 1.0 vitória do time A, 0.5 empate, 0.0 derrota.
+pub fn record_match_result(ctx: &ReducerContext, team_a: &[u32], team_b: &[u32], score_a: f32) {
+    let avg_a = average_rating(ctx, team_a);
+    let avg_b = average_rating(ctx, team_b);
+    let expected_a = 1.0 / (1.0 + 10f32.powf((avg_b - avg_a) / 400.0));
+
+    for (ids, score, expected) in [(team_a, score_a, expected_a), (team_b, 1.0 - score_a, 1.0 - expected_a)] {
+        for player_id in ids {
+            let mut rating = get_rating(ctx, *player_id);
+            let k = if is_placed(&rating) { K_FACTOR } else { PLACEMENT_K_FACTOR };
+            rating.rating += (k * (score - expected)).round() as i32;
+            rating.peak_rating = rating.peak_rating.max(rating.rating);
+            rating.matches_played += 1;
+            rating.placement_remaining = rating.placement_remaining.saturating_sub(1);
+            rating.last_match_at = Some(ctx.timestamp);
+            log::info!("🏅 Player {} arena rating -> {}", player_id, rating.rating);
+            save_rating(ctx, rating);
+        }
+    }
+}
+
+/// Decaimento semanal por inatividade (apenas players já posicionados)
+#[reducer]
+pub fn process_rating_decay(ctx: &ReducerContext, _schedule: RatingDecaySchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("process_rating_decay may only be invoked by the scheduler".to_string());
+    }
+    if crate::upgrade::is_frozen(ctx) {
+        return Ok(());
+    }
+    let cutoff = ctx.timestamp - TimeDuration::from_duration(Duration::from_secs(DECAY_INACTIVITY_SECS));
+    let inactive: Vec<ArenaRating> = ctx.db.arena_rating().iter()
+        .filter(|r| is_placed(r) && r.rating > DECAY_FLOOR)
+        .filter(|r| r.last_match_at.is_none_or(|t| t < cutoff))
+        .collect();
+    for mut rating in inactive {
+        rating.rating = (rating.rating - DECAY_AMOUNT).max(DECAY_FLOOR);
+        ctx.db.arena_rating().player_id().update(rating);
+    }
+    Ok(())
+}
+
+/// Arquiva os ratings da temporada, envia as recompensas por correio e
+/// aplica o reset parcial para a próxima temporada
+pub fn archive_season_ratings(ctx: &ReducerContext, season: &Season) {
+    let mut placed: Vec<ArenaRating> = ctx.db.arena_rating().iter().filter(is_placed).collect();
+    placed.sort_by_key(|r| std::cmp::Reverse(r.rating));
+
+    for (index, rating) in placed.iter().enumerate() {
+        let rank = index as u32 + 1;
+        ctx.db.season_rating().insert(SeasonRating {
+            id: 0,
+            season_id: season.id,
+            player_id: rating.player_id,
+            rating: rating.rating,
+            peak_rating: rating.peak_rating,
+            matches_played: rating.matches_played,
+            rank,
+        });
+
+        if let Some((_, gold, item)) = SEASON_REWARDS.iter().find(|(min, _, _)| rating.rating >= *min) {
+            send_system_mail(
+                ctx,
+                rating.player_id,
+                "Arena season rewards",
+                format!("{} ended with a rating of {} (rank #{}).", season.name, rating.rating, rank),
+                vec![ItemStack { item_id: item.to_string(), quantity: 1 }],
+                *gold,
+                "arena_season",
+            );
+        }
+    }
+
+    for mut rating in ctx.db.arena_rating().iter().collect::<Vec<_>>() {
+        rating.rating = (rating.rating + STARTING_RATING) / 2;
+        rating.peak_rating = rating.rating;
+        rating.matches_played = 0;
+        rating.placement_remaining = SEASON_PLACEMENT_MATCHES;
+        ctx.db.arena_rating().player_id().update(rating);
+    }
+}
@@ -0,0 +1,363 @@
+use crate::admin::require_admin;
+use crate::combat::enemy;
+use crate::player;
+use spacetimedb::{reducer, table, ReducerContext, SpacetimeType, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+pub const REPLAY_ARENA: &str = "arena";
+pub const REPLAY_WORLD_BOSS: &str = "world_boss";
+
+// Códigos dos eventos-chave gravados entre os frames
+pub const REPLAY_EVENT_HIT: u8 = 1;
+pub const REPLAY_EVENT_DOWNED: u8 = 2;
+
+const ENTITY_PLAYER: u8 = 0;
+const ENTITY_ENEMY: u8 = 1;
+
+/// Frames por chunk (um tick por frame, ~10 s)
+const FRAMES_PER_CHUNK: u32 = 200;
+/// Em lutas de world boss (mapa aberto) só entra quem está perto do boss
+const FOCUS_RADIUS: f32 = 400.0;
+/// Replays maiores que isso param de gravar
+const MAX_REPLAY_BYTES: u64 = 8 * 1024 * 1024;
+const REPLAY_RETENTION_DAYS: u64 = 7;
+const MAX_STORED_REPLAYS: usize = 100;
+
+/// Gravação de uma partida de arena ou luta de boss. O conteúdo fica em
+/// `replay_chunk`, na ordem de `seq`.
+///
+/// Formato de um chunk: sequência de frames, cada um com
+/// `varint(ms desde o início) varint(n)` e `n` entidades
+/// `u8(tipo) varint(id) zigzag(dx) zigzag(dy) zigzag(dvida)`, seguido de
+/// `varint(m)` e `m` eventos `u8(código) varint(a) varint(b) zigzag(valor)`.
+/// Posições em décimos de pixel e vida inteira; os deltas são relativos ao
+/// frame anterior do mesmo chunk (entidade nova parte de zero), então cada
+/// chunk pode ser lido sozinho.
+#[table(name = replay, public)]
+#[derive(Clone)]
+pub struct Replay {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub kind: String,
+    /// Id da partida ou do inimigo boss
+    pub reference: u64,
+    #[index(btree)]
+    pub map_id: String,
+    pub focus_enemy_id: Option<u32>,
+    pub started_at: Timestamp,
+    pub ended_at: Option<Timestamp>,
+    pub frame_count: u32,
+    pub chunk_count: u32,
+    pub byte_count: u64,
+}
+
+#[table(name = replay_chunk, public)]
+#[derive(Clone)]
+pub struct ReplayChunk {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub replay_id: u64,
+    pub seq: u32,
+    pub first_frame: u32,
+    pub frame_count: u32,
+    pub data: Vec<u8>,
+}
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct TrackedEntity {
+    pub kind: u8,
+    pub entity_id: u32,
+    pub x: i32,
+    pub y: i32,
+    pub health: i32,
+}
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct ReplayEvent {
+    pub code: u8,
+    pub a: u32,
+    pub b: u32,
+    pub value: i32,
+}
+
+/// Chunk em construção de um replay ativo
+#[table(name = replay_buffer)]
+#[derive(Clone)]
+pub struct ReplayBuffer {
+    #[primary_key]
+    pub replay_id: u64,
+    pub first_frame: u32,
+    pub frames: u32,
+    pub data: Vec<u8>,
+    pub previous: Vec<TrackedEntity>,
+    pub pending_events: Vec<ReplayEvent>,
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_zigzag(out: &mut Vec<u8>, value: i64) {
+    write_varint(out, ((value << 1) ^ (value >> 63)) as u64);
+}
+
+pub fn start_replay(ctx: &ReducerContext, kind: &str, reference: u64, map_id: &str, focus_enemy_id: Option<u32>) {
+    let replay = ctx.db.replay().insert(Replay {
+        id: 0,
+        kind: kind.to_string(),
+        reference,
+        map_id: map_id.to_string(),
+        focus_enemy_id,
+        started_at: ctx.timestamp,
+        ended_at: None,
+        frame_count: 0,
+        chunk_count: 0,
+        byte_count: 0,
+    });
+    ctx.db.replay_buffer().insert(ReplayBuffer {
+        replay_id: replay.id,
+        first_frame: 0,
+        frames: 0,
+        data: Vec::new(),
+        previous: Vec::new(),
+        pending_events: Vec::new(),
+    });
+    log::info!("🎬 Recording {} replay {} on '{}'", kind, replay.id, map_id);
+}
+
+pub fn stop_replay(ctx: &ReducerContext, kind: &str, reference: u64) {
+    let active = ctx.db.replay().iter().find(|r| r.ended_at.is_none() && r.kind == kind && r.reference == reference);
+    if let Some(replay) = active {
+        finish(ctx, replay);
+    }
+}
+
+fn finish(ctx: &ReducerContext, mut replay: Replay) {
+    if let Some(buffer) = ctx.db.replay_buffer().replay_id().find(replay.id) {
+        flush(ctx, &mut replay, buffer);
+        ctx.db.replay_buffer().replay_id().delete(replay.id);
+    }
+    replay.ended_at = Some(ctx.timestamp);
+    log::info!("🎬 Replay {} finished ({} frames, {} bytes)", replay.id, replay.frame_count, replay.byte_count);
+    ctx.db.replay().id().update(replay);
+}
+
+fn flush(ctx: &ReducerContext, replay: &mut Replay, buffer: ReplayBuffer) {
+    if buffer.frames == 0 {
+        return;
+    }
+    replay.byte_count += buffer.data.len() as u64;
+    ctx.db.replay_chunk().insert(ReplayChunk {
+        id: 0,
+        replay_id: replay.id,
+        seq: replay.chunk_count,
+        first_frame: buffer.first_frame,
+        frame_count: buffer.frames,
+        data: buffer.data,
+    });
+    replay.chunk_count += 1;
+}
+
+/// Evento-chave (golpe, queda) num mapa que está sendo gravado
+pub fn record_replay_event(ctx: &ReducerContext, map_id: &str, code: u8, a: u32, b: u32, value: f32) {
+    for replay in ctx.db.replay().map_id().filter(map_id).filter(|r| r.ended_at.is_none()) {
+        if let Some(mut buffer) = ctx.db.replay_buffer().replay_id().find(replay.id) {
+            buffer.pending_events.push(ReplayEvent { code, a, b, value: value.round() as i32 });
+            ctx.db.replay_buffer().replay_id().update(buffer);
+        }
+    }
+}
+
+fn snapshot(ctx: &ReducerContext, replay: &Replay) -> Vec<TrackedEntity> {
+    let focus = replay.focus_enemy_id.and_then(|id| ctx.db.enemy().id().find(id)).map(|e| (e.position_x, e.position_y));
+    let near = |x: f32, y: f32| focus.is_none_or(|(fx, fy)| (x - fx).powi(2) + (y - fy).powi(2) <= FOCUS_RADIUS * FOCUS_RADIUS);
+    let quantize = |kind: u8, entity_id: u32, x: f32, y: f32, health: f32| TrackedEntity {
+        kind,
+        entity_id,
+        x: (x * 10.0).round() as i32,
+        y: (y * 10.0).round() as i32,
+        health: health.round() as i32,
+    };
+
+    let mut entities: Vec<TrackedEntity> = ctx.db.player().current_map_id().filter(&replay.map_id)
+        .filter(|p| near(p.position_x, p.position_y))
+        .map(|p| quantize(ENTITY_PLAYER, p.id, p.position_x, p.position_y, p.health))
+        .collect();
+    entities.extend(ctx.db.enemy().map_id().filter(&replay.map_id)
+        .filter(|e| near(e.position_x, e.position_y))
+        .map(|e| quantize(ENTITY_ENEMY, e.id, e.position_x, e.position_y, e.health)));
+    entities
+}
+
+/// Grava um frame de cada replay ativo (tick). Retorna quantos foram gravados.
+pub fn capture_replay_frames(ctx: &ReducerContext) -> u64 {
+    let active: Vec<Replay> = ctx.db.replay().iter().filter(|r| r.ended_at.is_none()).collect();
+    let captured = active.len() as u64;
+    for mut replay in active {
+        let Some(mut buffer) = ctx.db.replay_buffer().replay_id().find(replay.id) else { continue };
+        let entities = snapshot(ctx, &replay);
+
+        let elapsed_ms = ctx.timestamp.duration_since(replay.started_at).unwrap_or_default().as_millis() as u64;
+        write_varint(&mut buffer.data, elapsed_ms);
+        write_varint(&mut buffer.data, entities.len() as u64);
+        for entity in &entities {
+            let previous = buffer.previous.iter().find(|p| p.kind == entity.kind && p.entity_id == entity.entity_id);
+            let (px, py, ph) = previous.map(|p| (p.x, p.y, p.health)).unwrap_or((0, 0, 0));
+            buffer.data.push(entity.kind);
+            write_varint(&mut buffer.data, entity.entity_id as u64);
+            write_zigzag(&mut buffer.data, (entity.x - px) as i64);
+            write_zigzag(&mut buffer.data, (entity.y - py) as i64);
+            write_zigzag(&mut buffer.data, (entity.health - ph) as i64);
+        }
+        write_varint(&mut buffer.data, buffer.pending_events.len() as u64);
+        for event in buffer.pending_events.drain(..) {
+            buffer.data.push(event.code);
+            write_varint(&mut buffer.data, event.a as u64);
+            write_varint(&mut buffer.data, event.b as u64);
+            write_zigzag(&mut buffer.data, event.value as i64);
+        }
+        buffer.previous = entities;
+        buffer.frames += 1;
+        replay.frame_count += 1;
+
+        if buffer.frames >= FRAMES_PER_CHUNK {
+            let next_first = replay.frame_count;
+            flush(ctx, &mut replay, buffer);
+            buffer = ReplayBuffer {
+                replay_id: replay.id,
+                first_frame: next_first,
+                frames: 0,
+                data: Vec::new(),
+                previous: Vec::new(),
+                pending_events: Vec::new(),
+            };
+        }
+
+        if replay.byte_count + buffer.data.len() as u64 > MAX_REPLAY_BYTES {
+            log::warn!("🎬 Replay {} hit the size limit", replay.id);
+            ctx.db.replay_buffer().replay_id().update(buffer);
+            finish(ctx, replay);
+            continue;
+        }
+        ctx.db.replay_buffer().replay_id().update(buffer);
+        ctx.db.replay().id().update(replay);
+    }
+    captured
+}
+
+fn delete_replay_rows(ctx: &ReducerContext, replay_id: u64) {
+    let chunks: Vec<u64> = ctx.db.replay_chunk().replay_id().filter(replay_id).map(|c| c.id).collect();
+    for id in chunks {
+        ctx.db.replay_chunk().id().delete(id);
+    }
+    ctx.db.replay_buffer().replay_id().delete(replay_id);
+    ctx.db.replay().id().delete(replay_id);
+}
+
+/// Retenção: apaga replays encerrados antigos e mantém no máximo `MAX_STORED_REPLAYS`
+pub fn prune_replays(ctx: &ReducerContext) {
+    let cutoff = ctx.timestamp - TimeDuration::from_duration(Duration::from_secs(REPLAY_RETENTION_DAYS * 24 * 3600));
+    let mut finished: Vec<Replay> = ctx.db.replay().iter().filter(|r| r.ended_at.is_some()).collect();
+    finished.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+    let expired: Vec<u64> = finished.iter().enumerate()
+        .filter(|(i, r)| *i >= MAX_STORED_REPLAYS || r.ended_at.is_some_and(|e| e < cutoff))
+        .map(|(_, r)| r.id)
+        .collect();
+    for id in &expired {
+        delete_replay_rows(ctx, *id);
+    }
+    if !expired.is_empty() {
+        log::info!("🧹 Pruned {} replays", expired.len());
+    }
+}
+
+#[reducer]
+pub fn delete_replay(ctx: &ReducerContext, replay_id: u64) -> Result<(), String> {
+    require_admin(ctx)?;
+    ctx.db.replay().id().find(replay_id).ok_or("Replay not found")?;
+    delete_replay_rows(ctx, replay_id);
+    crate::audit::record_audit(ctx, "replay", format!("Deleted replay {}", replay_id));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Leitura espelho do formato, como o cliente faz
+    fn read_varint(data: &[u8], pos: &mut usize) -> u64 {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = data[*pos];
+            *pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return value;
+            }
+            shift += 7;
+        }
+    }
+
+    fn read_zigzag(data: &[u8], pos: &mut usize) -> i64 {
+        let raw = read_varint(data, pos);
+        ((raw >> 1) as i64) ^ -((raw & 1) as i64)
+    }
+
+    fn varint(value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_varint(&mut out, value);
+        out
+    }
+
+    #[test]
+    fn varint_uses_seven_bits_per_byte() {
+        assert_eq!(varint(0), vec![0]);
+        assert_eq!(varint(127), vec![0x7f]);
+        assert_eq!(varint(128), vec![0x80, 0x01]);
+        assert_eq!(varint(300), vec![0xac, 0x02]);
+        assert_eq!(varint(u64::MAX).len(), 10);
+    }
+
+    #[test]
+    fn zigzag_keeps_small_deltas_short() {
+        let encoded = |value: i64| {
+            let mut out = Vec::new();
+            write_zigzag(&mut out, value);
+            out
+        };
+        assert_eq!(encoded(0), vec![0]);
+        assert_eq!(encoded(-1), vec![1]);
+        assert_eq!(encoded(1), vec![2]);
+        assert_eq!(encoded(-64), vec![0x7f]);
+    }
+
+    #[test]
+    fn frame_values_round_trip() {
+        let unsigned = [0, 1, 127, 128, 16_384, u32::MAX as u64, u64::MAX];
+        let signed = [0, -1, 1, -300, 300, i64::MIN, i64::MAX];
+        let mut data = Vec::new();
+        for value in unsigned {
+            write_varint(&mut data, value);
+        }
+        for value in signed {
+            write_zigzag(&mut data, value);
+        }
+
+        let mut pos = 0;
+        for value in unsigned {
+            assert_eq!(read_varint(&data, &mut pos), value);
+        }
+        for value in signed {
+            assert_eq!(read_zigzag(&data, &mut pos), value);
+        }
+        assert_eq!(pos, data.len());
+    }
+}
@@ -0,0 +1,63 @@
+use spacetimedb::{table, ReducerContext, Table};
+
+pub const FACTION_TAVERN_GUILD: &str = "tavern_guild";
+pub const FACTION_TOWN_GUARD: &str = "town_guard";
+
+/// Limiares de reputação (mínimo de cada patamar)
+pub const REP_FRIENDLY: i32 = 1000;
+pub const REP_HONORED: i32 = 3000;
+pub const REP_EXALTED: i32 = 6000;
+
+const MIN_REPUTATION: i32 = -6000;
+const MAX_REPUTATION: i32 = 12000;
+
+/// Reputação de cada player com cada facção
+#[table(name = player_reputation, public)]
+#[derive(Clone)]
+pub struct PlayerReputation {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub faction: String,
+    pub value: i32,
+}
+
+pub fn reputation(ctx: &ReducerContext, player_id: u32, faction: &str) -> i32 {
+    ctx.db.player_reputation().player_id().filter(player_id)
+        .find(|r| r.faction == faction)
+        .map(|r| r.value)
+        .unwrap_or(0)
+}
+
+pub fn add_reputation(ctx: &ReducerContext, player_id: u32, faction: &str, amount: i32) {
+    let existing = ctx.db.player_reputation().player_id().filter(player_id).find(|r| r.faction == faction);
+    match existing {
+        Some(mut row) => {
+            row.value = (row.value + amount).clamp(MIN_REPUTATION, MAX_REPUTATION);
+            ctx.db.player_reputation().id().update(row);
+        }
+        None => {
+            ctx.db.player_reputation().insert(PlayerReputation {
+                id: 0,
+                player_id,
+                faction: faction.to_string(),
+                value: amount.clamp(MIN_REPUTATION, MAX_REPUTATION),
+            });
+        }
+    }
+}
+
+/// Requisito opcional de reputação (facção vazia = sem requisito)
+pub fn meets_reputation(ctx: &ReducerContext, player_id: u32, faction: &str, required: i32) -> bool {
+    faction.is_empty() || reputation(ctx, player_id, faction) >= required
+}
+
+pub fn require_reputation(ctx: &ReducerContext, player_id: u32, faction: &str, required: i32) -> Result<(), String> {
+    if meets_reputation(ctx, player_id, faction, required) {
+        Ok(())
+    } else {
+        Err(format!("Requires {} reputation with {}", required, faction))
+    }
+}
@@ -39,7 +39,7 @@ fn generate_resource_id(ctx: &ReducerContext, key_id: &str) -> u32 {
         let final_id = base_hash.wrapping_add(disambiguation);
         
         // Check if this ID already exists
-        if ctx.db.resource_registry().id().find(&final_id).is_none() {
+        if ctx.db.resource_registry().id().find(final_id).is_none() {
             return final_id;
         }
         
@@ -75,8 +75,8 @@ pub fn register_resource(
     }
     
     // Validate resource type
-    if !matches!(resource_type.as_str(), "map" | "item" | "npc") {
-        return Err("Invalid resource type. Must be 'map', 'item', or 'npc'".into());
+    if !matches!(resource_type.as_str(), "map" | "item" | "npc" | crate::dungeon_gen::RESOURCE_TYPE_ROOM) {
+        return Err("Invalid resource type. Must be 'map', 'item', 'npc' or 'room'".into());
     }
     
     // Generate unique ID with collision handling
@@ -123,7 +123,7 @@ pub fn get_resource_by_key(
     // Look up the resource ID from the mapping table
     if let Some(mapping) = ctx.db.resource_id_mapping().key_id().find(&key_id) {
         // Get the resource data using the ID
-        if let Some(resource) = ctx.db.resource_registry().id().find(&mapping.resource_id) {
+        if let Some(resource) = ctx.db.resource_registry().id().find(mapping.resource_id) {
             log::info!("Retrieved resource: key_id={}, type={}, id={}", 
                       resource.key_id, resource.resource_type, resource.id);
             // In a real implementation, this would return the resource data to the client
@@ -149,7 +149,7 @@ pub fn get_resource_by_id(
     resource_id: u32,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Get the resource data directly by ID
-    if let Some(resource) = ctx.db.resource_registry().id().find(&resource_id) {
+    if let Some(resource) = ctx.db.resource_registry().id().find(resource_id) {
         log::info!("Retrieved resource: id={}, key_id={}, type={}", 
                   resource.id, resource.key_id, resource.resource_type);
         // In a real implementation, this would return the resource data to the client
@@ -173,13 +173,13 @@ pub fn update_resource(
     // Look up the resource ID from the mapping table
     if let Some(mapping) = ctx.db.resource_id_mapping().key_id().find(&key_id) {
         // Get the existing resource
-        if let Some(resource) = ctx.db.resource_registry().id().find(&mapping.resource_id) {
+        if let Some(resource) = ctx.db.resource_registry().id().find(mapping.resource_id) {
             // Create updated resource
             let mut updated_resource = resource.clone();
             updated_resource.data = new_data;
             
             // Delete old and insert updated
-            ctx.db.resource_registry().id().delete(&mapping.resource_id);
+            ctx.db.resource_registry().id().delete(mapping.resource_id);
             ctx.db.resource_registry().insert(updated_resource);
             
             log::info!("Updated resource: key_id={}, id={}", key_id, mapping.resource_id);
@@ -205,7 +205,7 @@ pub fn remove_resource(
         let resource_id = mapping.resource_id;
         
         // Remove both the resource and the mapping
-        ctx.db.resource_registry().id().delete(&resource_id);
+        ctx.db.resource_registry().id().delete(resource_id);
         ctx.db.resource_id_mapping().key_id().delete(&key_id);
         
         log::info!("Removed resource: key_id={}, id={}", key_id, resource_id);
@@ -224,8 +224,8 @@ pub fn list_resources_by_type(
     resource_type: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Validate resource type
-    if !matches!(resource_type.as_str(), "map" | "item" | "npc") {
-        return Err("Invalid resource type. Must be 'map', 'item', or 'npc'".into());
+    if !matches!(resource_type.as_str(), "map" | "item" | "npc" | crate::dungeon_gen::RESOURCE_TYPE_ROOM) {
+        return Err("Invalid resource type. Must be 'map', 'item', 'npc' or 'room'".into());
     }
     
     // Get all resources of the specified type
@@ -0,0 +1,86 @@
+use crate::progression::{get_progress, grant_xp, xp_for_level};
+use crate::Player;
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+
+/// Fração da XP de um nível acumulada por hora deslogado numa zona segura
+const RESTED_LEVEL_FRACTION_PER_HOUR: f64 = 0.05;
+/// O bônus acumulado para em 1,5 nível de XP
+const RESTED_CAP_LEVELS: f64 = 1.5;
+const MIN_KILL_XP: u64 = 5;
+
+/// Onde e quando o player deslogou, e o bônus de descanso acumulado
+#[table(name = rested_state, public)]
+#[derive(Clone)]
+pub struct RestedState {
+    #[primary_key]
+    pub player_id: u32,
+    pub logout_map_id: String,
+    pub logged_out_at: Option<Timestamp>,
+    pub rested_xp: u64,
+}
+
+fn state_for(ctx: &ReducerContext, player_id: u32) -> RestedState {
+    ctx.db.rested_state().player_id().find(player_id).unwrap_or(RestedState {
+        player_id,
+        logout_map_id: String::new(),
+        logged_out_at: None,
+        rested_xp: 0,
+    })
+}
+
+fn save(ctx: &ReducerContext, state: RestedState) {
+    if ctx.db.rested_state().player_id().find(state.player_id).is_some() {
+        ctx.db.rested_state().player_id().update(state);
+    } else {
+        ctx.db.rested_state().insert(state);
+    }
+}
+
+/// XP de um nível inteiro no nível atual do player
+fn level_span(ctx: &ReducerContext, player_id: u32) -> u64 {
+    let level = get_progress(ctx, player_id).level;
+    xp_for_level(level + 1).saturating_sub(xp_for_level(level)).max(1)
+}
+
+/// O player está deslogado? (não há flag de online; vale o logout registrado)
+pub fn is_logged_out(ctx: &ReducerContext, player_id: u32) -> bool {
+    ctx.db.rested_state().player_id().find(player_id).is_some_and(|s| s.logged_out_at.is_some())
+}
+
+pub fn record_logout(ctx: &ReducerContext, player: &Player) {
+    let mut state = state_for(ctx, player.id);
+    state.logout_map_id = player.current_map_id.clone();
+    state.logged_out_at = Some(ctx.timestamp);
+    save(ctx, state);
+}
+
+/// Na reconexão: converte o tempo deslogado em bônus, se o logout foi numa cidade
+pub fn accrue_on_reconnect(ctx: &ReducerContext, player: &Player) {
+    let Some(mut state) = ctx.db.rested_state().player_id().find(player.id) else { return };
+    let Some(logged_out_at) = state.logged_out_at.take() else { return };
+    crate::hireling::resume_after_logout(ctx, player.id, ctx.timestamp.duration_since(logged_out_at).unwrap_or_default());
+
+    if crate::pvp::is_safe_zone(ctx, &state.logout_map_id) {
+        let hours = ctx.timestamp.duration_since(logged_out_at).unwrap_or_default().as_secs_f64() / 3600.0;
+        let span = level_span(ctx, player.id) as f64;
+        let cap = (span * RESTED_CAP_LEVELS) as u64;
+        let earned = (span * RESTED_LEVEL_FRACTION_PER_HOUR * hours) as u64;
+        state.rested_xp = state.rested_xp.saturating_add(earned).min(cap);
+        log::info!("🛏️ Player {} rested {:.1} h in '{}': {} rested XP", player.id, hours, state.logout_map_id, state.rested_xp);
+    }
+    save(ctx, state);
+}
+
+/// XP de abate; o bônus de descanso dobra o valor enquanto durar.
+/// Farm AFK detectado reduz a base (e, com ela, o bônus).
+pub fn grant_kill_xp(ctx: &ReducerContext, player_id: u32, enemy_max_health: f32) {
+    let returns = crate::afk_detection::kill_returns(ctx, player_id);
+    let base = (((enemy_max_health / 4.0) as u64).max(MIN_KILL_XP) as f32 * returns) as u64;
+    let mut bonus = 0;
+    if let Some(mut state) = ctx.db.rested_state().player_id().find(player_id).filter(|s| s.rested_xp > 0) {
+        bonus = base.min(state.rested_xp);
+        state.rested_xp -= bonus;
+        ctx.db.rested_state().player_id().update(state);
+    }
+    grant_xp(ctx, player_id, base + bonus, if bonus > 0 { "kill (rested)" } else { "kill" });
+}
@@ -0,0 +1,145 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use crate::crafting::ItemStack;
+use crate::currency::{add_currency, CURRENCY_GOLD};
+use crate::mail::send_system_mail;
+use crate::party::sender_player;
+use crate::player;
+use crate::progression::grant_xp;
+use spacetimedb::{reducer, table, ReducerContext, SpacetimeType, Table};
+
+const MICROS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000;
+const LOGIN_GOLD_PER_STREAK_DAY: u64 = 50;
+/// A sequência de login para de crescer aqui (e o bônus semanal é pago)
+const LOGIN_STREAK_CAP: u32 = 7;
+const LOGIN_WEEKLY_ITEM: &str = "health_potion";
+const LOGIN_WEEKLY_QUANTITY: i32 = 3;
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct CurrencyAmount {
+    pub currency: String,
+    pub amount: u64,
+}
+
+/// Recompensa composta entregue de uma vez (quests, conquistas, eventos, login)
+#[derive(SpacetimeType, Clone, Debug, Default)]
+pub struct RewardBundle {
+    pub items: Vec<ItemStack>,
+    pub currencies: Vec<CurrencyAmount>,
+    pub xp: u64,
+}
+
+impl RewardBundle {
+    pub fn gold(amount: u64) -> Self {
+        Self::default().with_currency(CURRENCY_GOLD, amount)
+    }
+
+    pub fn with_item(mut self, item_id: &str, quantity: i32) -> Self {
+        self.items.push(ItemStack { item_id: item_id.to_string(), quantity });
+        self
+    }
+
+    pub fn with_currency(mut self, currency: &str, amount: u64) -> Self {
+        self.currencies.push(CurrencyAmount { currency: currency.to_string(), amount });
+        self
+    }
+
+    pub fn with_xp(mut self, xp: u64) -> Self {
+        self.xp += xp;
+        self
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.items.iter().any(|s| s.item_id.trim().is_empty() || s.quantity <= 0) {
+            return Err("Reward items need an id and a positive quantity".to_string());
+        }
+        if self.currencies.iter().any(|c| c.currency.trim().is_empty()) {
+            return Err("Reward currencies need a name".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Caminho único de entrega de recompensas. Tudo é validado antes da primeira
+/// escrita e nenhuma etapa depois disso falha: itens que não cabem no
+/// inventário seguem por correio, então o bundle chega inteiro ou nada muda.
+pub fn grant_reward_bundle(ctx: &ReducerContext, player_id: u32, bundle: &RewardBundle, source: &str) -> Result<(), String> {
+    bundle.validate()?;
+    if ctx.db.player().id().find(player_id).is_none() {
+        return Err("Player not found".to_string());
+    }
+
+    let mut overflow = Vec::new();
+    for stack in &bundle.items {
+        if crate::inventory::add_item_to_inventory(ctx, player_id, stack.item_id.clone(), stack.quantity).is_err() {
+            overflow.push(stack.clone());
+        }
+    }
+    if !overflow.is_empty() {
+        send_system_mail(
+            ctx, player_id, "Reward delivery",
+            format!("Your inventory was full, so part of your '{}' reward was sent here.", source),
+            overflow, 0, source,
+        );
+    }
+
+    for currency in bundle.currencies.iter().filter(|c| c.amount > 0) {
+        add_currency(ctx, player_id, &currency.currency, currency.amount, source);
+    }
+    if bundle.xp > 0 {
+        grant_xp(ctx, player_id, bundle.xp, source);
+    }
+
+    log::info!("🎁 Player {} received '{}' reward ({} items, {} currencies, {} xp)",
+        player_id, source, bundle.items.len(), bundle.currencies.len(), bundle.xp);
+    Ok(())
+}
+
+/// Entrega manual (compensações, suporte)
+#[reducer]
+pub fn grant_reward(ctx: &ReducerContext, player_id: u32, bundle: RewardBundle, reason: String) -> Result<(), String> {
+    require_admin(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    grant_reward_bundle(ctx, player_id, &bundle, "admin")?;
+    record_audit(ctx, "reward", format!("Granted reward to player {}: {}", player_id, reason));
+    Ok(())
+}
+
+/// Sequência de logins diários de cada player
+#[table(name = login_streak, public)]
+#[derive(Clone)]
+pub struct LoginStreak {
+    #[primary_key]
+    pub player_id: u32,
+    pub last_claim_day: i64,
+    pub streak: u32,
+}
+
+/// Resgata a recompensa diária; dias seguidos aumentam o ouro até o limite
+#[reducer]
+pub fn claim_login_reward(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    let today = ctx.timestamp.to_micros_since_unix_epoch() / MICROS_PER_DAY;
+
+    let existing = ctx.db.login_streak().player_id().find(player.id);
+    let streak = match &existing {
+        Some(s) if s.last_claim_day == today => return Err("Daily reward already claimed".to_string()),
+        Some(s) if s.last_claim_day == today - 1 => (s.streak + 1).min(LOGIN_STREAK_CAP),
+        _ => 1,
+    };
+
+    let mut bundle = RewardBundle::gold(LOGIN_GOLD_PER_STREAK_DAY * streak as u64);
+    if streak == LOGIN_STREAK_CAP {
+        bundle = bundle.with_item(LOGIN_WEEKLY_ITEM, LOGIN_WEEKLY_QUANTITY);
+    }
+    grant_reward_bundle(ctx, player.id, &bundle, "daily_login")?;
+
+    let row = LoginStreak { player_id: player.id, last_claim_day: today, streak };
+    if existing.is_some() {
+        ctx.db.login_streak().player_id().update(row);
+    } else {
+        ctx.db.login_streak().insert(row);
+    }
+    Ok(())
+}
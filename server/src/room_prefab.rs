@@ -0,0 +1,174 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use crate::dungeon_gen::{
+    door_tile, parse_room_prefab, RoomPrefab, RESOURCE_TYPE_ROOM, ROOM_BOSS, ROOM_ENTRANCE, ROOM_LOOT, ROOM_SIZE,
+    TILE_BOSS_MARKER, TILE_ENEMY_MARKER, TILE_LOOT_MARKER,
+};
+use crate::map::is_blocking_tile;
+use crate::resource_registry::{register_resource, resource_id_mapping, resource_registry};
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+/// Resultado da última validação de um prefab de sala. O gerador só usa
+/// prefabs válidos cujo `data` não mudou desde a validação.
+#[table(name = room_prefab_validation, public)]
+#[derive(Clone)]
+pub struct RoomPrefabValidation {
+    #[primary_key]
+    pub key_id: String,
+    pub data_hash: u64,
+    pub is_valid: bool,
+    /// "CODIGO: mensagem" para cada problema encontrado
+    pub issues: Vec<String>,
+    pub validated_at: Timestamp,
+}
+
+fn data_hash(data: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn tile(prefab: &RoomPrefab, x: u32, y: u32) -> u32 {
+    prefab.tiles[(y * ROOM_SIZE + x) as usize]
+}
+
+fn is_marker(tile_id: u32) -> bool {
+    [TILE_ENEMY_MARKER, TILE_LOOT_MARKER, TILE_BOSS_MARKER].contains(&tile_id)
+}
+
+fn passable(tile_id: u32) -> bool {
+    is_marker(tile_id) || !is_blocking_tile(tile_id)
+}
+
+/// Conectores: portas declaradas abertas na posição padrão (para alinhar com
+/// a sala vizinha) e o resto da borda fechado. Integridade de colisão: todas
+/// as portas e marcadores alcançáveis entre si por tiles livres.
+fn check_prefab(prefab: &RoomPrefab) -> Vec<String> {
+    let mut issues = Vec::new();
+    let doors: Vec<(u32, u32)> = (0..4).filter(|side| prefab.doors[*side]).map(door_tile).collect();
+
+    if doors.is_empty() {
+        issues.push("NO_CONNECTORS: room has no doors".to_string());
+    }
+    if prefab.kind == ROOM_ENTRANCE && !passable(tile(prefab, ROOM_SIZE / 2, ROOM_SIZE / 2)) {
+        issues.push("SPAWN_BLOCKED: entrance center must be walkable".to_string());
+    }
+    for (side, name) in ["N", "E", "S", "W"].iter().enumerate() {
+        let (x, y) = door_tile(side);
+        let open = passable(tile(prefab, x, y));
+        if prefab.doors[side] && !open {
+            issues.push(format!("CONNECTOR_BLOCKED: door {} at ({}, {}) is a blocked tile", name, x, y));
+        }
+        if !prefab.doors[side] && open {
+            issues.push(format!("UNDECLARED_OPENING: ({}, {}) is open but {} has no door", x, y, name));
+        }
+    }
+    for y in 0..ROOM_SIZE {
+        for x in 0..ROOM_SIZE {
+            let on_edge = x == 0 || y == 0 || x == ROOM_SIZE - 1 || y == ROOM_SIZE - 1;
+            if on_edge && !(0..4).any(|side| door_tile(side) == (x, y)) && passable(tile(prefab, x, y)) {
+                issues.push(format!("EDGE_LEAK: edge tile ({}, {}) is open outside a connector", x, y));
+            }
+        }
+    }
+
+    let required_marker = match prefab.kind.as_str() {
+        ROOM_BOSS => Some(TILE_BOSS_MARKER),
+        ROOM_LOOT => Some(TILE_LOOT_MARKER),
+        _ => None,
+    };
+    if let Some(marker) = required_marker {
+        if !prefab.tiles.contains(&marker) {
+            issues.push(format!("MISSING_MARKER: {} rooms need marker tile {}", prefab.kind, marker));
+        }
+    }
+
+    // Flood fill a partir da primeira porta
+    if let Some(&start) = doors.first() {
+        let mut reached = vec![false; prefab.tiles.len()];
+        reached[(start.1 * ROOM_SIZE + start.0) as usize] = true;
+        let mut queue = VecDeque::from([start]);
+        while let Some((x, y)) = queue.pop_front() {
+            let neighbors = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+            for (nx, ny) in neighbors {
+                if nx >= ROOM_SIZE || ny >= ROOM_SIZE {
+                    continue;
+                }
+                let index = (ny * ROOM_SIZE + nx) as usize;
+                if !reached[index] && passable(prefab.tiles[index]) {
+                    reached[index] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        for (x, y) in &doors {
+            if !reached[(y * ROOM_SIZE + x) as usize] {
+                issues.push(format!("UNREACHABLE_CONNECTOR: door at ({}, {}) is cut off from the others", x, y));
+            }
+        }
+        for (index, tile_id) in prefab.tiles.iter().enumerate() {
+            if is_marker(*tile_id) && !reached[index] {
+                issues.push(format!(
+                    "UNREACHABLE_MARKER: marker {} at ({}, {}) cannot be reached", tile_id, index as u32 % ROOM_SIZE, index as u32 / ROOM_SIZE,
+                ));
+            }
+        }
+    }
+    issues
+}
+
+/// O prefab pode ser usado pelo gerador: validado e sem alterações desde então
+pub fn is_prefab_validated(ctx: &ReducerContext, key_id: &str, data: &str) -> bool {
+    ctx.db.room_prefab_validation().key_id().find(key_id.to_string())
+        .is_some_and(|v| v.is_valid && v.data_hash == data_hash(data))
+}
+
+fn run_validation(ctx: &ReducerContext, key_id: &str) -> Result<bool, String> {
+    let resource_id = ctx.db.resource_id_mapping().key_id().find(key_id.to_string())
+        .ok_or_else(|| format!("Room prefab '{}' not registered", key_id))?
+        .resource_id;
+    let resource = ctx.db.resource_registry().id().find(resource_id)
+        .filter(|r| r.resource_type == RESOURCE_TYPE_ROOM)
+        .ok_or_else(|| format!("'{}' is not a room prefab", key_id))?;
+
+    let issues = match parse_room_prefab(key_id, &resource.data) {
+        Ok(prefab) => check_prefab(&prefab),
+        Err(e) => vec![format!("PARSE_ERROR: {}", e)],
+    };
+    let is_valid = issues.is_empty();
+    let validation = RoomPrefabValidation {
+        key_id: key_id.to_string(),
+        data_hash: data_hash(&resource.data),
+        is_valid,
+        issues,
+        validated_at: ctx.timestamp,
+    };
+    if ctx.db.room_prefab_validation().key_id().find(key_id.to_string()).is_some() {
+        ctx.db.room_prefab_validation().key_id().update(validation);
+    } else {
+        ctx.db.room_prefab_validation().insert(validation);
+    }
+    Ok(is_valid)
+}
+
+/// Registra um prefab de sala no `resource_registry` e já o valida
+#[reducer]
+pub fn register_room_prefab(ctx: &ReducerContext, key_id: String, data: String) -> Result<(), String> {
+    require_admin(ctx)?;
+    register_resource(ctx, key_id.clone(), RESOURCE_TYPE_ROOM.to_string(), data).map_err(|e| e.to_string())?;
+    let is_valid = run_validation(ctx, &key_id)?;
+    record_audit(ctx, "room_prefab", format!("Registered room prefab '{}' (valid: {})", key_id, is_valid));
+    Ok(())
+}
+
+/// Revalida um prefab (ex: depois de `update_resource`); o relatório fica em `room_prefab_validation`
+#[reducer]
+pub fn validate_room_prefab(ctx: &ReducerContext, key_id: String) -> Result<(), String> {
+    require_admin(ctx)?;
+    let is_valid = run_validation(ctx, &key_id)?;
+    log::info!("🧱 Room prefab '{}' validated: {}", key_id, if is_valid { "ok" } else { "issues found" });
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use crate::dungeon::{dungeon_for_map, DungeonInstance};
+use crate::party::party_member_ids;
+use crate::{player, Player};
+use spacetimedb::{table, ReducerContext, Table, Timestamp};
+
+pub const RUN_IN_PROGRESS: &str = "InProgress";
+pub const RUN_CLEARED: &str = "Cleared";
+pub const RUN_RESET: &str = "Reset";
+pub const RUN_ABANDONED: &str = "Abandoned";
+
+/// Estatísticas de uma run de dungeon. Fica "InProgress" até o boss morrer
+/// (Cleared) ou a instância ser reiniciada/fechada; então vira a tela final da party.
+#[table(name = run_report, public)]
+#[derive(Clone)]
+pub struct RunReport {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub dungeon_id: u64,
+    pub party_id: u64,
+    pub template_name: String,
+    pub tier: u32,
+    pub member_ids: Vec<u32>,
+    pub outcome: String,
+    pub started_at: Timestamp,
+    pub finished_at: Option<Timestamp>,
+    pub clear_time_ms: Option<u64>,
+    pub deaths: u32,
+    pub wipes: u32,
+}
+
+/// Números de cada membro em uma run
+#[table(name = run_member_stat, public)]
+#[derive(Clone)]
+pub struct RunMemberStat {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub run_id: u64,
+    pub player_id: u32,
+    pub damage_done: f32,
+    pub deaths: u32,
+}
+
+fn active_run(ctx: &ReducerContext, dungeon_id: u64) -> Option<RunReport> {
+    ctx.db.run_report().dungeon_id().filter(dungeon_id).find(|r| r.outcome == RUN_IN_PROGRESS)
+}
+
+fn active_run_for_map(ctx: &ReducerContext, map_id: &str) -> Option<RunReport> {
+    dungeon_for_map(ctx, map_id).and_then(|d| active_run(ctx, d.id))
+}
+
+fn update_member_stat(ctx: &ReducerContext, run_id: u64, player_id: u32, apply: impl FnOnce(&mut RunMemberStat)) {
+    let existing = ctx.db.run_member_stat().run_id().filter(run_id).find(|s| s.player_id == player_id);
+    match existing {
+        Some(mut stat) => {
+            apply(&mut stat);
+            ctx.db.run_member_stat().id().update(stat);
+        }
+        None => {
+            let mut stat = RunMemberStat { id: 0, run_id, player_id, damage_done: 0.0, deaths: 0 };
+            apply(&mut stat);
+            ctx.db.run_member_stat().insert(stat);
+        }
+    }
+}
+
+/// Abre uma nova run para a instância (criação e após cada reset)
+pub fn start_run(ctx: &ReducerContext, dungeon: &DungeonInstance) {
+    ctx.db.run_report().insert(RunReport {
+        id: 0,
+        dungeon_id: dungeon.id,
+        party_id: dungeon.party_id,
+        template_name: dungeon.template_name.clone(),
+        tier: dungeon.tier,
+        member_ids: party_member_ids(ctx, dungeon.party_id),
+        outcome: RUN_IN_PROGRESS.to_string(),
+        started_at: ctx.timestamp,
+        finished_at: None,
+        clear_time_ms: None,
+        deaths: 0,
+        wipes: 0,
+    });
+}
+
+/// Fecha a run em andamento com o resultado dado. Retorna o relatório final.
+pub fn finish_run(ctx: &ReducerContext, dungeon_id: u64, outcome: &str) -> Option<RunReport> {
+    let mut report = active_run(ctx, dungeon_id)?;
+    report.outcome = outcome.to_string();
+    report.finished_at = Some(ctx.timestamp);
+    if outcome == RUN_CLEARED {
+        report.clear_time_ms = ctx.timestamp.duration_since(report.started_at).map(|d| d.as_millis() as u64);
+    }
+    // Quem entrou na party durante a run também aparece no relatório
+    for member_id in party_member_ids(ctx, report.party_id) {
+        if !report.member_ids.contains(&member_id) {
+            report.member_ids.push(member_id);
+        }
+    }
+    ctx.db.run_report().id().update(report.clone());
+
+    log::info!("📊 Run {} of '{}' finished: {} ({} deaths, {} wipes)",
+        report.id, report.template_name, outcome, report.deaths, report.wipes);
+    Some(report)
+}
+
+pub fn record_damage(ctx: &ReducerContext, map_id: &str, player_id: u32, damage: f32) {
+    if let Some(run) = active_run_for_map(ctx, map_id) {
+        update_member_stat(ctx, run.id, player_id, |s| s.damage_done += damage);
+    }
+}
+
+/// Chamado depois que o player caiu (linha já atualizada). Conta a morte e,
+/// se todos da party dentro da instância estiverem caídos, conta um wipe.
+pub fn record_player_downed(ctx: &ReducerContext, player: &Player) {
+    let Some(mut run) = active_run_for_map(ctx, &player.current_map_id) else { return };
+
+    update_member_stat(ctx, run.id, player.id, |s| s.deaths += 1);
+    run.deaths += 1;
+
+    let inside: Vec<Player> = ctx.db.player().iter()
+        .filter(|p| p.current_map_id == player.current_map_id && run.member_ids.contains(&p.id))
+        .collect();
+    if !inside.is_empty() && inside.iter().all(|p| p.is_downed) {
+        run.wipes += 1;
+        log::info!("💀 Party {} wiped in run {}", run.party_id, run.id);
+    }
+
+    ctx.db.run_report().id().update(run);
+}
+
+/// Chamado quando um inimigo morre: o boss encerra a run como concluída
+pub fn record_enemy_defeated(ctx: &ReducerContext, map_id: &str, enemy_type: &str) {
+    if !crate::combat::is_boss_type(enemy_type) {
+        return;
+    }
+    if let Some(dungeon) = dungeon_for_map(ctx, map_id) {
+        if let Some(report) = finish_run(ctx, dungeon.id, RUN_CLEARED) {
+            crate::world_first::record_world_first(
+                ctx, &crate::world_first::first_boss_kill(&report.template_name),
+                format!("defeated the boss of {}", report.template_name),
+                &format!("Conqueror of {}", report.template_name), &report.member_ids,
+            );
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::ability_queue::queued_ability;
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use crate::combat::{enemy, projectile};
+use crate::inventory::{inventory_header, inventory_item, player_equipment};
+use crate::map::map_instance;
+use crate::player;
+use spacetimedb::{reducer, table, ReducerContext, ScheduleAt, Table};
+use std::time::Duration;
+
+pub const SANITATION_INTERVAL_SECS: u64 = 60;
+
+/// Agenda da limpeza periódica de entidades órfãs
+#[table(name = sanitation_schedule, scheduled(run_sanitation))]
+pub struct SanitationSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+pub fn ensure_sanitation_schedule(ctx: &ReducerContext) {
+    if ctx.db.sanitation_schedule().count() == 0 {
+        ctx.db.sanitation_schedule().insert(SanitationSchedule {
+            scheduled_id: 0,
+            scheduled_at: Duration::from_secs(SANITATION_INTERVAL_SECS).into(),
+        });
+    }
+}
+
+#[reducer]
+pub fn run_sanitation(ctx: &ReducerContext, _schedule: SanitationSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("run_sanitation may only be invoked by the scheduler".to_string());
+    }
+    if crate::upgrade::is_frozen(ctx) {
+        return Ok(());
+    }
+    sanitize_world(ctx);
+    crate::structure::process_structure_decay(ctx);
+    crate::guild_war::process_guild_wars(ctx);
+    crate::lfg::expire_group_listings(ctx);
+    crate::kill_feed::prune_kill_feeds(ctx);
+    crate::map::reconcile_map_populations(ctx);
+    crate::work_order::expire_accepted_orders(ctx);
+    crate::escrow::verify_escrow_invariants(ctx, crate::work_order::escrow_reference_is_live);
+    crate::external_event::prune_acknowledged_events(ctx);
+    crate::privacy::prune_expired_exports(ctx);
+    crate::threat::prune_threat(ctx);
+    crate::challenge_tower::prune_abandoned_tower_runs(ctx);
+    crate::replay::prune_replays(ctx);
+    crate::grief_protection::prune_expired_tags(ctx);
+    Ok(())
+}
+
+/// Dispara a limpeza imediatamente (admin)
+#[reducer]
+pub fn run_sanitation_now(ctx: &ReducerContext) -> Result<(), String> {
+    require_admin(ctx)?;
+    sanitize_world(ctx);
+    Ok(())
+}
+
+fn instance_exists(ctx: &ReducerContext, map_id: &str) -> bool {
+    ctx.db.map_instance().key_id().find(map_id.to_string()).is_some()
+}
+
+fn entity_exists(ctx: &ReducerContext, entity_id: u32) -> bool {
+    ctx.db.player().id().find(entity_id).is_some() || ctx.db.enemy().id().find(entity_id).is_some()
+}
+
+/// Remove linhas que apontam para donos/mapas/jogadores que não existem mais
+pub fn sanitize_world(ctx: &ReducerContext) {
+    let orphan_projectiles: Vec<u32> = ctx.db.projectile().iter()
+        .filter(|p| !entity_exists(ctx, p.owner_id) || !instance_exists(ctx, &p.map_id))
+        .map(|p| p.id)
+        .collect();
+    for id in &orphan_projectiles {
+        ctx.db.projectile().id().delete(id);
+    }
+
+    let orphan_enemies: Vec<u32> = ctx.db.enemy().iter()
+        .filter(|e| !instance_exists(ctx, &e.map_id))
+        .map(|e| e.id)
+        .collect();
+    for id in &orphan_enemies {
+        ctx.db.enemy().id().delete(id);
+    }
+
+    let orphan_items: Vec<u32> = ctx.db.inventory_item().iter()
+        .filter(|i| ctx.db.player().id().find(i.player_id).is_none())
+        .map(|i| i.id)
+        .collect();
+    for id in &orphan_items {
+        ctx.db.inventory_item().id().delete(id);
+    }
+
+    let orphan_equipment: Vec<u32> = ctx.db.player_equipment().iter()
+        .filter(|e| ctx.db.player().id().find(e.player_id).is_none())
+        .map(|e| e.player_id)
+        .collect();
+    for id in &orphan_equipment {
+        ctx.db.player_equipment().player_id().delete(id);
+    }
+
+    let orphan_headers: Vec<u32> = ctx.db.inventory_header().iter()
+        .filter(|h| ctx.db.player().id().find(h.player_id).is_none())
+        .map(|h| h.player_id)
+        .collect();
+    for id in &orphan_headers {
+        ctx.db.inventory_header().player_id().delete(id);
+    }
+
+    let orphan_queue: Vec<u32> = ctx.db.queued_ability().iter()
+        .filter(|q| ctx.db.player().id().find(q.player_id).is_none())
+        .map(|q| q.player_id)
+        .collect();
+    for id in &orphan_queue {
+        ctx.db.queued_ability().player_id().delete(id);
+    }
+
+    let total = orphan_projectiles.len() + orphan_enemies.len() + orphan_items.len()
+        + orphan_equipment.len() + orphan_headers.len() + orphan_queue.len();
+    if total == 0 {
+        return;
+    }
+
+    record_audit(ctx, "sanitation", format!(
+        "Removed {} projectiles {:?}, {} enemies {:?}, {} inventory rows {:?}, {} equipment rows {:?}, {} inventory headers {:?}, {} queued abilities {:?}",
+        orphan_projectiles.len(), orphan_projectiles,
+        orphan_enemies.len(), orphan_enemies,
+        orphan_items.len(), orphan_items,
+        orphan_equipment.len(), orphan_equipment,
+        orphan_headers.len(), orphan_headers,
+        orphan_queue.len(), orphan_queue,
+    ));
+}
@@ -0,0 +1,132 @@
+use crate::cooldown::{try_start_cooldown, CATEGORY_SCENIC, SCENIC_MARKER_COOLDOWN_MS};
+use crate::friend::is_friend_of;
+use crate::party::sender_player;
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+pub const MAX_MARKERS_PER_PLAYER: usize = 20;
+const MAX_CAPTION_LENGTH: usize = 80;
+/// Quantos pontos ficam em destaque e o mínimo de votos para entrar
+pub const POPULAR_SPOTS: usize = 10;
+const POPULAR_MIN_VOTES: u32 = 3;
+
+/// Marcador de paisagem (modo foto). Aparece no mapa para os amigos do dono;
+/// os mais votados viram "pontos populares" e ficam visíveis para todos.
+#[table(name = scenic_marker, public)]
+#[derive(Clone)]
+pub struct ScenicMarker {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub owner_id: u32,
+    #[index(btree)]
+    pub map_id: String,
+    pub position_x: f32,
+    pub position_y: f32,
+    pub caption: String,
+    pub votes: u32,
+    pub popular: bool,
+    pub created_at: Timestamp,
+}
+
+#[table(name = scenic_vote, public)]
+#[derive(Clone)]
+pub struct ScenicVote {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub marker_id: u64,
+    pub voter_id: u32,
+    pub voted_at: Timestamp,
+}
+
+fn delete_marker(ctx: &ReducerContext, marker_id: u64) {
+    let votes: Vec<u64> = ctx.db.scenic_vote().marker_id().filter(marker_id).map(|v| v.id).collect();
+    for id in votes {
+        ctx.db.scenic_vote().id().delete(id);
+    }
+    ctx.db.scenic_marker().id().delete(marker_id);
+}
+
+/// Marcadores que o player pode ver: os próprios, os de quem o tem como amigo e os populares
+pub fn can_see_marker(ctx: &ReducerContext, marker: &ScenicMarker, player_id: u32) -> bool {
+    marker.popular || marker.owner_id == player_id || is_friend_of(ctx, marker.owner_id, player_id)
+}
+
+/// Recalcula os pontos populares (chamado pela agregação periódica)
+pub fn refresh_popular_spots(ctx: &ReducerContext) {
+    let mut markers: Vec<ScenicMarker> = ctx.db.scenic_marker().iter().collect();
+    markers.sort_by_key(|m| (std::cmp::Reverse(m.votes), m.created_at));
+
+    for (i, mut marker) in markers.into_iter().enumerate() {
+        let popular = i < POPULAR_SPOTS && marker.votes >= POPULAR_MIN_VOTES;
+        if marker.popular != popular {
+            marker.popular = popular;
+            ctx.db.scenic_marker().id().update(marker);
+        }
+    }
+}
+
+#[reducer]
+pub fn place_scenic_marker(ctx: &ReducerContext, caption: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let caption = caption.trim().to_string();
+    if caption.is_empty() || caption.chars().count() > MAX_CAPTION_LENGTH {
+        return Err(format!("Caption must have 1 to {} characters", MAX_CAPTION_LENGTH));
+    }
+    try_start_cooldown(ctx, ctx.sender, CATEGORY_SCENIC, "place_marker", SCENIC_MARKER_COOLDOWN_MS)?;
+
+    // Acima do limite, o marcador mais antigo dá lugar ao novo
+    let mut own: Vec<ScenicMarker> = ctx.db.scenic_marker().owner_id().filter(player.id).collect();
+    if own.len() >= MAX_MARKERS_PER_PLAYER {
+        own.sort_by_key(|m| m.created_at);
+        for marker in own.iter().take(own.len() + 1 - MAX_MARKERS_PER_PLAYER) {
+            delete_marker(ctx, marker.id);
+        }
+    }
+
+    ctx.db.scenic_marker().insert(ScenicMarker {
+        id: 0,
+        owner_id: player.id,
+        map_id: player.current_map_id,
+        position_x: player.position_x,
+        position_y: player.position_y,
+        caption,
+        votes: 0,
+        popular: false,
+        created_at: ctx.timestamp,
+    });
+    Ok(())
+}
+
+#[reducer]
+pub fn remove_scenic_marker(ctx: &ReducerContext, marker_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let marker = ctx.db.scenic_marker().id().find(marker_id).ok_or("Marker not found")?;
+    if marker.owner_id != player.id {
+        return Err("Not your marker".to_string());
+    }
+    delete_marker(ctx, marker_id);
+    Ok(())
+}
+
+#[reducer]
+pub fn vote_scenic_marker(ctx: &ReducerContext, marker_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let mut marker = ctx.db.scenic_marker().id().find(marker_id).ok_or("Marker not found")?;
+    if marker.owner_id == player.id {
+        return Err("Cannot vote for your own marker".to_string());
+    }
+    if !can_see_marker(ctx, &marker, player.id) {
+        return Err("Marker not found".to_string());
+    }
+    if ctx.db.scenic_vote().marker_id().filter(marker_id).any(|v| v.voter_id == player.id) {
+        return Err("Already voted for this marker".to_string());
+    }
+
+    ctx.db.scenic_vote().insert(ScenicVote { id: 0, marker_id, voter_id: player.id, voted_at: ctx.timestamp });
+    marker.votes += 1;
+    ctx.db.scenic_marker().id().update(marker);
+    Ok(())
+}
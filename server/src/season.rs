@@ -0,0 +1,57 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+/// Temporadas competitivas. Só uma fica aberta (`ended_at` vazio); dados
+/// marcados com o id de temporadas encerradas ficam como arquivo.
+#[table(name = season, public)]
+#[derive(Clone)]
+pub struct Season {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub name: String,
+    pub started_at: Timestamp,
+    pub ended_at: Option<Timestamp>,
+}
+
+/// Temporada aberta; cria a primeira se ainda não houver nenhuma
+pub fn current_season(ctx: &ReducerContext) -> Season {
+    if let Some(season) = ctx.db.season().iter().find(|s| s.ended_at.is_none()) {
+        return season;
+    }
+    ctx.db.season().insert(Season {
+        id: 0,
+        name: "Season 1".to_string(),
+        started_at: ctx.timestamp,
+        ended_at: None,
+    })
+}
+
+/// Encerra a temporada atual (com uma última agregação) e abre a próxima
+#[reducer]
+pub fn end_season(ctx: &ReducerContext, next_season_name: String) -> Result<(), String> {
+    require_admin(ctx)?;
+    let next_season_name = next_season_name.trim().to_string();
+    if next_season_name.is_empty() {
+        return Err("Season name cannot be empty".to_string());
+    }
+
+    crate::aggregation::aggregate_all(ctx);
+
+    let mut season = current_season(ctx);
+    crate::rating::archive_season_ratings(ctx, &season);
+    season.ended_at = Some(ctx.timestamp);
+    ctx.db.season().id().update(season.clone());
+
+    let next = ctx.db.season().insert(Season {
+        id: 0,
+        name: next_season_name,
+        started_at: ctx.timestamp,
+        ended_at: None,
+    });
+
+    record_audit(ctx, "season", format!("Season '{}' ended, '{}' started", season.name, next.name));
+    log::info!("🏁 Season '{}' archived, '{}' started", season.name, next.name);
+    Ok(())
+}
@@ -0,0 +1,321 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use crate::combat::{allocate_enemy_id, enemy, spawn_enemy};
+use crate::currency::CURRENCY_EVENT_TOKEN;
+use crate::feature_flag::set_feature;
+use crate::localization::{announce_localized, Message};
+use crate::map::{is_map_hot, random_spawn_point, template_for_map};
+use crate::vendor::{vendor, vendor_item, Vendor, VendorItem};
+use spacetimedb::{reducer, table, ReducerContext, ScheduleAt, SpacetimeType, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+pub const SEASONAL_EVENT_INTERVAL_SECS: u64 = 10;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventState {
+    Scheduled,
+    Active,
+    Ended,
+}
+
+/// Evento sazonal com janela fixa. Enquanto ativo, liga a flag de decoração
+/// (o cliente troca para a variante decorada dos mapas), mantém os spawners
+/// extras e abre os vendedores do evento; ao fim tudo é desmontado.
+#[table(name = seasonal_event, public)]
+#[derive(Clone)]
+pub struct SeasonalEvent {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[unique]
+    pub name: String,
+    pub decor_flag: String,
+    pub starts_at: Timestamp,
+    pub ends_at: Timestamp,
+    pub state: EventState,
+    /// Vendedores criados na ativação (removidos no encerramento)
+    pub vendor_ids: Vec<u64>,
+}
+
+/// Spawner extra de um evento: mantém até `max_alive` inimigos ao redor do centro
+#[table(name = event_spawner, public)]
+#[derive(Clone)]
+pub struct EventSpawner {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub event_id: u64,
+    pub map_id: String,
+    pub enemy_type: String,
+    pub center_x: f32,
+    pub center_y: f32,
+    pub radius: f32,
+    pub max_alive: u32,
+    pub respawn_secs: u64,
+    pub alive_ids: Vec<u32>,
+    pub next_spawn_at: Timestamp,
+}
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct EventVendorItem {
+    pub item_id: String,
+    pub price: u64,
+}
+
+/// Modelo de vendedor do evento; vira um `vendor` (moeda de evento) na ativação
+#[table(name = event_vendor, public)]
+#[derive(Clone)]
+pub struct EventVendor {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub event_id: u64,
+    pub name: String,
+    pub map_id: String,
+    pub position_x: f32,
+    pub position_y: f32,
+    pub items: Vec<EventVendorItem>,
+}
+
+#[table(name = seasonal_event_schedule, scheduled(run_seasonal_events))]
+pub struct SeasonalEventSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+pub fn ensure_seasonal_event_schedule(ctx: &ReducerContext) {
+    if ctx.db.seasonal_event_schedule().count() == 0 {
+        ctx.db.seasonal_event_schedule().insert(SeasonalEventSchedule {
+            scheduled_id: 0,
+            scheduled_at: Duration::from_secs(SEASONAL_EVENT_INTERVAL_SECS).into(),
+        });
+    }
+}
+
+fn editable_event(ctx: &ReducerContext, event_id: u64) -> Result<SeasonalEvent, String> {
+    let event = ctx.db.seasonal_event().id().find(event_id).ok_or("Event not found")?;
+    if event.state != EventState::Scheduled {
+        return Err("Only scheduled events can be changed".to_string());
+    }
+    Ok(event)
+}
+
+#[reducer]
+pub fn schedule_seasonal_event(
+    ctx: &ReducerContext,
+    name: String,
+    decor_flag: String,
+    starts_at: Timestamp,
+    ends_at: Timestamp,
+) -> Result<(), String> {
+    require_admin(ctx)?;
+    if ends_at <= starts_at || ends_at <= ctx.timestamp {
+        return Err("Event window must end after it starts and in the future".to_string());
+    }
+    if ctx.db.seasonal_event().name().find(name.clone()).is_some() {
+        return Err("An event with that name already exists".to_string());
+    }
+
+    record_audit(ctx, "seasonal_event", format!("Scheduled '{}' ({:?} - {:?})", name, starts_at, ends_at));
+    ctx.db.seasonal_event().insert(SeasonalEvent {
+        id: 0,
+        name,
+        decor_flag,
+        starts_at,
+        ends_at,
+        state: EventState::Scheduled,
+        vendor_ids: Vec::new(),
+    });
+    Ok(())
+}
+
+#[reducer]
+#[allow(clippy::too_many_arguments)]
+pub fn add_event_spawner(
+    ctx: &ReducerContext,
+    event_id: u64,
+    map_id: String,
+    enemy_type: String,
+    center_x: f32,
+    center_y: f32,
+    radius: f32,
+    max_alive: u32,
+    respawn_secs: u64,
+) -> Result<(), String> {
+    require_admin(ctx)?;
+    let event = editable_event(ctx, event_id)?;
+    template_for_map(ctx, &map_id).ok_or_else(|| format!("Map '{}' not found", map_id))?;
+    ctx.db.event_spawner().insert(EventSpawner {
+        id: 0,
+        event_id: event.id,
+        map_id,
+        enemy_type,
+        center_x,
+        center_y,
+        radius,
+        max_alive,
+        respawn_secs,
+        alive_ids: Vec::new(),
+        next_spawn_at: event.starts_at,
+    });
+    Ok(())
+}
+
+#[reducer]
+pub fn add_event_vendor(
+    ctx: &ReducerContext,
+    event_id: u64,
+    name: String,
+    map_id: String,
+    position_x: f32,
+    position_y: f32,
+    items: Vec<EventVendorItem>,
+) -> Result<(), String> {
+    require_admin(ctx)?;
+    let event = editable_event(ctx, event_id)?;
+    template_for_map(ctx, &map_id).ok_or_else(|| format!("Map '{}' not found", map_id))?;
+    ctx.db.event_vendor().insert(EventVendor { id: 0, event_id: event.id, name, map_id, position_x, position_y, items });
+    Ok(())
+}
+
+/// Encerra o evento antes da hora (ou cancela um agendado)
+#[reducer]
+pub fn end_seasonal_event(ctx: &ReducerContext, event_id: u64) -> Result<(), String> {
+    require_admin(ctx)?;
+    let event = ctx.db.seasonal_event().id().find(event_id).ok_or("Event not found")?;
+    if event.state == EventState::Ended {
+        return Err("Event already ended".to_string());
+    }
+    record_audit(ctx, "seasonal_event", format!("Ended '{}' early", event.name));
+    teardown_event(ctx, event);
+    Ok(())
+}
+
+#[reducer]
+pub fn run_seasonal_events(ctx: &ReducerContext, _schedule: SeasonalEventSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("run_seasonal_events may only be invoked by the scheduler".to_string());
+    }
+    if crate::upgrade::is_frozen(ctx) {
+        return Ok(());
+    }
+
+    let events: Vec<SeasonalEvent> = ctx.db.seasonal_event().iter().filter(|e| e.state != EventState::Ended).collect();
+    for event in events {
+        if event.ends_at <= ctx.timestamp {
+            teardown_event(ctx, event);
+        } else if event.state == EventState::Scheduled && event.starts_at <= ctx.timestamp {
+            activate_event(ctx, event);
+        } else if event.state == EventState::Active {
+            process_event_spawners(ctx, event.id);
+        }
+    }
+    Ok(())
+}
+
+fn activate_event(ctx: &ReducerContext, mut event: SeasonalEvent) {
+    if !event.decor_flag.is_empty() {
+        set_feature(ctx, &event.decor_flag, true);
+    }
+
+    for spec in ctx.db.event_vendor().event_id().filter(event.id) {
+        let vendor = ctx.db.vendor().insert(Vendor {
+            id: 0,
+            name: spec.name,
+            map_id: spec.map_id,
+            position_x: spec.position_x,
+            position_y: spec.position_y,
+        });
+        for item in spec.items {
+            ctx.db.vendor_item().insert(VendorItem {
+                id: 0,
+                vendor_id: vendor.id,
+                item_id: item.item_id,
+                price: item.price,
+                currency: CURRENCY_EVENT_TOKEN.to_string(),
+                required_faction: String::new(),
+                required_reputation: 0,
+            });
+        }
+        event.vendor_ids.push(vendor.id);
+    }
+
+    event.state = EventState::Active;
+    announce_localized(ctx, "seasonal_event", Message::new("announce.seasonal_event.started").param("event", &event.name));
+    ctx.db.seasonal_event().id().update(event.clone());
+    process_event_spawners(ctx, event.id);
+}
+
+fn teardown_event(ctx: &ReducerContext, mut event: SeasonalEvent) {
+    let was_active = event.state == EventState::Active;
+    if !event.decor_flag.is_empty() {
+        set_feature(ctx, &event.decor_flag, false);
+    }
+
+    for vendor_id in event.vendor_ids.drain(..) {
+        let items: Vec<u64> = ctx.db.vendor_item().vendor_id().filter(vendor_id).map(|i| i.id).collect();
+        for id in items {
+            ctx.db.vendor_item().id().delete(id);
+        }
+        ctx.db.vendor().id().delete(vendor_id);
+        crate::npc_bark::on_vendor_removed(ctx, vendor_id);
+        crate::story_flag::on_vendor_removed(ctx, vendor_id);
+    }
+
+    let spawners: Vec<EventSpawner> = ctx.db.event_spawner().event_id().filter(event.id).collect();
+    for mut spawner in spawners {
+        for enemy_id in spawner.alive_ids.drain(..) {
+            ctx.db.enemy().id().delete(enemy_id);
+        }
+        ctx.db.event_spawner().id().update(spawner);
+    }
+
+    event.state = EventState::Ended;
+    if was_active {
+        announce_localized(ctx, "seasonal_event", Message::new("announce.seasonal_event.ended").param("event", &event.name));
+    }
+    ctx.db.seasonal_event().id().update(event);
+}
+
+/// Instância reaquecida: o timer dos spawners ficou pausado enquanto ela estava fria
+pub fn on_map_warmed(ctx: &ReducerContext, map_id: &str, paused: Duration) {
+    let spawners: Vec<EventSpawner> = ctx.db.event_spawner().iter().filter(|s| s.map_id == map_id).collect();
+    for mut spawner in spawners {
+        spawner.alive_ids.retain(|id| ctx.db.enemy().id().find(*id).is_some());
+        spawner.next_spawn_at += TimeDuration::from_duration(paused);
+        ctx.db.event_spawner().id().update(spawner);
+    }
+}
+
+fn process_event_spawners(ctx: &ReducerContext, event_id: u64) {
+    // Instâncias frias não spawnam; o timer retoma em `on_map_warmed`
+    let spawners: Vec<EventSpawner> = ctx.db.event_spawner().event_id().filter(event_id)
+        .filter(|s| is_map_hot(ctx, &s.map_id))
+        .collect();
+    for mut spawner in spawners {
+        let before = spawner.alive_ids.len();
+        spawner.alive_ids.retain(|id| ctx.db.enemy().id().find(*id).is_some());
+        let mut changed = spawner.alive_ids.len() != before;
+
+        if spawner.alive_ids.len() < spawner.max_alive as usize && spawner.next_spawn_at <= ctx.timestamp {
+            // Sem ponto livre de players o spawn fica pendente e é tentado no próximo tick
+            if let Some((x, y)) = random_spawn_point(ctx, &spawner.map_id, spawner.center_x, spawner.center_y, spawner.radius) {
+                let enemy_id = allocate_enemy_id(ctx);
+                match spawn_enemy(ctx, enemy_id, x, y, spawner.map_id.clone(), spawner.enemy_type.clone()) {
+                    Ok(()) => spawner.alive_ids.push(enemy_id),
+                    Err(e) => log::warn!("🎃 Event spawner {} failed: {}", spawner.id, e),
+                }
+                spawner.next_spawn_at = ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(spawner.respawn_secs));
+                changed = true;
+            }
+        }
+
+        if changed {
+            ctx.db.event_spawner().id().update(spawner);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use crate::cooldown::try_start_cooldown;
+use crate::{player, player__view};
+use spacetimedb::{reducer, table, view, Identity, ReducerContext, Table, Timestamp, ViewContext};
+
+/// Dano de um jogador em shadow-ban contra inimigos (contra players é zero)
+const SHADOW_DAMAGE_MULTIPLIER: f32 = 0.25;
+/// Ações de mercado aceitas, no máximo, uma vez por janela
+const SHADOW_MARKET_COOLDOWN_MS: u64 = 10 * 60 * 1000;
+const CATEGORY_SHADOW: &str = "shadow";
+
+/// Identidade sob investigação. Nada é avisado a ela: o chat vai para um
+/// espelho que só ela vê, ataques contra players não pegam, o dano em
+/// inimigos e as ações de mercado ficam silenciosamente limitados.
+#[table(name = shadow_ban)]
+#[derive(Clone)]
+pub struct ShadowBan {
+    #[primary_key]
+    pub identity: Identity,
+    pub reason: String,
+    pub banned_by: Identity,
+    pub banned_at: Timestamp,
+}
+
+/// Espelho do `chat_message` para quem está em shadow-ban. Privada: o
+/// autor lê as próprias mensagens por `my_shadow_chat_messages`.
+#[table(name = shadow_chat_message)]
+#[derive(Clone)]
+pub struct ShadowChatMessage {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub channel: String,
+    pub channel_id: u64,
+    #[index(btree)]
+    pub sender_id: u32,
+    pub sender_name: String,
+    pub text: String,
+    pub sent_at: Timestamp,
+}
+
+#[view(name = my_shadow_chat_messages, public)]
+pub fn my_shadow_chat_messages(ctx: &ViewContext) -> Vec<ShadowChatMessage> {
+    match ctx.db.player().identity().find(ctx.sender) {
+        Some(player) => ctx.db.shadow_chat_message().sender_id().filter(player.id).collect(),
+        None => Vec::new(),
+    }
+}
+
+pub fn is_shadow_banned(ctx: &ReducerContext, identity: Identity) -> bool {
+    ctx.db.shadow_ban().identity().find(identity).is_some()
+}
+
+pub fn is_player_shadow_banned(ctx: &ReducerContext, player_id: u32) -> bool {
+    ctx.db.player().id().find(player_id).is_some_and(|p| is_shadow_banned(ctx, p.identity))
+}
+
+/// Multiplicador aplicado ao dano causado por um player
+pub fn damage_multiplier(ctx: &ReducerContext, player_id: u32) -> f32 {
+    if is_player_shadow_banned(ctx, player_id) {
+        SHADOW_DAMAGE_MULTIPLIER
+    } else {
+        1.0
+    }
+}
+
+/// Guard das ações de mercado: para quem está em shadow-ban, falha com um
+/// erro genérico fora da janela permitida
+pub fn throttle_market(ctx: &ReducerContext) -> Result<(), String> {
+    if is_shadow_banned(ctx, ctx.sender) {
+        try_start_cooldown(ctx, ctx.sender, CATEGORY_SHADOW, "market", SHADOW_MARKET_COOLDOWN_MS)
+            .map_err(|_| "The board is busy, try again later".to_string())?;
+    }
+    Ok(())
+}
+
+pub fn record_shadow_chat(ctx: &ReducerContext, channel: String, channel_id: u64, sender: &crate::Player, text: String) {
+    ctx.db.shadow_chat_message().insert(ShadowChatMessage {
+        id: 0,
+        channel,
+        channel_id,
+        sender_id: sender.id,
+        sender_name: sender.username_display.clone(),
+        text,
+        sent_at: ctx.timestamp,
+    });
+}
+
+#[reducer]
+pub fn shadow_ban_identity(ctx: &ReducerContext, identity: Identity, reason: String) -> Result<(), String> {
+    require_admin(ctx)?;
+    if is_shadow_banned(ctx, identity) {
+        return Err("Identity is already shadow-banned".to_string());
+    }
+    ctx.db.shadow_ban().insert(ShadowBan { identity, reason: reason.clone(), banned_by: ctx.sender, banned_at: ctx.timestamp });
+    record_audit(ctx, "shadow_ban", format!("Shadow-banned {}: {}", identity.to_hex(), reason));
+    Ok(())
+}
+
+#[reducer]
+pub fn lift_shadow_ban(ctx: &ReducerContext, identity: Identity) -> Result<(), String> {
+    require_admin(ctx)?;
+    if !ctx.db.shadow_ban().identity().delete(identity) {
+        return Err("Identity is not shadow-banned".to_string());
+    }
+    record_audit(ctx, "shadow_ban", format!("Lifted shadow-ban of {}", identity.to_hex()));
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use crate::combat::is_in_combat;
+use crate::inventory::{add_item_to_inventory, inventory_item, is_soulbound, recompute_inventory_header};
+use crate::party::sender_player;
+use spacetimedb::{reducer, table, Identity, ReducerContext, Table};
+
+pub const SHARED_BANK_SLOTS: usize = 30;
+
+/// Aba de banco da conta: qualquer personagem da mesma `Identity` deposita e
+/// saca, então materiais passam entre personagens sem mandar correio a si mesmo.
+/// Cada linha é uma pilha (um slot) de um item.
+#[table(name = shared_bank_slot, public)]
+#[derive(Clone)]
+pub struct SharedBankSlot {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub item_id: String,
+    pub quantity: i32,
+}
+
+/// Move parte de uma pilha do inventário para o banco da conta
+#[reducer]
+pub fn deposit_to_shared_bank(ctx: &ReducerContext, inventory_item_id: u32, quantity: i32) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    if is_in_combat(ctx, player.id) {
+        return Err("Cannot use the bank while in combat".to_string());
+    }
+    let mut item = ctx.db.inventory_item().id().find(inventory_item_id)
+        .filter(|i| i.player_id == player.id)
+        .ok_or("Item not found")?;
+    if quantity <= 0 || quantity > item.quantity {
+        return Err("Invalid quantity".to_string());
+    }
+    if item.is_equipped {
+        return Err("Unequip the item first".to_string());
+    }
+    if is_soulbound(&item.item_id) {
+        return Err(format!("'{}' is soulbound", item.item_id));
+    }
+    if item.dye_color.is_some() {
+        return Err("Remove the dye before banking the item".to_string());
+    }
+
+    let existing = ctx.db.shared_bank_slot().identity().filter(player.identity).find(|s| s.item_id == item.item_id);
+    match existing {
+        Some(mut slot) => {
+            slot.quantity = slot.quantity.checked_add(quantity).ok_or("Bank slot is full")?;
+            ctx.db.shared_bank_slot().id().update(slot);
+        }
+        None => {
+            if ctx.db.shared_bank_slot().identity().filter(player.identity).count() >= SHARED_BANK_SLOTS {
+                return Err("Shared bank is full".to_string());
+            }
+            ctx.db.shared_bank_slot().insert(SharedBankSlot {
+                id: 0,
+                identity: player.identity,
+                item_id: item.item_id.clone(),
+                quantity,
+            });
+        }
+    }
+
+    if item.quantity == quantity {
+        ctx.db.inventory_item().id().delete(item.id);
+        recompute_inventory_header(ctx, player.id);
+    } else {
+        item.quantity -= quantity;
+        ctx.db.inventory_item().id().update(item.clone());
+    }
+    log::info!("🏦 Player {} banked {} x{}", player.id, item.item_id, quantity);
+    Ok(())
+}
+
+/// Saca do banco da conta para o inventário do personagem atual
+#[reducer]
+pub fn withdraw_from_shared_bank(ctx: &ReducerContext, slot_id: u64, quantity: i32) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    if is_in_combat(ctx, player.id) {
+        return Err("Cannot use the bank while in combat".to_string());
+    }
+    let mut slot = ctx.db.shared_bank_slot().id().find(slot_id)
+        .filter(|s| s.identity == player.identity)
+        .ok_or("Bank slot not found")?;
+    if quantity <= 0 || quantity > slot.quantity {
+        return Err("Invalid quantity".to_string());
+    }
+
+    add_item_to_inventory(ctx, player.id, slot.item_id.clone(), quantity).map_err(|e| e.to_string())?;
+    slot.quantity -= quantity;
+    if slot.quantity == 0 {
+        ctx.db.shared_bank_slot().id().delete(slot.id);
+    } else {
+        ctx.db.shared_bank_slot().id().update(slot.clone());
+    }
+    log::info!("🏦 Player {} withdrew {} x{}", player.id, slot.item_id, quantity);
+    Ok(())
+}
+
+/// Leva o banco para a nova identidade da conta (reclaim autorizado). Pilhas do mesmo
+/// item se juntam quando cabem; senão seguem como slots separados.
+pub fn transfer_shared_bank(ctx: &ReducerContext, from: Identity, to: Identity) {
+    for mut slot in ctx.db.shared_bank_slot().identity().filter(from).collect::<Vec<_>>() {
+        let target = ctx.db.shared_bank_slot().identity().filter(to)
+            .find(|s| s.item_id == slot.item_id && s.quantity.checked_add(slot.quantity).is_some());
+        match target {
+            Some(mut target) => {
+                target.quantity += slot.quantity;
+                ctx.db.shared_bank_slot().id().update(target);
+                ctx.db.shared_bank_slot().id().delete(slot.id);
+            }
+            None => {
+                slot.identity = to;
+                ctx.db.shared_bank_slot().id().update(slot);
+            }
+        }
+    }
+}
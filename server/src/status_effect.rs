@@ -0,0 +1,176 @@
+use spacetimedb::{table, ReducerContext, Table, TimeDuration, Timestamp};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// Multiplica o dano causado por (1 + magnitude)
+pub const EFFECT_DAMAGE_BOOST: &str = "damage_boost";
+/// Reduz o dano recebido por (1 - magnitude)
+pub const EFFECT_DAMAGE_REDUCTION: &str = "damage_reduction";
+
+/// Reduz a velocidade de movimento por (1 - magnitude)
+pub const EFFECT_MOVEMENT_SLOW: &str = "movement_slow";
+
+/// Soma `magnitude` às chances de crítico no crafting
+pub const EFFECT_CRAFTING_FOCUS: &str = "crafting_focus";
+
+/// Imune a knockback/puxões enquanto ativo (magnitude ignorada)
+pub const EFFECT_UNSTOPPABLE: &str = "unstoppable";
+
+// Categorias de efeito: a purificação remove só categorias nocivas
+pub const CATEGORY_BUFF: &str = "buff";
+pub const CATEGORY_IMPAIRMENT: &str = "impairment";
+pub const CLEANSABLE_CATEGORIES: &[&str] = &[CATEGORY_IMPAIRMENT];
+
+/// Redução máxima somada de todas as fontes
+const MAX_DAMAGE_REDUCTION: f32 = 0.75;
+/// Velocidade mínima restante com lentidão acumulada
+const MIN_MOVEMENT_MULTIPLIER: f32 = 0.25;
+
+/// Efeito ativo em um player. `source` identifica quem aplicou (ex: "instance:<mapa>")
+/// para que a fonte possa removê-lo; `expires_at` vazio = até a fonte remover.
+/// Efeitos com o mesmo `stack_group` (não vazio) não acumulam: vale só o mais forte.
+#[table(name = status_effect, public)]
+#[derive(Clone)]
+pub struct StatusEffect {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub effect_id: String,
+    pub magnitude: f32,
+    pub source: String,
+    pub applied_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+    pub stack_group: String,
+}
+
+/// Aplica (ou renova) um efeito; a mesma fonte não acumula o mesmo efeito.
+pub fn apply_status_effect(
+    ctx: &ReducerContext,
+    player_id: u32,
+    effect_id: &str,
+    magnitude: f32,
+    source: &str,
+    duration: Option<Duration>,
+) {
+    apply_grouped_status_effect(ctx, player_id, effect_id, magnitude, source, duration, "");
+}
+
+/// Como `apply_status_effect`, mas dentro de um grupo de acúmulo
+pub fn apply_grouped_status_effect(
+    ctx: &ReducerContext,
+    player_id: u32,
+    effect_id: &str,
+    magnitude: f32,
+    source: &str,
+    duration: Option<Duration>,
+    stack_group: &str,
+) {
+    let expires_at = duration.map(|d| ctx.timestamp + TimeDuration::from_duration(d));
+
+    let existing = ctx.db.status_effect().player_id().filter(player_id)
+        .find(|e| e.effect_id == effect_id && e.source == source);
+
+    match existing {
+        Some(mut effect) => {
+            effect.magnitude = magnitude;
+            effect.applied_at = ctx.timestamp;
+            effect.expires_at = expires_at;
+            effect.stack_group = stack_group.to_string();
+            ctx.db.status_effect().id().update(effect);
+        }
+        None => {
+            ctx.db.status_effect().insert(StatusEffect {
+                id: 0,
+                player_id,
+                effect_id: effect_id.to_string(),
+                magnitude,
+                source: source.to_string(),
+                applied_at: ctx.timestamp,
+                expires_at,
+                stack_group: stack_group.to_string(),
+            });
+        }
+    }
+}
+
+pub fn effect_category(effect_id: &str) -> &'static str {
+    match effect_id {
+        EFFECT_MOVEMENT_SLOW => CATEGORY_IMPAIRMENT,
+        _ => CATEGORY_BUFF,
+    }
+}
+
+/// Remove os efeitos ativos de uma categoria; retorna quantos saíram
+pub fn remove_effects_in_category(ctx: &ReducerContext, player_id: u32, category: &str) -> usize {
+    let ids: Vec<u64> = ctx.db.status_effect().player_id().filter(player_id)
+        .filter(|e| effect_category(&e.effect_id) == category)
+        .map(|e| e.id)
+        .collect();
+    for id in &ids {
+        ctx.db.status_effect().id().delete(id);
+    }
+    ids.len()
+}
+
+pub fn remove_effects_from_source(ctx: &ReducerContext, player_id: u32, source: &str) {
+    let ids: Vec<u64> = ctx.db.status_effect().player_id().filter(player_id)
+        .filter(|e| e.source == source)
+        .map(|e| e.id)
+        .collect();
+    for id in ids {
+        ctx.db.status_effect().id().delete(id);
+    }
+}
+
+fn active_effects(ctx: &ReducerContext, player_id: u32, effect_id: &str) -> Vec<StatusEffect> {
+    let mut effects: Vec<StatusEffect> = ctx.db.status_effect().player_id().filter(player_id)
+        .filter(|e| e.effect_id == effect_id && e.expires_at.is_none_or(|t| t > ctx.timestamp))
+        .collect();
+    // Dentro de um grupo de acúmulo só o mais forte conta
+    effects.sort_by(|a, b| b.magnitude.total_cmp(&a.magnitude));
+    let mut groups = HashSet::new();
+    effects.retain(|e| e.stack_group.is_empty() || groups.insert(e.stack_group.clone()));
+    effects
+}
+
+pub fn outgoing_damage_multiplier(ctx: &ReducerContext, player_id: u32) -> f32 {
+    active_effects(ctx, player_id, EFFECT_DAMAGE_BOOST).iter()
+        .fold(1.0, |acc, e| acc * (1.0 + e.magnitude))
+}
+
+pub fn incoming_damage_multiplier(ctx: &ReducerContext, player_id: u32) -> f32 {
+    let reduction: f32 = active_effects(ctx, player_id, EFFECT_DAMAGE_REDUCTION).iter()
+        .map(|e| e.magnitude)
+        .sum();
+    1.0 - reduction.clamp(0.0, MAX_DAMAGE_REDUCTION)
+}
+
+pub fn movement_speed_multiplier(ctx: &ReducerContext, player_id: u32) -> f32 {
+    let slow: f32 = active_effects(ctx, player_id, EFFECT_MOVEMENT_SLOW).iter()
+        .map(|e| e.magnitude)
+        .sum();
+    (1.0 - slow).max(MIN_MOVEMENT_MULTIPLIER)
+}
+
+pub fn crafting_focus_bonus(ctx: &ReducerContext, player_id: u32) -> f32 {
+    active_effects(ctx, player_id, EFFECT_CRAFTING_FOCUS).iter()
+        .map(|e| e.magnitude)
+        .sum()
+}
+
+pub fn is_immune_to_forced_movement(ctx: &ReducerContext, player_id: u32) -> bool {
+    !active_effects(ctx, player_id, EFFECT_UNSTOPPABLE).is_empty()
+}
+
+/// Checagem por tick: remove efeitos expirados
+pub fn process_status_effects(ctx: &ReducerContext) {
+    let expired: Vec<u64> = ctx.db.status_effect().iter()
+        .filter(|e| e.expires_at.is_some_and(|t| t <= ctx.timestamp))
+        .map(|e| e.id)
+        .collect();
+    for id in expired {
+        ctx.db.status_effect().id().delete(id);
+    }
+}
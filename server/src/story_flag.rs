@@ -0,0 +1,264 @@
+use crate::admin::require_admin;
+use crate::combat::{allocate_enemy_id, enemy, spawn_enemy};
+use crate::inventory::{count_item, remove_item_from_inventory};
+use crate::local_event::{post_local_event, LOCAL_EVENT_BARK};
+use crate::map::{is_map_hot, map_transition, template_for_map, TILE_SIZE};
+use crate::party::sender_player;
+use crate::player;
+use crate::vendor::vendor;
+use spacetimedb::{reducer, table, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+const DIALOGUE_RANGE: f32 = TILE_SIZE * 3.0;
+
+/// Cutscenes conhecidas: (id, flag exigida para assistir, flag concedida ao terminar)
+const STORY_CUTSCENES: &[(&str, Option<&str>, &str)] = &[
+    ("intro", None, "intro_seen"),
+    ("tavern_fire", Some("intro_seen"), "tavern_fire_seen"),
+];
+
+/// Marco de história de um player (quests, diálogos, cutscenes). Consumido por
+/// transições, ramos de diálogo e spawners, então o mundo muda conforme ele avança.
+#[table(name = story_flag, public)]
+#[derive(Clone)]
+pub struct StoryFlag {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub flag: String,
+    pub set_at: Timestamp,
+}
+
+/// Transição que só funciona para quem tem a flag
+#[table(name = transition_requirement, public)]
+#[derive(Clone)]
+pub struct TransitionRequirement {
+    #[primary_key]
+    pub transition_id: u32,
+    pub required_flag: String,
+}
+
+/// Opção de diálogo de um NPC. Aparece só para quem tem `required_flag` e ainda
+/// não tem `hidden_by_flag`; pode exigir (e consumir) um item-chave.
+#[table(name = dialogue_option, public)]
+#[derive(Clone)]
+pub struct DialogueOption {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub vendor_id: u64,
+    pub prompt: String,
+    pub response: String,
+    pub required_flag: Option<String>,
+    pub hidden_by_flag: Option<String>,
+    pub key_item_id: Option<String>,
+    pub consumes_key_item: bool,
+    pub sets_flag: Option<String>,
+}
+
+/// Spawner que só fica ativo enquanto alguém com a flag está no mapa
+#[table(name = story_spawner, public)]
+#[derive(Clone)]
+pub struct StorySpawner {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub map_id: String,
+    pub enemy_type: String,
+    pub position_x: f32,
+    pub position_y: f32,
+    pub required_flag: String,
+    pub respawn_secs: u64,
+    pub alive_id: Option<u32>,
+    pub next_spawn_at: Timestamp,
+}
+
+pub fn has_story_flag(ctx: &ReducerContext, player_id: u32, flag: &str) -> bool {
+    ctx.db.story_flag().player_id().filter(player_id).any(|f| f.flag == flag)
+}
+
+/// Marca a flag (idempotente)
+pub fn set_story_flag(ctx: &ReducerContext, player_id: u32, flag: &str) {
+    if has_story_flag(ctx, player_id, flag) {
+        return;
+    }
+    ctx.db.story_flag().insert(StoryFlag { id: 0, player_id, flag: flag.to_string(), set_at: ctx.timestamp });
+    log::info!("📖 Player {} reached story flag '{}'", player_id, flag);
+    crate::tutorial::on_story_flag_set(ctx, player_id, flag);
+}
+
+/// A transição está liberada para o player (sem requisito = sempre)
+pub fn transition_allowed(ctx: &ReducerContext, player_id: u32, transition_id: u32) -> bool {
+    ctx.db.transition_requirement().transition_id().find(transition_id)
+        .is_none_or(|r| has_story_flag(ctx, player_id, &r.required_flag))
+}
+
+fn option_visible(ctx: &ReducerContext, player_id: u32, option: &DialogueOption) -> bool {
+    option.required_flag.as_deref().is_none_or(|f| has_story_flag(ctx, player_id, f))
+        && option.hidden_by_flag.as_deref().is_none_or(|f| !has_story_flag(ctx, player_id, f))
+}
+
+/// Escolhe uma opção de diálogo: checa alcance, flags e item-chave, e marca a flag da opção
+#[reducer]
+pub fn choose_dialogue_option(ctx: &ReducerContext, option_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    let option = ctx.db.dialogue_option().id().find(option_id).ok_or("Dialogue option not found")?;
+    let npc = ctx.db.vendor().id().find(option.vendor_id).ok_or("NPC not found")?;
+    let distance = ((player.position_x - npc.position_x).powi(2) + (player.position_y - npc.position_y).powi(2)).sqrt();
+    if player.current_map_id != npc.map_id || distance > DIALOGUE_RANGE {
+        return Err("Too far from the NPC".to_string());
+    }
+    if !option_visible(ctx, player.id, &option) {
+        return Err("That option is not available".to_string());
+    }
+    if let Some(item_id) = &option.key_item_id {
+        if count_item(ctx, player.id, item_id) < 1 {
+            return Err(format!("Requires {}", item_id));
+        }
+        if option.consumes_key_item {
+            remove_item_from_inventory(ctx, player.id, item_id, 1)?;
+        }
+    }
+    if let Some(flag) = &option.sets_flag {
+        set_story_flag(ctx, player.id, flag);
+    }
+    post_local_event(ctx, &npc.map_id, LOCAL_EVENT_BARK, &npc.name, option.response.clone(), (npc.position_x, npc.position_y));
+    Ok(())
+}
+
+/// O cliente avisa que a cutscene terminou; a flag só é concedida se ela estava liberada
+#[reducer]
+pub fn complete_cutscene(ctx: &ReducerContext, cutscene_id: String) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let (_, required, grants) = STORY_CUTSCENES.iter().find(|(id, _, _)| *id == cutscene_id).ok_or("Unknown cutscene")?;
+    if required.is_some_and(|f| !has_story_flag(ctx, player.id, f)) {
+        return Err("Cutscene not unlocked".to_string());
+    }
+    set_story_flag(ctx, player.id, grants);
+    Ok(())
+}
+
+#[reducer]
+pub fn set_transition_requirement(ctx: &ReducerContext, transition_id: u32, required_flag: Option<String>) -> Result<(), String> {
+    require_admin(ctx)?;
+    ctx.db.map_transition().id().find(transition_id).ok_or("Transition not found")?;
+    ctx.db.transition_requirement().transition_id().delete(transition_id);
+    if let Some(required_flag) = required_flag {
+        ctx.db.transition_requirement().insert(TransitionRequirement { transition_id, required_flag });
+    }
+    Ok(())
+}
+
+#[reducer]
+#[allow(clippy::too_many_arguments)]
+pub fn add_dialogue_option(
+    ctx: &ReducerContext,
+    vendor_id: u64,
+    prompt: String,
+    response: String,
+    required_flag: Option<String>,
+    hidden_by_flag: Option<String>,
+    key_item_id: Option<String>,
+    consumes_key_item: bool,
+    sets_flag: Option<String>,
+) -> Result<(), String> {
+    require_admin(ctx)?;
+    ctx.db.vendor().id().find(vendor_id).ok_or("NPC not found")?;
+    ctx.db.dialogue_option().insert(DialogueOption {
+        id: 0,
+        vendor_id,
+        prompt,
+        response,
+        required_flag,
+        hidden_by_flag,
+        key_item_id,
+        consumes_key_item,
+        sets_flag,
+    });
+    Ok(())
+}
+
+pub fn on_vendor_removed(ctx: &ReducerContext, vendor_id: u64) {
+    let options: Vec<u64> = ctx.db.dialogue_option().vendor_id().filter(vendor_id).map(|o| o.id).collect();
+    for id in options {
+        ctx.db.dialogue_option().id().delete(id);
+    }
+}
+
+#[reducer]
+pub fn remove_dialogue_option(ctx: &ReducerContext, option_id: u64) -> Result<(), String> {
+    require_admin(ctx)?;
+    ctx.db.dialogue_option().id().delete(option_id);
+    Ok(())
+}
+
+#[reducer]
+pub fn add_story_spawner(
+    ctx: &ReducerContext,
+    map_id: String,
+    enemy_type: String,
+    position_x: f32,
+    position_y: f32,
+    required_flag: String,
+    respawn_secs: u64,
+) -> Result<(), String> {
+    require_admin(ctx)?;
+    template_for_map(ctx, &map_id).ok_or_else(|| format!("Map '{}' not found", map_id))?;
+    ctx.db.story_spawner().insert(StorySpawner {
+        id: 0,
+        map_id,
+        enemy_type,
+        position_x,
+        position_y,
+        required_flag,
+        respawn_secs,
+        alive_id: None,
+        next_spawn_at: ctx.timestamp,
+    });
+    Ok(())
+}
+
+/// Ativa os spawners cujos mapas têm alguém com a flag e recolhe o inimigo
+/// dos que ficaram sem ninguém. Retorna quantos spawners foram avaliados.
+pub fn process_story_spawners(ctx: &ReducerContext) -> u64 {
+    let spawners: Vec<StorySpawner> = ctx.db.story_spawner().iter().collect();
+    let processed = spawners.len() as u64;
+    for mut spawner in spawners {
+        let active = is_map_hot(ctx, &spawner.map_id)
+            && ctx.db.player().current_map_id().filter(&spawner.map_id).any(|p| has_story_flag(ctx, p.id, &spawner.required_flag));
+        let alive = spawner.alive_id.filter(|id| ctx.db.enemy().id().find(id).is_some());
+
+        let changed = match (active, alive) {
+            (false, Some(enemy_id)) => {
+                ctx.db.enemy().id().delete(enemy_id);
+                crate::threat::clear_enemy(ctx, enemy_id);
+                spawner.alive_id = None;
+                true
+            }
+            (true, None) if spawner.alive_id.is_some() => {
+                // Morto desde a última checagem: espera o respawn
+                spawner.alive_id = None;
+                spawner.next_spawn_at = ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(spawner.respawn_secs));
+                true
+            }
+            (true, None) if spawner.next_spawn_at <= ctx.timestamp => {
+                let enemy_id = allocate_enemy_id(ctx);
+                match spawn_enemy(ctx, enemy_id, spawner.position_x, spawner.position_y, spawner.map_id.clone(), spawner.enemy_type.clone()) {
+                    Ok(()) => spawner.alive_id = Some(enemy_id),
+                    Err(e) => log::warn!("Story spawner {} failed: {}", spawner.id, e),
+                }
+                true
+            }
+            _ => false,
+        };
+        if changed {
+            ctx.db.story_spawner().id().update(spawner);
+        }
+    }
+    processed
+}
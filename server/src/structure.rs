@@ -0,0 +1,504 @@
+use crate::combat::enemy;
+use crate::inventory::{count_item, remove_item_from_inventory};
+use crate::map::{clear_tile_override, map_instance, map_transition, set_tile_override, template_for_map, tile_at, TILE_SIZE};
+use crate::party::sender_player;
+use crate::player;
+use spacetimedb::{reducer, table, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+/// Tiles das estruturas (entulho e ponte são caminháveis, o resto bloqueia)
+pub const TILE_BARRICADE: u32 = 60;
+pub const TILE_GATE: u32 = 61;
+pub const TILE_RUBBLE: u32 = 62;
+pub const TILE_FENCE: u32 = 63;
+pub const TILE_BRIDGE: u32 = 64;
+pub const TILE_CRAFTING_STATION: u32 = 65;
+pub const TILE_GATE_OPEN: u32 = 66;
+pub const TILE_DOOR: u32 = 67;
+pub const TILE_DOOR_OPEN: u32 = 68;
+
+/// Tiles de água (pontes só podem ser construídas sobre eles)
+const WATER_TILES: &[u32] = &[8, 10];
+
+const REPAIR_RANGE: f32 = TILE_SIZE * 2.0;
+const BUILD_RANGE: f32 = TILE_SIZE * 3.0;
+const CRAFTING_STATION_RANGE: f32 = TILE_SIZE * 2.0;
+const CRAFTING_STATION_TIER: u32 = 1;
+/// Raio (em tiles) ao redor do spawn do mapa onde não se constrói
+const SPAWN_NO_BUILD_RADIUS: u32 = 3;
+/// Estruturas de players somem se não forem reparadas neste intervalo
+const PLAYER_STRUCTURE_DECAY_SECS: u64 = 7 * 24 * 60 * 60;
+
+struct StructureDef {
+    structure_type: &'static str,
+    max_health: f32,
+    intact_tile: u32,
+    repair_item: &'static str,
+    repair_quantity: i32,
+    repair_amount: f32,
+    /// Construível por players (consome a planta e os materiais)
+    buildable: bool,
+    build_item: &'static str,
+    build_quantity: i32,
+    on_water: bool,
+    /// Tile quando aberta (portas); 0 = não abre
+    open_tile: u32,
+}
+
+const STRUCTURE_TYPES: &[StructureDef] = &[
+    StructureDef { structure_type: "barricade", max_health: 300.0, intact_tile: TILE_BARRICADE, repair_item: "wood", repair_quantity: 2, repair_amount: 100.0, buildable: false, build_item: "", build_quantity: 0, on_water: false, open_tile: 0 },
+    StructureDef { structure_type: "gate", max_health: 800.0, intact_tile: TILE_GATE, repair_item: "stone", repair_quantity: 3, repair_amount: 200.0, buildable: false, build_item: "", build_quantity: 0, on_water: false, open_tile: TILE_GATE_OPEN },
+    StructureDef { structure_type: "fence", max_health: 150.0, intact_tile: TILE_FENCE, repair_item: "wood", repair_quantity: 1, repair_amount: 75.0, buildable: true, build_item: "wood", build_quantity: 2, on_water: false, open_tile: 0 },
+    StructureDef { structure_type: "bridge", max_health: 400.0, intact_tile: TILE_BRIDGE, repair_item: "wood", repair_quantity: 2, repair_amount: 100.0, buildable: true, build_item: "wood", build_quantity: 6, on_water: true, open_tile: 0 },
+    StructureDef { structure_type: "crafting_station", max_health: 250.0, intact_tile: TILE_CRAFTING_STATION, repair_item: "stone", repair_quantity: 2, repair_amount: 100.0, buildable: true, build_item: "stone", build_quantity: 4, on_water: false, open_tile: 0 },
+    StructureDef { structure_type: "door", max_health: 200.0, intact_tile: TILE_DOOR, repair_item: "wood", repair_quantity: 1, repair_amount: 75.0, buildable: true, build_item: "wood", build_quantity: 3, on_water: false, open_tile: TILE_DOOR_OPEN },
+];
+
+/// Área retangular (em tiles) onde players não podem construir
+#[table(name = no_build_zone, public)]
+#[derive(Clone)]
+pub struct NoBuildZone {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub map_id: String,
+    pub tile_x: u32,
+    pub tile_y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Estrutura destrutível. O estado (intacta/destruída) é refletido na camada
+/// de colisão através de uma WorldMutation no tile.
+#[table(name = structure, public)]
+#[derive(Clone)]
+pub struct Structure {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub map_id: String,
+    pub structure_type: String,
+    pub tile_x: u32,
+    pub tile_y: u32,
+    pub health: f32,
+    pub max_health: f32,
+    pub is_destroyed: bool,
+    pub updated_at: Timestamp,
+    /// Estruturas de players: dono e quando somem por falta de manutenção
+    pub owner_id: Option<u32>,
+    pub decays_at: Option<Timestamp>,
+    /// Quem pode interagir: "owner", "guild", "friends" ou "public" (+ concessões explícitas)
+    pub access_level: String,
+    pub is_open: bool,
+}
+
+pub const ACCESS_OWNER: &str = "owner";
+pub const ACCESS_GUILD: &str = "guild";
+pub const ACCESS_FRIENDS: &str = "friends";
+pub const ACCESS_PUBLIC: &str = "public";
+const ACCESS_LEVELS: &[&str] = &[ACCESS_OWNER, ACCESS_GUILD, ACCESS_FRIENDS, ACCESS_PUBLIC];
+
+/// Acesso concedido individualmente pelo dono
+#[table(name = structure_access, public)]
+#[derive(Clone)]
+pub struct StructureAccess {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub structure_id: u64,
+    pub player_id: u32,
+    pub granted_at: Timestamp,
+}
+
+fn structure_def(structure_type: &str) -> Option<&'static StructureDef> {
+    STRUCTURE_TYPES.iter().find(|d| d.structure_type == structure_type)
+}
+
+fn blueprint_item(structure_type: &str) -> String {
+    format!("blueprint_{}", structure_type)
+}
+
+fn decay_deadline(ctx: &ReducerContext) -> Timestamp {
+    ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(PLAYER_STRUCTURE_DECAY_SECS))
+}
+
+fn tile_center(tile_x: u32, tile_y: u32) -> (f32, f32) {
+    (tile_x as f32 * TILE_SIZE + TILE_SIZE / 2.0, tile_y as f32 * TILE_SIZE + TILE_SIZE / 2.0)
+}
+
+fn apply_collision(ctx: &ReducerContext, structure: &Structure) -> Result<(), String> {
+    let def = structure_def(&structure.structure_type).ok_or("Unknown structure type")?;
+    let tile = if structure.is_destroyed {
+        TILE_RUBBLE
+    } else if structure.is_open && def.open_tile != 0 {
+        def.open_tile
+    } else {
+        def.intact_tile
+    };
+    set_tile_override(ctx, &structure.map_id, structure.tile_x, structure.tile_y, tile)
+}
+
+pub fn structure_at(ctx: &ReducerContext, map_id: &str, tile_x: u32, tile_y: u32) -> Option<Structure> {
+    ctx.db.structure().map_id().filter(map_id).find(|s| s.tile_x == tile_x && s.tile_y == tile_y)
+}
+
+/// Cria uma estrutura intacta no tile (sem validar regiões/ocupação: quem chama valida)
+pub fn place_structure(ctx: &ReducerContext, map_id: &str, structure_type: &str, tile_x: u32, tile_y: u32) -> Result<Structure, String> {
+    let def = structure_def(structure_type).ok_or_else(|| format!("Unknown structure type '{}'", structure_type))?;
+    template_for_map(ctx, map_id).ok_or_else(|| format!("Map '{}' not found", map_id))?;
+    if structure_at(ctx, map_id, tile_x, tile_y).is_some() {
+        return Err("A structure already occupies that tile".to_string());
+    }
+
+    let structure = ctx.db.structure().insert(Structure {
+        id: 0,
+        map_id: map_id.to_string(),
+        structure_type: structure_type.to_string(),
+        tile_x,
+        tile_y,
+        health: def.max_health,
+        max_health: def.max_health,
+        is_destroyed: false,
+        updated_at: ctx.timestamp,
+        owner_id: None,
+        decays_at: None,
+        access_level: ACCESS_PUBLIC.to_string(),
+        is_open: false,
+    });
+    apply_collision(ctx, &structure)?;
+    Ok(structure)
+}
+
+/// Dano de inimigos e eventos de cerco. Ao chegar a zero a estrutura vira entulho.
+pub fn damage_structure(ctx: &ReducerContext, structure_id: u64, damage: f32, source: &str) {
+    let Some(mut structure) = ctx.db.structure().id().find(structure_id) else { return };
+    if structure.is_destroyed {
+        return;
+    }
+
+    structure.health = (structure.health - damage).max(0.0);
+    structure.updated_at = ctx.timestamp;
+    if structure.health <= 0.0 {
+        structure.is_destroyed = true;
+        log::info!("💥 {} {} destroyed by {} in {}", structure.structure_type, structure.id, source, structure.map_id);
+        if let Err(e) = apply_collision(ctx, &structure) {
+            log::warn!("Structure {} collision update failed: {}", structure.id, e);
+        }
+    }
+    ctx.db.structure().id().update(structure);
+}
+
+/// Admin: posiciona barricadas/portões fixos do mundo
+#[reducer]
+pub fn create_structure(ctx: &ReducerContext, map_id: String, structure_type: String, tile_x: u32, tile_y: u32) -> Result<(), String> {
+    crate::admin::require_admin(ctx)?;
+    place_structure(ctx, &map_id, &structure_type, tile_x, tile_y)?;
+    Ok(())
+}
+
+/// Reparo com materiais; uma estrutura destruída volta a bloquear quando reparada
+#[reducer]
+pub fn repair_structure(ctx: &ReducerContext, structure_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    if player.is_downed {
+        return Err("Cannot repair while downed".to_string());
+    }
+
+    let mut structure = ctx.db.structure().id().find(structure_id).ok_or("Structure not found")?;
+    let def = structure_def(&structure.structure_type).ok_or("Unknown structure type")?;
+    if player.current_map_id != structure.map_id {
+        return Err("Structure is in another map".to_string());
+    }
+
+    let (center_x, center_y) = tile_center(structure.tile_x, structure.tile_y);
+    if ((player.position_x - center_x).powi(2) + (player.position_y - center_y).powi(2)).sqrt() > REPAIR_RANGE {
+        return Err("Too far from the structure".to_string());
+    }
+    if structure.health >= structure.max_health {
+        return Err("Structure is not damaged".to_string());
+    }
+    crate::grief_protection::ensure_can_interact(ctx, crate::grief_protection::TARGET_STRUCTURE, structure.id, player.id)?;
+    if count_item(ctx, player.id, def.repair_item) < def.repair_quantity {
+        return Err(format!("Repair needs {} x{}", def.repair_item, def.repair_quantity));
+    }
+
+    // Não deixa a estrutura voltar a bloquear em cima de alguém
+    if structure.is_destroyed && is_tile_occupied(ctx, &structure.map_id, structure.tile_x, structure.tile_y) {
+        return Err("Someone is standing in the way".to_string());
+    }
+
+    remove_item_from_inventory(ctx, player.id, def.repair_item, def.repair_quantity)?;
+    structure.health = (structure.health + def.repair_amount).min(structure.max_health);
+    structure.updated_at = ctx.timestamp;
+    // Manutenção adia o decaimento de estruturas de players
+    if structure.owner_id.is_some() {
+        structure.decays_at = Some(decay_deadline(ctx));
+    }
+    if structure.is_destroyed {
+        structure.is_destroyed = false;
+        apply_collision(ctx, &structure)?;
+    }
+    log::info!("🔨 Player {} repaired {} {} to {}/{}", player.id, structure.structure_type, structure.id, structure.health, structure.max_health);
+    ctx.db.structure().id().update(structure);
+    Ok(())
+}
+
+fn is_tile_occupied(ctx: &ReducerContext, map_id: &str, tile_x: u32, tile_y: u32) -> bool {
+    let on_tile = |x: f32, y: f32| (x / TILE_SIZE) as u32 == tile_x && (y / TILE_SIZE) as u32 == tile_y;
+    ctx.db.player().iter().any(|p| p.current_map_id == map_id && on_tile(p.position_x, p.position_y))
+        || ctx.db.enemy().iter().any(|e| e.map_id == map_id && on_tile(e.position_x, e.position_y))
+}
+
+/// Regiões protegidas: zonas cadastradas, gatilhos de transição e o entorno do spawn
+fn is_no_build_tile(ctx: &ReducerContext, map_id: &str, tile_x: u32, tile_y: u32) -> bool {
+    let in_zone = ctx.db.no_build_zone().map_id().filter(map_id).any(|z| {
+        tile_x >= z.tile_x && tile_x < z.tile_x + z.width && tile_y >= z.tile_y && tile_y < z.tile_y + z.height
+    });
+    if in_zone {
+        return true;
+    }
+
+    let (center_x, center_y) = tile_center(tile_x, tile_y);
+    let in_transition = ctx.db.map_transition().iter().any(|t| {
+        t.map_id == map_id
+            && center_x >= t.x - TILE_SIZE && center_x <= t.x + t.width + TILE_SIZE
+            && center_y >= t.y - TILE_SIZE && center_y <= t.y + t.height + TILE_SIZE
+    });
+    if in_transition {
+        return true;
+    }
+
+    template_for_map(ctx, map_id).is_some_and(|t| {
+        let spawn_x = (t.spawn_x / TILE_SIZE) as u32;
+        let spawn_y = (t.spawn_y / TILE_SIZE) as u32;
+        tile_x.abs_diff(spawn_x) <= SPAWN_NO_BUILD_RADIUS && tile_y.abs_diff(spawn_y) <= SPAWN_NO_BUILD_RADIUS
+    })
+}
+
+/// Constrói uma estrutura a partir de uma planta (consumida junto com os materiais)
+#[reducer]
+pub fn build_structure(ctx: &ReducerContext, structure_type: String, tile_x: u32, tile_y: u32) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    if player.is_downed {
+        return Err("Cannot build while downed".to_string());
+    }
+    let def = structure_def(&structure_type)
+        .filter(|d| d.buildable)
+        .ok_or_else(|| format!("'{}' cannot be built by players", structure_type))?;
+
+    let map_id = player.current_map_id.clone();
+    let template = template_for_map(ctx, &map_id).ok_or("Map not found")?;
+    let (center_x, center_y) = tile_center(tile_x, tile_y);
+    if ((player.position_x - center_x).powi(2) + (player.position_y - center_y).powi(2)).sqrt() > BUILD_RANGE {
+        return Err("Too far from the build site".to_string());
+    }
+
+    // Validação do local
+    if is_no_build_tile(ctx, &map_id, tile_x, tile_y) {
+        return Err("Building is not allowed here".to_string());
+    }
+    let instance_id = ctx.db.map_instance().key_id().find(map_id.clone()).map(|i| i.id);
+    let current_tile = tile_at(ctx, &template, instance_id, tile_x, tile_y).ok_or("Tile out of bounds")?;
+    let valid_ground = if def.on_water {
+        WATER_TILES.contains(&current_tile)
+    } else {
+        !crate::map::is_blocking_tile(current_tile)
+    };
+    if !valid_ground {
+        return Err(if def.on_water { "Must be built over water" } else { "That tile is blocked" }.to_string());
+    }
+    if structure_at(ctx, &map_id, tile_x, tile_y).is_some() {
+        return Err("A structure already occupies that tile".to_string());
+    }
+    if is_tile_occupied(ctx, &map_id, tile_x, tile_y) {
+        return Err("Someone is standing in the way".to_string());
+    }
+
+    // Custo
+    let blueprint = blueprint_item(&structure_type);
+    if count_item(ctx, player.id, &blueprint) < 1 {
+        return Err(format!("You need a '{}'", blueprint));
+    }
+    if count_item(ctx, player.id, def.build_item) < def.build_quantity {
+        return Err(format!("Building needs {} x{}", def.build_item, def.build_quantity));
+    }
+    remove_item_from_inventory(ctx, player.id, &blueprint, 1)?;
+    remove_item_from_inventory(ctx, player.id, def.build_item, def.build_quantity)?;
+
+    let mut structure = place_structure(ctx, &map_id, &structure_type, tile_x, tile_y)?;
+    structure.owner_id = Some(player.id);
+    structure.decays_at = Some(decay_deadline(ctx));
+    structure.access_level = ACCESS_OWNER.to_string();
+    ctx.db.structure().id().update(structure.clone());
+    crate::grief_protection::on_structure_built(ctx, structure.id, player.id);
+
+    log::info!("🏗️ Player {} built {} {} at ({}, {}) in {}", player.id, structure_type, structure.id, tile_x, tile_y, map_id);
+    Ok(())
+}
+
+pub fn remove_structure(ctx: &ReducerContext, structure: &Structure) {
+    clear_tile_override(ctx, &structure.map_id, structure.tile_x, structure.tile_y);
+    let grants: Vec<u64> = ctx.db.structure_access().structure_id().filter(structure.id).map(|a| a.id).collect();
+    for id in grants {
+        ctx.db.structure_access().id().delete(id);
+    }
+    crate::grief_protection::clear_tag(ctx, crate::grief_protection::TARGET_STRUCTURE, structure.id);
+    ctx.db.structure().id().delete(structure.id);
+}
+
+/// O dono desmonta a própria estrutura
+#[reducer]
+pub fn demolish_structure(ctx: &ReducerContext, structure_id: u64) -> Result<(), String> {
+    let structure = require_owner(ctx, structure_id)?;
+    remove_structure(ctx, &structure);
+    log::info!("🏚️ {} {} demolished by its owner", structure.structure_type, structure_id);
+    Ok(())
+}
+
+/// Remove estruturas de players cujo prazo de manutenção venceu
+pub fn process_structure_decay(ctx: &ReducerContext) {
+    let decayed: Vec<Structure> = ctx.db.structure().iter()
+        .filter(|s| s.decays_at.is_some_and(|t| t <= ctx.timestamp))
+        .collect();
+    for structure in decayed {
+        log::info!("🍂 {} {} in {} decayed", structure.structure_type, structure.id, structure.map_id);
+        remove_structure(ctx, &structure);
+    }
+}
+
+#[reducer]
+pub fn add_no_build_zone(ctx: &ReducerContext, map_id: String, tile_x: u32, tile_y: u32, width: u32, height: u32) -> Result<(), String> {
+    crate::admin::require_admin(ctx)?;
+    if width == 0 || height == 0 {
+        return Err("Zone must not be empty".to_string());
+    }
+    template_for_map(ctx, &map_id).ok_or_else(|| format!("Map '{}' not found", map_id))?;
+    ctx.db.no_build_zone().insert(NoBuildZone { id: 0, map_id, tile_x, tile_y, width, height });
+    Ok(())
+}
+
+#[reducer]
+pub fn remove_no_build_zone(ctx: &ReducerContext, zone_id: u64) -> Result<(), String> {
+    crate::admin::require_admin(ctx)?;
+    ctx.db.no_build_zone().id().delete(zone_id);
+    Ok(())
+}
+
+/// Regra de acesso usada pelos reducers de interação (portas, estações etc.).
+/// Estruturas sem dono (do mundo) seguem apenas o nível de acesso.
+pub fn can_access_structure(ctx: &ReducerContext, structure: &Structure, player_id: u32) -> bool {
+    let Some(owner_id) = structure.owner_id else {
+        return structure.access_level == ACCESS_PUBLIC;
+    };
+    if owner_id == player_id {
+        return true;
+    }
+    if ctx.db.structure_access().structure_id().filter(structure.id).any(|a| a.player_id == player_id) {
+        return true;
+    }
+    match structure.access_level.as_str() {
+        ACCESS_PUBLIC => true,
+        ACCESS_GUILD => crate::guild::same_guild(ctx, owner_id, player_id),
+        ACCESS_FRIENDS => crate::friend::is_friend_of(ctx, owner_id, player_id),
+        _ => false,
+    }
+}
+
+/// Tier da melhor estação de crafting acessível ao alcance do player (0 = nenhuma)
+pub fn crafting_station_tier(ctx: &ReducerContext, player: &crate::Player) -> u32 {
+    ctx.db.structure().map_id().filter(player.current_map_id.as_str())
+        .filter(|s| !s.is_destroyed && s.structure_type == "crafting_station")
+        .filter(|s| {
+            let (x, y) = tile_center(s.tile_x, s.tile_y);
+            ((player.position_x - x).powi(2) + (player.position_y - y).powi(2)).sqrt() <= CRAFTING_STATION_RANGE
+        })
+        .filter(|s| can_access_structure(ctx, s, player.id))
+        .map(|_| CRAFTING_STATION_TIER)
+        .max()
+        .unwrap_or(0)
+}
+
+fn require_owner(ctx: &ReducerContext, structure_id: u64) -> Result<Structure, String> {
+    let player = sender_player(ctx)?;
+    let structure = ctx.db.structure().id().find(structure_id).ok_or("Structure not found")?;
+    if structure.owner_id != Some(player.id) {
+        return Err("You don't own that structure".to_string());
+    }
+    Ok(structure)
+}
+
+#[reducer]
+pub fn set_structure_access(ctx: &ReducerContext, structure_id: u64, access_level: String) -> Result<(), String> {
+    let mut structure = require_owner(ctx, structure_id)?;
+    if !ACCESS_LEVELS.contains(&access_level.as_str()) {
+        return Err(format!("Unknown access level '{}'", access_level));
+    }
+    structure.access_level = access_level;
+    ctx.db.structure().id().update(structure);
+    Ok(())
+}
+
+#[reducer]
+pub fn grant_structure_access(ctx: &ReducerContext, structure_id: u64, player_id: u32) -> Result<(), String> {
+    let structure = require_owner(ctx, structure_id)?;
+    if ctx.db.player().id().find(player_id).is_none() {
+        return Err("Player not found".to_string());
+    }
+    if ctx.db.structure_access().structure_id().filter(structure.id).any(|a| a.player_id == player_id) {
+        return Ok(());
+    }
+    ctx.db.structure_access().insert(StructureAccess { id: 0, structure_id, player_id, granted_at: ctx.timestamp });
+    Ok(())
+}
+
+#[reducer]
+pub fn revoke_structure_access(ctx: &ReducerContext, structure_id: u64, player_id: u32) -> Result<(), String> {
+    require_owner(ctx, structure_id)?;
+    let grants: Vec<u64> = ctx.db.structure_access().structure_id().filter(structure_id)
+        .filter(|a| a.player_id == player_id)
+        .map(|a| a.id)
+        .collect();
+    for id in grants {
+        ctx.db.structure_access().id().delete(id);
+    }
+    Ok(())
+}
+
+/// Abre/fecha uma porta ou portão (troca o tile de colisão)
+#[reducer]
+pub fn toggle_door(ctx: &ReducerContext, structure_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let mut structure = ctx.db.structure().id().find(structure_id).ok_or("Structure not found")?;
+    let def = structure_def(&structure.structure_type).ok_or("Unknown structure type")?;
+    if def.open_tile == 0 {
+        return Err("That structure is not a door".to_string());
+    }
+    if structure.is_destroyed {
+        return Err("The door is destroyed".to_string());
+    }
+    if player.current_map_id != structure.map_id {
+        return Err("Structure is in another map".to_string());
+    }
+    let (center_x, center_y) = tile_center(structure.tile_x, structure.tile_y);
+    if ((player.position_x - center_x).powi(2) + (player.position_y - center_y).powi(2)).sqrt() > REPAIR_RANGE {
+        return Err("Too far from the door".to_string());
+    }
+    if !can_access_structure(ctx, &structure, player.id) {
+        return Err("You don't have access to that door".to_string());
+    }
+    crate::grief_protection::ensure_can_interact(ctx, crate::grief_protection::TARGET_STRUCTURE, structure.id, player.id)?;
+    // Fechar em cima de alguém prenderia o player na colisão
+    if structure.is_open && is_tile_occupied(ctx, &structure.map_id, structure.tile_x, structure.tile_y) {
+        return Err("Someone is standing in the way".to_string());
+    }
+
+    structure.is_open = !structure.is_open;
+    apply_collision(ctx, &structure)?;
+    ctx.db.structure().id().update(structure);
+    Ok(())
+}
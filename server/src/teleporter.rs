@@ -0,0 +1,186 @@
+use crate::cooldown::{is_on_cooldown, start_cooldown, CATEGORY_TRANSITION};
+use crate::map::{map_template, TILE_SIZE};
+use crate::movement::refresh_player_motion;
+use crate::{player, Player};
+use spacetimedb::{table, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+/// Tiles 40..=47 são pads de teleporte: pads com o mesmo tile no mesmo mapa
+/// formam uma rede e cada um leva ao próximo (em ordem de leitura do CSV).
+pub const TELEPORTER_TILE_MIN: u32 = 40;
+pub const TELEPORTER_TILE_MAX: u32 = 47;
+
+const TELEPORT_CHANNEL_MS: u64 = 1500;
+const TELEPORT_COOLDOWN_MS: u64 = 5000;
+const TELEPORT_COOLDOWN_KEY: &str = "teleporter";
+
+pub const CHANNEL_STATE_CHANNELING: &str = "Channeling";
+/// Recém-chegado: precisa sair do pad antes de poder canalizar de novo
+const CHANNEL_STATE_ARRIVED: &str = "Arrived";
+
+#[table(name = teleporter_pad, public)]
+#[derive(Clone)]
+pub struct TeleporterPad {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub map_id: String,
+    pub network_id: u32, // tile id do pad
+    pub sequence: u32,   // ordem dentro da rede
+    pub tile_x: u32,
+    pub tile_y: u32,
+}
+
+/// Canalização em andamento (ou chegada recente) de um player em um pad
+#[table(name = teleport_channel, public)]
+#[derive(Clone)]
+pub struct TeleportChannel {
+    #[primary_key]
+    pub player_id: u32,
+    pub pad_id: u64,
+    pub state: String,
+    pub started_at: Timestamp,
+    pub completes_at: Timestamp,
+}
+
+pub fn is_teleporter_tile(tile_id: u32) -> bool {
+    (TELEPORTER_TILE_MIN..=TELEPORTER_TILE_MAX).contains(&tile_id)
+}
+
+fn pad_center(pad: &TeleporterPad) -> (f32, f32) {
+    (pad.tile_x as f32 * TILE_SIZE + TILE_SIZE / 2.0, pad.tile_y as f32 * TILE_SIZE + TILE_SIZE / 2.0)
+}
+
+/// Recria os pads a partir dos tiles dos templates (init e redeploy de templates)
+pub fn rebuild_teleporter_pads(ctx: &ReducerContext) {
+    for pad in ctx.db.teleporter_pad().iter() {
+        ctx.db.teleporter_pad().id().delete(pad.id);
+    }
+
+    for template in ctx.db.map_template().iter() {
+        let mut sequence_by_network: Vec<(u32, u32)> = Vec::new();
+
+        for (index, tile_id) in template.tile_data.iter().enumerate() {
+            if !is_teleporter_tile(*tile_id) {
+                continue;
+            }
+
+            let sequence = match sequence_by_network.iter_mut().find(|(n, _)| n == tile_id) {
+                Some((_, next)) => { *next += 1; *next - 1 }
+                None => { sequence_by_network.push((*tile_id, 1)); 0 }
+            };
+
+            ctx.db.teleporter_pad().insert(TeleporterPad {
+                id: 0,
+                map_id: template.name.clone(),
+                network_id: *tile_id,
+                sequence,
+                tile_x: index as u32 % template.width,
+                tile_y: index as u32 / template.width,
+            });
+        }
+
+        for (network_id, count) in sequence_by_network {
+            if count < 2 {
+                log::warn!("⚠️ Teleporter network {} in '{}' has a single pad and leads nowhere", network_id, template.name);
+            }
+        }
+    }
+}
+
+fn pad_under_player(pads: &[TeleporterPad], player: &Player) -> Option<TeleporterPad> {
+    let tile_x = (player.position_x / TILE_SIZE) as u32;
+    let tile_y = (player.position_y / TILE_SIZE) as u32;
+    pads.iter().find(|p| p.tile_x == tile_x && p.tile_y == tile_y).cloned()
+}
+
+fn next_pad_in_network(pads: &[TeleporterPad], from: &TeleporterPad) -> Option<TeleporterPad> {
+    let mut network: Vec<&TeleporterPad> = pads.iter().filter(|p| p.network_id == from.network_id).collect();
+    if network.len() < 2 {
+        return None;
+    }
+    network.sort_by_key(|p| p.sequence);
+    let position = network.iter().position(|p| p.id == from.id)?;
+    Some(network[(position + 1) % network.len()].clone())
+}
+
+/// Checagem por tick: inicia, cancela ou conclui canalizações de teleporte
+pub fn process_teleporters(ctx: &ReducerContext) {
+    let pads: Vec<TeleporterPad> = ctx.db.teleporter_pad().iter().collect();
+    if pads.is_empty() {
+        return;
+    }
+
+    let mut maps_with_pads: Vec<&str> = pads.iter().map(|p| p.map_id.as_str()).collect();
+    maps_with_pads.sort_unstable();
+    maps_with_pads.dedup();
+
+    let players: Vec<Player> = ctx.db.player().iter()
+        .filter(|p| !p.is_downed && maps_with_pads.contains(&p.current_map_id.as_str()))
+        .collect();
+
+    for player in players {
+        let map_pads: Vec<TeleporterPad> = pads.iter().filter(|p| p.map_id == player.current_map_id).cloned().collect();
+        let standing_on = pad_under_player(&map_pads, &player);
+        let channel = ctx.db.teleport_channel().player_id().find(player.id);
+
+        match (standing_on, channel) {
+            // Saiu do pad: cancela canalização / libera o pad de chegada
+            (None, Some(channel)) => {
+                ctx.db.teleport_channel().player_id().delete(channel.player_id);
+            }
+            (None, None) => {}
+            // Trocou de pad no meio da canalização
+            (Some(pad), Some(channel)) if channel.pad_id != pad.id => {
+                ctx.db.teleport_channel().player_id().delete(channel.player_id);
+            }
+            (Some(pad), Some(channel)) => {
+                if channel.state == CHANNEL_STATE_CHANNELING && channel.completes_at <= ctx.timestamp {
+                    complete_teleport(ctx, player, &pad, &map_pads);
+                }
+            }
+            (Some(pad), None) => {
+                if is_on_cooldown(ctx, player.identity, CATEGORY_TRANSITION, TELEPORT_COOLDOWN_KEY) {
+                    continue;
+                }
+                ctx.db.teleport_channel().insert(TeleportChannel {
+                    player_id: player.id,
+                    pad_id: pad.id,
+                    state: CHANNEL_STATE_CHANNELING.to_string(),
+                    started_at: ctx.timestamp,
+                    completes_at: ctx.timestamp + TimeDuration::from_duration(Duration::from_millis(TELEPORT_CHANNEL_MS)),
+                });
+                log::info!("🌀 Player {} channeling teleporter {} in {}", player.id, pad.id, pad.map_id);
+            }
+        }
+    }
+}
+
+fn complete_teleport(ctx: &ReducerContext, player: Player, from: &TeleporterPad, map_pads: &[TeleporterPad]) {
+    ctx.db.teleport_channel().player_id().delete(player.id);
+
+    let Some(destination) = next_pad_in_network(map_pads, from) else {
+        return;
+    };
+    let (dest_x, dest_y) = pad_center(&destination);
+
+    let mut updated_player = player.clone();
+    updated_player.position_x = dest_x;
+    updated_player.position_y = dest_y;
+    updated_player.velocity_x = 0.0;
+    updated_player.velocity_y = 0.0;
+    refresh_player_motion(&mut updated_player, ctx.timestamp);
+    ctx.db.player().id().update(updated_player);
+
+    start_cooldown(ctx, player.identity, CATEGORY_TRANSITION, TELEPORT_COOLDOWN_KEY, TELEPORT_COOLDOWN_MS);
+    ctx.db.teleport_channel().insert(TeleportChannel {
+        player_id: player.id,
+        pad_id: destination.id,
+        state: CHANNEL_STATE_ARRIVED.to_string(),
+        started_at: ctx.timestamp,
+        completes_at: ctx.timestamp,
+    });
+
+    log::info!("🌀 Player {} teleported from pad {} to pad {} in {}", player.id, from.id, destination.id, from.map_id);
+}
@@ -0,0 +1,184 @@
+use crate::combat::{enemy, refresh_enemy_motion, Enemy};
+use crate::cooldown::{try_start_cooldown, CATEGORY_ABILITY, SHOUT_COOLDOWN_MS, TAUNT_COOLDOWN_MS};
+use crate::map::TILE_SIZE;
+use crate::party::sender_player;
+use crate::{player, Player};
+use spacetimedb::{reducer, table, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+pub const TAUNT_RANGE: f32 = TILE_SIZE * 10.0;
+pub const TAUNT_DURATION_SECS: u64 = 4;
+pub const SHOUT_RADIUS: f32 = TILE_SIZE * 6.0;
+pub const SHOUT_THREAT: f32 = 100.0;
+
+/// Ameaça acumulada de um player contra um inimigo (dano causado, provocações).
+/// O inimigo persegue quem estiver no topo da tabela.
+#[table(name = threat_entry, public)]
+#[derive(Clone)]
+pub struct ThreatEntry {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub enemy_id: u32,
+    pub player_id: u32,
+    pub threat: f32,
+    pub updated_at: Timestamp,
+}
+
+/// Provocação ativa: o inimigo é forçado a perseguir `player_id` até `expires_at`
+#[table(name = enemy_taunt, public)]
+#[derive(Clone)]
+pub struct EnemyTaunt {
+    #[primary_key]
+    pub enemy_id: u32,
+    pub player_id: u32,
+    pub expires_at: Timestamp,
+}
+
+fn entry_for(ctx: &ReducerContext, enemy_id: u32, player_id: u32) -> Option<ThreatEntry> {
+    ctx.db.threat_entry().enemy_id().filter(enemy_id).find(|t| t.player_id == player_id)
+}
+
+pub fn add_threat(ctx: &ReducerContext, enemy_id: u32, player_id: u32, amount: f32) {
+    if amount <= 0.0 {
+        return;
+    }
+    match entry_for(ctx, enemy_id, player_id) {
+        Some(mut entry) => {
+            entry.threat += amount;
+            entry.updated_at = ctx.timestamp;
+            ctx.db.threat_entry().id().update(entry);
+        }
+        None => {
+            ctx.db.threat_entry().insert(ThreatEntry {
+                id: 0,
+                enemy_id,
+                player_id,
+                threat: amount,
+                updated_at: ctx.timestamp,
+            });
+        }
+    }
+}
+
+fn can_be_targeted(player: &Player, enemy: &Enemy) -> bool {
+    !player.is_downed && player.current_map_id == enemy.map_id
+}
+
+/// Quem o inimigo deve perseguir: o provocador enquanto a provocação durar,
+/// senão o player de maior ameaça que ainda pode ser alvo
+pub fn threat_target(ctx: &ReducerContext, enemy: &Enemy) -> Option<u32> {
+    let taunter = ctx.db.enemy_taunt().enemy_id().find(enemy.id)
+        .filter(|t| t.expires_at > ctx.timestamp)
+        .and_then(|t| ctx.db.player().id().find(t.player_id))
+        .filter(|p| can_be_targeted(p, enemy));
+    if let Some(taunter) = taunter {
+        return Some(taunter.id);
+    }
+    ctx.db.threat_entry().enemy_id().filter(enemy.id)
+        .filter(|t| ctx.db.player().id().find(t.player_id).is_some_and(|p| can_be_targeted(&p, enemy)))
+        .max_by(|a, b| a.threat.total_cmp(&b.threat))
+        .map(|t| t.player_id)
+}
+
+fn top_threat(ctx: &ReducerContext, enemy_id: u32) -> f32 {
+    ctx.db.threat_entry().enemy_id().filter(enemy_id).map(|t| t.threat).fold(0.0, f32::max)
+}
+
+/// Inimigo derrotado ou removido: a tabela dele deixa de existir
+pub fn clear_enemy(ctx: &ReducerContext, enemy_id: u32) {
+    let ids: Vec<u64> = ctx.db.threat_entry().enemy_id().filter(enemy_id).map(|t| t.id).collect();
+    for id in ids {
+        ctx.db.threat_entry().id().delete(id);
+    }
+    ctx.db.enemy_taunt().enemy_id().delete(enemy_id);
+}
+
+/// Quem cai sai de todas as tabelas de ameaça
+pub fn on_player_downed(ctx: &ReducerContext, player_id: u32) {
+    let ids: Vec<u64> = ctx.db.threat_entry().iter().filter(|t| t.player_id == player_id).map(|t| t.id).collect();
+    for id in ids {
+        ctx.db.threat_entry().id().delete(id);
+    }
+}
+
+/// Limpeza periódica: entradas de inimigos que já não existem e provocações vencidas
+pub fn prune_threat(ctx: &ReducerContext) {
+    let orphaned: Vec<u64> = ctx.db.threat_entry().iter()
+        .filter(|t| ctx.db.enemy().id().find(t.enemy_id).is_none())
+        .map(|t| t.id)
+        .collect();
+    for id in orphaned {
+        ctx.db.threat_entry().id().delete(id);
+    }
+    let expired: Vec<u32> = ctx.db.enemy_taunt().iter()
+        .filter(|t| t.expires_at <= ctx.timestamp || ctx.db.enemy().id().find(t.enemy_id).is_none())
+        .map(|t| t.enemy_id)
+        .collect();
+    for enemy_id in expired {
+        ctx.db.enemy_taunt().enemy_id().delete(enemy_id);
+    }
+}
+
+fn distance_to(player: &Player, enemy: &Enemy) -> f32 {
+    ((player.position_x - enemy.position_x).powi(2) + (player.position_y - enemy.position_y).powi(2)).sqrt()
+}
+
+/// Provoca um inimigo: ele troca de alvo para o provocador por alguns segundos
+/// e a ameaça do provocador sobe até a do topo, para ele não perder o alvo logo depois
+#[reducer]
+pub fn taunt(ctx: &ReducerContext, enemy_id: u32) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if player.is_downed {
+        return Err("Cannot taunt while downed".to_string());
+    }
+    let mut enemy = ctx.db.enemy().id().find(enemy_id).filter(|e| e.is_active).ok_or("Enemy not found")?;
+    if enemy.map_id != player.current_map_id || distance_to(&player, &enemy) > TAUNT_RANGE {
+        return Err("Enemy is out of range".to_string());
+    }
+    try_start_cooldown(ctx, ctx.sender, CATEGORY_ABILITY, "taunt", TAUNT_COOLDOWN_MS)?;
+
+    let own = entry_for(ctx, enemy_id, player.id).map(|t| t.threat).unwrap_or(0.0);
+    add_threat(ctx, enemy_id, player.id, top_threat(ctx, enemy_id) - own);
+
+    let taunt = EnemyTaunt {
+        enemy_id,
+        player_id: player.id,
+        expires_at: ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(TAUNT_DURATION_SECS)),
+    };
+    if ctx.db.enemy_taunt().enemy_id().find(enemy_id).is_some() {
+        ctx.db.enemy_taunt().enemy_id().update(taunt);
+    } else {
+        ctx.db.enemy_taunt().insert(taunt);
+    }
+
+    enemy.state = "Chasing".to_string();
+    enemy.target_player_id = Some(player.id);
+    enemy.target_map_id = Some(player.current_map_id.clone());
+    refresh_enemy_motion(&mut enemy, ctx.timestamp);
+    ctx.db.enemy().id().update(enemy);
+
+    log::info!("🛡️ Player {} taunted enemy {}", player.id, enemy_id);
+    Ok(())
+}
+
+/// Grito de guerra: ameaça fixa em todos os inimigos ativos ao redor
+#[reducer]
+pub fn battle_shout(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    if player.is_downed {
+        return Err("Cannot shout while downed".to_string());
+    }
+    try_start_cooldown(ctx, ctx.sender, CATEGORY_ABILITY, "shout", SHOUT_COOLDOWN_MS)?;
+
+    let nearby: Vec<u32> = ctx.db.enemy().map_id().filter(&player.current_map_id)
+        .filter(|e| e.is_active && distance_to(&player, e) <= SHOUT_RADIUS)
+        .map(|e| e.id)
+        .collect();
+    for enemy_id in &nearby {
+        add_threat(ctx, *enemy_id, player.id, SHOUT_THREAT);
+    }
+    log::info!("📣 Player {} shouted at {} enemies", player.id, nearby.len());
+    Ok(())
+}
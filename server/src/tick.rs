@@ -0,0 +1,95 @@
+use crate::ability_queue::queued_ability;
+use crate::arena::arena_match;
+use crate::caravan::caravan_delivery;
+use crate::damage_meter::encounter;
+use crate::invasion::invasion;
+use crate::status_effect::status_effect;
+use crate::teleporter::teleport_channel;
+use crate::tick_budget::TickMeter;
+use spacetimedb::{reducer, table, ReducerContext, ScheduleAt, Table};
+use std::time::Duration;
+
+pub const WORLD_TICK_MS: u64 = 50;
+
+/// Agenda do tick autoritativo do mundo (uma única linha em loop)
+#[table(name = world_tick_schedule, scheduled(world_tick))]
+pub struct WorldTickSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+/// Garante que o tick está agendado (init e republish sem limpar o banco)
+pub fn ensure_world_tick(ctx: &ReducerContext) {
+    if ctx.db.world_tick_schedule().count() == 0 {
+        ctx.db.world_tick_schedule().insert(WorldTickSchedule {
+            scheduled_id: 0,
+            scheduled_at: Duration::from_millis(WORLD_TICK_MS).into(),
+        });
+        log::info!("⏱️ World tick agendado a cada {} ms", WORLD_TICK_MS);
+    }
+}
+
+#[reducer]
+pub fn world_tick(ctx: &ReducerContext, _schedule: WorldTickSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("world_tick may only be invoked by the scheduler".to_string());
+    }
+    // Congelado para upgrade: nada avança até o resume
+    if crate::upgrade::is_frozen(ctx) {
+        return Ok(());
+    }
+
+    // Custo de cada sistema ~ linhas que ele percorre
+    let mut meter = TickMeter::default();
+    meter.measure("ability_queue", || {
+        let units = ctx.db.queued_ability().count();
+        crate::ability_queue::process_ability_queue(ctx);
+        units
+    });
+    meter.measure("enemy_wander", || crate::enemy_ai::process_enemy_wander(ctx));
+    meter.measure("teleporters", || {
+        let units = ctx.db.teleport_channel().count();
+        crate::teleporter::process_teleporters(ctx);
+        units
+    });
+    meter.measure("story_spawners", || crate::story_flag::process_story_spawners(ctx));
+    meter.measure("hirelings", || crate::hireling::process_hirelings(ctx));
+    meter.measure("party_auras", || crate::aura::process_party_auras(ctx));
+    meter.measure("status_effects", || {
+        let units = ctx.db.status_effect().count();
+        crate::status_effect::process_status_effects(ctx);
+        units
+    });
+    meter.measure("invasions", || {
+        let units = ctx.db.invasion().iter().map(|i| i.invader_ids.len() as u64).sum();
+        crate::invasion::process_invasions(ctx);
+        units
+    });
+    meter.measure("caravans", || {
+        let units = ctx.db.caravan_delivery().count();
+        crate::caravan::process_caravans(ctx);
+        units
+    });
+    meter.measure("arena", || {
+        let units = ctx.db.arena_match().count();
+        crate::arena::process_arena_matches(ctx);
+        units
+    });
+    meter.measure("projectiles", || {
+        crate::combat::process_projectiles(ctx, WORLD_TICK_MS as f32 / 1000.0).unwrap_or_else(|e| {
+            log::warn!("⚠️ Projectile update failed: {}", e);
+            0
+        })
+    });
+    meter.measure("replays", || crate::replay::capture_replay_frames(ctx));
+    meter.measure("damage_meter", || {
+        let units = ctx.db.encounter().count();
+        crate::damage_meter::close_idle_encounters(ctx);
+        units
+    });
+    meter.finish(ctx);
+
+    Ok(())
+}
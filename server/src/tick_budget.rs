@@ -0,0 +1,160 @@
+use crate::metric::record_metric;
+use spacetimedb::{table, ReducerContext, SpacetimeType, Table};
+
+// O módulo roda em WASM sem relógio: o custo de cada sistema é medido em
+// unidades de trabalho (linhas processadas), que acompanham o tempo gasto.
+const TICK_BUDGET_UNITS: u64 = 2000;
+/// Ticks seguidos acima do orçamento antes de subir um nível de throttle
+const OVER_BUDGET_TICKS: u32 = 20;
+/// Ticks seguidos abaixo de `RECOVERY_RATIO` do orçamento antes de descer um nível
+const RECOVERY_TICKS: u32 = 200;
+const RECOVERY_RATIO: f64 = 0.75;
+pub const MAX_THROTTLE_LEVEL: u8 = 2;
+
+pub const METRIC_TICK_THROTTLE: &str = "tick_throttle";
+
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct SystemCost {
+    pub system: String,
+    pub units: u64,
+}
+
+/// Estado do orçamento do world tick (linha única, id 0)
+#[table(name = tick_budget)]
+#[derive(Clone)]
+pub struct TickBudget {
+    #[primary_key]
+    pub id: u32,
+    pub tick: u64,
+    pub throttle_level: u8,
+    pub over_budget_ticks: u32,
+    pub under_budget_ticks: u32,
+    pub last_total_units: u64,
+    pub last_costs: Vec<SystemCost>,
+}
+
+fn budget(ctx: &ReducerContext) -> TickBudget {
+    ctx.db.tick_budget().id().find(0).unwrap_or(TickBudget {
+        id: 0,
+        tick: 0,
+        throttle_level: 0,
+        over_budget_ticks: 0,
+        under_budget_ticks: 0,
+        last_total_units: 0,
+        last_costs: Vec::new(),
+    })
+}
+
+pub fn throttle_level(ctx: &ReducerContext) -> u8 {
+    ctx.db.tick_budget().id().find(0).map(|b| b.throttle_level).unwrap_or(0)
+}
+
+/// Multiplicador do intervalo de atualização da IA de inimigos longe de players
+pub fn distant_ai_slowdown(ctx: &ReducerContext) -> u64 {
+    match throttle_level(ctx) {
+        0 => 1,
+        1 => 2,
+        _ => 4,
+    }
+}
+
+/// Teto de sub-passos de colisão por atualização de projéteis
+pub fn max_projectile_substeps(ctx: &ReducerContext) -> u32 {
+    match throttle_level(ctx) {
+        0 => 4,
+        1 => 2,
+        _ => 1,
+    }
+}
+
+/// Sub-passos de um projétil: só quem anda mais que `collision_diameter` numa
+/// atualização pode atravessar um alvo e precisa de mais de um
+pub fn projectile_substeps(distance: f32, collision_diameter: f32, max_substeps: u32) -> u32 {
+    ((distance / collision_diameter).ceil() as u32).clamp(1, max_substeps.max(1))
+}
+
+pub fn current_tick(ctx: &ReducerContext) -> u64 {
+    ctx.db.tick_budget().id().find(0).map(|b| b.tick).unwrap_or(0)
+}
+
+/// Coleta o custo de cada sistema durante um tick
+#[derive(Default)]
+pub struct TickMeter {
+    costs: Vec<SystemCost>,
+}
+
+impl TickMeter {
+    /// Roda o sistema; `run` retorna quantas unidades de trabalho consumiu
+    pub fn measure(&mut self, system: &str, run: impl FnOnce() -> u64) {
+        let units = run();
+        self.costs.push(SystemCost { system: system.to_string(), units });
+    }
+
+    /// Fecha o tick: compara com o orçamento e ajusta o nível de throttle
+    pub fn finish(self, ctx: &ReducerContext) {
+        let mut state = budget(ctx);
+        let total: u64 = self.costs.iter().map(|c| c.units).sum();
+        state.tick += 1;
+        state.last_total_units = total;
+
+        if total > TICK_BUDGET_UNITS {
+            state.over_budget_ticks += 1;
+            state.under_budget_ticks = 0;
+        } else if (total as f64) < TICK_BUDGET_UNITS as f64 * RECOVERY_RATIO {
+            state.under_budget_ticks += 1;
+            state.over_budget_ticks = 0;
+        } else {
+            state.over_budget_ticks = 0;
+            state.under_budget_ticks = 0;
+        }
+
+        let previous = state.throttle_level;
+        if state.over_budget_ticks >= OVER_BUDGET_TICKS && state.throttle_level < MAX_THROTTLE_LEVEL {
+            state.throttle_level += 1;
+            state.over_budget_ticks = 0;
+        } else if state.under_budget_ticks >= RECOVERY_TICKS && state.throttle_level > 0 {
+            state.throttle_level -= 1;
+            state.under_budget_ticks = 0;
+        }
+
+        state.last_costs = self.costs;
+        if state.throttle_level != previous {
+            let breakdown = state.last_costs.iter()
+                .map(|c| format!("{}={}", c.system, c.units))
+                .collect::<Vec<_>>()
+                .join(", ");
+            record_metric(
+                ctx,
+                METRIC_TICK_THROTTLE,
+                state.throttle_level as f64,
+                format!("level {} -> {} at {} units/tick ({})", previous, state.throttle_level, total, breakdown),
+            );
+            log::warn!("⏱️ Tick throttle {} -> {} ({} units, budget {})",
+                       previous, state.throttle_level, total, TICK_BUDGET_UNITS);
+        }
+
+        if ctx.db.tick_budget().id().find(0).is_some() {
+            ctx.db.tick_budget().id().update(state);
+        } else {
+            ctx.db.tick_budget().insert(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_projectiles_take_a_single_step() {
+        assert_eq!(projectile_substeps(0.0, 10.0, 4), 1);
+        assert_eq!(projectile_substeps(10.0, 10.0, 4), 1);
+    }
+
+    #[test]
+    fn fast_projectiles_split_up_to_the_cap() {
+        assert_eq!(projectile_substeps(20.0, 10.0, 4), 2);
+        assert_eq!(projectile_substeps(100.0, 10.0, 4), 4);
+        assert_eq!(projectile_substeps(100.0, 10.0, 1), 1);
+    }
+}
@@ -0,0 +1,319 @@
+use crate::admin::require_admin;
+use crate::arena::create_arena_match;
+use crate::audit::record_audit;
+use crate::mail::send_system_mail;
+use crate::party::sender_player;
+use crate::player;
+use spacetimedb::rand::seq::SliceRandom;
+use spacetimedb::{reducer, table, ReducerContext, ScheduleAt, SpacetimeType, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+/// Intervalo entre a geração de uma rodada e o início das partidas
+const ROUND_START_DELAY_SECS: u64 = 60;
+const MIN_ENTRANTS: usize = 2;
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub enum TournamentState {
+    SignUp,
+    InProgress,
+    Finished,
+}
+
+/// Torneio de eliminação simples disputado em partidas 1v1 na arena
+#[table(name = tournament, public)]
+#[derive(Clone)]
+pub struct Tournament {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub name: String,
+    pub arena_template: String,
+    pub max_entrants: u32,
+    pub prize_gold: u64,
+    pub state: TournamentState,
+    pub current_round: u32,
+    pub winner_id: Option<u32>,
+    pub created_at: Timestamp,
+}
+
+#[table(name = tournament_entrant, public)]
+#[derive(Clone)]
+pub struct TournamentEntrant {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub tournament_id: u64,
+    pub player_id: u32,
+    pub signed_up_at: Timestamp,
+}
+
+/// Confronto do chaveamento. Vagas vazias (`None`) são byes: o outro avança direto.
+#[table(name = tournament_match, public)]
+#[derive(Clone)]
+pub struct TournamentMatch {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub tournament_id: u64,
+    pub round: u32,
+    pub slot: u32,
+    pub player_a: Option<u32>,
+    pub player_b: Option<u32>,
+    pub arena_match_id: Option<u64>,
+    pub winner_id: Option<u32>,
+    pub scheduled_at: Timestamp,
+}
+
+/// Agenda de início de cada confronto (one-shot)
+#[table(name = tournament_match_schedule, scheduled(start_tournament_match))]
+pub struct TournamentMatchSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+    pub match_id: u64,
+}
+
+#[reducer]
+pub fn create_tournament(ctx: &ReducerContext, name: String, arena_template: String, max_entrants: u32, prize_gold: u64) -> Result<(), String> {
+    require_admin(ctx)?;
+    if (max_entrants as usize) < MIN_ENTRANTS {
+        return Err(format!("A tournament needs at least {} entrants", MIN_ENTRANTS));
+    }
+    let tournament = ctx.db.tournament().insert(Tournament {
+        id: 0,
+        name,
+        arena_template,
+        max_entrants,
+        prize_gold,
+        state: TournamentState::SignUp,
+        current_round: 0,
+        winner_id: None,
+        created_at: ctx.timestamp,
+    });
+    record_audit(ctx, "tournament", format!("Tournament {} '{}' created", tournament.id, tournament.name));
+    Ok(())
+}
+
+#[reducer]
+pub fn sign_up_tournament(ctx: &ReducerContext, tournament_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let tournament = ctx.db.tournament().id().find(tournament_id).ok_or("Tournament not found")?;
+    if tournament.state != TournamentState::SignUp {
+        return Err("Sign-ups are closed".to_string());
+    }
+    let entrants: Vec<TournamentEntrant> = ctx.db.tournament_entrant().tournament_id().filter(tournament_id).collect();
+    if entrants.iter().any(|e| e.player_id == player.id) {
+        return Err("Already signed up".to_string());
+    }
+    if entrants.len() >= tournament.max_entrants as usize {
+        return Err("Tournament is full".to_string());
+    }
+    ctx.db.tournament_entrant().insert(TournamentEntrant {
+        id: 0,
+        tournament_id,
+        player_id: player.id,
+        signed_up_at: ctx.timestamp,
+    });
+    Ok(())
+}
+
+#[reducer]
+pub fn withdraw_from_tournament(ctx: &ReducerContext, tournament_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let tournament = ctx.db.tournament().id().find(tournament_id).ok_or("Tournament not found")?;
+    if tournament.state != TournamentState::SignUp {
+        return Err("The bracket has already been generated".to_string());
+    }
+    let entry = ctx.db.tournament_entrant().tournament_id().filter(tournament_id)
+        .find(|e| e.player_id == player.id)
+        .ok_or("Not signed up")?;
+    ctx.db.tournament_entrant().id().delete(entry.id);
+    Ok(())
+}
+
+/// Fecha as inscrições e gera a primeira rodada (chaveamento aleatório com byes)
+#[reducer]
+pub fn start_tournament(ctx: &ReducerContext, tournament_id: u64) -> Result<(), String> {
+    require_admin(ctx)?;
+    let mut tournament = ctx.db.tournament().id().find(tournament_id).ok_or("Tournament not found")?;
+    if tournament.state != TournamentState::SignUp {
+        return Err("Tournament already started".to_string());
+    }
+    let mut players: Vec<Option<u32>> = ctx.db.tournament_entrant().tournament_id().filter(tournament_id)
+        .map(|e| Some(e.player_id))
+        .collect();
+    if players.len() < MIN_ENTRANTS {
+        return Err(format!("A tournament needs at least {} entrants", MIN_ENTRANTS));
+    }
+    players.shuffle(&mut ctx.rng());
+    players.resize(players.len().next_power_of_two(), None);
+    // Cada bye fica em um confronto diferente (a primeira metade está sempre cheia)
+    let half = players.len() / 2;
+    let players: Vec<Option<u32>> = (0..half).flat_map(|i| [players[i], players[i + half]]).collect();
+
+    tournament.state = TournamentState::InProgress;
+    tournament.current_round = 1;
+    ctx.db.tournament().id().update(tournament.clone());
+    create_round(ctx, &tournament, 1, &players);
+    log::info!("🏆 Tournament '{}' started with {} entrants", tournament.name, players.iter().flatten().count());
+    Ok(())
+}
+
+fn create_round(ctx: &ReducerContext, tournament: &Tournament, round: u32, players: &[Option<u32>]) {
+    let scheduled_at = ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(ROUND_START_DELAY_SECS));
+    for (slot, pair) in players.chunks(2).enumerate() {
+        let (player_a, player_b) = (pair[0], pair.get(1).copied().flatten());
+        // Bye: quem está sozinho avança sem jogar
+        let winner_id = match (player_a, player_b) {
+            (Some(a), None) => Some(a),
+            (None, Some(b)) => Some(b),
+            _ => None,
+        };
+        let tournament_match = ctx.db.tournament_match().insert(TournamentMatch {
+            id: 0,
+            tournament_id: tournament.id,
+            round,
+            slot: slot as u32,
+            player_a,
+            player_b,
+            arena_match_id: None,
+            winner_id,
+            scheduled_at,
+        });
+        if winner_id.is_none() && player_a.is_some() {
+            ctx.db.tournament_match_schedule().insert(TournamentMatchSchedule {
+                scheduled_id: 0,
+                scheduled_at: scheduled_at.into(),
+                match_id: tournament_match.id,
+            });
+        }
+    }
+    advance_if_round_complete(ctx, tournament.id);
+}
+
+#[reducer]
+pub fn start_tournament_match(ctx: &ReducerContext, schedule: TournamentMatchSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("start_tournament_match may only be invoked by the scheduler".to_string());
+    }
+    if crate::upgrade::is_frozen(ctx) {
+        crate::upgrade::hold_timer(ctx, crate::upgrade::TIMER_TOURNAMENT_MATCH, schedule.match_id);
+        return Ok(());
+    }
+    let Some(mut tournament_match) = ctx.db.tournament_match().id().find(schedule.match_id) else {
+        return Ok(());
+    };
+    let Some(tournament) = ctx.db.tournament().id().find(tournament_match.tournament_id) else {
+        return Ok(());
+    };
+    let (Some(a), Some(b)) = (tournament_match.player_a, tournament_match.player_b) else {
+        return Ok(());
+    };
+
+    // Ausente (conta apagada ou deslogado) perde por W.O.
+    let present = |id: u32| ctx.db.player().id().find(id).is_some() && !crate::rested::is_logged_out(ctx, id);
+    let walkover = match (present(a), present(b)) {
+        (true, true) => None,
+        (true, false) => Some(a),
+        (false, _) => Some(b),
+    };
+    if let Some(winner) = walkover {
+        record_winner(ctx, &mut tournament_match, winner);
+        return Ok(());
+    }
+
+    match create_arena_match(ctx, &tournament.arena_template, &[a], &[b], false) {
+        Ok(arena) => {
+            tournament_match.arena_match_id = Some(arena.id);
+            ctx.db.tournament_match().id().update(tournament_match);
+        }
+        Err(e) => {
+            // Não dá para jogar (ex: player já em outra partida): o cabeça de chave avança
+            log::warn!("Tournament match {} could not start: {}", tournament_match.id, e);
+            record_winner(ctx, &mut tournament_match, a);
+        }
+    }
+    Ok(())
+}
+
+/// Resultado de uma partida de arena: avança o vencedor se ela pertence a um torneio.
+/// Empates favorecem o jogador A (melhor posicionado no chaveamento).
+pub fn on_arena_match_finished(ctx: &ReducerContext, arena_match_id: u64, score_a: f32) {
+    let Some(mut tournament_match) = ctx.db.tournament_match().iter()
+        .find(|m| m.arena_match_id == Some(arena_match_id) && m.winner_id.is_none())
+    else {
+        return;
+    };
+    let winner = if score_a >= 0.5 { tournament_match.player_a } else { tournament_match.player_b };
+    if let Some(winner) = winner {
+        record_winner(ctx, &mut tournament_match, winner);
+    }
+}
+
+fn record_winner(ctx: &ReducerContext, tournament_match: &mut TournamentMatch, winner_id: u32) {
+    tournament_match.winner_id = Some(winner_id);
+    ctx.db.tournament_match().id().update(tournament_match.clone());
+    log::info!("🏆 Player {} won tournament match {}", winner_id, tournament_match.id);
+    advance_if_round_complete(ctx, tournament_match.tournament_id);
+}
+
+/// Quando todos os confrontos da rodada têm vencedor, gera a próxima (ou encerra)
+fn advance_if_round_complete(ctx: &ReducerContext, tournament_id: u64) {
+    let Some(mut tournament) = ctx.db.tournament().id().find(tournament_id) else { return };
+    if tournament.state != TournamentState::InProgress {
+        return;
+    }
+    let mut round: Vec<TournamentMatch> = ctx.db.tournament_match().tournament_id().filter(tournament_id)
+        .filter(|m| m.round == tournament.current_round)
+        .collect();
+    if round.iter().any(|m| m.winner_id.is_none()) {
+        return;
+    }
+    round.sort_by_key(|m| m.slot);
+
+    if round.len() == 1 {
+        let final_match = &round[0];
+        let Some(winner_id) = final_match.winner_id else { return };
+        let runner_up = [final_match.player_a, final_match.player_b].into_iter().flatten().find(|id| *id != winner_id);
+        tournament.state = TournamentState::Finished;
+        tournament.winner_id = Some(winner_id);
+        ctx.db.tournament().id().update(tournament.clone());
+        pay_out(ctx, &tournament, winner_id, runner_up);
+        return;
+    }
+
+    let winners: Vec<Option<u32>> = round.iter().map(|m| m.winner_id).collect();
+    tournament.current_round += 1;
+    ctx.db.tournament().id().update(tournament.clone());
+    create_round(ctx, &tournament, tournament.current_round, &winners);
+}
+
+fn pay_out(ctx: &ReducerContext, tournament: &Tournament, winner_id: u32, runner_up: Option<u32>) {
+    send_system_mail(
+        ctx,
+        winner_id,
+        "Tournament victory",
+        format!("You won {}!", tournament.name),
+        Vec::new(),
+        tournament.prize_gold,
+        "tournament",
+    );
+    if let Some(runner_up) = runner_up {
+        send_system_mail(
+            ctx,
+            runner_up,
+            "Tournament runner-up",
+            format!("You reached the final of {}.", tournament.name),
+            Vec::new(),
+            tournament.prize_gold / 2,
+            "tournament",
+        );
+    }
+    crate::localization::announce_localized(ctx, "tournament", crate::localization::Message::new("announce.tournament.won")
+        .param("player", winner_id)
+        .param("tournament", &tournament.name));
+    record_audit(ctx, "tournament", format!("Tournament {} won by player {}", tournament.id, winner_id));
+}
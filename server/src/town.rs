@@ -0,0 +1,35 @@
+use spacetimedb::{table, ReducerContext, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+/// Estado dos serviços de uma cidade (vendedores etc.)
+#[table(name = town_status, public)]
+#[derive(Clone)]
+pub struct TownStatus {
+    #[primary_key]
+    pub map_id: String,
+    pub services_closed_until: Option<Timestamp>,
+    pub reason: String,
+}
+
+pub fn town_services_open(ctx: &ReducerContext, map_id: &str) -> bool {
+    ctx.db.town_status().map_id().find(map_id.to_string())
+        .and_then(|s| s.services_closed_until)
+        .is_none_or(|until| until <= ctx.timestamp)
+}
+
+/// Fecha os serviços da cidade por um tempo (não encurta um fechamento maior)
+pub fn close_town_services(ctx: &ReducerContext, map_id: &str, duration: Duration, reason: &str) {
+    let until = ctx.timestamp + TimeDuration::from_duration(duration);
+    let status = TownStatus {
+        map_id: map_id.to_string(),
+        services_closed_until: Some(until),
+        reason: reason.to_string(),
+    };
+
+    match ctx.db.town_status().map_id().find(map_id.to_string()) {
+        Some(existing) if existing.services_closed_until.is_some_and(|t| t >= until) => {}
+        Some(_) => { ctx.db.town_status().map_id().update(status); }
+        None => { ctx.db.town_status().insert(status); }
+    }
+    log::info!("🏚️ Town services in '{}' closed for {} s ({})", map_id, duration.as_secs(), reason);
+}
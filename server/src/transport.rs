@@ -0,0 +1,124 @@
+use crate::admin::require_admin;
+use crate::map::relocate_player;
+use crate::{player, Player};
+use spacetimedb::{reducer, table, ReducerContext, ScheduleAt, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+/// Rota de transporte agendado (balsa, elevador). Clientes mostram a contagem
+/// regressiva a partir de `next_departure_at`.
+#[table(name = transport_route, public)]
+#[derive(Clone)]
+pub struct TransportRoute {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u32,
+    pub name: String,
+    pub map_id: String,
+    // Região de embarque (pixels)
+    pub boarding_x: f32,
+    pub boarding_y: f32,
+    pub boarding_width: f32,
+    pub boarding_height: f32,
+    pub dest_map_id: String,
+    pub dest_x: f32,
+    pub dest_y: f32,
+    pub interval_secs: u32,
+    pub next_departure_at: Timestamp,
+    pub last_departure_passengers: u32,
+}
+
+/// Uma linha por rota, disparando a partida em loop
+#[table(name = transport_departure_schedule, scheduled(depart_transport))]
+pub struct TransportDepartureSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+    #[index(btree)]
+    pub route_id: u32,
+}
+
+fn interval_of(route: &TransportRoute) -> Duration {
+    Duration::from_secs(route.interval_secs.max(1) as u64)
+}
+
+fn in_boarding_region(route: &TransportRoute, player: &Player) -> bool {
+    player.current_map_id == route.map_id
+        && player.position_x >= route.boarding_x && player.position_x <= route.boarding_x + route.boarding_width
+        && player.position_y >= route.boarding_y && player.position_y <= route.boarding_y + route.boarding_height
+}
+
+#[reducer]
+pub fn create_transport_route(ctx: &ReducerContext, route: TransportRoute) -> Result<(), String> {
+    require_admin(ctx)?;
+
+    if route.interval_secs == 0 {
+        return Err("Transport interval must be positive".to_string());
+    }
+    if crate::map::get_or_create_map_instance(ctx, &route.dest_map_id).is_none() {
+        return Err(format!("Destination map '{}' not found", route.dest_map_id));
+    }
+
+    let interval = interval_of(&route);
+    let route = ctx.db.transport_route().insert(TransportRoute {
+        id: 0,
+        next_departure_at: ctx.timestamp + TimeDuration::from_duration(interval),
+        last_departure_passengers: 0,
+        ..route
+    });
+    ctx.db.transport_departure_schedule().insert(TransportDepartureSchedule {
+        scheduled_id: 0,
+        scheduled_at: interval.into(),
+        route_id: route.id,
+    });
+
+    log::info!("⛴️ Transport '{}' created: {} -> {} every {}s", route.name, route.map_id, route.dest_map_id, route.interval_secs);
+    Ok(())
+}
+
+#[reducer]
+pub fn remove_transport_route(ctx: &ReducerContext, route_id: u32) -> Result<(), String> {
+    require_admin(ctx)?;
+
+    for schedule in ctx.db.transport_departure_schedule().route_id().filter(route_id) {
+        ctx.db.transport_departure_schedule().scheduled_id().delete(schedule.scheduled_id);
+    }
+    ctx.db.transport_route().id().delete(route_id);
+
+    log::info!("⛴️ Transport route {} removed", route_id);
+    Ok(())
+}
+
+/// Partida: todos os players na região de embarque viajam juntos
+#[reducer]
+pub fn depart_transport(ctx: &ReducerContext, schedule: TransportDepartureSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("depart_transport may only be invoked by the scheduler".to_string());
+    }
+    if crate::upgrade::is_frozen(ctx) {
+        return Ok(());
+    }
+
+    let Some(mut route) = ctx.db.transport_route().id().find(schedule.route_id) else {
+        ctx.db.transport_departure_schedule().scheduled_id().delete(schedule.scheduled_id);
+        return Ok(());
+    };
+
+    let passengers: Vec<Player> = ctx.db.player().iter()
+        .filter(|p| !p.is_downed && in_boarding_region(&route, p))
+        .collect();
+
+    let mut moved = 0;
+    for passenger in &passengers {
+        if relocate_player(ctx, passenger, &route.dest_map_id, route.dest_x, route.dest_y)? {
+            moved += 1;
+        }
+    }
+
+    route.next_departure_at = ctx.timestamp + TimeDuration::from_duration(interval_of(&route));
+    route.last_departure_passengers = moved;
+    log::info!("⛴️ Transport '{}' departed with {} passengers to {}", route.name, moved, route.dest_map_id);
+    ctx.db.transport_route().id().update(route);
+
+    Ok(())
+}
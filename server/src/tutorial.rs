@@ -0,0 +1,270 @@
+use crate::combat::{allocate_enemy_id, enemy, spawn_enemy};
+use crate::map::{
+    create_map_instance, destroy_map_instance, get_spawn_point, random_spawn_point, relocate_player, template_for_map, STARTING_MAP,
+};
+use crate::party::sender_player;
+use crate::player;
+use crate::reward::{grant_reward_bundle, RewardBundle};
+use crate::story_flag::{has_story_flag, set_story_flag};
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+/// Template da ilha; cada player novo ganha uma instância própria
+pub const TUTORIAL_TEMPLATE: &str = "tutorial_island";
+/// Flag de história marcada ao sair da ilha (concluindo ou pulando)
+pub const TUTORIAL_COMPLETE_FLAG: &str = "tutorial_complete";
+const TUTORIAL_SPAWN_RADIUS: f32 = 96.0;
+
+pub enum StepGoal {
+    /// Espera uma flag de história (ex: a da cutscene de abertura)
+    Flag(&'static str),
+    /// Encontro roteirizado: os inimigos nascem ao entrar no passo e a
+    /// recompensa é garantida ao derrotar todos
+    Clear {
+        enemies: &'static [(&'static str, u32)],
+        health_scale: f32,
+        drops: &'static [(&'static str, i32)],
+    },
+    /// Último passo: o player zarpa para o mundo compartilhado
+    Depart,
+}
+
+pub struct TutorialStep {
+    pub id: &'static str,
+    pub objective: &'static str,
+    pub goal: StepGoal,
+}
+
+/// Cadeia de missões guiada da ilha, em ordem
+pub const TUTORIAL_STEPS: &[TutorialStep] = &[
+    TutorialStep { id: "arrival", objective: "Watch the opening scene", goal: StepGoal::Flag("intro_seen") },
+    TutorialStep {
+        id: "first_blood",
+        objective: "Defeat the goblin on the beach",
+        goal: StepGoal::Clear { enemies: &[("Goblin", 1)], health_scale: 0.5, drops: &[("sword", 1)] },
+    },
+    TutorialStep {
+        id: "ambush",
+        objective: "Survive the ambush",
+        goal: StepGoal::Clear { enemies: &[("Goblin", 2)], health_scale: 0.75, drops: &[("health_potion", 3)] },
+    },
+    TutorialStep { id: "set_sail", objective: "Board the boat to the mainland", goal: StepGoal::Depart },
+];
+
+/// O passo espera exatamente esta flag de história
+fn step_waits_for_flag(step: u32, flag: &str) -> bool {
+    matches!(TUTORIAL_STEPS.get(step as usize), Some(TutorialStep { goal: StepGoal::Flag(f), .. }) if *f == flag)
+}
+
+/// O passo é o embarque final (só dele se sai concluindo)
+fn is_departure_step(step: u32) -> bool {
+    matches!(TUTORIAL_STEPS.get(step as usize), Some(TutorialStep { goal: StepGoal::Depart, .. }))
+}
+
+/// Progresso de um player na ilha tutorial
+#[table(name = tutorial_run, public)]
+#[derive(Clone)]
+pub struct TutorialRun {
+    #[primary_key]
+    pub player_id: u32,
+    #[unique]
+    pub map_key: String,
+    pub step: u32,
+    pub objective: String,
+    pub enemy_ids: Vec<u32>,
+    pub started_at: Timestamp,
+}
+
+/// Leva o player recém-registrado para a própria ilha. Sem o template
+/// carregado, ele começa direto no mundo compartilhado.
+pub fn start_tutorial(ctx: &ReducerContext, player_id: u32) {
+    if template_for_map(ctx, TUTORIAL_TEMPLATE).is_none() {
+        return;
+    }
+    let Some(player) = ctx.db.player().id().find(player_id) else { return };
+    let map_key = format!("{}@tutorial{}", TUTORIAL_TEMPLATE, player_id);
+    let started = create_map_instance(ctx, &map_key, TUTORIAL_TEMPLATE).and_then(|_| {
+        let (spawn_x, spawn_y) = get_spawn_point(ctx, &map_key);
+        relocate_player(ctx, &player, &map_key, spawn_x, spawn_y)
+    });
+    if !matches!(started, Ok(true)) {
+        log::warn!("Tutorial for player {} could not start: {:?}", player_id, started);
+        destroy_map_instance(ctx, &map_key);
+        return;
+    }
+
+    let run = ctx.db.tutorial_run().insert(TutorialRun {
+        player_id,
+        map_key,
+        step: 0,
+        objective: String::new(),
+        enemy_ids: Vec::new(),
+        started_at: ctx.timestamp,
+    });
+    log::info!("🏝️ Player {} started the tutorial", player_id);
+    enter_step(ctx, run, 0);
+}
+
+fn enter_step(ctx: &ReducerContext, mut run: TutorialRun, step: u32) {
+    let Some(def) = TUTORIAL_STEPS.get(step as usize) else { return };
+    run.step = step;
+    run.objective = def.objective.to_string();
+    run.enemy_ids.clear();
+
+    match &def.goal {
+        // Flag já conquistada (ex: cutscene vista antes) conclui o passo na hora
+        StepGoal::Flag(flag) if has_story_flag(ctx, run.player_id, flag) => return complete_step(ctx, run),
+        StepGoal::Clear { enemies, health_scale, .. } => {
+            let (spawn_x, spawn_y) = get_spawn_point(ctx, &run.map_key);
+            for (enemy_type, count) in enemies.iter() {
+                for _ in 0..*count {
+                    let Some((x, y)) = random_spawn_point(ctx, &run.map_key, spawn_x, spawn_y, TUTORIAL_SPAWN_RADIUS) else { continue };
+                    let enemy_id = allocate_enemy_id(ctx);
+                    if spawn_enemy(ctx, enemy_id, x, y, run.map_key.clone(), enemy_type.to_string()).is_err() {
+                        continue;
+                    }
+                    if let Some(mut spawned) = ctx.db.enemy().id().find(enemy_id) {
+                        spawned.max_health *= health_scale;
+                        spawned.health = spawned.max_health;
+                        ctx.db.enemy().id().update(spawned);
+                    }
+                    run.enemy_ids.push(enemy_id);
+                }
+            }
+            // Sem espaço para o encontro o passo não pode travar a ilha
+            if run.enemy_ids.is_empty() {
+                return complete_step(ctx, run);
+            }
+        }
+        _ => {}
+    }
+    ctx.db.tutorial_run().player_id().update(run);
+}
+
+fn complete_step(ctx: &ReducerContext, run: TutorialRun) {
+    let Some(def) = TUTORIAL_STEPS.get(run.step as usize) else { return };
+    set_story_flag(ctx, run.player_id, &format!("tutorial:{}", def.id));
+    if let StepGoal::Clear { drops, .. } = &def.goal {
+        let bundle = drops.iter().fold(RewardBundle::default(), |b, (item_id, quantity)| b.with_item(item_id, *quantity));
+        if let Err(e) = grant_reward_bundle(ctx, run.player_id, &bundle, "tutorial") {
+            log::warn!("Tutorial drop for player {} failed: {}", run.player_id, e);
+        }
+    }
+    let next = run.step + 1;
+    enter_step(ctx, run, next);
+}
+
+/// Chamado quando uma flag de história é marcada
+pub fn on_story_flag_set(ctx: &ReducerContext, player_id: u32, flag: &str) {
+    let Some(run) = ctx.db.tutorial_run().player_id().find(player_id) else { return };
+    if step_waits_for_flag(run.step, flag) {
+        complete_step(ctx, run);
+    }
+}
+
+/// Chamado na derrota de um inimigo: o último do encontro conclui o passo
+pub fn on_enemy_defeated(ctx: &ReducerContext, map_id: &str, enemy_id: u32) {
+    let Some(mut run) = ctx.db.tutorial_run().map_key().find(map_id.to_string()) else { return };
+    if !run.enemy_ids.contains(&enemy_id) {
+        return;
+    }
+    // Confere também os que sumiram sem abate, para o passo não travar
+    run.enemy_ids.retain(|id| *id != enemy_id && ctx.db.enemy().id().find(id).is_some_and(|e| e.map_id == run.map_key));
+    if run.enemy_ids.is_empty() {
+        complete_step(ctx, run);
+    } else {
+        ctx.db.tutorial_run().player_id().update(run);
+    }
+}
+
+/// Chamado quando um inimigo sai do mapa sem ser abatido (remoção, troca de
+/// mapa): conta como abate para o encontro não ficar esperando por ele
+pub fn on_enemy_removed(ctx: &ReducerContext, map_id: &str, enemy_id: u32) {
+    on_enemy_defeated(ctx, map_id, enemy_id);
+}
+
+/// Fecha a ilha: player vai para o mundo compartilhado e a instância some
+fn close_tutorial(ctx: &ReducerContext, run: &TutorialRun) -> Result<(), String> {
+    if let Some(player) = ctx.db.player().id().find(run.player_id).filter(|p| p.current_map_id == run.map_key) {
+        let (spawn_x, spawn_y) = get_spawn_point(ctx, STARTING_MAP);
+        relocate_player(ctx, &player, STARTING_MAP, spawn_x, spawn_y)?;
+    }
+    for enemy_id in &run.enemy_ids {
+        ctx.db.enemy().id().delete(enemy_id);
+        crate::threat::clear_enemy(ctx, *enemy_id);
+    }
+    destroy_map_instance(ctx, &run.map_key);
+    ctx.db.tutorial_run().player_id().delete(run.player_id);
+    set_story_flag(ctx, run.player_id, TUTORIAL_COMPLETE_FLAG);
+    Ok(())
+}
+
+/// Zarpa para o continente ao fim da cadeia de missões
+#[reducer]
+pub fn finish_tutorial(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let run = ctx.db.tutorial_run().player_id().find(player.id).ok_or("You are not on the tutorial island")?;
+    if !is_departure_step(run.step) {
+        return Err(format!("Finish the current objective first: {}", run.objective));
+    }
+    close_tutorial(ctx, &run)?;
+    log::info!("⛵ Player {} finished the tutorial", player.id);
+    Ok(())
+}
+
+#[reducer]
+pub fn skip_tutorial(ctx: &ReducerContext) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let run = ctx.db.tutorial_run().player_id().find(player.id).ok_or("You are not on the tutorial island")?;
+    close_tutorial(ctx, &run)?;
+    log::info!("⏭️ Player {} skipped the tutorial at step {}", player.id, run.step);
+    Ok(())
+}
+
+pub fn on_player_erased(ctx: &ReducerContext, player_id: u32) {
+    if let Some(run) = ctx.db.tutorial_run().player_id().find(player_id) {
+        for enemy_id in &run.enemy_ids {
+            ctx.db.enemy().id().delete(enemy_id);
+        }
+        destroy_map_instance(ctx, &run.map_key);
+        ctx.db.tutorial_run().player_id().delete(player_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opening_step_waits_for_its_own_flag() {
+        assert!(step_waits_for_flag(0, "intro_seen"));
+        assert!(!step_waits_for_flag(0, "tutorial_complete"));
+        assert!(!step_waits_for_flag(1, "intro_seen"));
+        assert!(!step_waits_for_flag(TUTORIAL_STEPS.len() as u32, "intro_seen"));
+    }
+
+    #[test]
+    fn chain_ends_with_the_departure() {
+        let last = TUTORIAL_STEPS.len() as u32 - 1;
+        assert!(is_departure_step(last));
+        assert!((0..last).all(|step| !is_departure_step(step)));
+        assert!(!is_departure_step(last + 1));
+    }
+
+    #[test]
+    fn every_encounter_spawns_and_pays_out() {
+        for step in TUTORIAL_STEPS {
+            if let StepGoal::Clear { enemies, health_scale, drops } = &step.goal {
+                assert!(enemies.iter().map(|(_, count)| count).sum::<u32>() > 0, "{} spawns nothing", step.id);
+                assert!(*health_scale > 0.0, "{} spawns dead enemies", step.id);
+                assert!(drops.iter().all(|(_, quantity)| *quantity > 0), "{} drops an empty stack", step.id);
+            }
+        }
+    }
+
+    #[test]
+    fn step_ids_are_unique() {
+        for (i, step) in TUTORIAL_STEPS.iter().enumerate() {
+            assert!(TUTORIAL_STEPS[i + 1..].iter().all(|other| other.id != step.id), "duplicate step '{}'", step.id);
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use crate::ability_queue::queued_ability;
+use crate::admin::require_admin;
+use crate::arena::arena_match;
+use crate::audit::record_audit;
+use crate::caravan::caravan_delivery;
+use crate::challenge_tower::tower_run;
+use crate::cooldown::cooldown;
+use crate::grief_protection::ownership_tag;
+use crate::guild_war::guild_war;
+use crate::hireling::hireling;
+use crate::invasion::invasion;
+use crate::lfg::group_listing;
+use crate::movement::player_jump;
+use crate::npc_bark::npc_bark_cooldown;
+use crate::pvp::pvp_flag;
+use crate::seasonal_event::{event_spawner, seasonal_event};
+use crate::status_effect::status_effect;
+use crate::story_flag::story_spawner;
+use crate::structure::structure;
+use crate::teleporter::{teleport_channel, CHANNEL_STATE_CHANNELING};
+use crate::threat::enemy_taunt;
+use crate::tournament::{tournament_match_schedule, TournamentMatchSchedule};
+use crate::transport::transport_route;
+use crate::work_order::work_order;
+use crate::world_boss::{world_boss, world_boss_spawn_schedule, WorldBossSpawnSchedule};
+use spacetimedb::{reducer, table, Identity, ReducerContext, ScheduleAt, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+// Timers agendados guardados durante o congelamento
+pub const TIMER_WORLD_BOSS_SPAWN: &str = "world_boss_spawn";
+pub const TIMER_TOURNAMENT_MATCH: &str = "tournament_match";
+
+/// Congelamento para troca de módulo (linha única, id 0). As tabelas
+/// sobrevivem ao publish, mas o relógio não para: enquanto a linha existe o
+/// world tick e as rotinas agendadas ficam parados, reducers que movem itens
+/// ou moedas recusam operações e os timers de disparo único ficam guardados
+/// em `frozen_timer`. O resume devolve o
+/// tempo congelado a todos os prazos.
+#[table(name = upgrade_freeze, public)]
+#[derive(Clone)]
+pub struct UpgradeFreeze {
+    #[primary_key]
+    pub id: u32,
+    pub reason: String,
+    pub frozen_by: Identity,
+    pub frozen_at: Timestamp,
+}
+
+/// Timer agendado retirado da fila no congelamento, com o tempo que faltava
+#[table(name = frozen_timer)]
+#[derive(Clone)]
+pub struct FrozenTimer {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub kind: String,
+    /// Id da linha dona do timer (ex: partida de torneio); zero se não houver
+    pub reference: u64,
+    pub remaining_ms: u64,
+}
+
+pub fn is_frozen(ctx: &ReducerContext) -> bool {
+    ctx.db.upgrade_freeze().id().find(0).is_some()
+}
+
+/// Guard para reducers que movem bens entre players
+pub fn require_not_frozen(ctx: &ReducerContext) -> Result<(), String> {
+    if is_frozen(ctx) {
+        Err("The server is preparing for an update, try again in a moment".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Timer de disparo único que venceu durante o congelamento: volta à fila
+/// assim que o módulo for retomado
+pub fn hold_timer(ctx: &ReducerContext, kind: &str, reference: u64) {
+    ctx.db.frozen_timer().insert(FrozenTimer { id: 0, kind: kind.to_string(), reference, remaining_ms: 0 });
+}
+
+fn remaining_ms(ctx: &ReducerContext, at: &ScheduleAt) -> Option<u64> {
+    match at {
+        ScheduleAt::Time(at) => Some(at.duration_since(ctx.timestamp).unwrap_or_default().as_millis() as u64),
+        ScheduleAt::Interval(_) => None,
+    }
+}
+
+/// Prepara o módulo para um hot-swap: fecha o que está em andamento e guarda
+/// os timers de disparo único
+#[reducer]
+pub fn freeze_for_upgrade(ctx: &ReducerContext, reason: String) -> Result<(), String> {
+    require_admin(ctx)?;
+    if is_frozen(ctx) {
+        return Err("The module is already frozen".to_string());
+    }
+
+    // Canalizações de teleporte são desfeitas: o player recanaliza depois
+    let channels: Vec<u32> = ctx.db.teleport_channel().iter().filter(|c| c.state == CHANNEL_STATE_CHANNELING).map(|c| c.player_id).collect();
+    for player_id in &channels {
+        ctx.db.teleport_channel().player_id().delete(player_id);
+    }
+
+    let mut timers = 0;
+    for schedule in ctx.db.world_boss_spawn_schedule().iter().collect::<Vec<_>>() {
+        let Some(remaining_ms) = remaining_ms(ctx, &schedule.scheduled_at) else { continue };
+        ctx.db.frozen_timer().insert(FrozenTimer { id: 0, kind: TIMER_WORLD_BOSS_SPAWN.to_string(), reference: 0, remaining_ms });
+        ctx.db.world_boss_spawn_schedule().scheduled_id().delete(schedule.scheduled_id);
+        timers += 1;
+    }
+    for schedule in ctx.db.tournament_match_schedule().iter().collect::<Vec<_>>() {
+        let Some(remaining_ms) = remaining_ms(ctx, &schedule.scheduled_at) else { continue };
+        ctx.db.frozen_timer().insert(FrozenTimer {
+            id: 0,
+            kind: TIMER_TOURNAMENT_MATCH.to_string(),
+            reference: schedule.match_id,
+            remaining_ms,
+        });
+        ctx.db.tournament_match_schedule().scheduled_id().delete(schedule.scheduled_id);
+        timers += 1;
+    }
+
+    ctx.db.upgrade_freeze().insert(UpgradeFreeze { id: 0, reason: reason.clone(), frozen_by: ctx.sender, frozen_at: ctx.timestamp });
+    record_audit(ctx, "upgrade", format!("Frozen for upgrade: {} ({} channels cancelled, {} timers held)", reason, channels.len(), timers));
+    log::warn!("🧊 Module frozen for upgrade: {}", reason);
+    Ok(())
+}
+
+/// Retoma depois do publish: prazos andam o tempo congelado e os timers voltam à fila
+#[reducer]
+pub fn resume_after_upgrade(ctx: &ReducerContext) -> Result<(), String> {
+    require_admin(ctx)?;
+    let freeze = ctx.db.upgrade_freeze().id().find(0).ok_or("The module is not frozen")?;
+    let frozen = ctx.timestamp.duration_since(freeze.frozen_at).unwrap_or_default();
+    let frozen_for = TimeDuration::from_duration(frozen);
+
+    for mut queued in ctx.db.queued_ability().iter().collect::<Vec<_>>() {
+        queued.execute_at += frozen_for;
+        ctx.db.queued_ability().player_id().update(queued);
+    }
+    for mut arena_match in ctx.db.arena_match().iter().collect::<Vec<_>>() {
+        arena_match.ends_at += frozen_for;
+        ctx.db.arena_match().id().update(arena_match);
+    }
+    for mut invasion in ctx.db.invasion().iter().collect::<Vec<_>>() {
+        invasion.ends_at += frozen_for;
+        ctx.db.invasion().id().update(invasion);
+    }
+    for mut delivery in ctx.db.caravan_delivery().iter().collect::<Vec<_>>() {
+        delivery.deadline += frozen_for;
+        delivery.next_ambush_check_at += frozen_for;
+        ctx.db.caravan_delivery().player_id().update(delivery);
+    }
+    for mut effect in ctx.db.status_effect().iter().filter(|e| e.expires_at.is_some()).collect::<Vec<_>>() {
+        effect.expires_at = effect.expires_at.map(|at| at + frozen_for);
+        ctx.db.status_effect().id().update(effect);
+    }
+    for mut hired in ctx.db.hireling().iter().collect::<Vec<_>>() {
+        hired.expires_at += frozen_for;
+        hired.next_action_at += frozen_for;
+        ctx.db.hireling().id().update(hired);
+    }
+    for mut run in ctx.db.tower_run().iter().collect::<Vec<_>>() {
+        run.floor_started_at += frozen_for;
+        ctx.db.tower_run().id().update(run);
+    }
+    // Só os que ainda não venceram: um já liberado continua liberado
+    for mut active in ctx.db.cooldown().iter().filter(|c| c.ready_at > freeze.frozen_at).collect::<Vec<_>>() {
+        active.started_at += frozen_for;
+        active.ready_at += frozen_for;
+        ctx.db.cooldown().id().update(active);
+    }
+    // O prazo de aceite é o que devolve a custódia do pagamento ao autor
+    for mut order in ctx.db.work_order().iter().filter(|o| o.accept_deadline.is_some()).collect::<Vec<_>>() {
+        order.accept_deadline = order.accept_deadline.map(|at| at + frozen_for);
+        ctx.db.work_order().id().update(order);
+    }
+    // Prazos que ainda não venceram andam junto com o congelamento
+    let pending = |at: Timestamp| at > freeze.frozen_at;
+    for mut listing in ctx.db.group_listing().iter().filter(|l| pending(l.expires_at)).collect::<Vec<_>>() {
+        listing.expires_at += frozen_for;
+        ctx.db.group_listing().id().update(listing);
+    }
+    for mut built in ctx.db.structure().iter().filter(|s| s.decays_at.is_some_and(pending)).collect::<Vec<_>>() {
+        built.decays_at = built.decays_at.map(|at| at + frozen_for);
+        ctx.db.structure().id().update(built);
+    }
+    for mut tag in ctx.db.ownership_tag().iter().filter(|t| pending(t.expires_at)).collect::<Vec<_>>() {
+        tag.expires_at += frozen_for;
+        ctx.db.ownership_tag().id().update(tag);
+    }
+    for mut flag in ctx.db.pvp_flag().iter().filter(|f| pending(f.effective_at)).collect::<Vec<_>>() {
+        flag.effective_at += frozen_for;
+        ctx.db.pvp_flag().player_id().update(flag);
+    }
+    for mut taunt in ctx.db.enemy_taunt().iter().filter(|t| pending(t.expires_at)).collect::<Vec<_>>() {
+        taunt.expires_at += frozen_for;
+        ctx.db.enemy_taunt().enemy_id().update(taunt);
+    }
+    for mut event in ctx.db.seasonal_event().iter().filter(|e| pending(e.ends_at)).collect::<Vec<_>>() {
+        if pending(event.starts_at) {
+            event.starts_at += frozen_for;
+        }
+        event.ends_at += frozen_for;
+        ctx.db.seasonal_event().id().update(event);
+    }
+    for mut spawner in ctx.db.event_spawner().iter().filter(|s| pending(s.next_spawn_at)).collect::<Vec<_>>() {
+        spawner.next_spawn_at += frozen_for;
+        ctx.db.event_spawner().id().update(spawner);
+    }
+    for mut spawner in ctx.db.story_spawner().iter().filter(|s| pending(s.next_spawn_at)).collect::<Vec<_>>() {
+        spawner.next_spawn_at += frozen_for;
+        ctx.db.story_spawner().id().update(spawner);
+    }
+    for mut bark in ctx.db.npc_bark_cooldown().iter().filter(|b| pending(b.next_bark_at)).collect::<Vec<_>>() {
+        bark.next_bark_at += frozen_for;
+        ctx.db.npc_bark_cooldown().vendor_id().update(bark);
+    }
+    for mut war in ctx.db.guild_war().iter().filter(|w| w.ends_at.is_some_and(pending)).collect::<Vec<_>>() {
+        war.ends_at = war.ends_at.map(|at| at + frozen_for);
+        ctx.db.guild_war().id().update(war);
+    }
+    for mut jump in ctx.db.player_jump().iter().filter(|j| pending(j.lands_at)).collect::<Vec<_>>() {
+        jump.started_at += frozen_for;
+        jump.lands_at += frozen_for;
+        ctx.db.player_jump().player_id().update(jump);
+    }
+    // Partidas seguem o intervalo fixo do agendamento: as puladas no congelamento
+    // não voltam, a próxima é a primeira do ciclo depois de agora
+    for mut route in ctx.db.transport_route().iter().filter(|r| r.next_departure_at <= ctx.timestamp).collect::<Vec<_>>() {
+        let interval = Duration::from_secs(route.interval_secs.max(1) as u64);
+        let behind = ctx.timestamp.duration_since(route.next_departure_at).unwrap_or_default();
+        let cycles = behind.as_secs() / interval.as_secs() + 1;
+        route.next_departure_at += TimeDuration::from_duration(interval * cycles as u32);
+        ctx.db.transport_route().id().update(route);
+    }
+
+    for timer in ctx.db.frozen_timer().iter().collect::<Vec<_>>() {
+        ctx.db.frozen_timer().id().delete(timer.id);
+        let at: ScheduleAt = (ctx.timestamp + TimeDuration::from_duration(Duration::from_millis(timer.remaining_ms))).into();
+        match timer.kind.as_str() {
+            // Um spawn agendado ou boss vivo no meio tempo tem precedência: nada de boss duplicado
+            TIMER_WORLD_BOSS_SPAWN => {
+                if ctx.db.world_boss().count() == 0 && ctx.db.world_boss_spawn_schedule().count() == 0 {
+                    ctx.db.world_boss_spawn_schedule().insert(WorldBossSpawnSchedule { scheduled_id: 0, scheduled_at: at });
+                }
+            }
+            TIMER_TOURNAMENT_MATCH => {
+                if !ctx.db.tournament_match_schedule().iter().any(|s| s.match_id == timer.reference) {
+                    ctx.db.tournament_match_schedule().insert(TournamentMatchSchedule {
+                        scheduled_id: 0,
+                        scheduled_at: at,
+                        match_id: timer.reference,
+                    });
+                }
+            }
+            kind => log::warn!("⚠️ Unknown frozen timer '{}' dropped", kind),
+        }
+    }
+
+    ctx.db.upgrade_freeze().id().delete(0);
+    record_audit(ctx, "upgrade", format!("Resumed after {} s frozen", frozen.as_secs()));
+    log::info!("🔥 Module resumed after upgrade ({})", freeze.reason);
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use crate::admin::require_admin;
+use crate::currency::{spend_currency, CURRENCY_GOLD};
+use crate::feature_flag::{require_feature, MARKET_ENABLED};
+use crate::map::{template_for_map, TILE_SIZE};
+use crate::party::sender_player;
+use crate::reputation::require_reputation;
+use crate::town::town_services_open;
+use spacetimedb::{reducer, table, ReducerContext, Table};
+
+const VENDOR_RANGE: f32 = TILE_SIZE * 3.0;
+
+#[table(name = vendor, public)]
+#[derive(Clone)]
+pub struct Vendor {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub name: String,
+    #[index(btree)]
+    pub map_id: String,
+    pub position_x: f32,
+    pub position_y: f32,
+}
+
+/// Item à venda. Itens com facção exigem reputação mínima na compra.
+#[table(name = vendor_item, public)]
+#[derive(Clone)]
+pub struct VendorItem {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub vendor_id: u64,
+    pub item_id: String,
+    pub price: u64,
+    pub currency: String,
+    pub required_faction: String, // vazio = sem requisito
+    pub required_reputation: i32,
+}
+
+#[reducer]
+pub fn create_vendor(ctx: &ReducerContext, name: String, map_id: String, position_x: f32, position_y: f32) -> Result<(), String> {
+    require_admin(ctx)?;
+    template_for_map(ctx, &map_id).ok_or_else(|| format!("Map '{}' not found", map_id))?;
+    ctx.db.vendor().insert(Vendor { id: 0, name, map_id, position_x, position_y });
+    Ok(())
+}
+
+#[reducer]
+pub fn add_vendor_item(
+    ctx: &ReducerContext,
+    vendor_id: u64,
+    item_id: String,
+    price: u64,
+    required_faction: String,
+    required_reputation: i32,
+) -> Result<(), String> {
+    require_admin(ctx)?;
+    ctx.db.vendor().id().find(vendor_id).ok_or("Vendor not found")?;
+    ctx.db.vendor_item().insert(VendorItem {
+        id: 0,
+        vendor_id,
+        item_id,
+        price,
+        currency: CURRENCY_GOLD.to_string(),
+        required_faction,
+        required_reputation,
+    });
+    Ok(())
+}
+
+#[reducer]
+pub fn remove_vendor_item(ctx: &ReducerContext, vendor_item_id: u64) -> Result<(), String> {
+    require_admin(ctx)?;
+    ctx.db.vendor_item().id().delete(vendor_item_id);
+    Ok(())
+}
+
+#[reducer]
+pub fn buy_from_vendor(ctx: &ReducerContext, vendor_item_id: u64, quantity: u32) -> Result<(), String> {
+    require_feature(ctx, MARKET_ENABLED)?;
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    if quantity == 0 {
+        return Err("Quantity must be positive".to_string());
+    }
+    let stack_quantity = i32::try_from(quantity).map_err(|_| "Quantity too large")?;
+    let listing = ctx.db.vendor_item().id().find(vendor_item_id).ok_or("Item not sold here")?;
+    let vendor = ctx.db.vendor().id().find(listing.vendor_id).ok_or("Vendor not found")?;
+
+    if player.current_map_id != vendor.map_id {
+        return Err("Vendor is in another map".to_string());
+    }
+    let distance = ((player.position_x - vendor.position_x).powi(2) + (player.position_y - vendor.position_y).powi(2)).sqrt();
+    if distance > VENDOR_RANGE {
+        return Err("Too far from the vendor".to_string());
+    }
+    if !town_services_open(ctx, &vendor.map_id) {
+        return Err(format!("{} is closed", vendor.name));
+    }
+    require_reputation(ctx, player.id, &listing.required_faction, listing.required_reputation)?;
+
+    let total = listing.price.checked_mul(quantity as u64).ok_or("Quantity too large")?;
+    spend_currency(ctx, player.id, &listing.currency, total, "vendor")?;
+    crate::inventory::add_item_to_inventory(ctx, player.id, listing.item_id.clone(), stack_quantity)
+        .map_err(|e| e.to_string())?;
+
+    log::info!("🛒 Player {} bought {} x{} from {} for {} {}", player.id, listing.item_id, quantity, vendor.name, total, listing.currency);
+    Ok(())
+}
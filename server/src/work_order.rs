@@ -0,0 +1,292 @@
+use crate::crafting::ItemStack;
+use crate::escrow;
+use crate::feature_flag::{require_feature, MARKET_ENABLED};
+use crate::inventory::{count_item, interactable_object, remove_item_from_inventory};
+use crate::map::TILE_SIZE;
+use crate::party::sender_player;
+use crate::reward::{grant_reward_bundle, RewardBundle};
+use crate::Player;
+use spacetimedb::{reducer, table, ReducerContext, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+/// Tipo de `interactable_object` que funciona como quadro de encomendas
+pub const WORK_BOARD_OBJECT: &str = "work_board";
+const BOARD_RANGE: f32 = TILE_SIZE * 3.0;
+const MAX_OPEN_ORDERS_PER_PLAYER: usize = 5;
+/// Prazo do crafter para entregar depois de aceitar
+const ACCEPT_DEADLINE_SECS: u64 = 48 * 3600;
+
+#[derive(SpacetimeType, Clone, Debug, PartialEq)]
+pub enum WorkOrderState {
+    Open,
+    Accepted,
+    Fulfilled,
+    Cancelled,
+}
+
+/// Encomenda de crafting. O pagamento e os materiais ficam no serviço de
+/// custódia (`escrow`) até a encomenda ser cumprida ou cancelada;
+/// `escrow_materials` espelha o que está guardado para o cliente.
+#[table(name = work_order, public)]
+#[derive(Clone)]
+pub struct WorkOrder {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub board_id: u32,
+    pub poster_id: u32,
+    pub item_id: String,
+    pub quantity: i32,
+    pub payment_gold: u64,
+    pub escrow_materials: Vec<ItemStack>,
+    pub crafter_id: Option<u32>,
+    pub state: WorkOrderState,
+    /// Enquanto aceita: depois disso a limpeza cancela e devolve a custódia
+    pub accept_deadline: Option<Timestamp>,
+    pub posted_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+fn require_near_board(ctx: &ReducerContext, player: &Player, board_id: u32) -> Result<(), String> {
+    let board = ctx.db.interactable_object().id().find(board_id)
+        .filter(|o| o.object_type == WORK_BOARD_OBJECT)
+        .ok_or("Work order board not found")?;
+    let distance = ((player.position_x - board.position_x).powi(2) + (player.position_y - board.position_y).powi(2)).sqrt();
+    if player.current_map_id != board.map_id || distance > BOARD_RANGE {
+        return Err("Too far from the work order board".to_string());
+    }
+    Ok(())
+}
+
+fn payment_ref(order_id: u64) -> String {
+    format!("work_order:{}:payment", order_id)
+}
+
+fn materials_ref(order_id: u64) -> String {
+    format!("work_order:{}:materials", order_id)
+}
+
+/// Custódias de encomendas só existem enquanto a encomenda está em andamento
+pub fn escrow_reference_is_live(ctx: &ReducerContext, reference: &str) -> bool {
+    let Some(order_id) = reference.strip_prefix("work_order:")
+        .and_then(|rest| rest.split(':').next())
+        .and_then(|id| id.parse::<u64>().ok())
+    else {
+        return false;
+    };
+    ctx.db.work_order().id().find(order_id)
+        .is_some_and(|o| matches!(o.state, WorkOrderState::Open | WorkOrderState::Accepted))
+}
+
+/// Encomendas anteriores ao serviço de custódia guardavam tudo na própria linha
+pub fn adopt_legacy_escrow(ctx: &ReducerContext) {
+    for order in ctx.db.work_order().iter() {
+        if !matches!(order.state, WorkOrderState::Open | WorkOrderState::Accepted) {
+            continue;
+        }
+        if escrow::holds_for(ctx, &payment_ref(order.id)).is_empty() {
+            escrow::adopt(ctx, order.poster_id, &payment_ref(order.id), Vec::new(), order.payment_gold);
+        }
+        if order.state == WorkOrderState::Open && escrow::holds_for(ctx, &materials_ref(order.id)).is_empty() {
+            escrow::adopt(ctx, order.poster_id, &materials_ref(order.id), order.escrow_materials.clone(), 0);
+        }
+    }
+}
+
+/// Conta apagada: encomendas dela são canceladas sem reembolso e as que ela
+/// aceitou voltam a ficar abertas, com os materiais de novo em custódia
+pub fn on_player_erased(ctx: &ReducerContext, player_id: u32) {
+    let orders: Vec<WorkOrder> = ctx.db.work_order().iter()
+        .filter(|o| matches!(o.state, WorkOrderState::Open | WorkOrderState::Accepted))
+        .filter(|o| o.poster_id == player_id || o.crafter_id == Some(player_id))
+        .collect();
+    for mut order in orders {
+        if order.poster_id == player_id {
+            escrow::discard(ctx, &payment_ref(order.id));
+            escrow::discard(ctx, &materials_ref(order.id));
+            order.escrow_materials.clear();
+            order.state = WorkOrderState::Cancelled;
+        } else {
+            escrow::adopt(ctx, order.poster_id, &materials_ref(order.id), order.escrow_materials.clone(), 0);
+            order.crafter_id = None;
+            order.state = WorkOrderState::Open;
+            order.accept_deadline = None;
+        }
+        order.updated_at = ctx.timestamp;
+        ctx.db.work_order().id().update(order);
+    }
+}
+
+/// Encomendas aceitas que passaram do prazo (chamado pela limpeza periódica):
+/// o pagamento volta por correio para quem postou, junto com o que ainda
+/// restar dos materiais no inventário do crafter
+pub fn expire_accepted_orders(ctx: &ReducerContext) {
+    let expired: Vec<WorkOrder> = ctx.db.work_order().iter()
+        .filter(|o| o.state == WorkOrderState::Accepted && o.accept_deadline.is_some_and(|d| d <= ctx.timestamp))
+        .collect();
+    for mut order in expired {
+        let mut recovered = Vec::new();
+        if let Some(crafter_id) = order.crafter_id {
+            for stack in &order.escrow_materials {
+                let quantity = count_item(ctx, crafter_id, &stack.item_id).min(stack.quantity);
+                if quantity > 0 && remove_item_from_inventory(ctx, crafter_id, &stack.item_id, quantity).is_ok() {
+                    recovered.push(ItemStack { item_id: stack.item_id.clone(), quantity });
+                }
+            }
+        }
+        escrow::refund_by_mail(ctx, &payment_ref(order.id), "Work order expired");
+        if !recovered.is_empty() {
+            crate::mail::send_system_mail(ctx, order.poster_id, "Work order expired", format!("Materials recovered from work order {}", order.id), recovered, 0, "work_order");
+        }
+        order.escrow_materials.clear();
+        order.state = WorkOrderState::Cancelled;
+        order.accept_deadline = None;
+        order.updated_at = ctx.timestamp;
+        log::info!("📋 Work order {} expired with crafter {:?}", order.id, order.crafter_id);
+        ctx.db.work_order().id().update(order);
+    }
+}
+
+#[reducer]
+pub fn post_work_order(
+    ctx: &ReducerContext,
+    board_id: u32,
+    item_id: String,
+    quantity: i32,
+    payment_gold: u64,
+    materials: Vec<ItemStack>,
+) -> Result<(), String> {
+    require_feature(ctx, MARKET_ENABLED)?;
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    crate::shadow_ban::throttle_market(ctx)?;
+    require_near_board(ctx, &player, board_id)?;
+    if quantity <= 0 {
+        return Err("Quantity must be positive".to_string());
+    }
+    let open_orders = ctx.db.work_order().iter()
+        .filter(|o| o.poster_id == player.id && matches!(o.state, WorkOrderState::Open | WorkOrderState::Accepted))
+        .count();
+    if open_orders >= MAX_OPEN_ORDERS_PER_PLAYER {
+        return Err("Too many open work orders".to_string());
+    }
+
+    let order = ctx.db.work_order().insert(WorkOrder {
+        id: 0,
+        board_id,
+        poster_id: player.id,
+        item_id,
+        quantity,
+        payment_gold,
+        escrow_materials: materials.clone(),
+        crafter_id: None,
+        state: WorkOrderState::Open,
+        accept_deadline: None,
+        posted_at: ctx.timestamp,
+        updated_at: ctx.timestamp,
+    });
+    escrow::hold(ctx, player.id, player.id, &payment_ref(order.id), &[], payment_gold)?;
+    escrow::hold(ctx, player.id, player.id, &materials_ref(order.id), &materials, 0)?;
+    log::info!("📋 Player {} posted work order {} for {} x{} ({} gold)", player.id, order.id, order.item_id, order.quantity, order.payment_gold);
+    Ok(())
+}
+
+/// Cancela uma encomenda ainda não aceita e devolve a custódia
+#[reducer]
+pub fn cancel_work_order(ctx: &ReducerContext, order_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    let mut order = ctx.db.work_order().id().find(order_id).ok_or("Work order not found")?;
+    if order.poster_id != player.id {
+        return Err("Not your work order".to_string());
+    }
+    if order.state != WorkOrderState::Open {
+        return Err("Only open work orders can be cancelled".to_string());
+    }
+
+    escrow::refund(ctx, &payment_ref(order.id))?;
+    escrow::refund(ctx, &materials_ref(order.id))?;
+    order.escrow_materials.clear();
+    order.state = WorkOrderState::Cancelled;
+    order.updated_at = ctx.timestamp;
+    ctx.db.work_order().id().update(order);
+    log::info!("📋 Work order {} cancelled", order_id);
+    Ok(())
+}
+
+/// Aceita a encomenda: os materiais em custódia passam para o crafter
+#[reducer]
+pub fn accept_work_order(ctx: &ReducerContext, order_id: u64) -> Result<(), String> {
+    require_feature(ctx, MARKET_ENABLED)?;
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    crate::shadow_ban::throttle_market(ctx)?;
+    let mut order = ctx.db.work_order().id().find(order_id).ok_or("Work order not found")?;
+    require_near_board(ctx, &player, order.board_id)?;
+    if order.state != WorkOrderState::Open {
+        return Err("Work order is not open".to_string());
+    }
+    if order.poster_id == player.id {
+        return Err("Cannot accept your own work order".to_string());
+    }
+
+    escrow::release(ctx, &materials_ref(order.id), player.id)?;
+    order.crafter_id = Some(player.id);
+    order.state = WorkOrderState::Accepted;
+    order.accept_deadline = Some(ctx.timestamp + Duration::from_secs(ACCEPT_DEADLINE_SECS));
+    order.updated_at = ctx.timestamp;
+    ctx.db.work_order().id().update(order);
+    log::info!("📋 Player {} accepted work order {}", player.id, order_id);
+    Ok(())
+}
+
+/// Desiste da encomenda: o crafter devolve os materiais e ela volta a ficar aberta
+#[reducer]
+pub fn abandon_work_order(ctx: &ReducerContext, order_id: u64) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    let mut order = ctx.db.work_order().id().find(order_id).ok_or("Work order not found")?;
+    if order.state != WorkOrderState::Accepted || order.crafter_id != Some(player.id) {
+        return Err("You have not accepted this work order".to_string());
+    }
+    // Os materiais voltam para a custódia, ainda em nome de quem postou
+    escrow::hold(ctx, player.id, order.poster_id, &materials_ref(order.id), &order.escrow_materials, 0)
+        .map_err(|e| format!("Return the materials to abandon the order ({})", e))?;
+
+    order.crafter_id = None;
+    order.state = WorkOrderState::Open;
+    order.accept_deadline = None;
+    order.updated_at = ctx.timestamp;
+    ctx.db.work_order().id().update(order);
+    log::info!("📋 Player {} abandoned work order {}", player.id, order_id);
+    Ok(())
+}
+
+/// Entrega os itens: troca atômica de itens pelo pagamento em custódia
+#[reducer]
+pub fn fulfill_work_order(ctx: &ReducerContext, order_id: u64) -> Result<(), String> {
+    require_feature(ctx, MARKET_ENABLED)?;
+    let player = sender_player(ctx)?;
+    crate::upgrade::require_not_frozen(ctx)?;
+    crate::shadow_ban::throttle_market(ctx)?;
+    let mut order = ctx.db.work_order().id().find(order_id).ok_or("Work order not found")?;
+    require_near_board(ctx, &player, order.board_id)?;
+    if order.state != WorkOrderState::Accepted || order.crafter_id != Some(player.id) {
+        return Err("You have not accepted this work order".to_string());
+    }
+
+    remove_item_from_inventory(ctx, player.id, &order.item_id, order.quantity)
+        .map_err(|_| format!("Requires {} x{}", order.item_id, order.quantity))?;
+    // Com a bolsa do autor cheia, a encomenda chega pelo correio
+    let goods = RewardBundle::default().with_item(&order.item_id, order.quantity);
+    grant_reward_bundle(ctx, order.poster_id, &goods, "work_order")?;
+    escrow::release(ctx, &payment_ref(order.id), player.id)?;
+
+    order.escrow_materials.clear();
+    order.state = WorkOrderState::Fulfilled;
+    order.updated_at = ctx.timestamp;
+    ctx.db.work_order().id().update(order);
+    log::info!("📋 Player {} fulfilled work order {}", player.id, order_id);
+    Ok(())
+}
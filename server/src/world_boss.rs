@@ -0,0 +1,220 @@
+use crate::combat::{allocate_enemy_id, enemy, spawn_enemy, Enemy};
+use crate::localization::{announce_localized, Message};
+use crate::map::{map_template, random_spawn_point};
+use crate::reward::{grant_reward_bundle, RewardBundle};
+use spacetimedb::rand::Rng;
+use spacetimedb::{reducer, table, ReducerContext, ScheduleAt, Table, TimeDuration, Timestamp};
+use std::time::Duration;
+
+pub const WORLD_BOSS_ENEMY_TYPE: &str = "WorldBoss";
+
+/// Janela entre a morte de um boss e o próximo spawn (mais um atraso aleatório)
+const WORLD_BOSS_RESPAWN_SECS: u64 = 4 * 60 * 60;
+const WORLD_BOSS_RESPAWN_JITTER_SECS: u64 = 60 * 60;
+/// Espera antes de tentar de novo quando não há área livre de players
+const WORLD_BOSS_SPAWN_RETRY_SECS: u64 = 60;
+
+/// Cada participante extra soma esta fração da vida base
+const HEALTH_PER_EXTRA_PARTICIPANT: f32 = 0.5;
+/// Fração mínima do dano total para ter direito ao loot
+const LOOT_MIN_CONTRIBUTION: f32 = 0.05;
+const WORLD_BOSS_LOOT_ITEM: &str = "world_boss_chest";
+const WORLD_BOSS_XP: u64 = 500;
+
+/// Boss de mundo vivo (a entidade fica na tabela `enemy`)
+#[table(name = world_boss, public)]
+#[derive(Clone)]
+pub struct WorldBoss {
+    #[primary_key]
+    pub enemy_id: u32,
+    pub map_id: String,
+    pub base_max_health: f32,
+    pub participant_count: u32,
+    pub spawned_at: Timestamp,
+}
+
+/// Dano causado por cada player a um boss (define o loot)
+#[table(name = world_boss_contribution, public)]
+#[derive(Clone)]
+pub struct WorldBossContribution {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub enemy_id: u32,
+    pub player_id: u32,
+    pub damage: f32,
+    pub loot_eligible: bool,
+}
+
+/// Próximo spawn agendado (uma execução por linha)
+#[table(name = world_boss_spawn_schedule, scheduled(spawn_world_boss))]
+pub struct WorldBossSpawnSchedule {
+    #[primary_key]
+    #[auto_inc]
+    pub scheduled_id: u64,
+    pub scheduled_at: ScheduleAt,
+}
+
+fn schedule_next_spawn(ctx: &ReducerContext) {
+    let jitter = ctx.rng().gen_range(0..=WORLD_BOSS_RESPAWN_JITTER_SECS);
+    let delay = Duration::from_secs(WORLD_BOSS_RESPAWN_SECS + jitter);
+    let at = ctx.timestamp + TimeDuration::from_duration(delay);
+    ctx.db.world_boss_spawn_schedule().insert(WorldBossSpawnSchedule {
+        scheduled_id: 0,
+        scheduled_at: at.into(),
+    });
+    log::info!("🐉 Next world boss in {} s", delay.as_secs());
+}
+
+/// Spawn adiado (área ocupada por players): tenta de novo em pouco tempo
+fn schedule_spawn_retry(ctx: &ReducerContext) {
+    let at = ctx.timestamp + TimeDuration::from_duration(Duration::from_secs(WORLD_BOSS_SPAWN_RETRY_SECS));
+    ctx.db.world_boss_spawn_schedule().insert(WorldBossSpawnSchedule {
+        scheduled_id: 0,
+        scheduled_at: at.into(),
+    });
+}
+
+/// Garante que existe um boss vivo ou um spawn agendado (init e republish).
+/// Bosses removidos por outros caminhos (admin, limpeza) liberam o próximo spawn.
+pub fn ensure_world_boss_schedule(ctx: &ReducerContext) {
+    // Congelado: o spawn pendente está guardado e volta no resume
+    if crate::upgrade::is_frozen(ctx) {
+        return;
+    }
+    for boss in ctx.db.world_boss().iter() {
+        if ctx.db.enemy().id().find(boss.enemy_id).is_none() {
+            clear_boss(ctx, boss.enemy_id);
+        }
+    }
+
+    if ctx.db.world_boss().count() == 0 && ctx.db.world_boss_spawn_schedule().count() == 0 {
+        schedule_next_spawn(ctx);
+    }
+}
+
+#[reducer]
+pub fn spawn_world_boss(ctx: &ReducerContext, _schedule: WorldBossSpawnSchedule) -> Result<(), String> {
+    if ctx.sender != ctx.identity() {
+        return Err("spawn_world_boss may only be invoked by the scheduler".to_string());
+    }
+    if crate::upgrade::is_frozen(ctx) {
+        crate::upgrade::hold_timer(ctx, crate::upgrade::TIMER_WORLD_BOSS_SPAWN, 0);
+        return Ok(());
+    }
+    if ctx.db.world_boss().count() > 0 {
+        return Ok(());
+    }
+
+    let outdoor_maps: Vec<_> = ctx.db.map_template().iter()
+        .filter(|t| !t.is_indoor && t.name != crate::tutorial::TUTORIAL_TEMPLATE)
+        .collect();
+    if outdoor_maps.is_empty() {
+        log::warn!("⚠️ No outdoor map for a world boss");
+        schedule_next_spawn(ctx);
+        return Ok(());
+    }
+    let template = &outdoor_maps[ctx.rng().gen_range(0..outdoor_maps.len())];
+
+    let radius = (template.width.max(template.height) * crate::map::TILE_SIZE_PX) as f32 / 2.0;
+    let Some((x, y)) = random_spawn_point(ctx, &template.name, template.spawn_x, template.spawn_y, radius) else {
+        log::info!("🐉 No clear spot for the world boss in '{}', retrying shortly", template.name);
+        schedule_spawn_retry(ctx);
+        return Ok(());
+    };
+
+    let enemy_id = allocate_enemy_id(ctx);
+    spawn_enemy(ctx, enemy_id, x, y, template.name.clone(), WORLD_BOSS_ENEMY_TYPE.to_string())
+        .map_err(|e| e.to_string())?;
+    let boss = ctx.db.enemy().id().find(enemy_id).ok_or("World boss spawn failed")?;
+
+    ctx.db.world_boss().insert(WorldBoss {
+        enemy_id,
+        map_id: template.name.clone(),
+        base_max_health: boss.max_health,
+        participant_count: 0,
+        spawned_at: ctx.timestamp,
+    });
+    crate::replay::start_replay(ctx, crate::replay::REPLAY_WORLD_BOSS, enemy_id as u64, &template.name, Some(enemy_id));
+    announce_localized(ctx, "world_boss", Message::new("announce.world_boss.appeared").param("map", &template.name));
+    Ok(())
+}
+
+/// Chamado após o dano ser aplicado (antes de salvar o inimigo): registra a
+/// contribuição e escala a vida quando entra um novo participante.
+pub fn on_world_boss_damaged(ctx: &ReducerContext, enemy: &mut Enemy, attacker_id: u32, damage: f32) {
+    let Some(mut boss) = ctx.db.world_boss().enemy_id().find(enemy.id) else { return };
+    if crate::character::attacking_player(ctx, attacker_id).is_none() {
+        return;
+    }
+
+    let existing = ctx.db.world_boss_contribution().enemy_id().filter(enemy.id).find(|c| c.player_id == attacker_id);
+    match existing {
+        Some(mut contribution) => {
+            contribution.damage += damage;
+            ctx.db.world_boss_contribution().id().update(contribution);
+        }
+        None => {
+            ctx.db.world_boss_contribution().insert(WorldBossContribution {
+                id: 0,
+                enemy_id: enemy.id,
+                player_id: attacker_id,
+                damage,
+                loot_eligible: false,
+            });
+
+            boss.participant_count += 1;
+            let new_max = boss.base_max_health * (1.0 + HEALTH_PER_EXTRA_PARTICIPANT * (boss.participant_count - 1) as f32);
+            if new_max > enemy.max_health {
+                // Mantém a porcentagem de vida atual
+                enemy.health *= new_max / enemy.max_health;
+                enemy.max_health = new_max;
+            }
+            ctx.db.world_boss().enemy_id().update(boss);
+        }
+    }
+}
+
+/// Chamado quando o boss morre: distribui o loot por contribuição e agenda o próximo
+pub fn on_world_boss_defeated(ctx: &ReducerContext, enemy_id: u32) {
+    let Some(boss) = ctx.db.world_boss().enemy_id().find(enemy_id) else { return };
+
+    let contributions: Vec<WorldBossContribution> = ctx.db.world_boss_contribution().enemy_id().filter(enemy_id).collect();
+    let total_damage: f32 = contributions.iter().map(|c| c.damage).sum();
+
+    let mut eligible = Vec::new();
+    for mut contribution in contributions {
+        if total_damage <= 0.0 || contribution.damage / total_damage < LOOT_MIN_CONTRIBUTION {
+            continue;
+        }
+        contribution.loot_eligible = true;
+        let player_id = contribution.player_id;
+        ctx.db.world_boss_contribution().id().update(contribution);
+
+        let loot = RewardBundle::default().with_item(WORLD_BOSS_LOOT_ITEM, 1).with_xp(WORLD_BOSS_XP);
+        if let Err(e) = grant_reward_bundle(ctx, player_id, &loot, "world_boss") {
+            log::warn!("World boss loot for player {} failed: {}", player_id, e);
+        }
+        eligible.push(player_id);
+    }
+    crate::world_first::record_world_first(
+        ctx, crate::world_first::FIRST_WORLD_BOSS, "defeated the world boss".to_string(), "Worldbreaker", &eligible,
+    );
+
+    announce_localized(ctx, "world_boss", Message::new("announce.world_boss.defeated")
+        .param("map", &boss.map_id)
+        .param("heroes", eligible.len()));
+    ctx.db.world_boss().enemy_id().delete(enemy_id);
+    crate::replay::stop_replay(ctx, crate::replay::REPLAY_WORLD_BOSS, enemy_id as u64);
+    schedule_next_spawn(ctx);
+}
+
+fn clear_boss(ctx: &ReducerContext, enemy_id: u32) {
+    crate::replay::stop_replay(ctx, crate::replay::REPLAY_WORLD_BOSS, enemy_id as u64);
+    ctx.db.world_boss().enemy_id().delete(enemy_id);
+    let contributions: Vec<u64> = ctx.db.world_boss_contribution().enemy_id().filter(enemy_id).map(|c| c.id).collect();
+    for id in contributions {
+        ctx.db.world_boss_contribution().id().delete(id);
+    }
+}
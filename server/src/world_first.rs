@@ -0,0 +1,103 @@
+use crate::localization::{announce_localized, Message};
+use crate::party::sender_player;
+use crate::player;
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+pub const FIRST_LEVEL_CAP: &str = "level_cap";
+pub const FIRST_WORLD_BOSS: &str = "world_boss";
+
+/// Feitos inéditos no servidor. Permanente: a primeira linha de cada
+/// `achievement` nunca é sobrescrita.
+#[table(name = world_first, public)]
+#[derive(Clone)]
+pub struct WorldFirst {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[unique]
+    pub achievement: String,
+    pub description: String,
+    pub player_ids: Vec<u32>,
+    pub player_names: Vec<String>,
+    pub title: String,
+    pub achieved_at: Timestamp,
+}
+
+/// Títulos conquistados; no máximo um ativo por player
+#[table(name = player_title, public)]
+#[derive(Clone)]
+pub struct PlayerTitle {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    #[index(btree)]
+    pub player_id: u32,
+    pub title: String,
+    pub active: bool,
+    pub granted_at: Timestamp,
+}
+
+pub fn first_boss_kill(template_name: &str) -> String {
+    format!("boss:{}", template_name)
+}
+
+pub fn grant_title(ctx: &ReducerContext, player_id: u32, title: &str) {
+    if ctx.db.player_title().player_id().filter(player_id).any(|t| t.title == title) {
+        return;
+    }
+    ctx.db.player_title().insert(PlayerTitle {
+        id: 0,
+        player_id,
+        title: title.to_string(),
+        active: false,
+        granted_at: ctx.timestamp,
+    });
+}
+
+/// Registra o feito se ainda ninguém o conquistou: anuncia e concede o título único
+pub fn record_world_first(ctx: &ReducerContext, achievement: &str, description: String, title: &str, player_ids: &[u32]) {
+    if player_ids.is_empty() || ctx.db.world_first().achievement().find(achievement.to_string()).is_some() {
+        return;
+    }
+
+    let player_names: Vec<String> = player_ids.iter()
+        .filter_map(|id| ctx.db.player().id().find(*id).map(|p| p.username_display))
+        .collect();
+    for player_id in player_ids {
+        grant_title(ctx, *player_id, title);
+    }
+
+    announce_localized(ctx, "world_first", Message::new("announce.world_first")
+        .param("players", player_names.join(", "))
+        .param("description", &description));
+    ctx.db.world_first().insert(WorldFirst {
+        id: 0,
+        achievement: achievement.to_string(),
+        description,
+        player_ids: player_ids.to_vec(),
+        player_names,
+        title: title.to_string(),
+        achieved_at: ctx.timestamp,
+    });
+}
+
+/// Escolhe o título exibido (None remove)
+#[reducer]
+pub fn set_active_title(ctx: &ReducerContext, title: Option<String>) -> Result<(), String> {
+    let player = sender_player(ctx)?;
+    let titles: Vec<PlayerTitle> = ctx.db.player_title().player_id().filter(player.id).collect();
+    if let Some(title) = &title {
+        if !titles.iter().any(|t| &t.title == title) {
+            return Err("You don't have that title".to_string());
+        }
+    }
+
+    for mut row in titles {
+        let active = title.as_ref() == Some(&row.title);
+        if row.active != active {
+            row.active = active;
+            ctx.db.player_title().id().update(row);
+        }
+    }
+    Ok(())
+}
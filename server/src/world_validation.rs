@@ -0,0 +1,118 @@
+use crate::admin::require_admin;
+use crate::audit::record_audit;
+use crate::map::{is_walkable_position, map_template, map_transition, MapTransition, TILE_SIZE};
+use spacetimedb::{reducer, table, ReducerContext, Table, Timestamp};
+
+/// Problema encontrado pela última execução de `validate_world`
+#[table(name = world_validation_issue, public)]
+#[derive(Clone)]
+pub struct WorldValidationIssue {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub transition_id: u32,
+    pub severity: String,   // "error", "warning"
+    pub issue_code: String, // "MISSING_SOURCE_MAP", "DEST_BLOCKED", ...
+    pub message: String,
+    pub checked_at: Timestamp,
+}
+
+struct Issue {
+    severity: &'static str,
+    code: &'static str,
+    message: String,
+}
+
+fn error(code: &'static str, message: String) -> Issue {
+    Issue { severity: "error", code, message }
+}
+
+fn warning(code: &'static str, message: String) -> Issue {
+    Issue { severity: "warning", code, message }
+}
+
+/// Confere todas as transições contra os templates e grava o relatório.
+/// Cada execução substitui o relatório anterior.
+#[reducer]
+pub fn validate_world(ctx: &ReducerContext) -> Result<(), String> {
+    require_admin(ctx)?;
+
+    for old in ctx.db.world_validation_issue().iter() {
+        ctx.db.world_validation_issue().id().delete(old.id);
+    }
+
+    let transitions: Vec<MapTransition> = ctx.db.map_transition().iter().collect();
+    let mut error_count = 0;
+    let mut warning_count = 0;
+
+    for t in &transitions {
+        for issue in check_transition(ctx, t, &transitions) {
+            if issue.severity == "error" { error_count += 1; } else { warning_count += 1; }
+            ctx.db.world_validation_issue().insert(WorldValidationIssue {
+                id: 0,
+                transition_id: t.id,
+                severity: issue.severity.to_string(),
+                issue_code: issue.code.to_string(),
+                message: issue.message,
+                checked_at: ctx.timestamp,
+            });
+        }
+    }
+
+    record_audit(ctx, "world_validation", format!(
+        "Checked {} transitions: {} errors, {} warnings", transitions.len(), error_count, warning_count,
+    ));
+    Ok(())
+}
+
+fn check_transition(ctx: &ReducerContext, t: &MapTransition, all: &[MapTransition]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    match ctx.db.map_template().name().find(t.map_id.clone()) {
+        Some(source) => {
+            let (max_x, max_y) = (source.width as f32 * TILE_SIZE, source.height as f32 * TILE_SIZE);
+            if t.x < 0.0 || t.y < 0.0 || t.x + t.width > max_x || t.y + t.height > max_y {
+                issues.push(error("SOURCE_OUT_OF_BOUNDS", format!(
+                    "Trigger ({}, {}, {}x{}) is outside '{}' ({}x{} px)", t.x, t.y, t.width, t.height, t.map_id, max_x, max_y,
+                )));
+            }
+        }
+        None => issues.push(error("MISSING_SOURCE_MAP", format!("Source map '{}' has no template", t.map_id))),
+    }
+
+    match ctx.db.map_template().name().find(t.dest_map_id.clone()) {
+        Some(dest) => {
+            let (max_x, max_y) = (dest.width as f32 * TILE_SIZE, dest.height as f32 * TILE_SIZE);
+            if t.dest_x < 0.0 || t.dest_y < 0.0 || t.dest_x >= max_x || t.dest_y >= max_y {
+                issues.push(error("DEST_OUT_OF_BOUNDS", format!(
+                    "Destination ({}, {}) is outside '{}' ({}x{} px)", t.dest_x, t.dest_y, t.dest_map_id, max_x, max_y,
+                )));
+            } else if !is_walkable_position(ctx, &dest, None, t.dest_x, t.dest_y) {
+                issues.push(error("DEST_BLOCKED", format!(
+                    "Destination ({}, {}) in '{}' is a blocked tile", t.dest_x, t.dest_y, t.dest_map_id,
+                )));
+            }
+
+            // Destino dentro de outra porta causa ping-pong
+            let lands_on_trigger = all.iter().any(|o| {
+                o.map_id == t.dest_map_id
+                    && t.dest_x >= o.x && t.dest_x <= o.x + o.width
+                    && t.dest_y >= o.y && t.dest_y <= o.y + o.height
+            });
+            if lands_on_trigger {
+                issues.push(warning("DEST_ON_TRIGGER", format!(
+                    "Destination ({}, {}) in '{}' lands inside another transition", t.dest_x, t.dest_y, t.dest_map_id,
+                )));
+            }
+        }
+        None => issues.push(error("MISSING_DEST_MAP", format!("Destination map '{}' has no template", t.dest_map_id))),
+    }
+
+    if !all.iter().any(|o| o.map_id == t.dest_map_id && o.dest_map_id == t.map_id) {
+        issues.push(warning("NO_RECIPROCAL", format!(
+            "No transition leads back from '{}' to '{}'", t.dest_map_id, t.map_id,
+        )));
+    }
+
+    issues
+}